@@ -0,0 +1,231 @@
+use std::{path::PathBuf, sync::Arc};
+
+use futures::future;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{rems, DismissEvent, Model, Task, ViewContext, WeakView, WindowContext};
+use language::{Buffer, Point, ToPoint};
+use picker::{Picker, PickerDelegate};
+use project::{File, Project};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::{open_bookmark, Toggle};
+
+pub struct BookmarkPickerDelegate {
+    workspace: WeakView<Workspace>,
+    entries: Vec<BookmarkEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+struct BookmarkEntry {
+    buffer: Model<Buffer>,
+    position: text::Anchor,
+    abs_path: PathBuf,
+    title: String,
+    preview: String,
+}
+
+impl BookmarkPickerDelegate {
+    /// Opens the buffers of every bookmarked file, so that the picker can show the current
+    /// position and text of each bookmark, then shows the picker.
+    pub(crate) fn toggle(workspace: &mut Workspace, _: &Toggle, cx: &mut ViewContext<Workspace>) {
+        let project = workspace.project().clone();
+        let abs_paths = project
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_keys()
+            .collect::<Vec<_>>();
+        let open_buffers = abs_paths
+            .into_iter()
+            .map(|abs_path| {
+                project.update(cx, |project, cx| project.open_local_buffer(abs_path, cx))
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|workspace, mut cx| async move {
+            let buffers = future::join_all(open_buffers)
+                .await
+                .into_iter()
+                .filter_map(|buffer| buffer.log_err())
+                .collect::<Vec<_>>();
+            workspace.update(&mut cx, |workspace, cx| {
+                let handle = cx.view().downgrade();
+                workspace.toggle_modal(cx, move |cx| {
+                    let delegate = Self::new(handle, &project, buffers, cx);
+                    Picker::uniform_list(delegate, cx).width(rems(34.))
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: &Model<Project>,
+        buffers: Vec<Model<Buffer>>,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Self {
+        let bookmark_store = project.read(cx).bookmark_store().read(cx);
+        let mut entries = Vec::new();
+        for buffer_handle in buffers {
+            let buffer = buffer_handle.read(cx);
+            let Some(file) = File::from_dyn(buffer.file()) else {
+                continue;
+            };
+            let abs_path = file.abs_path(cx);
+            let path = file.path().to_string_lossy();
+            for bookmark in bookmark_store.bookmarks_for_buffer(buffer.remote_id()) {
+                let row = bookmark.position.to_point(buffer).row;
+                let location = format!("{path}:{}", row + 1);
+                let title = match &bookmark.data {
+                    Some(label) => format!("{label} {location}"),
+                    None => location,
+                };
+                let preview = buffer
+                    .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                    .collect::<String>()
+                    .trim()
+                    .to_string();
+                entries.push(BookmarkEntry {
+                    buffer: buffer_handle.clone(),
+                    position: bookmark.position,
+                    abs_path: abs_path.clone(),
+                    title,
+                    preview,
+                });
+            }
+        }
+        entries.sort_by(|a, b| {
+            a.abs_path.cmp(&b.abs_path).then_with(|| {
+                let buffer = a.buffer.read(cx);
+                a.position.cmp(&b.position, buffer)
+            })
+        });
+
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.title.clone()))
+            .collect();
+        Self {
+            workspace,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarkPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search bookmarks...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .map(|mat| &self.entries[mat.candidate_id])
+        else {
+            return;
+        };
+        let abs_path = entry.abs_path.clone();
+        let row = entry.position.to_point(entry.buffer.read(cx)).row;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_bookmark(workspace, abs_path, row, cx).detach_and_log_err(cx);
+            })
+            .log_err();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut ViewContext<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(Icon::new(IconName::Bookmark).color(Color::Accent))
+                .child(
+                    v_flex()
+                        .child(HighlightedLabel::new(
+                            entry.title.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(entry.preview.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
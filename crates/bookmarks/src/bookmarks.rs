@@ -0,0 +1,294 @@
+mod bookmark_picker;
+mod label_prompt;
+pub mod persistence;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use collections::{BTreeMap, HashSet};
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{actions, AppContext, Model, Task, ViewContext, WindowContext};
+use language::{Point, ToPoint};
+use project::{Bookmark, BookmarkStore, BookmarkStoreEvent, Project};
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::{
+    bookmark_picker::BookmarkPickerDelegate, label_prompt::BookmarkLabelPrompt,
+    persistence::BOOKMARKS_DB,
+};
+
+actions!(bookmarks, [Toggle, GoToNext, GoToPrevious, EditLabel]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            workspace
+                .register_action(BookmarkPickerDelegate::toggle)
+                .register_action(|workspace, _: &GoToNext, cx| {
+                    go_to_bookmark(workspace, true, cx);
+                })
+                .register_action(|workspace, _: &GoToPrevious, cx| {
+                    go_to_bookmark(workspace, false, cx);
+                })
+                .register_action(BookmarkLabelPrompt::toggle);
+            persist_bookmarks(workspace, cx);
+        },
+    )
+    .detach();
+}
+
+/// Returns the path and row of the newest cursor of the active editor, if it is in a local file.
+fn active_position(workspace: &Workspace, cx: &WindowContext) -> Option<(PathBuf, u32)> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let editor = editor.read(cx);
+    let head = editor.selections.newest::<Point>(cx).head();
+    let (buffer, position) = editor
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(head, cx)?;
+    let buffer = buffer.read(cx);
+    let abs_path = project::File::from_dyn(buffer.file())?
+        .as_local()?
+        .abs_path(cx);
+    Some((abs_path, position.to_point(buffer).row))
+}
+
+/// Moves to the bookmark after or before the cursor of the active editor, across files, wrapping
+/// around at either end.
+fn go_to_bookmark(workspace: &mut Workspace, forward: bool, cx: &mut ViewContext<Workspace>) {
+    let bookmarks = workspace
+        .project()
+        .read(cx)
+        .bookmark_store()
+        .read(cx)
+        .all_bookmarks(cx)
+        .into_iter()
+        .flat_map(|(abs_path, bookmarks)| {
+            bookmarks
+                .into_iter()
+                .map(move |bookmark| (abs_path.clone(), bookmark.row))
+        })
+        .collect::<Vec<_>>();
+    let current = active_position(workspace, cx);
+    let target = if forward {
+        current
+            .and_then(|current| bookmarks.iter().find(|bookmark| **bookmark > current))
+            .or(bookmarks.first())
+    } else {
+        current
+            .and_then(|current| bookmarks.iter().rev().find(|bookmark| **bookmark < current))
+            .or(bookmarks.last())
+    };
+    if let Some((abs_path, row)) = target.cloned() {
+        open_bookmark(workspace, abs_path, row, cx).detach_and_log_err(cx);
+    }
+}
+
+/// Opens the given file and moves the cursor to the start of the bookmarked row.
+fn open_bookmark(
+    workspace: &mut Workspace,
+    abs_path: PathBuf,
+    row: u32,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let open_task = workspace.open_abs_path(abs_path, true, cx);
+    cx.spawn(|_, mut cx| async move {
+        let item = open_task.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let position = Point::new(row, 0);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([position..position])
+                });
+            })?;
+        }
+        Ok(())
+    })
+}
+
+/// Restores the bookmarks saved for each local worktree of the workspace's project, and saves
+/// them again whenever they change.
+fn persist_bookmarks(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        return;
+    }
+    let bookmark_store = project.read(cx).bookmark_store().clone();
+
+    let mut restored_worktrees = HashSet::default();
+    restore_bookmarks(&project, &bookmark_store, &mut restored_worktrees, cx);
+    cx.subscribe(&project, move |_, project, event, cx| {
+        if let project::Event::WorktreeAdded = event {
+            let bookmark_store = project.read(cx).bookmark_store().clone();
+            restore_bookmarks(&project, &bookmark_store, &mut restored_worktrees, cx);
+        }
+    })
+    .detach();
+
+    cx.subscribe(&bookmark_store, |workspace, bookmark_store, event, cx| {
+        let BookmarkStoreEvent::BookmarksChanged { abs_path } = event;
+        let Some((worktree, _)) = workspace
+            .project()
+            .read(cx)
+            .find_local_worktree(abs_path, cx)
+        else {
+            return;
+        };
+        let worktree_path = worktree.read(cx).abs_path().to_path_buf();
+        let bookmarks = bookmark_store
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_iter()
+            .filter_map(|(abs_path, bookmarks)| {
+                let path = abs_path.strip_prefix(&worktree_path).ok()?.to_path_buf();
+                Some(
+                    bookmarks
+                        .into_iter()
+                        .map(move |bookmark| (path.clone(), bookmark.row, bookmark.label)),
+                )
+            })
+            .flatten()
+            .collect();
+        cx.background_executor()
+            .spawn(BOOKMARKS_DB.save_bookmarks(worktree_path, bookmarks))
+            .detach_and_log_err(cx);
+    })
+    .detach();
+}
+
+fn restore_bookmarks(
+    project: &Model<Project>,
+    bookmark_store: &Model<BookmarkStore>,
+    restored_worktrees: &mut HashSet<PathBuf>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let worktree_paths = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .filter(|worktree| worktree.read(cx).is_local())
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        .filter(|worktree_path| restored_worktrees.insert(worktree_path.clone()))
+        .collect::<Vec<_>>();
+
+    for worktree_path in worktree_paths {
+        let bookmark_store = bookmark_store.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let saved_bookmarks = BOOKMARKS_DB
+                .get_bookmarks(worktree_path.clone())
+                .log_err()
+                .unwrap_or_default();
+            if saved_bookmarks.is_empty() {
+                return anyhow::Ok(());
+            }
+
+            let mut bookmarks = BTreeMap::<PathBuf, Vec<Bookmark>>::default();
+            for (path, row, label) in saved_bookmarks {
+                bookmarks
+                    .entry(worktree_path.join(path))
+                    .or_default()
+                    .push(Bookmark { row, label });
+            }
+            bookmark_store.update(&mut cx, |store, cx| store.insert_bookmarks(bookmarks, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::AppState;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_go_to_bookmark(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "fn a() {\n    b();\n}\n",
+                "b.rs": "fn b() {\n    c();\n}\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from("/dir/a.rs"), true, cx)
+            })
+            .await
+            .unwrap();
+        project.update(cx, |project, cx| {
+            project.bookmark_store().update(cx, |store, cx| {
+                store.insert_bookmarks(
+                    BTreeMap::from_iter([
+                        (
+                            PathBuf::from("/dir/a.rs"),
+                            vec![Bookmark {
+                                row: 1,
+                                label: None,
+                            }],
+                        ),
+                        (
+                            PathBuf::from("/dir/b.rs"),
+                            vec![Bookmark {
+                                row: 2,
+                                label: Some("end of b".to_string()),
+                            }],
+                        ),
+                    ]),
+                    cx,
+                )
+            })
+        });
+
+        cx.dispatch_action(GoToNext);
+        cx.run_until_parked();
+        assert_active_position(&workspace, "/dir/a.rs", 1, cx);
+
+        cx.dispatch_action(GoToNext);
+        cx.run_until_parked();
+        assert_active_position(&workspace, "/dir/b.rs", 2, cx);
+
+        // Navigation wraps around at either end.
+        cx.dispatch_action(GoToNext);
+        cx.run_until_parked();
+        assert_active_position(&workspace, "/dir/a.rs", 1, cx);
+
+        cx.dispatch_action(GoToPrevious);
+        cx.run_until_parked();
+        assert_active_position(&workspace, "/dir/b.rs", 2, cx);
+    }
+
+    #[track_caller]
+    fn assert_active_position(
+        workspace: &gpui::View<Workspace>,
+        abs_path: &str,
+        row: u32,
+        cx: &mut VisualTestContext,
+    ) {
+        let position = workspace.update(cx, |workspace, cx| active_position(workspace, cx));
+        assert_eq!(position, Some((Path::new(abs_path).to_path_buf(), row)));
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
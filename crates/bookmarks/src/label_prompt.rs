@@ -0,0 +1,150 @@
+use editor::Editor;
+use gpui::{
+    div, prelude::*, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, SharedString, Subscription, View, ViewContext,
+};
+use language::{Buffer, Point, ToPoint};
+use project::BookmarkStore;
+use theme::ActiveTheme;
+use ui::{h_flex, prelude::*, v_flex, Label};
+use workspace::{ModalView, Workspace};
+
+use crate::EditLabel;
+
+/// A prompt for the label of the bookmark on the line of the cursor.
+pub struct BookmarkLabelPrompt {
+    label_editor: View<Editor>,
+    bookmark_store: Model<BookmarkStore>,
+    buffer: Model<Buffer>,
+    position: text::Anchor,
+    help_text: SharedString,
+    _subscription: Subscription,
+}
+
+impl ModalView for BookmarkLabelPrompt {}
+
+impl FocusableView for BookmarkLabelPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkLabelPrompt {}
+
+impl BookmarkLabelPrompt {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &EditLabel,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let head = editor.read(cx).selections.newest::<Point>(cx).head();
+        let Some((buffer, position)) = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+        let bookmark_store = workspace.project().read(cx).bookmark_store().clone();
+        workspace.toggle_modal(cx, move |cx| {
+            Self::new(bookmark_store, buffer, position, cx)
+        });
+    }
+
+    fn new(
+        bookmark_store: Model<BookmarkStore>,
+        buffer: Model<Buffer>,
+        position: text::Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        let bookmark = bookmark_store
+            .read(cx)
+            .bookmarks_for_buffer(snapshot.remote_id())
+            .iter()
+            .find(|bookmark| bookmark.position.to_point(&snapshot).row == row);
+        let help_text = if bookmark.is_some() {
+            format!("Label the bookmark on line {}", row + 1)
+        } else {
+            format!("Add a labeled bookmark on line {}", row + 1)
+        };
+        let label = bookmark.and_then(|bookmark| bookmark.data.clone());
+
+        let label_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Bookmark label", cx);
+            if let Some(label) = label {
+                editor.set_text(label, cx);
+                editor.select_all(&editor::actions::SelectAll, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&label_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            position,
+            help_text: help_text.into(),
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let label = self.label_editor.read(cx).text(cx);
+        self.bookmark_store.update(cx, |store, cx| {
+            store.set_label(&self.buffer, self.position, Some(label), cx)
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabelPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .elevation_2(cx)
+            .key_context("BookmarkLabelPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.label_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new(self.help_text.clone()).color(Color::Muted)),
+                    ),
+            )
+    }
+}
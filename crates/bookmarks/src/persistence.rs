@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use db::{define_connection, query, replace_rows, sqlez_macros::sql};
+use workspace::WorkspaceDb;
+
+define_connection! {
+    pub static ref BOOKMARKS_DB: BookmarksDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE bookmarks (
+                worktree_path BLOB NOT NULL,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                PRIMARY KEY(worktree_path, path, row)
+            ) STRICT;
+        )];
+}
+
+impl BookmarksDb {
+    query! {
+        pub fn get_bookmarks(worktree_path: PathBuf) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+            SELECT path, row, label
+            FROM bookmarks
+            WHERE worktree_path = ?
+            ORDER BY path, row
+        }
+    }
+
+    /// Replaces the bookmarks saved for the worktree, with paths relative to its root.
+    pub async fn save_bookmarks(
+        &self,
+        worktree_path: PathBuf,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) -> anyhow::Result<()> {
+        self.write(move |conn| {
+            replace_rows(
+                conn,
+                sql!(DELETE FROM bookmarks WHERE worktree_path = ?),
+                sql!(INSERT INTO bookmarks(worktree_path, path, row, label) VALUES (?, ?, ?, ?)),
+                worktree_path,
+                bookmarks,
+            )
+        })
+        .await
+    }
+}
@@ -132,6 +132,7 @@ CREATE TABLE "language_servers" (
     "id" INTEGER NOT NULL,
     "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
     "name" VARCHAR NOT NULL,
+    "signature_help_trigger_characters" VARCHAR NOT NULL DEFAULT '[]',
     PRIMARY KEY(project_id, id)
 );
 CREATE INDEX "index_language_servers_on_project_id" ON "language_servers" ("project_id");
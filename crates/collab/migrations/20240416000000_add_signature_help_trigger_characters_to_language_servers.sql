@@ -0,0 +1 @@
+ALTER TABLE language_servers ADD COLUMN signature_help_trigger_characters VARCHAR NOT NULL DEFAULT '[]';
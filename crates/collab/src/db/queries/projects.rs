@@ -437,17 +437,28 @@ impl Database {
             }
 
             // Add the newly-started language server.
+            let signature_help_trigger_characters = server
+                .capabilities
+                .as_ref()
+                .map(|capabilities| capabilities.signature_help_trigger_characters.as_slice())
+                .unwrap_or_default();
             language_server::Entity::insert(language_server::ActiveModel {
                 project_id: ActiveValue::set(project_id),
                 id: ActiveValue::set(server.id as i64),
                 name: ActiveValue::set(server.name.clone()),
+                signature_help_trigger_characters: ActiveValue::set(serde_json::to_string(
+                    signature_help_trigger_characters,
+                )?),
             })
             .on_conflict(
                 OnConflict::columns([
                     language_server::Column::ProjectId,
                     language_server::Column::Id,
                 ])
-                .update_column(language_server::Column::Name)
+                .update_columns([
+                    language_server::Column::Name,
+                    language_server::Column::SignatureHelpTriggerCharacters,
+                ])
                 .to_owned(),
             )
             .exec(&*tx)
@@ -748,10 +759,7 @@ impl Database {
             worktrees,
             language_servers: language_servers
                 .into_iter()
-                .map(|language_server| proto::LanguageServer {
-                    id: language_server.id as u64,
-                    name: language_server.name,
-                })
+                .map(language_server::Model::to_proto)
                 .collect(),
         };
         Ok((project, replica_id as ReplicaId))
@@ -1,4 +1,5 @@
 use crate::db::ProjectId;
+use rpc::proto;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -9,6 +10,23 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
     pub name: String,
+    /// The characters triggering signature help, as a JSON array.
+    pub signature_help_trigger_characters: String,
+}
+
+impl Model {
+    pub fn to_proto(self) -> proto::LanguageServer {
+        proto::LanguageServer {
+            id: self.id as u64,
+            name: self.name,
+            capabilities: Some(proto::LanguageServerCapabilities {
+                signature_help_trigger_characters: serde_json::from_str(
+                    &self.signature_help_trigger_characters,
+                )
+                .unwrap_or_default(),
+            }),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
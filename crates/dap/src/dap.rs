@@ -0,0 +1,766 @@
+mod debug_template;
+pub mod events;
+pub mod requests;
+pub mod types;
+
+pub use debug_template::{DebugRequestKind, DebugTemplate, DebugTemplates};
+
+use anyhow::{anyhow, Context, Result};
+use collections::HashMap;
+use futures::{channel::oneshot, io::BufWriter, AsyncRead, AsyncWrite, Future};
+use gpui::{AsyncAppContext, BackgroundExecutor, Task};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::{
+    channel,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    process::{self, Child},
+};
+
+#[cfg(target_os = "windows")]
+use smol::process::windows::CommandExt;
+
+use std::{
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering::SeqCst},
+        Arc,
+    },
+};
+use util::ResultExt;
+
+use events::Event;
+use requests::Request;
+use types::{Capabilities, InitializeRequestArguments};
+
+const HEADER_DELIMITER: &[u8; 4] = b"\r\n\r\n";
+const CONTENT_LEN_HEADER: &str = "Content-Length: ";
+
+type EventHandler = Box<dyn Send + FnMut(Value, AsyncAppContext)>;
+type RequestHandler = Box<dyn Send + FnMut(u64, Value, AsyncAppContext)>;
+type ResponseHandler = Box<dyn Send + FnOnce(Result<Value>)>;
+
+/// Represents a launchable debug adapter.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DebugAdapterBinary {
+    pub path: PathBuf,
+    pub arguments: Vec<OsString>,
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// Identifies a running debug adapter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct DebugAdapterId(pub usize);
+
+/// A client of a running debug adapter process, exchanging
+/// [Debug Adapter Protocol](https://microsoft.github.io/debug-adapter-protocol/specification)
+/// messages with it over its stdio.
+pub struct DebugAdapterClient {
+    id: DebugAdapterId,
+    name: Arc<str>,
+    next_seq: Arc<AtomicU64>,
+    outbound_tx: channel::Sender<String>,
+    capabilities: Capabilities,
+    event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+    response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    #[allow(clippy::type_complexity)]
+    io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
+    process: Arc<Mutex<Option<Child>>>,
+}
+
+/// Debug Adapter Protocol message, as received from the other side of the connection.
+#[derive(Debug, Deserialize)]
+struct AnyMessage {
+    seq: u64,
+    #[serde(rename = "type")]
+    kind: MessageKind,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    event: Option<String>,
+    #[serde(default)]
+    request_seq: Option<u64>,
+    #[serde(default)]
+    success: Option<bool>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    arguments: Value,
+    #[serde(default)]
+    body: Value,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MessageKind {
+    Request,
+    Response,
+    Event,
+}
+
+/// Debug Adapter Protocol message, as sent to the other side of the connection.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum OutgoingMessage<'a> {
+    Request {
+        seq: u64,
+        command: &'a str,
+        #[serde(skip_serializing_if = "Value::is_null")]
+        arguments: Value,
+    },
+    Response {
+        seq: u64,
+        request_seq: u64,
+        success: bool,
+        command: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        #[serde(skip_serializing_if = "Value::is_null")]
+        body: Value,
+    },
+    Event {
+        seq: u64,
+        event: &'a str,
+        #[serde(skip_serializing_if = "Value::is_null")]
+        body: Value,
+    },
+}
+
+impl DebugAdapterClient {
+    /// Starts a debug adapter process.
+    pub fn new(
+        id: DebugAdapterId,
+        binary: DebugAdapterBinary,
+        working_dir: &Path,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        log::info!(
+            "starting debug adapter. binary path: {:?}, working directory: {:?}, args: {:?}",
+            binary.path,
+            working_dir,
+            &binary.arguments
+        );
+
+        let mut command = process::Command::new(&binary.path);
+        command
+            .current_dir(working_dir)
+            .args(binary.arguments)
+            .envs(binary.env.unwrap_or_default())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        let mut process = command
+            .spawn()
+            .with_context(|| format!("failed to spawn debug adapter {:?}", binary.path))?;
+
+        let stdin = process.stdin.take().unwrap();
+        let stdout = process.stdout.take().unwrap();
+        let stderr = process.stderr.take().unwrap();
+        let mut client = Self::new_internal(id, stdin, stdout, Some(stderr), Some(process), cx);
+        if let Some(name) = binary.path.file_name() {
+            client.name = name.to_string_lossy().into();
+        }
+        Ok(client)
+    }
+
+    fn new_internal<Stdin, Stdout, Stderr>(
+        id: DebugAdapterId,
+        stdin: Stdin,
+        stdout: Stdout,
+        stderr: Option<Stderr>,
+        process: Option<Child>,
+        cx: AsyncAppContext,
+    ) -> Self
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+        Stdout: AsyncRead + Unpin + Send + 'static,
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let next_seq = Arc::new(AtomicU64::new(1));
+        let event_handlers = Arc::new(Mutex::new(HashMap::<_, EventHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+
+        let stdout_input_task = cx.spawn({
+            let event_handlers = event_handlers.clone();
+            let request_handlers = request_handlers.clone();
+            let response_handlers = response_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
+            let next_seq = next_seq.clone();
+            move |cx| {
+                Self::handle_input(
+                    stdout,
+                    event_handlers,
+                    request_handlers,
+                    response_handlers,
+                    outbound_tx,
+                    next_seq,
+                    cx,
+                )
+                .log_err()
+            }
+        });
+        let stderr_input_task = stderr
+            .map(|stderr| cx.spawn(|_| Self::handle_stderr(stderr).log_err()))
+            .unwrap_or_else(|| Task::ready(None));
+        let input_task = cx.spawn(|_| async move {
+            let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            stdout.or(stderr)
+        });
+        let output_task = cx
+            .background_executor()
+            .spawn(Self::handle_output(stdin, outbound_rx).log_err());
+
+        Self {
+            id,
+            name: "".into(),
+            next_seq,
+            outbound_tx,
+            capabilities: Default::default(),
+            event_handlers,
+            request_handlers,
+            response_handlers,
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            process: Arc::new(Mutex::new(process)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_input<Stdout>(
+        stdout: Stdout,
+        event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
+        next_seq: Arc<AtomicU64>,
+        cx: AsyncAppContext,
+    ) -> Result<()>
+    where
+        Stdout: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stdout = BufReader::new(stdout);
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            read_headers(&mut stdout, &mut buffer).await?;
+
+            let headers = std::str::from_utf8(&buffer)?;
+            let message_len = headers
+                .split('\n')
+                .find_map(|line| line.strip_prefix(CONTENT_LEN_HEADER))
+                .ok_or_else(|| anyhow!("invalid DAP message header {headers:?}"))?
+                .trim_end()
+                .parse()?;
+
+            buffer.resize(message_len, 0);
+            stdout.read_exact(&mut buffer).await?;
+
+            let message = match serde_json::from_slice::<AnyMessage>(&buffer) {
+                Ok(message) => message,
+                Err(error) => {
+                    log::warn!(
+                        "failed to deserialize DAP message: {error}\n{}",
+                        String::from_utf8_lossy(&buffer)
+                    );
+                    continue;
+                }
+            };
+            log::trace!("incoming DAP message: {message:?}");
+
+            match message.kind {
+                MessageKind::Response => {
+                    let Some(request_seq) = message.request_seq else {
+                        continue;
+                    };
+                    let handler = response_handlers
+                        .lock()
+                        .as_mut()
+                        .and_then(|handlers| handlers.remove(&request_seq));
+                    if let Some(handler) = handler {
+                        if message.success.unwrap_or(false) {
+                            handler(Ok(message.body));
+                        } else {
+                            let command = message.command.unwrap_or_default();
+                            handler(Err(anyhow!(
+                                "{command} request failed: {}",
+                                message.message.unwrap_or_default()
+                            )));
+                        }
+                    }
+                }
+                MessageKind::Event => {
+                    let event = message.event.unwrap_or_default();
+                    if let Some(handler) = event_handlers.lock().get_mut(event.as_str()) {
+                        handler(message.body, cx.clone());
+                    } else {
+                        log::info!("unhandled DAP event {event}");
+                    }
+                }
+                MessageKind::Request => {
+                    let command = message.command.unwrap_or_default();
+                    if let Some(handler) = request_handlers.lock().get_mut(command.as_str()) {
+                        handler(message.seq, message.arguments, cx.clone());
+                    } else {
+                        send_message(&outbound_tx, &next_seq, |seq| OutgoingMessage::Response {
+                            seq,
+                            request_seq: message.seq,
+                            success: false,
+                            command: &command,
+                            message: Some(format!("unsupported request {command}")),
+                            body: Value::Null,
+                        })
+                        .log_err();
+                    }
+                }
+            }
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+    }
+
+    async fn handle_stderr<Stderr>(stderr: Stderr) -> Result<()>
+    where
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stderr = BufReader::new(stderr);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if stderr.read_until(b'\n', &mut buffer).await? == 0 {
+                return Ok(());
+            }
+            log::debug!("debug adapter stderr: {}", String::from_utf8_lossy(&buffer));
+        }
+    }
+
+    async fn handle_output<Stdin>(
+        stdin: Stdin,
+        outbound_rx: channel::Receiver<String>,
+    ) -> Result<()>
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut stdin = BufWriter::new(stdin);
+        let mut content_len_buffer = Vec::new();
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing DAP message: {message}");
+            content_len_buffer.clear();
+            content_len_buffer
+                .extend_from_slice(format!("{CONTENT_LEN_HEADER}{}", message.len()).as_bytes());
+            stdin.write_all(&content_len_buffer).await?;
+            stdin.write_all(HEADER_DELIMITER).await?;
+            stdin.write_all(message.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Initializes the debug adapter, which must be done before sending it any other request.
+    pub async fn initialize(mut self, arguments: InitializeRequestArguments) -> Result<Arc<Self>> {
+        self.capabilities = self.request::<requests::Initialize>(arguments).await?;
+        Ok(Arc::new(self))
+    }
+
+    pub fn id(&self) -> DebugAdapterId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Sends a request to the other side of the connection, resolving to the body of its response.
+    pub fn request<T: Request>(
+        &self,
+        arguments: T::Arguments,
+    ) -> impl 'static + Future<Output = Result<T::Response>> {
+        let (tx, rx) = oneshot::channel();
+        let seq = self.next_seq.fetch_add(1, SeqCst);
+        let handle_response = self
+            .response_handlers
+            .lock()
+            .as_mut()
+            .ok_or_else(|| anyhow!("debug adapter shut down"))
+            .map(|handlers| {
+                handlers.insert(
+                    seq,
+                    Box::new(move |response: Result<Value>| {
+                        let response = response.and_then(|body| {
+                            serde_json::from_value::<T::Response>(body).with_context(|| {
+                                format!("failed to deserialize {} response", T::COMMAND)
+                            })
+                        });
+                        tx.send(response).ok();
+                    }),
+                );
+            });
+        let send = serde_json::to_value(arguments)
+            .context("failed to serialize DAP request arguments")
+            .and_then(|arguments| {
+                write_message(
+                    &self.outbound_tx,
+                    OutgoingMessage::Request {
+                        seq,
+                        command: T::COMMAND,
+                        arguments,
+                    },
+                )
+            });
+
+        async move {
+            handle_response?;
+            send?;
+            rx.await
+                .map_err(|_| anyhow!("debug adapter shut down before responding"))?
+        }
+    }
+
+    /// Sends an event to the other side of the connection.
+    pub fn send_event<T: Event>(&self, body: T::Body) -> Result<()> {
+        let body = serde_json::to_value(body)?;
+        send_message(&self.outbound_tx, &self.next_seq, |seq| {
+            OutgoingMessage::Event {
+                seq,
+                event: T::EVENT,
+                body,
+            }
+        })
+    }
+
+    /// Registers a handler for an event, replacing any existing handler of that event.
+    pub fn on_event<T, F>(&self, mut f: F)
+    where
+        T: Event,
+        F: 'static + Send + FnMut(T::Body, AsyncAppContext),
+    {
+        self.event_handlers.lock().insert(
+            T::EVENT,
+            Box::new(move |body, cx| match serde_json::from_value(body) {
+                Ok(body) => f(body, cx),
+                Err(error) => log::error!("failed to deserialize {} event: {error}", T::EVENT),
+            }),
+        );
+    }
+
+    /// Registers a handler for a request sent by the other side of the connection, replacing any
+    /// existing handler of that request.
+    pub fn on_request<T, F, Fut>(&self, mut f: F)
+    where
+        T: Request,
+        F: 'static + Send + FnMut(T::Arguments, AsyncAppContext) -> Fut,
+        Fut: 'static + Future<Output = Result<T::Response>>,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        let next_seq = self.next_seq.clone();
+        self.request_handlers.lock().insert(
+            T::COMMAND,
+            Box::new(move |request_seq, arguments, cx| {
+                let response = serde_json::from_value::<T::Arguments>(arguments)
+                    .map(|arguments| f(arguments, cx.clone()));
+                let outbound_tx = outbound_tx.clone();
+                let next_seq = next_seq.clone();
+                cx.foreground_executor()
+                    .spawn(async move {
+                        let response = match response {
+                            Ok(response) => response.await,
+                            Err(error) => Err(error.into()),
+                        };
+                        let (success, message, body) =
+                            match response.and_then(|body| Ok(serde_json::to_value(body)?)) {
+                                Ok(body) => (true, None, body),
+                                Err(error) => (false, Some(error.to_string()), Value::Null),
+                            };
+                        send_message(&outbound_tx, &next_seq, |seq| OutgoingMessage::Response {
+                            seq,
+                            request_seq,
+                            success,
+                            command: T::COMMAND,
+                            message,
+                            body,
+                        })
+                        .log_err();
+                    })
+                    .detach();
+            }),
+        );
+    }
+
+    /// Stops reading the debug adapter's output and kills its process.
+    pub fn shutdown(&self) {
+        self.response_handlers.lock().take();
+        self.io_tasks.lock().take();
+        if let Some(mut process) = self.process.lock().take() {
+            process.kill().log_err();
+        }
+    }
+}
+
+impl Drop for DebugAdapterClient {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl fmt::Display for DebugAdapterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for DebugAdapterClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugAdapterClient")
+            .field("id", &self.id.0)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+fn send_message<'a>(
+    outbound_tx: &channel::Sender<String>,
+    next_seq: &AtomicU64,
+    build_message: impl FnOnce(u64) -> OutgoingMessage<'a>,
+) -> Result<()> {
+    write_message(outbound_tx, build_message(next_seq.fetch_add(1, SeqCst)))
+}
+
+fn write_message(outbound_tx: &channel::Sender<String>, message: OutgoingMessage) -> Result<()> {
+    let message = serde_json::to_string(&message)?;
+    outbound_tx
+        .try_send(message)
+        .context("failed to write to debug adapter's stdin")
+}
+
+async fn read_headers<Stdout>(reader: &mut BufReader<Stdout>, buffer: &mut Vec<u8>) -> Result<()>
+where
+    Stdout: AsyncRead + Unpin + Send + 'static,
+{
+    loop {
+        if buffer.len() >= HEADER_DELIMITER.len()
+            && buffer[(buffer.len() - HEADER_DELIMITER.len())..] == HEADER_DELIMITER[..]
+        {
+            return Ok(());
+        }
+
+        if reader.read_until(b'\n', buffer).await? == 0 {
+            return Err(anyhow!("cannot read DAP message headers"));
+        }
+    }
+}
+
+/// Mock debug adapter for use in tests.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Clone)]
+pub struct FakeDebugAdapter {
+    pub adapter: Arc<DebugAdapterClient>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeDebugAdapter {
+    /// Constructs a fake debug adapter, along with a client connected to it.
+    ///
+    /// The fake adapter answers the requests needed to start a debug session, sending the
+    /// `initialized` event once asked to launch or attach.
+    pub fn new(
+        id: DebugAdapterId,
+        capabilities: Capabilities,
+        cx: AsyncAppContext,
+    ) -> (DebugAdapterClient, FakeDebugAdapter) {
+        let (stdin_writer, stdin_reader) = async_pipe::pipe();
+        let (stdout_writer, stdout_reader) = async_pipe::pipe();
+
+        let mut client = DebugAdapterClient::new_internal(
+            id,
+            stdin_writer,
+            stdout_reader,
+            None::<async_pipe::PipeReader>,
+            None,
+            cx.clone(),
+        );
+        client.name = "fake-debug-adapter".into();
+        let fake = FakeDebugAdapter {
+            adapter: Arc::new(DebugAdapterClient::new_internal(
+                id,
+                stdout_writer,
+                stdin_reader,
+                None::<async_pipe::PipeReader>,
+                None,
+                cx,
+            )),
+        };
+
+        fake.handle_request::<requests::Initialize, _, _>(move |_, _| {
+            let capabilities = capabilities.clone();
+            async move { Ok(capabilities) }
+        });
+        for start_request in [requests::Launch::COMMAND, requests::Attach::COMMAND] {
+            let adapter = Arc::downgrade(&fake.adapter);
+            fake.adapter.request_handlers.lock().insert(
+                start_request,
+                Box::new(move |request_seq, _, _| {
+                    if let Some(adapter) = adapter.upgrade() {
+                        send_message(&adapter.outbound_tx, &adapter.next_seq, |seq| {
+                            OutgoingMessage::Response {
+                                seq,
+                                request_seq,
+                                success: true,
+                                command: start_request,
+                                message: None,
+                                body: Value::Null,
+                            }
+                        })
+                        .log_err();
+                        adapter.send_event::<events::Initialized>(None).log_err();
+                    }
+                }),
+            );
+        }
+        fake.handle_request::<requests::ConfigurationDone, _, _>(|_, _| async { Ok(()) });
+        fake.handle_request::<requests::Disconnect, _, _>(|_, _| async { Ok(()) });
+
+        (client, fake)
+    }
+
+    /// Sends an event to the client.
+    pub fn send_event<T: Event>(&self, body: T::Body) {
+        self.adapter.send_event::<T>(body).log_err();
+    }
+
+    /// Registers a handler for a specific kind of request, replacing any existing handler for
+    /// that request.
+    pub fn handle_request<T, F, Fut>(
+        &self,
+        mut handler: F,
+    ) -> futures::channel::mpsc::UnboundedReceiver<()>
+    where
+        T: 'static + Request,
+        F: 'static + Send + FnMut(T::Arguments, AsyncAppContext) -> Fut,
+        Fut: 'static + Send + Future<Output = Result<T::Response>>,
+    {
+        let (responded_tx, responded_rx) = futures::channel::mpsc::unbounded();
+        self.adapter.on_request::<T, _, _>(move |arguments, cx| {
+            let response = handler(arguments, cx);
+            let responded_tx = responded_tx.clone();
+            async move {
+                let response = response.await;
+                responded_tx.unbounded_send(()).ok();
+                response
+            }
+        });
+        responded_rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_fake(cx: &mut TestAppContext) {
+        let (client, fake) = FakeDebugAdapter::new(
+            DebugAdapterId(0),
+            Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+
+        let (stopped_tx, stopped_rx) = oneshot::channel();
+        let mut stopped_tx = Some(stopped_tx);
+        client.on_event::<events::Stopped, _>(move |body, _| {
+            if let Some(stopped_tx) = stopped_tx.take() {
+                stopped_tx.send(body).ok();
+            }
+        });
+
+        let client = cx
+            .background_executor
+            .spawn(client.initialize(InitializeRequestArguments {
+                adapter_id: "fake".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            client.capabilities().supports_configuration_done_request,
+            Some(true)
+        );
+
+        fake.handle_request::<requests::SetBreakpoints, _, _>(|arguments, _| async move {
+            Ok(types::SetBreakpointsResponse {
+                breakpoints: arguments
+                    .breakpoints
+                    .into_iter()
+                    .map(|breakpoint| types::Breakpoint {
+                        verified: true,
+                        line: Some(breakpoint.line),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        });
+        let response = client
+            .request::<requests::SetBreakpoints>(types::SetBreakpointsArguments {
+                source: types::Source {
+                    path: Some("/root/main.rs".into()),
+                    ..Default::default()
+                },
+                breakpoints: vec![types::SourceBreakpoint {
+                    line: 3,
+                    ..Default::default()
+                }],
+                source_modified: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.breakpoints,
+            [types::Breakpoint {
+                verified: true,
+                line: Some(3),
+                ..Default::default()
+            }]
+        );
+
+        let error = client.request::<requests::Threads>(()).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "threads request failed: unsupported request threads"
+        );
+
+        fake.send_event::<events::Stopped>(types::StoppedEventBody {
+            reason: "breakpoint".into(),
+            description: None,
+            thread_id: Some(1),
+            all_threads_stopped: Some(true),
+            text: None,
+        });
+        let stopped = stopped_rx.await.unwrap();
+        assert_eq!(stopped.reason, "breakpoint");
+        assert_eq!(stopped.thread_id, Some(1));
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use collections::HashMap;
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+use crate::DebugAdapterBinary;
+
+/// A template definition of a debug session: the debug adapter to spawn, and how it should start
+/// debugging.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugTemplate {
+    /// Human readable name of the debug session to display in the UI.
+    pub label: String,
+    /// Executable command of the debug adapter to spawn.
+    pub command: String,
+    /// Arguments to the debug adapter's command.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Env overrides for the debug adapter's command.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Current working directory to spawn the debug adapter into, defaults to current project root.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Whether to launch a new program to debug, or to attach to a running one.
+    #[serde(default)]
+    pub request: DebugRequestKind,
+    /// Arguments of the launch or attach request, specific to the debug adapter, such as the
+    /// program to debug.
+    #[serde(default)]
+    pub configuration: serde_json::Value,
+}
+
+/// How a debug session starts debugging.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugRequestKind {
+    /// Launch a new program to debug.
+    #[default]
+    Launch,
+    /// Attach to a running program.
+    Attach,
+}
+
+/// A group of debug templates defined in a JSON file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DebugTemplates(pub Vec<DebugTemplate>);
+
+impl DebugTemplates {
+    /// Generates JSON schema of the debug templates JSON format.
+    pub fn generate_json_schema() -> serde_json_lenient::Value {
+        let schema = SchemaSettings::draft07()
+            .with(|settings| settings.option_add_null_type = false)
+            .into_generator()
+            .into_root_schema_for::<Self>();
+
+        serde_json_lenient::to_value(schema).unwrap()
+    }
+}
+
+impl DebugTemplate {
+    /// Returns the debug adapter to spawn for this template, along with the directory to spawn
+    /// it in, relative to the given project root when the template's `cwd` is relative.
+    pub fn adapter_binary(&self, project_root: Option<PathBuf>) -> (DebugAdapterBinary, PathBuf) {
+        let cwd = match (&self.cwd, project_root) {
+            (Some(cwd), Some(root)) => root.join(cwd),
+            (Some(cwd), None) => PathBuf::from(cwd),
+            (None, Some(root)) => root,
+            (None, None) => PathBuf::from("/"),
+        };
+        let binary = DebugAdapterBinary {
+            path: PathBuf::from(&self.command),
+            arguments: self.args.iter().map(Into::into).collect(),
+            env: Some(self.env.clone()),
+        };
+        (binary, cwd)
+    }
+}
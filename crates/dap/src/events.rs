@@ -0,0 +1,36 @@
+//! Events sent by debug adapters, each with the type of its body.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::types::*;
+
+/// An event of the Debug Adapter Protocol.
+pub trait Event: 'static {
+    type Body: DeserializeOwned + Serialize + Send + 'static;
+    const EVENT: &'static str;
+}
+
+macro_rules! events {
+    ($($name:ident($event:literal): $body:ty),* $(,)?) => {
+        $(
+            #[derive(Debug)]
+            pub enum $name {}
+
+            impl Event for $name {
+                type Body = $body;
+                const EVENT: &'static str = $event;
+            }
+        )*
+    };
+}
+
+events! {
+    Initialized("initialized"): Option<Value>,
+    Stopped("stopped"): StoppedEventBody,
+    Continued("continued"): ContinuedEventBody,
+    Exited("exited"): ExitedEventBody,
+    Terminated("terminated"): Option<Value>,
+    Output("output"): OutputEventBody,
+    Thread("thread"): ThreadEventBody,
+}
@@ -0,0 +1,47 @@
+//! Requests sent to debug adapters, each with the type of its arguments and of its response's body.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::types::*;
+
+/// A request of the Debug Adapter Protocol.
+pub trait Request: 'static {
+    type Arguments: DeserializeOwned + Serialize + Send + 'static;
+    type Response: DeserializeOwned + Serialize + Send + 'static;
+    const COMMAND: &'static str;
+}
+
+macro_rules! requests {
+    ($($name:ident($command:literal): $arguments:ty => $response:ty),* $(,)?) => {
+        $(
+            #[derive(Debug)]
+            pub enum $name {}
+
+            impl Request for $name {
+                type Arguments = $arguments;
+                type Response = $response;
+                const COMMAND: &'static str = $command;
+            }
+        )*
+    };
+}
+
+requests! {
+    Initialize("initialize"): InitializeRequestArguments => Capabilities,
+    Launch("launch"): Value => (),
+    Attach("attach"): Value => (),
+    ConfigurationDone("configurationDone"): () => (),
+    SetBreakpoints("setBreakpoints"): SetBreakpointsArguments => SetBreakpointsResponse,
+    Threads("threads"): () => ThreadsResponse,
+    StackTrace("stackTrace"): StackTraceArguments => StackTraceResponse,
+    Scopes("scopes"): ScopesArguments => ScopesResponse,
+    Variables("variables"): VariablesArguments => VariablesResponse,
+    Evaluate("evaluate"): EvaluateArguments => EvaluateResponse,
+    Continue("continue"): ContinueArguments => ContinueResponse,
+    Next("next"): ThreadArguments => (),
+    StepIn("stepIn"): ThreadArguments => (),
+    StepOut("stepOut"): ThreadArguments => (),
+    Pause("pause"): ThreadArguments => (),
+    Disconnect("disconnect"): DisconnectArguments => (),
+}
@@ -14,6 +14,8 @@ pub use util::paths::DB_DIR;
 
 use release_channel::ReleaseChannel;
 pub use release_channel::RELEASE_CHANNEL;
+use sqlez::bindable::Bind;
+use sqlez::connection::Connection;
 use sqlez::domain::Migrator;
 use sqlez::thread_safe_connection::ThreadSafeConnection;
 use sqlez_macros::sql;
@@ -190,6 +192,29 @@ where
         .detach()
 }
 
+/// Replaces the rows of a table that belong to a key, such as the breakpoints of a workspace, in a
+/// single savepoint. Each inserted row is bound after the key.
+pub fn replace_rows<K, R>(
+    connection: &Connection,
+    delete_query: &str,
+    insert_query: &str,
+    key: K,
+    rows: impl IntoIterator<Item = R>,
+) -> anyhow::Result<()>
+where
+    K: Bind + Clone,
+    R: Bind,
+{
+    connection.with_savepoint("replace_rows", || {
+        connection.exec_bound(delete_query)?(key.clone()).context("clearing old rows")?;
+        let mut insert = connection.exec_bound(insert_query)?;
+        for row in rows {
+            insert((key.clone(), row)).context("inserting row")?;
+        }
+        Ok(())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::thread;
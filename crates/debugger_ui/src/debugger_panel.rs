@@ -0,0 +1,777 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use dap::{DebugAdapterId, DebugTemplate, DebugTemplates};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View, WeakView,
+};
+use language::{Bias, Point};
+use project::{BreakpointStoreEvent, Fs, Project};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::{paths::LOCAL_DEBUG_RELATIVE_PATH, post_inc, ResultExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace, WorkspaceId,
+};
+
+use crate::{
+    debugger_panel_settings::DebuggerPanelSettings,
+    persistence::DEBUGGER_DB,
+    session::{DebugSession, DebugSessionEvent, DebugSessionStatus},
+};
+
+const DEBUGGER_PANEL_KEY: &str = "DebuggerPanel";
+const BREAKPOINTS_SERIALIZATION_DEBOUNCE: Duration = Duration::from_millis(500);
+
+actions!(
+    debugger,
+    [
+        ToggleFocus,
+        Start,
+        Continue,
+        Pause,
+        StepOver,
+        StepInto,
+        StepOut,
+        Stop
+    ]
+);
+
+/// Row highlight of the line the debuggee is stopped at.
+enum DebugStoppedRow {}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedDebuggerPanel {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+/// A panel to start debug sessions from the project's debug templates, and to inspect and drive
+/// the running one.
+pub struct DebuggerPanel {
+    workspace: WeakView<Workspace>,
+    workspace_id: WorkspaceId,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    templates: Vec<DebugTemplate>,
+    session: Option<Model<DebugSession>>,
+    pending_session: Option<Task<()>>,
+    next_adapter_id: usize,
+    evaluate_editor: View<Editor>,
+    stopped_editor: Option<WeakView<Editor>>,
+    pending_serialization: Task<Option<()>>,
+    pending_breakpoints_serialization: Task<Option<()>>,
+    _session_subscriptions: Vec<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DebuggerPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_id = workspace.database_id();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let evaluate_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Evaluate an expression…", cx);
+                editor
+            });
+
+            let breakpoint_store = project.read(cx).breakpoint_store().clone();
+            let subscriptions = vec![cx.subscribe(
+                &breakpoint_store,
+                |this, _, _: &BreakpointStoreEvent, cx| this.serialize_breakpoints(cx),
+            )];
+
+            let mut this = Self {
+                workspace: workspace_handle,
+                workspace_id,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                height: None,
+                templates: Vec::new(),
+                session: None,
+                pending_session: None,
+                next_adapter_id: 0,
+                evaluate_editor,
+                stopped_editor: None,
+                pending_serialization: Task::ready(None),
+                pending_breakpoints_serialization: Task::ready(None),
+                _session_subscriptions: Vec::new(),
+                _subscriptions: subscriptions,
+            };
+            this.reload_templates(cx).detach();
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let workspace_id = workspace.update(&mut cx, |workspace, _| workspace.database_id())?;
+            let (serialized_panel, breakpoints) = cx
+                .background_executor()
+                .spawn(async move {
+                    (
+                        KEY_VALUE_STORE.read_kvp(DEBUGGER_PANEL_KEY),
+                        DEBUGGER_DB.get_breakpoints(workspace_id),
+                    )
+                })
+                .await;
+            let serialized_panel = if let Some(panel) = serialized_panel.log_err().flatten() {
+                Some(serde_json::from_str::<SerializedDebuggerPanel>(&panel)?)
+            } else {
+                None
+            };
+            let breakpoints = breakpoints.log_err().unwrap_or_default();
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                panel.update(cx, |panel, cx| {
+                    if let Some(serialized_panel) = serialized_panel {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        panel.height = serialized_panel.height.map(|h| h.round());
+                    }
+                    if !breakpoints.is_empty() {
+                        let mut breakpoints_by_path = BTreeMap::<PathBuf, Vec<u32>>::default();
+                        for (path, row) in breakpoints {
+                            breakpoints_by_path.entry(path).or_default().push(row);
+                        }
+                        panel
+                            .project
+                            .read(cx)
+                            .breakpoint_store()
+                            .clone()
+                            .update(cx, |store, cx| {
+                                store.set_breakpoints(breakpoints_by_path, cx)
+                            });
+                    }
+                    cx.notify();
+                });
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let height = self.height;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        DEBUGGER_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedDebuggerPanel { width, height })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn serialize_breakpoints(&mut self, cx: &mut ViewContext<Self>) {
+        let workspace_id = self.workspace_id;
+        self.pending_breakpoints_serialization = cx.spawn(|this, mut cx| {
+            async move {
+                cx.background_executor()
+                    .timer(BREAKPOINTS_SERIALIZATION_DEBOUNCE)
+                    .await;
+                let breakpoints = this.update(&mut cx, |this, cx| {
+                    let project = this.project.read(cx);
+                    project
+                        .breakpoint_store()
+                        .read(cx)
+                        .all_breakpoints(cx)
+                        .into_iter()
+                        .flat_map(|(path, rows)| {
+                            rows.into_iter().map(move |row| (path.clone(), row))
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                DEBUGGER_DB
+                    .save_breakpoints(workspace_id, breakpoints)
+                    .await
+            }
+            .log_err()
+        });
+    }
+
+    /// Reloads the debug templates from the `.zed/debug.json` file of each project root.
+    fn reload_templates(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
+        let paths = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| {
+                worktree
+                    .read(cx)
+                    .abs_path()
+                    .join(*LOCAL_DEBUG_RELATIVE_PATH)
+            })
+            .collect::<Vec<_>>();
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let mut templates = Vec::new();
+            for path in paths {
+                let Ok(contents) = fs.load(&path).await else {
+                    continue;
+                };
+                match serde_json_lenient::from_str::<DebugTemplates>(&contents) {
+                    Ok(file_templates) => templates.extend(file_templates.0),
+                    Err(error) => {
+                        log::error!("failed to parse debug templates at {path:?}: {error}")
+                    }
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.templates = templates;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    pub fn session(&self) -> Option<&Model<DebugSession>> {
+        self.session.as_ref()
+    }
+
+    fn can_start(&self, cx: &AppContext) -> bool {
+        self.pending_session.is_none()
+            && self.session.as_ref().map_or(true, |session| {
+                *session.read(cx).status() == DebugSessionStatus::Exited
+            })
+    }
+
+    /// Starts debugging with the first debug template of the project.
+    pub fn start_first(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.can_start(cx) {
+            return;
+        }
+        let reload = self.reload_templates(cx);
+        cx.spawn(|this, mut cx| async move {
+            reload.await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(template) = this.templates.first().cloned() {
+                    this.start(template, cx);
+                } else {
+                    this.show_error(
+                        anyhow::anyhow!(
+                            "no debug templates found, define some in {}",
+                            LOCAL_DEBUG_RELATIVE_PATH.display()
+                        ),
+                        cx,
+                    );
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub fn start(&mut self, template: DebugTemplate, cx: &mut ViewContext<Self>) {
+        if !self.can_start(cx) {
+            return;
+        }
+        let id = DebugAdapterId(post_inc(&mut self.next_adapter_id));
+        let start = DebugSession::start(id, template, self.project.clone(), cx);
+        self.pending_session = Some(cx.spawn(|this, mut cx| async move {
+            let session = start.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_session = None;
+                match session {
+                    Ok(session) => this.set_session(session, cx),
+                    Err(error) => this.show_error(error, cx),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn set_session(&mut self, session: Model<DebugSession>, cx: &mut ViewContext<Self>) {
+        self.clear_stopped_row(cx);
+        self._session_subscriptions = vec![
+            cx.observe(&session, |_, _, cx| cx.notify()),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                DebugSessionEvent::Stopped => this.open_selected_frame(cx),
+                DebugSessionEvent::Continued | DebugSessionEvent::Exited => {
+                    this.clear_stopped_row(cx)
+                }
+                DebugSessionEvent::Output => {}
+            }),
+        ];
+        self.session = Some(session);
+        cx.notify();
+    }
+
+    fn show_error(&mut self, error: anyhow::Error, cx: &mut ViewContext<Self>) {
+        log::error!("debugger error: {error:#}");
+        self.workspace
+            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+            .ok();
+    }
+
+    fn update_session(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+        update: impl FnOnce(&mut DebugSession, &mut gpui::ModelContext<DebugSession>),
+    ) {
+        if let Some(session) = self.session.clone() {
+            session.update(cx, update);
+        }
+    }
+
+    pub fn continue_thread(&mut self, cx: &mut ViewContext<Self>) {
+        if self.can_start(cx) {
+            self.start_first(cx);
+        } else {
+            self.update_session(cx, |session, cx| session.continue_thread(cx));
+        }
+    }
+
+    pub fn pause(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_session(cx, |session, cx| session.pause(cx));
+    }
+
+    pub fn step_over(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_session(cx, |session, cx| session.step_over(cx));
+    }
+
+    pub fn step_into(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_session(cx, |session, cx| session.step_into(cx));
+    }
+
+    pub fn step_out(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_session(cx, |session, cx| session.step_out(cx));
+    }
+
+    pub fn stop(&mut self, cx: &mut ViewContext<Self>) {
+        self.update_session(cx, |session, cx| session.stop(cx).detach());
+    }
+
+    fn select_frame(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.update_session(cx, |session, cx| session.select_frame(ix, cx));
+        self.open_selected_frame(cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let expression = self.evaluate_editor.update(cx, |editor, cx| {
+            let expression = editor.text(cx);
+            editor.clear(cx);
+            expression
+        });
+        if expression.trim().is_empty() {
+            return;
+        }
+        self.update_session(cx, |session, cx| session.evaluate(expression, cx));
+    }
+
+    /// Opens the source of the selected stack frame, highlighting the line it's stopped at.
+    fn open_selected_frame(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(session) = self.session.as_ref() else {
+            return;
+        };
+        let Some(frame) = session.read(cx).selected_frame() else {
+            return;
+        };
+        let Some(abs_path) = frame.source.as_ref().and_then(|source| source.path.clone()) else {
+            return;
+        };
+        let row = frame.line.saturating_sub(1) as u32;
+        let Some(open) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(abs_path, true, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            let editor = open
+                .await?
+                .downcast::<Editor>()
+                .context("debugged source isn't opened in an editor")?;
+            this.update(&mut cx, |this, cx| {
+                this.clear_stopped_row(cx);
+                editor.update(cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let point = snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                    let anchor = snapshot.anchor_before(point);
+                    editor.highlight_rows::<DebugStoppedRow>(
+                        anchor..anchor,
+                        Some(cx.theme().status().warning_background),
+                        cx,
+                    );
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([point..point])
+                    });
+                });
+                this.stopped_editor = Some(editor.downgrade());
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn clear_stopped_row(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(editor) = self
+            .stopped_editor
+            .take()
+            .and_then(|editor| editor.upgrade())
+        {
+            editor.update(cx, |editor, cx| {
+                editor.clear_row_highlights::<DebugStoppedRow>();
+                cx.notify();
+            });
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let status = self
+            .session
+            .as_ref()
+            .map(|session| session.read(cx).status().clone());
+        let title = match (&self.session, &status) {
+            _ if self.pending_session.is_some() => "Starting…".to_string(),
+            (Some(session), Some(status)) => {
+                let status = match status {
+                    DebugSessionStatus::Running => "Running".to_string(),
+                    DebugSessionStatus::Stopped { reason, .. } => format!("Paused on {reason}"),
+                    DebugSessionStatus::Exited => "Exited".to_string(),
+                };
+                format!("{} — {status}", session.read(cx).label())
+            }
+            _ => "Not debugging".to_string(),
+        };
+        let is_running = status == Some(DebugSessionStatus::Running);
+        let is_stopped = matches!(status, Some(DebugSessionStatus::Stopped { .. }));
+        let is_active = is_running || is_stopped;
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(render_button(
+                        "debugger-continue",
+                        IconName::DebugContinue,
+                        "Continue",
+                        Continue,
+                        is_stopped || !is_active,
+                    ))
+                    .child(render_button(
+                        "debugger-pause",
+                        IconName::DebugPause,
+                        "Pause",
+                        Pause,
+                        is_running,
+                    ))
+                    .child(render_button(
+                        "debugger-step-over",
+                        IconName::DebugStepOver,
+                        "Step Over",
+                        StepOver,
+                        is_stopped,
+                    ))
+                    .child(render_button(
+                        "debugger-step-into",
+                        IconName::DebugStepInto,
+                        "Step Into",
+                        StepInto,
+                        is_stopped,
+                    ))
+                    .child(render_button(
+                        "debugger-step-out",
+                        IconName::DebugStepOut,
+                        "Step Out",
+                        StepOut,
+                        is_stopped,
+                    ))
+                    .child(render_button(
+                        "debugger-stop",
+                        IconName::DebugStop,
+                        "Stop",
+                        Stop,
+                        is_active,
+                    )),
+            )
+    }
+
+    fn render_templates(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.templates.is_empty() {
+            div().child(
+                Label::new(format!(
+                    "Define debug templates in {} to start debugging.",
+                    LOCAL_DEBUG_RELATIVE_PATH.display()
+                ))
+                .color(Color::Muted)
+                .size(LabelSize::Small),
+            )
+        } else {
+            div().children(self.templates.iter().enumerate().map(|(ix, template)| {
+                let template = template.clone();
+                ListItem::new(("debug-template", ix))
+                    .start_slot(Icon::new(IconName::Play).color(Color::Muted))
+                    .child(Label::new(template.label.clone()))
+                    .on_click(cx.listener(move |this, _, cx| this.start(template.clone(), cx)))
+            }))
+        };
+        v_flex().p_2().gap_1().child(content)
+    }
+
+    fn render_session(
+        &self,
+        session: &Model<DebugSession>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let session_state = session.read(cx);
+        let selected_frame = session_state.selected_frame_ix();
+        let frames = session_state
+            .stack_frames()
+            .iter()
+            .enumerate()
+            .map(|(ix, frame)| {
+                let location = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.name.clone())
+                    .map(|name| format!("{name}:{}", frame.line))
+                    .unwrap_or_default();
+                ListItem::new(("stack-frame", ix))
+                    .selected(selected_frame == Some(ix))
+                    .child(Label::new(frame.name.clone()).size(LabelSize::Small))
+                    .end_slot(
+                        Label::new(location)
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| this.select_frame(ix, cx)))
+            })
+            .collect::<Vec<_>>();
+
+        let variables = session_state
+            .variables()
+            .iter()
+            .enumerate()
+            .map(|(ix, variable)| {
+                let session = session.clone();
+                let mut item = ListItem::new(("variable", ix))
+                    .indent_level(variable.depth)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(variable.name.clone()).size(LabelSize::Small))
+                            .child(
+                                Label::new(variable.value.clone())
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            ),
+                    );
+                if variable.variables_reference != 0 {
+                    item = item.toggle(variable.expanded).on_toggle(move |_, cx| {
+                        session.update(cx, |session, cx| session.toggle_variable(ix, cx))
+                    });
+                }
+                item
+            })
+            .collect::<Vec<_>>();
+
+        let output = session_state
+            .output()
+            .iter()
+            .map(|entry| {
+                let color = match entry.category.as_deref() {
+                    Some("stderr") => Color::Error,
+                    Some("input") => Color::Accent,
+                    Some("console") => Color::Muted,
+                    _ => Color::Default,
+                };
+                Label::new(entry.text.clone())
+                    .color(color)
+                    .size(LabelSize::Small)
+            })
+            .collect::<Vec<_>>();
+
+        let border_color = cx.theme().colors().border;
+        h_flex()
+            .flex_1()
+            .size_full()
+            .items_start()
+            .child(
+                v_flex()
+                    .id("debugger-stack-frames")
+                    .w_1_4()
+                    .h_full()
+                    .overflow_y_scroll()
+                    .border_r_1()
+                    .border_color(border_color)
+                    .child(section_header("Call Stack"))
+                    .children(frames),
+            )
+            .child(
+                v_flex()
+                    .id("debugger-variables")
+                    .w_1_4()
+                    .h_full()
+                    .overflow_y_scroll()
+                    .border_r_1()
+                    .border_color(border_color)
+                    .child(section_header("Variables"))
+                    .children(variables),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .h_full()
+                    .child(section_header("Console"))
+                    .child(
+                        v_flex()
+                            .id("debugger-console")
+                            .flex_1()
+                            .px_2()
+                            .overflow_y_scroll()
+                            .children(output),
+                    )
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .border_t_1()
+                            .border_color(border_color)
+                            .child(self.evaluate_editor.clone()),
+                    ),
+            )
+    }
+}
+
+fn section_header(title: &'static str) -> impl IntoElement {
+    div().px_2().py_1().child(
+        Label::new(title)
+            .color(Color::Muted)
+            .size(LabelSize::XSmall),
+    )
+}
+
+fn render_button(
+    id: &'static str,
+    icon: IconName,
+    tooltip: &'static str,
+    action: impl gpui::Action,
+    enabled: bool,
+) -> IconButton {
+    let tooltip_action = action.boxed_clone();
+    IconButton::new(id, icon)
+        .icon_size(IconSize::Small)
+        .disabled(!enabled)
+        .tooltip(move |cx| Tooltip::for_action(tooltip, &*tooltip_action, cx))
+        .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+}
+
+impl Render for DebuggerPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match self.session.clone() {
+            Some(session) => self.render_session(&session, cx).into_any_element(),
+            None => self.render_templates(cx).into_any_element(),
+        };
+        v_flex()
+            .key_context("DebuggerPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::confirm))
+            .child(self.render_toolbar(cx))
+            .child(content)
+    }
+}
+
+impl FocusableView for DebuggerPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DebuggerPanel {}
+
+impl Panel for DebuggerPanel {
+    fn persistent_name() -> &'static str {
+        "DebuggerPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        DebuggerPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<DebuggerPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        let settings = DebuggerPanelSettings::get_global(cx);
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        if active && self.session.is_none() {
+            self.reload_templates(cx).detach();
+        }
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        DebuggerPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Debug)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Debugger Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
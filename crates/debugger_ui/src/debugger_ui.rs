@@ -0,0 +1,56 @@
+mod debugger_panel;
+mod debugger_panel_settings;
+mod persistence;
+mod session;
+
+use gpui::{AppContext, ViewContext};
+use settings::Settings;
+use workspace::Workspace;
+
+pub use debugger_panel::{
+    Continue, DebuggerPanel, Pause, Start, StepInto, StepOut, StepOver, Stop, ToggleFocus,
+};
+pub use debugger_panel_settings::DebuggerPanelSettings;
+pub use session::{DebugSession, DebugSessionEvent, DebugSessionStatus};
+
+pub fn init(cx: &mut AppContext) {
+    DebuggerPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<DebuggerPanel>(cx);
+        });
+        workspace.register_action(|workspace, _: &Start, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.start_first(cx))
+        });
+        workspace.register_action(|workspace, _: &Continue, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.continue_thread(cx))
+        });
+        workspace.register_action(|workspace, _: &Pause, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.pause(cx))
+        });
+        workspace.register_action(|workspace, _: &StepOver, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.step_over(cx))
+        });
+        workspace.register_action(|workspace, _: &StepInto, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.step_into(cx))
+        });
+        workspace.register_action(|workspace, _: &StepOut, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.step_out(cx))
+        });
+        workspace.register_action(|workspace, _: &Stop, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.stop(cx))
+        });
+    })
+    .detach();
+}
+
+fn update_panel(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+    update: impl FnOnce(&mut DebuggerPanel, &mut ViewContext<DebuggerPanel>),
+) {
+    if let Some(panel) = workspace.panel::<DebuggerPanel>(cx) {
+        panel.update(cx, update);
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use db::{define_connection, query, replace_rows, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    pub static ref DEBUGGER_DB: DebuggerDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE breakpoints (
+                workspace_id INTEGER,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, path, row),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl DebuggerDb {
+    query! {
+        pub fn get_breakpoints(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32)>> {
+            SELECT path, row
+            FROM breakpoints
+            WHERE workspace_id = ?
+            ORDER BY path, row
+        }
+    }
+
+    /// Replaces the breakpoints saved for the workspace.
+    pub async fn save_breakpoints(
+        &self,
+        workspace_id: WorkspaceId,
+        breakpoints: Vec<(PathBuf, u32)>,
+    ) -> anyhow::Result<()> {
+        self.write(move |conn| {
+            replace_rows(
+                conn,
+                sql!(DELETE FROM breakpoints WHERE workspace_id = ?),
+                sql!(INSERT INTO breakpoints(workspace_id, path, row) VALUES (?, ?, ?)),
+                workspace_id,
+                breakpoints,
+            )
+        })
+        .await
+    }
+}
@@ -0,0 +1,811 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use dap::{
+    events, requests,
+    types::{
+        ContinueArguments, DisconnectArguments, EvaluateArguments, InitializeRequestArguments,
+        OutputEventBody, ScopesArguments, SetBreakpointsArguments, Source, SourceBreakpoint,
+        StackFrame, StackTraceArguments, StoppedEventBody, ThreadArguments, VariablesArguments,
+    },
+    DebugAdapterClient, DebugAdapterId, DebugRequestKind, DebugTemplate,
+};
+use futures::{channel::oneshot, StreamExt};
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext, Task};
+use project::{BreakpointStoreEvent, Project};
+use util::ResultExt;
+
+/// State of the debuggee of a debug session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DebugSessionStatus {
+    Running,
+    Stopped { thread_id: u64, reason: String },
+    Exited,
+}
+
+/// A variable of the selected stack frame, or one of its scopes, flattened into a tree of entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariableEntry {
+    pub depth: usize,
+    pub name: String,
+    pub value: String,
+    pub variables_reference: u64,
+    pub expanded: bool,
+}
+
+/// A line of the debug console.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputEntry {
+    pub category: Option<String>,
+    pub text: String,
+}
+
+pub enum DebugSessionEvent {
+    Stopped,
+    Continued,
+    Exited,
+    Output,
+}
+
+enum AdapterEvent {
+    Stopped(StoppedEventBody),
+    Continued,
+    Exited(i64),
+    Terminated,
+    Output(OutputEventBody),
+}
+
+/// A debug adapter running a debuggee, and the state reported by it.
+pub struct DebugSession {
+    label: String,
+    client: Arc<DebugAdapterClient>,
+    project: Model<Project>,
+    status: DebugSessionStatus,
+    stack_frames: Vec<StackFrame>,
+    selected_frame: Option<usize>,
+    variables: Vec<VariableEntry>,
+    output: Vec<OutputEntry>,
+    _breakpoints_subscription: gpui::Subscription,
+    _event_task: Task<()>,
+}
+
+impl EventEmitter<DebugSessionEvent> for DebugSession {}
+
+impl DebugSession {
+    /// Spawns the debug adapter of the given template, and starts debugging with it.
+    pub fn start(
+        id: DebugAdapterId,
+        template: DebugTemplate,
+        project: Model<Project>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Model<Self>>> {
+        let project_root = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+        let (binary, cwd) = template.adapter_binary(project_root);
+        let client = DebugAdapterClient::new(id, binary, &cwd, cx.to_async());
+        match client {
+            Ok(client) => Self::connect(client, template, project, cx),
+            Err(error) => Task::ready(Err(error)),
+        }
+    }
+
+    /// Starts debugging with a debug adapter, initializing it and sending it the project's
+    /// breakpoints before launching or attaching to the debuggee.
+    pub fn connect(
+        client: DebugAdapterClient,
+        template: DebugTemplate,
+        project: Model<Project>,
+        cx: &mut AppContext,
+    ) -> Task<Result<Model<Self>>> {
+        let (events_tx, mut events_rx) = futures::channel::mpsc::unbounded();
+        let (initialized_tx, initialized_rx) = oneshot::channel();
+        let mut initialized_tx = Some(initialized_tx);
+        client.on_event::<events::Initialized, _>(move |_, _| {
+            if let Some(initialized_tx) = initialized_tx.take() {
+                initialized_tx.send(()).ok();
+            }
+        });
+        client.on_event::<events::Stopped, _>({
+            let events_tx = events_tx.clone();
+            move |body, _| {
+                events_tx.unbounded_send(AdapterEvent::Stopped(body)).ok();
+            }
+        });
+        client.on_event::<events::Continued, _>({
+            let events_tx = events_tx.clone();
+            move |_, _| {
+                events_tx.unbounded_send(AdapterEvent::Continued).ok();
+            }
+        });
+        client.on_event::<events::Exited, _>({
+            let events_tx = events_tx.clone();
+            move |body, _| {
+                events_tx
+                    .unbounded_send(AdapterEvent::Exited(body.exit_code))
+                    .ok();
+            }
+        });
+        client.on_event::<events::Terminated, _>({
+            let events_tx = events_tx.clone();
+            move |_, _| {
+                events_tx.unbounded_send(AdapterEvent::Terminated).ok();
+            }
+        });
+        client.on_event::<events::Output, _>(move |body, _| {
+            events_tx.unbounded_send(AdapterEvent::Output(body)).ok();
+        });
+
+        cx.spawn(|mut cx| async move {
+            let client = client
+                .initialize(InitializeRequestArguments {
+                    client_id: Some("zed".into()),
+                    client_name: Some("Zed".into()),
+                    adapter_id: template.label.clone(),
+                    lines_start_at1: Some(true),
+                    columns_start_at1: Some(true),
+                    path_format: Some("path".into()),
+                    supports_variable_type: Some(true),
+                    ..Default::default()
+                })
+                .await
+                .context("initializing debug adapter")?;
+
+            // The adapter only reports that it's ready to be configured once asked to start
+            // debugging, and only starts once its configuration is done.
+            let start = match template.request {
+                DebugRequestKind::Launch => {
+                    client.request::<requests::Launch>(template.configuration.clone())
+                }
+                DebugRequestKind::Attach => {
+                    client.request::<requests::Attach>(template.configuration.clone())
+                }
+            };
+            initialized_rx
+                .await
+                .map_err(|_| anyhow!("debug adapter shut down before being initialized"))?;
+
+            let breakpoints = project.update(&mut cx, |project, cx| {
+                project.breakpoint_store().read(cx).all_breakpoints(cx)
+            })?;
+            for (abs_path, rows) in breakpoints {
+                set_breakpoints(&client, &abs_path, &rows).await.log_err();
+            }
+            if client
+                .capabilities()
+                .supports_configuration_done_request
+                .unwrap_or(false)
+            {
+                client.request::<requests::ConfigurationDone>(()).await?;
+            }
+            start.await.context("starting debuggee")?;
+
+            cx.new_model(|cx| {
+                let breakpoint_store = project.read(cx).breakpoint_store().clone();
+                let _breakpoints_subscription = cx.subscribe(
+                    &breakpoint_store,
+                    |this: &mut Self, store, event: &BreakpointStoreEvent, cx| {
+                        let BreakpointStoreEvent::BreakpointsChanged { abs_path } = event;
+                        let rows = store.read(cx).breakpoints_for_path(abs_path, cx);
+                        let client = this.client.clone();
+                        let abs_path = abs_path.clone();
+                        cx.background_executor()
+                            .spawn(async move {
+                                set_breakpoints(&client, &abs_path, &rows).await.log_err();
+                            })
+                            .detach();
+                    },
+                );
+                let _event_task = cx.spawn(|this, mut cx| async move {
+                    while let Some(event) = events_rx.next().await {
+                        if this
+                            .update(&mut cx, |this, cx| this.handle_adapter_event(event, cx))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+
+                Self {
+                    label: template.label,
+                    client,
+                    project,
+                    status: DebugSessionStatus::Running,
+                    stack_frames: Vec::new(),
+                    selected_frame: None,
+                    variables: Vec::new(),
+                    output: Vec::new(),
+                    _breakpoints_subscription,
+                    _event_task,
+                }
+            })
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn status(&self) -> &DebugSessionStatus {
+        &self.status
+    }
+
+    pub fn stack_frames(&self) -> &[StackFrame] {
+        &self.stack_frames
+    }
+
+    pub fn selected_frame(&self) -> Option<&StackFrame> {
+        self.stack_frames.get(self.selected_frame?)
+    }
+
+    pub fn selected_frame_ix(&self) -> Option<usize> {
+        self.selected_frame
+    }
+
+    pub fn variables(&self) -> &[VariableEntry] {
+        &self.variables
+    }
+
+    pub fn output(&self) -> &[OutputEntry] {
+        &self.output
+    }
+
+    pub fn project(&self) -> &Model<Project> {
+        &self.project
+    }
+
+    fn handle_adapter_event(&mut self, event: AdapterEvent, cx: &mut ModelContext<Self>) {
+        match event {
+            AdapterEvent::Stopped(body) => {
+                let Some(thread_id) = body.thread_id else {
+                    return;
+                };
+                self.status = DebugSessionStatus::Stopped {
+                    thread_id,
+                    reason: body.reason,
+                };
+                self.load_stack_trace(thread_id, cx);
+            }
+            AdapterEvent::Continued => self.set_running(cx),
+            AdapterEvent::Exited(exit_code) => {
+                self.push_output(None, format!("Process exited with code {exit_code}"), cx);
+            }
+            AdapterEvent::Terminated => {
+                self.status = DebugSessionStatus::Exited;
+                self.stack_frames.clear();
+                self.selected_frame = None;
+                self.variables.clear();
+                cx.emit(DebugSessionEvent::Exited);
+                cx.notify();
+            }
+            AdapterEvent::Output(body) => {
+                if body.category.as_deref() != Some("telemetry") {
+                    self.push_output(body.category, body.output, cx);
+                }
+            }
+        }
+    }
+
+    fn load_stack_trace(&mut self, thread_id: u64, cx: &mut ModelContext<Self>) {
+        let stack_trace = self
+            .client
+            .request::<requests::StackTrace>(StackTraceArguments {
+                thread_id,
+                start_frame: None,
+                levels: None,
+            });
+        cx.spawn(|this, mut cx| async move {
+            let stack_trace = stack_trace.await?;
+            this.update(&mut cx, |this, cx| {
+                this.stack_frames = stack_trace.stack_frames;
+                this.select_frame(0, cx);
+                cx.emit(DebugSessionEvent::Stopped);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Selects a frame of the stopped thread's stack, loading its variables.
+    pub fn select_frame(&mut self, ix: usize, cx: &mut ModelContext<Self>) {
+        self.variables.clear();
+        let Some(frame) = self.stack_frames.get(ix) else {
+            self.selected_frame = None;
+            cx.notify();
+            return;
+        };
+        self.selected_frame = Some(ix);
+        cx.notify();
+
+        let client = self.client.clone();
+        let frame_id = frame.id;
+        cx.spawn(|this, mut cx| async move {
+            let scopes = client
+                .request::<requests::Scopes>(ScopesArguments { frame_id })
+                .await?
+                .scopes;
+            let mut variables = Vec::new();
+            for scope in scopes {
+                let expanded = !scope.expensive;
+                variables.push(VariableEntry {
+                    depth: 0,
+                    name: scope.name,
+                    value: String::new(),
+                    variables_reference: scope.variables_reference,
+                    expanded,
+                });
+                if expanded {
+                    variables.extend(
+                        load_variables(&client, scope.variables_reference, 1)
+                            .await
+                            .log_err()
+                            .unwrap_or_default(),
+                    );
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                if this.selected_frame == Some(ix) {
+                    this.variables = variables;
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Expands a variable to show its children, or collapses it if it's already expanded.
+    pub fn toggle_variable(&mut self, ix: usize, cx: &mut ModelContext<Self>) {
+        let Some(entry) = self.variables.get(ix) else {
+            return;
+        };
+        if entry.variables_reference == 0 {
+            return;
+        }
+
+        let depth = entry.depth;
+        let children_end = self.variables[ix + 1..]
+            .iter()
+            .position(|child| child.depth <= depth)
+            .map_or(self.variables.len(), |end| ix + 1 + end);
+        let entry = &mut self.variables[ix];
+        if entry.expanded {
+            entry.expanded = false;
+            self.variables.drain(ix + 1..children_end);
+            cx.notify();
+            return;
+        }
+
+        entry.expanded = true;
+        let variables_reference = entry.variables_reference;
+        let client = self.client.clone();
+        cx.spawn(|this, mut cx| async move {
+            let children = load_variables(&client, variables_reference, depth + 1).await?;
+            this.update(&mut cx, |this, cx| {
+                let is_same_entry = this.variables.get(ix).map_or(false, |entry| {
+                    entry.expanded && entry.variables_reference == variables_reference
+                });
+                if is_same_entry {
+                    this.variables.splice(ix + 1..ix + 1, children);
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    /// Evaluates an expression in the context of the selected frame, printing it and its result
+    /// to the console.
+    pub fn evaluate(&mut self, expression: String, cx: &mut ModelContext<Self>) {
+        self.push_output(Some("input".into()), format!("> {expression}"), cx);
+        let evaluate = self
+            .client
+            .request::<requests::Evaluate>(EvaluateArguments {
+                expression,
+                frame_id: self.selected_frame().map(|frame| frame.id),
+                context: Some("repl".into()),
+            });
+        cx.spawn(|this, mut cx| async move {
+            let (category, text) = match evaluate.await {
+                Ok(response) => (None, response.result),
+                Err(error) => (Some("stderr".to_string()), error.to_string()),
+            };
+            this.update(&mut cx, |this, cx| this.push_output(category, text, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn continue_thread(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(thread_id) = self.stopped_thread_id() else {
+            return;
+        };
+        let request = self
+            .client
+            .request::<requests::Continue>(ContinueArguments {
+                thread_id,
+                single_thread: None,
+            });
+        self.set_running(cx);
+        cx.background_executor()
+            .spawn(async move { request.await.log_err() })
+            .detach();
+    }
+
+    pub fn step_over(&mut self, cx: &mut ModelContext<Self>) {
+        self.step::<requests::Next>(cx);
+    }
+
+    pub fn step_into(&mut self, cx: &mut ModelContext<Self>) {
+        self.step::<requests::StepIn>(cx);
+    }
+
+    pub fn step_out(&mut self, cx: &mut ModelContext<Self>) {
+        self.step::<requests::StepOut>(cx);
+    }
+
+    fn step<R>(&mut self, cx: &mut ModelContext<Self>)
+    where
+        R: requests::Request<Arguments = ThreadArguments, Response = ()>,
+    {
+        let Some(thread_id) = self.stopped_thread_id() else {
+            return;
+        };
+        let request = self.client.request::<R>(ThreadArguments { thread_id });
+        self.set_running(cx);
+        cx.background_executor()
+            .spawn(async move { request.await.log_err() })
+            .detach();
+    }
+
+    pub fn pause(&mut self, cx: &mut ModelContext<Self>) {
+        if self.status != DebugSessionStatus::Running {
+            return;
+        }
+        let client = self.client.clone();
+        cx.background_executor()
+            .spawn(async move {
+                let threads = client.request::<requests::Threads>(()).await?.threads;
+                let thread = threads
+                    .first()
+                    .ok_or_else(|| anyhow!("debuggee has no thread to pause"))?;
+                client
+                    .request::<requests::Pause>(ThreadArguments {
+                        thread_id: thread.id,
+                    })
+                    .await
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Disconnects from the debug adapter, terminating the debuggee.
+    pub fn stop(&mut self, cx: &mut ModelContext<Self>) -> Task<()> {
+        let client = self.client.clone();
+        let disconnect = client.request::<requests::Disconnect>(DisconnectArguments {
+            restart: None,
+            terminate_debuggee: Some(true),
+        });
+        self.handle_adapter_event(AdapterEvent::Terminated, cx);
+        cx.background_executor().spawn(async move {
+            disconnect.await.log_err();
+            client.shutdown();
+        })
+    }
+
+    fn stopped_thread_id(&self) -> Option<u64> {
+        match &self.status {
+            DebugSessionStatus::Stopped { thread_id, .. } => Some(*thread_id),
+            _ => None,
+        }
+    }
+
+    fn set_running(&mut self, cx: &mut ModelContext<Self>) {
+        if self.status == DebugSessionStatus::Exited {
+            return;
+        }
+        self.status = DebugSessionStatus::Running;
+        self.stack_frames.clear();
+        self.selected_frame = None;
+        self.variables.clear();
+        cx.emit(DebugSessionEvent::Continued);
+        cx.notify();
+    }
+
+    fn push_output(&mut self, category: Option<String>, text: String, cx: &mut ModelContext<Self>) {
+        self.output.push(OutputEntry {
+            category,
+            text: text.trim_end().to_string(),
+        });
+        cx.emit(DebugSessionEvent::Output);
+        cx.notify();
+    }
+}
+
+async fn set_breakpoints(client: &DebugAdapterClient, abs_path: &Path, rows: &[u32]) -> Result<()> {
+    client
+        .request::<requests::SetBreakpoints>(SetBreakpointsArguments {
+            source: Source {
+                name: abs_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                path: Some(PathBuf::from(abs_path)),
+                source_reference: None,
+            },
+            breakpoints: rows
+                .iter()
+                .map(|row| SourceBreakpoint {
+                    line: *row as u64 + 1,
+                    column: None,
+                    condition: None,
+                })
+                .collect(),
+            source_modified: None,
+        })
+        .await?;
+    Ok(())
+}
+
+async fn load_variables(
+    client: &DebugAdapterClient,
+    variables_reference: u64,
+    depth: usize,
+) -> Result<Vec<VariableEntry>> {
+    let variables = client
+        .request::<requests::Variables>(VariablesArguments {
+            variables_reference,
+        })
+        .await?
+        .variables;
+    Ok(variables
+        .into_iter()
+        .map(|variable| VariableEntry {
+            depth,
+            name: variable.name,
+            value: variable.value,
+            variables_reference: variable.variables_reference,
+            expanded: false,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::{
+        types::{
+            Breakpoint, Capabilities, ContinueResponse, EvaluateResponse, Scope, ScopesResponse,
+            SetBreakpointsResponse, StackTraceResponse, Variable, VariablesResponse,
+        },
+        FakeDebugAdapter,
+    };
+    use gpui::TestAppContext;
+    use language::Point;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::sync::Mutex;
+
+    #[gpui::test]
+    async fn test_debug_session(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({ "main.rs": "fn main() {\n    let a = 1;\n}\n" }),
+        )
+        .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/dir/main.rs", cx)
+            })
+            .await
+            .unwrap();
+        let breakpoint_store =
+            project.read_with(cx, |project, _| project.breakpoint_store().clone());
+        let toggle_breakpoint = |cx: &mut TestAppContext| {
+            breakpoint_store.update(cx, |store, cx| {
+                let position = buffer.read(cx).anchor_before(Point::new(1, 0));
+                store.toggle_breakpoint(&buffer, position, cx);
+            });
+        };
+        toggle_breakpoint(cx);
+
+        let (client, fake) = FakeDebugAdapter::new(
+            DebugAdapterId(0),
+            Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+        let set_breakpoints = Arc::new(Mutex::new(Vec::new()));
+        fake.handle_request::<requests::SetBreakpoints, _, _>({
+            let set_breakpoints = set_breakpoints.clone();
+            move |arguments, _| {
+                set_breakpoints.lock().unwrap().push(arguments.clone());
+                async move {
+                    Ok(SetBreakpointsResponse {
+                        breakpoints: arguments
+                            .breakpoints
+                            .iter()
+                            .map(|breakpoint| Breakpoint {
+                                verified: true,
+                                line: Some(breakpoint.line),
+                                ..Default::default()
+                            })
+                            .collect(),
+                    })
+                }
+            }
+        });
+
+        // Breakpoints are sent to the adapter before the debuggee is launched.
+        let session = cx
+            .update(|cx| {
+                DebugSession::connect(
+                    client,
+                    DebugTemplate {
+                        label: "fake".into(),
+                        ..Default::default()
+                    },
+                    project.clone(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            set_breakpoints
+                .lock()
+                .unwrap()
+                .drain(..)
+                .collect::<Vec<_>>(),
+            [SetBreakpointsArguments {
+                source: Source {
+                    name: Some("main.rs".into()),
+                    path: Some("/dir/main.rs".into()),
+                    source_reference: None,
+                },
+                breakpoints: vec![SourceBreakpoint {
+                    line: 2,
+                    column: None,
+                    condition: None,
+                }],
+                source_modified: None,
+            }]
+        );
+
+        // Once stopped, the stack trace and the variables of the top frame are loaded.
+        fake.handle_request::<requests::StackTrace, _, _>(|_, _| async move {
+            Ok(StackTraceResponse {
+                stack_frames: vec![StackFrame {
+                    id: 1,
+                    name: "main".into(),
+                    source: Some(Source {
+                        path: Some("/dir/main.rs".into()),
+                        ..Default::default()
+                    }),
+                    line: 2,
+                    column: 5,
+                }],
+                total_frames: None,
+            })
+        });
+        fake.handle_request::<requests::Scopes, _, _>(|_, _| async move {
+            Ok(ScopesResponse {
+                scopes: vec![Scope {
+                    name: "Locals".into(),
+                    variables_reference: 10,
+                    expensive: false,
+                }],
+            })
+        });
+        fake.handle_request::<requests::Variables, _, _>(|_, _| async move {
+            Ok(VariablesResponse {
+                variables: vec![Variable {
+                    name: "a".into(),
+                    value: "1".into(),
+                    type_: Some("i32".into()),
+                    variables_reference: 0,
+                }],
+            })
+        });
+        fake.send_event::<events::Stopped>(StoppedEventBody {
+            reason: "breakpoint".into(),
+            description: None,
+            thread_id: Some(1),
+            all_threads_stopped: Some(true),
+            text: None,
+        });
+        cx.run_until_parked();
+        session.read_with(cx, |session, _| {
+            assert_eq!(
+                session.status(),
+                &DebugSessionStatus::Stopped {
+                    thread_id: 1,
+                    reason: "breakpoint".into()
+                }
+            );
+            assert_eq!(session.selected_frame().unwrap().name, "main");
+            assert_eq!(
+                session
+                    .variables()
+                    .iter()
+                    .map(|variable| (
+                        variable.depth,
+                        variable.name.as_str(),
+                        variable.value.as_str()
+                    ))
+                    .collect::<Vec<_>>(),
+                [(0, "Locals", ""), (1, "a", "1")]
+            );
+        });
+
+        // Breakpoint changes are sent to the adapter while debugging.
+        toggle_breakpoint(cx);
+        cx.run_until_parked();
+        let sent = set_breakpoints
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].breakpoints.is_empty());
+
+        // Expressions are evaluated in the selected frame, with their results printed.
+        fake.handle_request::<requests::Evaluate, _, _>(|arguments, _| async move {
+            assert_eq!(arguments.frame_id, Some(1));
+            Ok(EvaluateResponse {
+                result: format!("{} = 2", arguments.expression),
+                type_: None,
+                variables_reference: 0,
+            })
+        });
+        session.update(cx, |session, cx| session.evaluate("a + 1".into(), cx));
+        cx.run_until_parked();
+        session.read_with(cx, |session, _| {
+            assert_eq!(
+                session
+                    .output()
+                    .iter()
+                    .map(|entry| entry.text.as_str())
+                    .collect::<Vec<_>>(),
+                ["> a + 1", "a + 1 = 2"]
+            );
+        });
+
+        fake.handle_request::<requests::Continue, _, _>(|arguments, _| async move {
+            assert_eq!(arguments.thread_id, 1);
+            Ok(ContinueResponse::default())
+        });
+        session.update(cx, |session, cx| session.continue_thread(cx));
+        cx.run_until_parked();
+        session.read_with(cx, |session, _| {
+            assert_eq!(session.status(), &DebugSessionStatus::Running);
+            assert!(session.stack_frames().is_empty());
+            assert!(session.variables().is_empty());
+        });
+
+        fake.send_event::<events::Terminated>(None);
+        cx.run_until_parked();
+        session.read_with(cx, |session, _| {
+            assert_eq!(session.status(), &DebugSessionStatus::Exited);
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            release_channel::init("0.0.0", cx);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
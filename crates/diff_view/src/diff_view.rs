@@ -0,0 +1,793 @@
+mod line_diff;
+
+use std::{
+    any::TypeId,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorEvent,
+};
+use gpui::{
+    actions, impl_actions, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle,
+    FocusableView, HighlightStyle, Hsla, Model, StyledText, Subscription, Task, View,
+};
+use language::{language_settings::SoftWrap, Buffer, Point};
+use project::{GitRevision, Item as _, Project, ProjectPath};
+use serde::Deserialize;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent, ItemHandle, TabContentParams},
+    Workspace,
+};
+
+pub use line_diff::LineHunk;
+
+actions!(
+    diff_view,
+    [
+        DiffWithHead,
+        DiffWithIndex,
+        CompareWithClipboard,
+        ToggleDiffMode
+    ]
+);
+
+/// Opens a diff view of the file of the active editor against its version in a commit.
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct DiffWithCommit {
+    /// The commit, given by anything `git rev-parse` resolves to one, such as its SHA.
+    pub commit: String,
+}
+
+impl_actions!(diff_view, [DiffWithCommit]);
+
+const DIFF_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &DiffWithHead, cx| {
+            diff_active_file_with_revision(workspace, GitRevision::Head, cx)
+        });
+        workspace.register_action(|workspace, _: &DiffWithIndex, cx| {
+            diff_active_file_with_revision(workspace, GitRevision::Index, cx)
+        });
+        workspace.register_action(|workspace, action: &DiffWithCommit, cx| {
+            diff_active_file_with_revision(
+                workspace,
+                GitRevision::Commit(action.commit.clone()),
+                cx,
+            )
+        });
+        workspace.register_action(|workspace, _: &CompareWithClipboard, cx| {
+            let Some(clipboard) = cx.read_from_clipboard() else {
+                return;
+            };
+            let Some((buffer, label)) = active_buffer(workspace, cx) else {
+                return;
+            };
+            open_diff_with_text(
+                workspace,
+                clipboard.text().clone(),
+                "Clipboard".into(),
+                buffer,
+                label,
+                cx,
+            );
+        });
+    })
+    .detach();
+}
+
+/// Returns the buffer of the active editor, along with the path of its file, or its title when it has none.
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<(Model<Buffer>, SharedString)> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let label = match buffer.read(cx).file() {
+        Some(file) => file.path().to_string_lossy().to_string(),
+        None => editor.read(cx).title(cx).to_string(),
+    };
+    Some((buffer, label.into()))
+}
+
+fn diff_active_file_with_revision(
+    workspace: &mut Workspace,
+    revision: GitRevision,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some((buffer, label)) = active_buffer(workspace, cx) else {
+        return;
+    };
+    let Some(project_path) = buffer.read(cx).project_path(cx) else {
+        return;
+    };
+    let old_label = match &revision {
+        GitRevision::Head => format!("{label} (HEAD)"),
+        GitRevision::Index => format!("{label} (Index)"),
+        GitRevision::Commit(commit) => {
+            format!("{label} ({})", &commit[..commit.len().min(7)])
+        }
+    };
+    let old_text = workspace
+        .project()
+        .read(cx)
+        .load_git_text(&project_path, revision, cx);
+    cx.spawn(|workspace, mut cx| async move {
+        let old_text = old_text.await?.unwrap_or_default();
+        workspace.update(&mut cx, |workspace, cx| {
+            open_diff_with_text(workspace, old_text, old_label.into(), buffer, label, cx)
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Opens a diff view of the buffer against the given text, shown in a read-only buffer with the same language.
+pub fn open_diff_with_text(
+    workspace: &mut Workspace,
+    old_text: String,
+    old_label: SharedString,
+    new_buffer: Model<Buffer>,
+    new_label: SharedString,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let languages = workspace.project().read(cx).languages().clone();
+    let language = new_buffer.read(cx).language().cloned();
+    let old_buffer = cx.new_model(|cx| {
+        let mut buffer = Buffer::local(old_text, cx);
+        buffer.set_language_registry(languages);
+        buffer.set_language(language, cx);
+        buffer
+    });
+    open_diff(workspace, old_buffer, old_label, new_buffer, new_label, cx);
+}
+
+/// Opens a diff view of two files of the project.
+pub fn compare_paths(
+    workspace: &mut Workspace,
+    old_path: ProjectPath,
+    new_path: ProjectPath,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let (old_buffer, new_buffer) = workspace.project().update(cx, |project, cx| {
+        (
+            project.open_buffer(old_path.clone(), cx),
+            project.open_buffer(new_path.clone(), cx),
+        )
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let (old_buffer, new_buffer) = futures::try_join!(old_buffer, new_buffer)?;
+        workspace.update(&mut cx, |workspace, cx| {
+            open_diff(
+                workspace,
+                old_buffer,
+                old_path.path.to_string_lossy().to_string().into(),
+                new_buffer,
+                new_path.path.to_string_lossy().to_string().into(),
+                cx,
+            )
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Opens a diff view of two buffers in the active pane.
+pub fn open_diff(
+    workspace: &mut Workspace,
+    old_buffer: Model<Buffer>,
+    old_label: SharedString,
+    new_buffer: Model<Buffer>,
+    new_label: SharedString,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let diff_view =
+        cx.new_view(|cx| DiffView::new(old_buffer, old_label, new_buffer, new_label, project, cx));
+    workspace.add_item_to_active_pane(Box::new(diff_view), cx);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffMode {
+    /// The two buffers next to each other, their changed lines aligned by padding, scrolling together.
+    SideBySide,
+    /// The new buffer, with the removed lines of the old one shown as blocks above the lines replacing them.
+    Unified,
+}
+
+struct DiffRows;
+struct DiffWords;
+
+/// A workspace item showing the changes between two buffers, either side by side or unified, with the
+/// changed words of the modified lines highlighted.
+pub struct DiffView {
+    old_buffer: Model<Buffer>,
+    new_buffer: Model<Buffer>,
+    old_label: SharedString,
+    new_label: SharedString,
+    mode: DiffMode,
+    old_editor: View<Editor>,
+    new_editor: View<Editor>,
+    unified_editor: View<Editor>,
+    hunks: Vec<LineHunk>,
+    old_blocks: HashSet<BlockId>,
+    new_blocks: HashSet<BlockId>,
+    unified_blocks: HashSet<BlockId>,
+    pending_diff: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DiffView {
+    pub fn new(
+        old_buffer: Model<Buffer>,
+        old_label: SharedString,
+        new_buffer: Model<Buffer>,
+        new_label: SharedString,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let old_editor = Self::new_editor(&old_buffer, &project, cx);
+        let new_editor = Self::new_editor(&new_buffer, &project, cx);
+        let unified_editor = Self::new_editor(&new_buffer, &project, cx);
+
+        let subscriptions = vec![
+            cx.subscribe(&old_buffer, |this, _, event, cx| {
+                if let language::Event::Edited = event {
+                    this.update_diff(cx);
+                }
+            }),
+            cx.subscribe(&new_buffer, |this, _, event, cx| {
+                if let language::Event::Edited = event {
+                    this.update_diff(cx);
+                }
+            }),
+            cx.subscribe(&old_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(true, cx);
+                }
+            }),
+            cx.subscribe(&new_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(false, cx);
+                }
+                cx.emit(event.clone());
+            }),
+            cx.subscribe(&unified_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+            }),
+        ];
+
+        let mut this = Self {
+            old_buffer,
+            new_buffer,
+            old_label,
+            new_label,
+            mode: DiffMode::SideBySide,
+            old_editor,
+            new_editor,
+            unified_editor,
+            hunks: Vec::new(),
+            old_blocks: HashSet::default(),
+            new_blocks: HashSet::default(),
+            unified_blocks: HashSet::default(),
+            pending_diff: None,
+            _subscriptions: subscriptions,
+        };
+        this.update_diff(cx);
+        this
+    }
+
+    /// Creates an editor for one of the buffers, read-only for the buffers that are not backed by a file.
+    fn new_editor(
+        buffer: &Model<Buffer>,
+        project: &Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Editor> {
+        let has_file = buffer.read(cx).file().is_some();
+        cx.new_view(|cx| {
+            let mut editor =
+                Editor::for_buffer(buffer.clone(), has_file.then(|| project.clone()), cx);
+            editor.set_read_only(!has_file);
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        })
+    }
+
+    pub fn mode(&self) -> DiffMode {
+        self.mode
+    }
+
+    pub fn hunks(&self) -> &[LineHunk] {
+        &self.hunks
+    }
+
+    pub fn toggle_mode(&mut self, cx: &mut ViewContext<Self>) {
+        let focused = self.focus_handle(cx).contains_focused(cx);
+        self.mode = match self.mode {
+            DiffMode::SideBySide => DiffMode::Unified,
+            DiffMode::Unified => DiffMode::SideBySide,
+        };
+        if focused {
+            cx.focus(&self.focus_handle(cx));
+        }
+        cx.notify();
+    }
+
+    fn update_diff(&mut self, cx: &mut ViewContext<Self>) {
+        let old_text = self.old_buffer.read(cx).text();
+        let new_snapshot = self.new_buffer.read(cx).text_snapshot();
+        self.pending_diff = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(DIFF_DEBOUNCE).await;
+            let hunks = cx
+                .background_executor()
+                .spawn(async move { line_diff::diff_lines(&old_text, &new_snapshot).await })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.hunks = hunks;
+                this.pending_diff = None;
+                this.update_decorations(cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Highlights the changed lines and words in the editors, pads the side by side editors to align their
+    /// changed lines, and shows the removed lines in the unified editor.
+    fn update_decorations(&mut self, cx: &mut ViewContext<Self>) {
+        let status = cx.theme().status();
+        let (deleted_background, created_background) =
+            (status.deleted_background, status.created_background);
+        let (mut deleted_words, mut created_words) = (status.deleted, status.created);
+        deleted_words.fade_out(0.7);
+        created_words.fade_out(0.7);
+
+        let old_text = self.old_buffer.read(cx).text();
+        let hunks = self.hunks.clone();
+
+        let old_padding = hunks
+            .iter()
+            .filter(|hunk| hunk.new_rows.len() > hunk.old_rows.len())
+            .map(|hunk| {
+                let rows = hunk.new_rows.len() - hunk.old_rows.len();
+                (hunk.old_rows.end, rows, padding_block())
+            })
+            .collect::<Vec<_>>();
+        let new_padding = hunks
+            .iter()
+            .filter(|hunk| hunk.old_rows.len() > hunk.new_rows.len())
+            .map(|hunk| {
+                let rows = hunk.old_rows.len() - hunk.new_rows.len();
+                (hunk.new_rows.end, rows, padding_block())
+            })
+            .collect::<Vec<_>>();
+        let removed_blocks = hunks
+            .iter()
+            .filter(|hunk| !hunk.old_rows.is_empty())
+            .map(|hunk| {
+                let lines = removed_lines(&old_text, hunk);
+                let rows = lines.len();
+                let render = move |first_line: usize, cx: &mut BlockContext| {
+                    render_removed_lines(
+                        &lines[first_line..],
+                        deleted_background,
+                        deleted_words,
+                        cx,
+                    )
+                };
+                (hunk.new_rows.start, rows, render)
+            })
+            .collect::<Vec<_>>();
+
+        decorate_editor(
+            &self.old_editor,
+            &mut self.old_blocks,
+            hunks.iter().map(|hunk| hunk.old_rows.clone()),
+            hunks.iter().flat_map(|hunk| hunk.old_words.clone()),
+            deleted_background,
+            deleted_words,
+            old_padding,
+            cx,
+        );
+        decorate_editor(
+            &self.new_editor,
+            &mut self.new_blocks,
+            hunks.iter().map(|hunk| hunk.new_rows.clone()),
+            hunks.iter().flat_map(|hunk| hunk.new_words.clone()),
+            created_background,
+            created_words,
+            new_padding,
+            cx,
+        );
+        decorate_editor(
+            &self.unified_editor,
+            &mut self.unified_blocks,
+            hunks.iter().map(|hunk| hunk.new_rows.clone()),
+            hunks.iter().flat_map(|hunk| hunk.new_words.clone()),
+            created_background,
+            created_words,
+            removed_blocks,
+            cx,
+        );
+    }
+
+    /// Scrolls the side by side editor that was not scrolled to the position of the one that was.
+    fn sync_scroll_position(&mut self, from_old_editor: bool, cx: &mut ViewContext<Self>) {
+        if self.mode != DiffMode::SideBySide {
+            return;
+        }
+        let (source, target) = if from_old_editor {
+            (&self.old_editor, &self.new_editor)
+        } else {
+            (&self.new_editor, &self.old_editor)
+        };
+        let position = source.update(cx, |editor, cx| editor.scroll_position(cx));
+        target.update(cx, |editor, cx| {
+            // Setting the position emits a scroll event, which must not be propagated back.
+            if editor.scroll_position(cx) != position {
+                editor.set_scroll_position(position, cx);
+            }
+        });
+    }
+
+    fn active_editor(&self) -> &View<Editor> {
+        match self.mode {
+            DiffMode::SideBySide => &self.new_editor,
+            DiffMode::Unified => &self.unified_editor,
+        }
+    }
+
+    /// The buffers of the view that are backed by a file.
+    fn file_buffers(&self, cx: &AppContext) -> HashSet<Model<Buffer>> {
+        [&self.old_buffer, &self.new_buffer]
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).file().is_some())
+            .cloned()
+            .collect()
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let summary = if self.pending_diff.is_some() && self.hunks.is_empty() {
+            "Comparing…".to_string()
+        } else {
+            match self.hunks.len() {
+                0 => "No changes".to_string(),
+                1 => "1 change".to_string(),
+                count => format!("{count} changes"),
+            }
+        };
+        let (mode_icon, mode_tooltip) = match self.mode {
+            DiffMode::SideBySide => (IconName::Menu, "Show Unified Diff"),
+            DiffMode::Unified => (IconName::Split, "Show Side by Side Diff"),
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(self.old_label.clone()).color(Color::Deleted))
+                    .child(Label::new("↔").color(Color::Muted))
+                    .child(Label::new(self.new_label.clone()).color(Color::Created)),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(summary)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        IconButton::new("diff-view-toggle-mode", mode_icon)
+                            .icon_size(IconSize::Small)
+                            .tooltip(move |cx| {
+                                Tooltip::for_action(mode_tooltip, &ToggleDiffMode, cx)
+                            })
+                            .on_click(cx.listener(|this, _, cx| this.toggle_mode(cx))),
+                    ),
+            )
+    }
+}
+
+/// Highlights the rows and words of the editor, and replaces its blocks with the given ones, placed above
+/// the given rows and rendered from the given line on, as tall blocks are split into several.
+#[allow(clippy::too_many_arguments)]
+fn decorate_editor(
+    editor: &View<Editor>,
+    block_ids: &mut HashSet<BlockId>,
+    rows: impl Iterator<Item = Range<u32>>,
+    words: impl Iterator<Item = Range<usize>>,
+    row_background: Hsla,
+    word_background: Hsla,
+    blocks: Vec<(
+        u32,
+        usize,
+        impl 'static + Send + Clone + Fn(usize, &mut BlockContext) -> AnyElement,
+    )>,
+    cx: &mut ViewContext<DiffView>,
+) {
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let max_point = snapshot.max_point();
+
+        editor.clear_row_highlights::<DiffRows>();
+        for rows in rows.filter(|rows| !rows.is_empty()) {
+            let start = snapshot.anchor_before(Point::new(rows.start.min(max_point.row), 0));
+            let end = snapshot.anchor_before(Point::new((rows.end - 1).min(max_point.row), 0));
+            editor.highlight_rows::<DiffRows>(start..end, Some(row_background), cx);
+        }
+        let words = words
+            .map(|range| {
+                let start = range.start.min(snapshot.len());
+                let end = range.end.min(snapshot.len());
+                snapshot.anchor_after(start)..snapshot.anchor_before(end)
+            })
+            .collect();
+        editor.highlight_text::<DiffWords>(
+            words,
+            HighlightStyle {
+                background_color: Some(word_background),
+                ..Default::default()
+            },
+            cx,
+        );
+
+        editor.remove_blocks(std::mem::take(block_ids), None, cx);
+        let blocks = blocks.into_iter().flat_map(|(row, rows, render)| {
+            let (position, disposition) = if row > max_point.row {
+                (max_point, BlockDisposition::Below)
+            } else {
+                (Point::new(row, 0), BlockDisposition::Above)
+            };
+            let position = snapshot.anchor_before(position);
+            // Blocks are at most `u8::MAX` rows high, so taller ones are split.
+            (0..rows)
+                .step_by(u8::MAX as usize)
+                .map(move |start| {
+                    let height = (rows - start).min(u8::MAX as usize) as u8;
+                    let render = render.clone();
+                    BlockProperties {
+                        position,
+                        height,
+                        style: BlockStyle::Flex,
+                        render: Box::new(move |cx: &mut BlockContext| render(start, cx)),
+                        disposition,
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+        block_ids.extend(editor.insert_blocks(blocks, None, cx));
+    });
+}
+
+fn padding_block() -> impl 'static + Send + Clone + Fn(usize, &mut BlockContext) -> AnyElement {
+    |_, _: &mut BlockContext| div().into_any_element()
+}
+
+/// Returns the removed lines of the hunk, each with the ranges of its removed words.
+fn removed_lines(old_text: &str, hunk: &LineHunk) -> Vec<(SharedString, Vec<Range<usize>>)> {
+    let mut offset = 0;
+    let mut lines = Vec::new();
+    for (row, line) in old_text.split_inclusive('\n').enumerate() {
+        let row = row as u32;
+        let line_range = offset..offset + line.len();
+        offset += line.len();
+        if row < hunk.old_rows.start {
+            continue;
+        }
+        if row >= hunk.old_rows.end {
+            break;
+        }
+        let line = line.trim_end_matches('\n');
+        let words = hunk
+            .old_words
+            .iter()
+            .filter(|word| word.start < line_range.end && line_range.start < word.end)
+            .map(|word| {
+                word.start.max(line_range.start) - line_range.start
+                    ..word.end.min(line_range.start + line.len()) - line_range.start
+            })
+            .filter(|word| !word.is_empty())
+            .collect();
+        lines.push((SharedString::from(line.to_string()), words));
+    }
+    lines
+}
+
+fn render_removed_lines(
+    lines: &[(SharedString, Vec<Range<usize>>)],
+    background: Hsla,
+    word_background: Hsla,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let text_style = cx.editor_style.text.clone();
+    let line_height = cx.line_height;
+    v_flex()
+        .size_full()
+        .pl(cx.gutter_dimensions.width)
+        .w(cx.max_width + cx.gutter_dimensions.width)
+        .bg(background)
+        .children(lines.iter().take(u8::MAX as usize).map(|(line, words)| {
+            div()
+                .h(line_height)
+                .child(StyledText::new(line.clone()).with_highlights(
+                    &text_style,
+                    words.iter().map(|word| {
+                        (
+                            word.clone(),
+                            HighlightStyle {
+                                background_color: Some(word_background),
+                                ..Default::default()
+                            },
+                        )
+                    }),
+                ))
+        }))
+        .into_any_element()
+}
+
+impl EventEmitter<EditorEvent> for DiffView {}
+
+impl FocusableView for DiffView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.active_editor().focus_handle(cx)
+    }
+}
+
+impl Render for DiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match self.mode {
+            DiffMode::SideBySide => h_flex()
+                .flex_1()
+                .size_full()
+                .child(
+                    div()
+                        .flex_1()
+                        .h_full()
+                        .border_r_1()
+                        .border_color(cx.theme().colors().border)
+                        .child(self.old_editor.clone()),
+                )
+                .child(div().flex_1().h_full().child(self.new_editor.clone()))
+                .into_any_element(),
+            DiffMode::Unified => div()
+                .flex_1()
+                .size_full()
+                .child(self.unified_editor.clone())
+                .into_any_element(),
+        };
+        v_flex()
+            .key_context("DiffView")
+            .size_full()
+            .on_action(cx.listener(|this, _: &ToggleDiffMode, cx| this.toggle_mode(cx)))
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+impl Item for DiffView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let file_name = |label: &SharedString| {
+            Path::new(label.as_ref())
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_else(|| label.to_string())
+        };
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::Split))
+            .child(
+                Label::new(format!(
+                    "{} ↔ {}",
+                    file_name(&self.old_label),
+                    file_name(&self.new_label)
+                ))
+                .color(if params.selected {
+                    Color::Default
+                } else {
+                    Color::Muted
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(format!("{} ↔ {}", self.old_label, self.new_label).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.new_editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.old_buffer.read(cx).is_dirty() || self.new_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.old_buffer.read(cx).has_conflict() || self.new_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &AppContext) -> bool {
+        !self.file_buffers(cx).is_empty()
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffers = self
+            .file_buffers(cx)
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).is_dirty())
+            .collect();
+        project.update(cx, |project, cx| project.save_buffers(buffers, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: PathBuf,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let buffers = self.file_buffers(cx);
+        let reload = project.update(cx, |project, cx| project.reload_buffers(buffers, true, cx));
+        cx.background_executor().spawn(async move {
+            reload.await.context("reloading the compared files")?;
+            Ok(())
+        })
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.active_editor().to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        for editor in [&self.old_editor, &self.new_editor, &self.unified_editor] {
+            editor.update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+        }
+    }
+}
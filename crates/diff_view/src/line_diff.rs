@@ -0,0 +1,151 @@
+use std::ops::Range;
+
+use git::diff::BufferDiff;
+use similar::{ChangeTag, TextDiff};
+use text::{BufferSnapshot, Point, ToOffset};
+
+/// Changed hunks longer than this, in bytes, are not diffed word by word.
+const MAX_WORD_DIFF_LEN: usize = 16 * 1024;
+
+/// A range of changed lines, along with the words that changed within them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineHunk {
+    /// The rows of the changed lines in the old text, empty for added lines.
+    pub old_rows: Range<u32>,
+    /// The rows of the changed lines in the new text, empty for removed lines.
+    pub new_rows: Range<u32>,
+    /// The byte ranges of the changed words in the old text.
+    pub old_words: Vec<Range<usize>>,
+    /// The byte ranges of the changed words in the new text.
+    pub new_words: Vec<Range<usize>>,
+}
+
+/// Diffs the old text against the new one line by line, and the lines of each modified hunk word by word.
+pub async fn diff_lines(old_text: &str, new: &BufferSnapshot) -> Vec<LineHunk> {
+    let mut diff = BufferDiff::new();
+    diff.update(old_text, new).await;
+    diff.hunks_with_base_ranges(old_text, new)
+        .map(|(hunk, old_range, old_rows)| {
+            let new_rows = hunk.associated_range;
+            let new_range = row_offset(new_rows.start, new)..row_offset(new_rows.end, new);
+            let (old_words, new_words) = if old_range.is_empty()
+                || new_range.is_empty()
+                || old_range.len() + new_range.len() > MAX_WORD_DIFF_LEN
+            {
+                (Vec::new(), Vec::new())
+            } else {
+                let new_hunk_text = new.text_for_range(new_range.clone()).collect::<String>();
+                let (old_words, new_words) =
+                    changed_words(&old_text[old_range.clone()], &new_hunk_text);
+                (
+                    offset_ranges(old_words, old_range.start),
+                    offset_ranges(new_words, new_range.start),
+                )
+            };
+            LineHunk {
+                old_rows,
+                new_rows,
+                old_words,
+                new_words,
+            }
+        })
+        .collect()
+}
+
+/// Returns the byte ranges of the words that were removed from the old text and of the ones that were
+/// inserted in the new text, adjacent words being merged into one range.
+fn changed_words(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let mut old_words = Vec::new();
+    let mut new_words = Vec::new();
+    let mut old_offset = 0;
+    let mut new_offset = 0;
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        let len = change.value().len();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old_offset += len;
+                new_offset += len;
+            }
+            ChangeTag::Delete => {
+                push_range(&mut old_words, old_offset..old_offset + len);
+                old_offset += len;
+            }
+            ChangeTag::Insert => {
+                push_range(&mut new_words, new_offset..new_offset + len);
+                new_offset += len;
+            }
+        }
+    }
+    (old_words, new_words)
+}
+
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+fn offset_ranges(ranges: Vec<Range<usize>>, offset: usize) -> Vec<Range<usize>> {
+    ranges
+        .into_iter()
+        .map(|range| range.start + offset..range.end + offset)
+        .collect()
+}
+
+/// Returns the offset of the start of the row, or the end of the buffer past its last row.
+fn row_offset(row: u32, buffer: &BufferSnapshot) -> usize {
+    if row > buffer.max_point().row {
+        buffer.len()
+    } else {
+        Point::new(row, 0).to_offset(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use text::{Buffer, BufferId};
+
+    use super::*;
+
+    #[test]
+    fn test_changed_words() {
+        assert_eq!(
+            changed_words("let a = 1;\n", "let b = 1;\n"),
+            (vec![4..5], vec![4..5])
+        );
+        assert_eq!(changed_words("x\n", "x y\n"), (vec![], vec![1..3]));
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let old_text = "one\ntwo\nthree\nfour\nfive\n";
+        let new_text = "one\ntwo 2\nthree\nfive\nsix\n";
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), new_text.to_string());
+
+        let hunks = futures::executor::block_on(diff_lines(old_text, &buffer.snapshot()));
+        assert_eq!(
+            hunks,
+            vec![
+                LineHunk {
+                    old_rows: 1..2,
+                    new_rows: 1..2,
+                    old_words: vec![],
+                    new_words: vec![7..9],
+                },
+                LineHunk {
+                    old_rows: 3..4,
+                    new_rows: 3..3,
+                    old_words: vec![],
+                    new_words: vec![],
+                },
+                LineHunk {
+                    old_rows: 5..5,
+                    new_rows: 4..5,
+                    old_words: vec![],
+                    new_words: vec![],
+                },
+            ]
+        );
+    }
+}
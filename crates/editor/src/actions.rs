@@ -71,6 +71,10 @@ pub struct UnfoldAt {
     pub buffer_row: u32,
 }
 
+/// Folds every foldable range nested at the given depth, where 1 is the outermost level.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct FoldLevel(pub u32);
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct MoveUpByLines {
     #[serde(default)]
@@ -94,6 +98,12 @@ pub struct SelectDownByLines {
     pub(super) lines: u32,
 }
 
+/// Shows the commit in the history of the file of the editor.
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ShowCommit {
+    pub sha: String,
+}
+
 impl_actions!(
     editor,
     [
@@ -109,22 +119,28 @@ impl_actions!(
         ToggleComments,
         FoldAt,
         UnfoldAt,
+        FoldLevel,
         MoveUpByLines,
         MoveDownByLines,
         SelectUpByLines,
         SelectDownByLines,
+        ShowCommit,
     ]
 );
 
 gpui::actions!(
     editor,
     [
+        AcceptBoth,
+        AcceptOurs,
         AcceptPartialCopilotSuggestion,
         AcceptPartialInlineCompletion,
+        AcceptTheirs,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
         Cancel,
+        CollapseAllHunkDiffs,
         ConfirmRename,
         ContextMenuFirst,
         ContextMenuLast,
@@ -155,18 +171,22 @@ gpui::actions!(
         DisplayCursorNames,
         DuplicateLineUp,
         DuplicateLineDown,
+        ExpandAllHunkDiffs,
         ExpandMacroRecursively,
         FindAllReferences,
         Fold,
+        FoldAll,
         FoldSelectedRanges,
         Format,
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
+        GoToExpandedHunk,
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
         GoToPrevDiagnostic,
+        GoToPrevExpandedHunk,
         GoToPrevHunk,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
@@ -238,21 +258,29 @@ gpui::actions!(
         ShowCharacterPalette,
         ShowCompletions,
         ShowInlineCompletion,
+        ShowSignatureHelp,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
+        SpawnRunnable,
         SplitSelectionIntoLines,
+        StageSelectedHunks,
         Tab,
         TabPrev,
+        ToggleBookmark,
+        ToggleBreakpoint,
         ToggleGitBlame,
+        ToggleHunkDiff,
         ToggleInlayHints,
         ToggleLineNumbers,
         ToggleSoftWrap,
         Transpose,
         Undo,
         UndoSelection,
+        UnfoldAll,
         UnfoldLines,
         UniqueLinesCaseSensitive,
-        UniqueLinesCaseInsensitive
+        UniqueLinesCaseInsensitive,
+        UnstageSelectedHunks
     ]
 );
@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use collections::HashSet;
+use gpui::{AppContext, ViewContext};
+use multi_buffer::{Anchor, Point};
+use ui::{prelude::*, IconButton, IconName, IconSize, Tooltip};
+
+use crate::{display_map::ToDisplayPoint, Editor, EditorSnapshot, ToggleBookmark};
+
+/// Returns the display rows in the given range with a bookmark set on them, along with the
+/// position and label of each bookmark.
+pub(crate) fn bookmark_display_rows(
+    editor: &Editor,
+    snapshot: &EditorSnapshot,
+    display_rows: Range<u32>,
+    cx: &AppContext,
+) -> Vec<(u32, Anchor, Option<SharedString>)> {
+    let Some(project) = editor.project.as_ref() else {
+        return Vec::new();
+    };
+    let bookmark_store = project.read(cx).bookmark_store().read(cx);
+    let buffer_snapshot = &snapshot.buffer_snapshot;
+
+    let mut bookmarks = Vec::new();
+    for (excerpt_id, buffer, range) in buffer_snapshot.excerpts() {
+        for bookmark in bookmark_store.bookmarks_for_buffer(buffer.remote_id()) {
+            if bookmark.position.cmp(&range.context.start, buffer).is_lt()
+                || bookmark.position.cmp(&range.context.end, buffer).is_gt()
+            {
+                continue;
+            }
+            let Some(anchor) = buffer_snapshot.anchor_in_excerpt(excerpt_id, bookmark.position)
+            else {
+                continue;
+            };
+            let row = anchor.to_display_point(snapshot).row();
+            if display_rows.contains(&row) {
+                let label = bookmark.data.clone().map(SharedString::from);
+                bookmarks.push((row, anchor, label));
+            }
+        }
+    }
+    bookmarks.sort_by_key(|(row, _, _)| *row);
+    bookmarks.dedup_by_key(|(row, _, _)| *row);
+    bookmarks
+}
+
+impl Editor {
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, cx: &mut ViewContext<Self>) {
+        let mut rows = HashSet::default();
+        let positions = self
+            .selections
+            .all::<Point>(cx)
+            .into_iter()
+            .filter(|selection| rows.insert(selection.head().row))
+            .map(|selection| Point::new(selection.head().row, 0))
+            .collect::<Vec<_>>();
+        for position in positions {
+            self.toggle_bookmark_at(position, cx);
+        }
+    }
+
+    fn toggle_bookmark_at<T: multi_buffer::ToOffset>(
+        &mut self,
+        position: T,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        project
+            .read(cx)
+            .bookmark_store()
+            .clone()
+            .update(cx, |store, cx| store.toggle_bookmark(&buffer, position, cx));
+    }
+
+    pub(crate) fn render_bookmark_indicator(
+        &self,
+        row: u32,
+        position: Anchor,
+        label: Option<SharedString>,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        let button = IconButton::new(("bookmark_indicator", row as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Accent)
+            .on_click(cx.listener(move |editor, _, cx| {
+                editor.toggle_bookmark_at(position, cx);
+            }));
+        match label {
+            Some(label) => button.tooltip(move |cx| Tooltip::text(label.clone(), cx)),
+            None => button,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::ops::Range;
+
+use collections::HashSet;
+use gpui::{AppContext, ViewContext};
+use multi_buffer::{Anchor, Point};
+use ui::{prelude::*, IconButton, IconName, IconSize};
+
+use crate::{display_map::ToDisplayPoint, Editor, EditorSnapshot, ToggleBreakpoint};
+
+/// Returns the display rows in the given range with a breakpoint set on them, along with the
+/// position of each breakpoint.
+pub(crate) fn breakpoint_display_rows(
+    editor: &Editor,
+    snapshot: &EditorSnapshot,
+    display_rows: Range<u32>,
+    cx: &AppContext,
+) -> Vec<(u32, Anchor)> {
+    let Some(project) = editor.project.as_ref() else {
+        return Vec::new();
+    };
+    let breakpoint_store = project.read(cx).breakpoint_store().read(cx);
+    let buffer_snapshot = &snapshot.buffer_snapshot;
+
+    let mut breakpoints = Vec::new();
+    for (excerpt_id, buffer, range) in buffer_snapshot.excerpts() {
+        for position in breakpoint_store.breakpoints_for_buffer(buffer.remote_id()) {
+            if position.cmp(&range.context.start, buffer).is_lt()
+                || position.cmp(&range.context.end, buffer).is_gt()
+            {
+                continue;
+            }
+            let Some(anchor) = buffer_snapshot.anchor_in_excerpt(excerpt_id, *position) else {
+                continue;
+            };
+            let row = anchor.to_display_point(snapshot).row();
+            if display_rows.contains(&row) {
+                breakpoints.push((row, anchor));
+            }
+        }
+    }
+    breakpoints.sort_by_key(|(row, _)| *row);
+    breakpoints.dedup_by_key(|(row, _)| *row);
+    breakpoints
+}
+
+impl Editor {
+    pub fn toggle_breakpoint(&mut self, _: &ToggleBreakpoint, cx: &mut ViewContext<Self>) {
+        let mut rows = HashSet::default();
+        let positions = self
+            .selections
+            .all::<Point>(cx)
+            .into_iter()
+            .filter(|selection| rows.insert(selection.head().row))
+            .map(|selection| Point::new(selection.head().row, 0))
+            .collect::<Vec<_>>();
+        for position in positions {
+            self.toggle_breakpoint_at(position, cx);
+        }
+    }
+
+    fn toggle_breakpoint_at<T: multi_buffer::ToOffset>(
+        &mut self,
+        position: T,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        project
+            .read(cx)
+            .breakpoint_store()
+            .clone()
+            .update(cx, |store, cx| {
+                store.toggle_breakpoint(&buffer, position, cx)
+            });
+    }
+
+    pub(crate) fn render_breakpoint_indicator(
+        &self,
+        row: u32,
+        position: Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(
+            ("breakpoint_indicator", row as usize),
+            IconName::DebugBreakpoint,
+        )
+        .icon_size(IconSize::XSmall)
+        .size(ui::ButtonSize::None)
+        .icon_color(Color::Error)
+        .on_click(cx.listener(move |editor, _, cx| {
+            editor.toggle_breakpoint_at(position, cx);
+        }))
+    }
+}
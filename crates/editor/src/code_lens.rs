@@ -0,0 +1,424 @@
+use std::{path::PathBuf, time::Duration};
+
+use collections::{HashMap, HashSet};
+use gpui::{Model, Task, ViewContext, WeakView};
+use language::{Buffer, BufferId, Point, ToOffset as _};
+use multi_buffer::ToPoint as _;
+use project::CodeLens;
+use serde::Deserialize;
+use settings::Settings;
+use task::{TaskContext, TaskTemplate};
+use ui::prelude::*;
+use util::{post_inc, ResultExt};
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    Bias, Editor, EditorMode, EditorSettings, FindAllReferences,
+};
+
+/// How long to wait after an edit before re-requesting code lenses.
+const CODE_LENS_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Code lenses of the buffers shown in an editor, along with the blocks displaying them.
+#[derive(Default)]
+pub(crate) struct CodeLensState {
+    buffers: HashMap<BufferId, BufferCodeLenses>,
+    fetch_tasks: HashMap<BufferId, Task<Option<()>>>,
+    next_fetch_id: usize,
+}
+
+struct BufferCodeLenses {
+    buffer: Model<Buffer>,
+    /// Distinguishes the lenses of different fetches, so that stale resolve responses are dropped.
+    fetch_id: usize,
+    lenses: Vec<CodeLens>,
+    /// Indices of the lenses that are being resolved.
+    resolving: HashSet<usize>,
+    /// Blocks displaying the lenses, along with the indices of the lenses shown by each block.
+    blocks: Vec<(BlockId, Vec<usize>)>,
+}
+
+/// Re-requests code lenses for every buffer in the editor, or removes all of them
+/// if code lenses are disabled.
+pub(crate) fn refresh_code_lenses(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = if EditorSettings::get_global(cx).code_lens {
+        editor
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .map(|buffer| (buffer.read(cx).remote_id(), buffer))
+            .collect::<HashMap<_, _>>()
+    } else {
+        HashMap::default()
+    };
+
+    let stale_buffer_ids = editor
+        .code_lens_state
+        .buffers
+        .keys()
+        .chain(editor.code_lens_state.fetch_tasks.keys())
+        .filter(|buffer_id| !buffers.contains_key(buffer_id))
+        .copied()
+        .collect::<HashSet<_>>();
+    for buffer_id in stale_buffer_ids {
+        editor.code_lens_state.fetch_tasks.remove(&buffer_id);
+        if let Some(buffer_lenses) = editor.code_lens_state.buffers.remove(&buffer_id) {
+            let block_ids = buffer_lenses.blocks.into_iter().map(|(id, _)| id).collect();
+            editor.remove_blocks(block_ids, None, cx);
+        }
+    }
+
+    for (buffer_id, buffer) in buffers {
+        let fetch_lenses = project.clone();
+        let task = cx.spawn(|editor, mut cx| {
+            async move {
+                if debounce {
+                    cx.background_executor().timer(CODE_LENS_DEBOUNCE).await;
+                }
+                let lenses = fetch_lenses
+                    .update(&mut cx, |project, cx| project.code_lens(&buffer, cx))?
+                    .await?;
+                editor.update(&mut cx, |editor, cx| {
+                    editor.code_lens_state.fetch_tasks.remove(&buffer_id);
+                    set_code_lenses(editor, buffer, lenses, cx);
+                })
+            }
+            .log_err()
+        });
+        editor.code_lens_state.fetch_tasks.insert(buffer_id, task);
+    }
+}
+
+fn set_code_lenses(
+    editor: &mut Editor,
+    buffer: Model<Buffer>,
+    mut lenses: Vec<CodeLens>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let buffer_id = buffer.read(cx).remote_id();
+    if let Some(old_lenses) = editor.code_lens_state.buffers.remove(&buffer_id) {
+        let block_ids = old_lenses.blocks.into_iter().map(|(id, _)| id).collect();
+        editor.remove_blocks(block_ids, None, cx);
+    }
+
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    lenses.sort_by(|a, b| a.range.start.cmp(&b.range.start, &buffer_snapshot));
+
+    // Lenses starting on the same line share a single block.
+    let mut lines = Vec::<(u32, Vec<usize>)>::new();
+    for (ix, lens) in lenses.iter().enumerate() {
+        let row = lens.range.start.to_point(&buffer_snapshot).row;
+        match lines.last_mut() {
+            Some((last_row, indices)) if *last_row == row => indices.push(ix),
+            _ => lines.push((row, vec![ix])),
+        }
+    }
+
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let editor_handle = cx.view().downgrade();
+    let mut line_indices = Vec::new();
+    let mut block_properties = Vec::new();
+    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+        let context = excerpt_range.context.to_offset(&buffer_snapshot);
+        for (_, indices) in &lines {
+            let start = &lenses[indices[0]].range.start;
+            if !context.contains(&start.to_offset(&buffer_snapshot)) {
+                continue;
+            }
+            let Some(position) = multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, *start) else {
+                continue;
+            };
+            block_properties.push(BlockProperties {
+                position,
+                height: 1,
+                style: BlockStyle::Fixed,
+                render: render_code_lens_block(
+                    editor_handle.clone(),
+                    buffer.clone(),
+                    indices.iter().map(|ix| lenses[*ix].clone()).collect(),
+                ),
+                disposition: BlockDisposition::Above,
+            });
+            line_indices.push(indices.clone());
+        }
+    }
+
+    let block_ids = editor.insert_blocks(block_properties, None, cx);
+    let fetch_id = post_inc(&mut editor.code_lens_state.next_fetch_id);
+    editor.code_lens_state.buffers.insert(
+        buffer_id,
+        BufferCodeLenses {
+            buffer,
+            fetch_id,
+            lenses,
+            resolving: HashSet::default(),
+            blocks: block_ids.into_iter().zip(line_indices).collect(),
+        },
+    );
+    resolve_visible_code_lenses(editor, cx);
+}
+
+/// Resolves the commands of the lenses in the visible part of the editor.
+pub(crate) fn resolve_visible_code_lenses(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.code_lens_state.buffers.is_empty() {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let visible_start = editor
+        .scroll_manager
+        .anchor()
+        .anchor
+        .to_point(&multi_buffer_snapshot);
+    let visible_end = multi_buffer_snapshot.clip_point(
+        visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+        Bias::Left,
+    );
+    let visible_ranges = multi_buffer.range_to_buffer_ranges(visible_start..visible_end, cx);
+
+    for (buffer, visible_range, _) in visible_ranges {
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(buffer_lenses) = editor.code_lens_state.buffers.get_mut(&buffer_id) else {
+            continue;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        for (ix, lens) in buffer_lenses.lenses.iter().enumerate() {
+            if lens.command().is_some() || buffer_lenses.resolving.contains(&ix) {
+                continue;
+            }
+            let offset = lens.range.start.to_offset(&buffer_snapshot);
+            if offset < visible_range.start || offset > visible_range.end {
+                continue;
+            }
+
+            buffer_lenses.resolving.insert(ix);
+            let fetch_id = buffer_lenses.fetch_id;
+            let resolve_task = project.update(cx, |project, cx| {
+                project.resolve_code_lens(&buffer, lens.clone(), cx)
+            });
+            cx.spawn(|editor, mut cx| {
+                async move {
+                    let lens = resolve_task.await;
+                    editor.update(&mut cx, |editor, cx| {
+                        code_lens_resolved(editor, buffer_id, fetch_id, ix, lens.log_err(), cx)
+                    })
+                }
+                .log_err()
+            })
+            .detach();
+        }
+    }
+}
+
+fn code_lens_resolved(
+    editor: &mut Editor,
+    buffer_id: BufferId,
+    fetch_id: usize,
+    ix: usize,
+    lens: Option<CodeLens>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let editor_handle = cx.view().downgrade();
+    let Some(buffer_lenses) = editor.code_lens_state.buffers.get_mut(&buffer_id) else {
+        return;
+    };
+    if buffer_lenses.fetch_id != fetch_id {
+        return;
+    }
+    buffer_lenses.resolving.remove(&ix);
+    let Some(lens) = lens else {
+        return;
+    };
+    buffer_lenses.lenses[ix] = lens;
+
+    let blocks = buffer_lenses
+        .blocks
+        .iter()
+        .filter(|(_, indices)| indices.contains(&ix))
+        .map(|(block_id, indices)| {
+            let render = render_code_lens_block(
+                editor_handle.clone(),
+                buffer_lenses.buffer.clone(),
+                indices
+                    .iter()
+                    .map(|ix| buffer_lenses.lenses[*ix].clone())
+                    .collect(),
+            );
+            (*block_id, render)
+        })
+        .collect::<HashMap<_, _>>();
+    editor.replace_blocks(blocks, None, cx);
+}
+
+fn render_code_lens_block(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    lenses: Vec<CodeLens>,
+) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        let buttons = lenses.iter().enumerate().filter_map(|(ix, lens)| {
+            let title = lens.command()?.title.clone();
+            let editor = editor.clone();
+            let buffer = buffer.clone();
+            let lens = lens.clone();
+            Some(
+                Button::new(("code-lens", ix), title)
+                    .label_size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .size(ButtonSize::Compact)
+                    .style(ButtonStyle::Transparent)
+                    .on_click(move |_, cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                execute_code_lens(editor, buffer.clone(), lens.clone(), cx)
+                            })
+                            .ok();
+                    }),
+            )
+        });
+
+        h_flex()
+            .id(cx.block_id)
+            .size_full()
+            .pl(cx.anchor_x)
+            .gap_1()
+            .children(buttons)
+            .into_any_element()
+    })
+}
+
+fn execute_code_lens(
+    editor: &mut Editor,
+    buffer: Model<Buffer>,
+    lens: CodeLens,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(command) = lens.command() else {
+        return;
+    };
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+
+    // Some commands are meant to be run by the client rather than the language server.
+    match command.command.as_str() {
+        "rust-analyzer.runSingle" => {
+            let runnable = command
+                .arguments
+                .as_ref()
+                .and_then(|arguments| arguments.first())
+                .and_then(|runnable| {
+                    serde_json::from_value::<RustAnalyzerRunnable>(runnable.clone()).log_err()
+                });
+            if let Some(spawn_in_terminal) = runnable
+                .and_then(|runnable| runnable.into_task_template())
+                .and_then(|(template, cwd)| {
+                    let task_context = TaskContext {
+                        cwd,
+                        task_variables: Default::default(),
+                    };
+                    template.resolve_task("code_lens", &task_context)?.resolved
+                })
+            {
+                workspace.update(cx, |_, cx| {
+                    cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
+                });
+            }
+        }
+        "rust-analyzer.showReferences" => {
+            let multi_buffer = editor.buffer.read(cx);
+            let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+            let Some(position) = multi_buffer
+                .excerpts_for_buffer(&buffer, cx)
+                .into_iter()
+                .find_map(|(excerpt_id, _)| {
+                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, lens.range.start)
+                })
+            else {
+                return;
+            };
+            editor.change_selections(None, cx, |selections| {
+                selections.select_anchor_ranges([position..position])
+            });
+            if let Some(task) = editor.find_all_references(&FindAllReferences, cx) {
+                task.detach_and_log_err(cx);
+            }
+        }
+        _ => {
+            let title = command.title.clone();
+            let Some(project) = editor.project.clone() else {
+                return;
+            };
+            let apply_code_lens = project.update(cx, |project, cx| {
+                project.apply_code_lens(buffer, lens, true, cx)
+            });
+            let workspace = workspace.downgrade();
+            cx.spawn(|editor, cx| async move {
+                let project_transaction = apply_code_lens.await?;
+                Editor::open_project_transaction(&editor, workspace, project_transaction, title, cx)
+                    .await
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+}
+
+/// The argument of rust-analyzer's `runSingle` command.
+#[derive(Deserialize)]
+struct RustAnalyzerRunnable {
+    label: String,
+    kind: String,
+    args: RustAnalyzerCargoRunnable,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RustAnalyzerCargoRunnable {
+    workspace_root: Option<PathBuf>,
+    #[serde(default)]
+    cargo_args: Vec<String>,
+    #[serde(default)]
+    cargo_extra_args: Vec<String>,
+    #[serde(default)]
+    executable_args: Vec<String>,
+}
+
+impl RustAnalyzerRunnable {
+    fn into_task_template(self) -> Option<(TaskTemplate, Option<PathBuf>)> {
+        if self.kind != "cargo" {
+            return None;
+        }
+        let mut args = self.args.cargo_args;
+        args.extend(self.args.cargo_extra_args);
+        if !self.args.executable_args.is_empty() {
+            args.push("--".to_string());
+            args.extend(self.args.executable_args);
+        }
+        let template = TaskTemplate {
+            label: self.label,
+            command: "cargo".to_string(),
+            args,
+            ..TaskTemplate::default()
+        };
+        Some((template, self.args.workspace_root))
+    }
+}
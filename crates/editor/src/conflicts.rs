@@ -0,0 +1,352 @@
+use std::{mem, ops::Range, time::Duration};
+
+use ::git::conflict::parse_conflicts;
+use collections::{HashMap, HashSet};
+use gpui::{Model, Task, ViewContext, WeakView};
+use language::{Buffer, BufferId, OffsetRangeExt as _, ToOffset as _};
+use text::BufferSnapshot;
+use ui::prelude::*;
+use util::ResultExt;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    AcceptBoth, AcceptOurs, AcceptTheirs, Editor, EditorMode,
+};
+
+/// How long to wait after an edit before parsing the buffers for conflicts again.
+const CONFLICTS_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Merge conflicts found in the editor's buffers, along with the blocks offering to resolve them.
+#[derive(Default)]
+pub(crate) struct ConflictsState {
+    conflicts: HashMap<BufferId, Vec<BufferConflict>>,
+    tasks: HashMap<BufferId, Task<Option<()>>>,
+    blocks: HashSet<BlockId>,
+}
+
+/// A conflict of a buffer, see [`git::conflict::Conflict`].
+#[derive(Clone, Debug)]
+struct BufferConflict {
+    range: Range<text::Anchor>,
+    ours: Range<text::Anchor>,
+    base: Option<Range<text::Anchor>>,
+    theirs: Range<text::Anchor>,
+}
+
+impl BufferConflict {
+    fn resolved_text(&self, resolution: Resolution, buffer: &BufferSnapshot) -> String {
+        let text =
+            |range: &Range<text::Anchor>| buffer.text_for_range(range.clone()).collect::<String>();
+        match resolution {
+            Resolution::Ours => text(&self.ours),
+            Resolution::Theirs => text(&self.theirs),
+            Resolution::Both => text(&self.ours) + &text(&self.theirs),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Resolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+struct ConflictOursRows;
+struct ConflictBaseRows;
+struct ConflictTheirsRows;
+
+/// Parses every buffer of the editor for conflict markers again.
+pub(crate) fn refresh_conflicts(editor: &mut Editor, debounce: bool, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .map(|buffer| (buffer.read(cx).remote_id(), buffer))
+        .collect::<HashMap<_, _>>();
+    let stale_buffer_ids = editor
+        .conflicts_state
+        .conflicts
+        .keys()
+        .chain(editor.conflicts_state.tasks.keys())
+        .filter(|buffer_id| !buffers.contains_key(buffer_id))
+        .copied()
+        .collect::<HashSet<_>>();
+    if !stale_buffer_ids.is_empty() {
+        for buffer_id in stale_buffer_ids {
+            editor.conflicts_state.tasks.remove(&buffer_id);
+            editor.conflicts_state.conflicts.remove(&buffer_id);
+        }
+        update_conflict_decorations(editor, cx);
+    }
+
+    for (buffer_id, buffer) in buffers {
+        let snapshot = buffer.read(cx).text_snapshot();
+        let task = cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(CONFLICTS_DEBOUNCE).await;
+            }
+            let conflicts = cx
+                .background_executor()
+                .spawn(async move { parse_buffer_conflicts(&snapshot) })
+                .await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.conflicts_state.tasks.remove(&buffer_id);
+                    let had_conflicts = editor.conflicts_state.conflicts.contains_key(&buffer_id);
+                    if conflicts.is_empty() {
+                        editor.conflicts_state.conflicts.remove(&buffer_id);
+                        if !had_conflicts {
+                            return;
+                        }
+                    } else {
+                        editor
+                            .conflicts_state
+                            .conflicts
+                            .insert(buffer_id, conflicts);
+                    }
+                    update_conflict_decorations(editor, cx);
+                })
+                .log_err()
+        });
+        editor.conflicts_state.tasks.insert(buffer_id, task);
+    }
+}
+
+fn parse_buffer_conflicts(buffer: &BufferSnapshot) -> Vec<BufferConflict> {
+    let text = buffer.text();
+    if !text.contains("<<<<<<<") {
+        return Vec::new();
+    }
+    let anchor_range =
+        |range: Range<usize>| buffer.anchor_before(range.start)..buffer.anchor_before(range.end);
+    parse_conflicts(&text)
+        .into_iter()
+        .map(|conflict| BufferConflict {
+            range: anchor_range(conflict.range),
+            ours: anchor_range(conflict.ours),
+            base: conflict.base.map(anchor_range),
+            theirs: anchor_range(conflict.theirs),
+        })
+        .collect()
+}
+
+/// Highlights the sections of the conflicts, and shows the buttons resolving them above them.
+fn update_conflict_decorations(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_row_highlights::<ConflictOursRows>();
+    editor.clear_row_highlights::<ConflictBaseRows>();
+    editor.clear_row_highlights::<ConflictTheirsRows>();
+    let old_blocks = mem::take(&mut editor.conflicts_state.blocks);
+    editor.remove_blocks(old_blocks, None, cx);
+
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let editor_handle = cx.view().downgrade();
+    let mut ours_rows = Vec::new();
+    let mut base_rows = Vec::new();
+    let mut theirs_rows = Vec::new();
+    let mut blocks = Vec::new();
+    for (buffer_id, conflicts) in &editor.conflicts_state.conflicts {
+        let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+            continue;
+        };
+        let buffer_snapshot = buffer.read(cx).text_snapshot();
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+            let context = excerpt_range.context.to_offset(&buffer_snapshot);
+            // Returns the range of the rows of the section, within the excerpt.
+            let rows = |start: &text::Anchor, end: &text::Anchor| {
+                let start = start.to_offset(&buffer_snapshot).max(context.start);
+                // Sections end at the start of the line following them.
+                let end = end
+                    .to_offset(&buffer_snapshot)
+                    .saturating_sub(1)
+                    .min(context.end);
+                if start > end {
+                    return None;
+                }
+                let start = multi_buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, buffer_snapshot.anchor_before(start))?;
+                let end = multi_buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, buffer_snapshot.anchor_before(end))?;
+                Some(start..end)
+            };
+
+            for conflict in conflicts {
+                let start = conflict.range.start.to_offset(&buffer_snapshot);
+                let end = conflict.range.end.to_offset(&buffer_snapshot);
+                if end <= context.start || context.end < start {
+                    continue;
+                }
+                ours_rows.extend(rows(&conflict.range.start, &conflict.ours.end));
+                let theirs_start = if let Some(base) = &conflict.base {
+                    base_rows.extend(rows(&conflict.ours.end, &base.end));
+                    &base.end
+                } else {
+                    &conflict.ours.end
+                };
+                theirs_rows.extend(rows(theirs_start, &conflict.range.end));
+
+                if context.contains(&start) {
+                    if let Some(position) =
+                        multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start)
+                    {
+                        blocks.push(BlockProperties {
+                            position,
+                            height: 1,
+                            style: BlockStyle::Fixed,
+                            render: render_conflict_block(
+                                editor_handle.clone(),
+                                buffer.clone(),
+                                conflict.range.start,
+                            ),
+                            disposition: BlockDisposition::Above,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let status = cx.theme().status();
+    let (ours_background, base_background, theirs_background) = (
+        status.info_background,
+        status.conflict_background,
+        status.success_background,
+    );
+    for rows in ours_rows {
+        editor.highlight_rows::<ConflictOursRows>(rows, Some(ours_background), cx);
+    }
+    for rows in base_rows {
+        editor.highlight_rows::<ConflictBaseRows>(rows, Some(base_background), cx);
+    }
+    for rows in theirs_rows {
+        editor.highlight_rows::<ConflictTheirsRows>(rows, Some(theirs_background), cx);
+    }
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+    editor.conflicts_state.blocks = block_ids.into_iter().collect();
+    cx.notify();
+}
+
+fn render_conflict_block(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    conflict_start: text::Anchor,
+) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        let button = |label: &'static str, resolution: Resolution| {
+            let editor = editor.clone();
+            let buffer = buffer.clone();
+            Button::new(label, label)
+                .label_size(LabelSize::Small)
+                .color(Color::Muted)
+                .size(ButtonSize::Compact)
+                .style(ButtonStyle::Transparent)
+                .on_click(move |_, cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.resolve_conflict_at(&buffer, conflict_start, resolution, cx)
+                        })
+                        .ok();
+                })
+        };
+
+        h_flex()
+            .id(cx.block_id)
+            .size_full()
+            .pl(cx.anchor_x)
+            .gap_1()
+            .child(button("Accept Ours", Resolution::Ours))
+            .child(button("Accept Theirs", Resolution::Theirs))
+            .child(button("Accept Both", Resolution::Both))
+            .into_any_element()
+    })
+}
+
+impl Editor {
+    pub fn accept_ours(&mut self, _: &AcceptOurs, cx: &mut ViewContext<Self>) {
+        self.resolve_selected_conflicts(Resolution::Ours, cx);
+    }
+
+    pub fn accept_theirs(&mut self, _: &AcceptTheirs, cx: &mut ViewContext<Self>) {
+        self.resolve_selected_conflicts(Resolution::Theirs, cx);
+    }
+
+    pub fn accept_both(&mut self, _: &AcceptBoth, cx: &mut ViewContext<Self>) {
+        self.resolve_selected_conflicts(Resolution::Both, cx);
+    }
+
+    /// Resolves the conflicts containing the heads of the selections.
+    fn resolve_selected_conflicts(&mut self, resolution: Resolution, cx: &mut ViewContext<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let mut conflict_positions = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            if let Some(position) = multi_buffer.text_anchor_for_position(selection.head(), cx) {
+                conflict_positions.push(position);
+            }
+        }
+        self.resolve_conflicts(conflict_positions, resolution, cx);
+    }
+
+    fn resolve_conflict_at(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: text::Anchor,
+        resolution: Resolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.resolve_conflicts(vec![(buffer.clone(), position)], resolution, cx);
+    }
+
+    /// Replaces the conflicts containing the given positions with the text of the chosen sides.
+    fn resolve_conflicts(
+        &mut self,
+        positions: Vec<(Model<Buffer>, text::Anchor)>,
+        resolution: Resolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut edits =
+            HashMap::<BufferId, (Model<Buffer>, Vec<(Range<usize>, String)>)>::default();
+        for (buffer, position) in positions {
+            let snapshot = buffer.read(cx).text_snapshot();
+            let buffer_id = snapshot.remote_id();
+            let Some(conflict) =
+                self.conflicts_state
+                    .conflicts
+                    .get(&buffer_id)
+                    .and_then(|conflicts| {
+                        conflicts.iter().find(|conflict| {
+                            conflict.range.start.cmp(&position, &snapshot).is_le()
+                                && conflict.range.end.cmp(&position, &snapshot).is_gt()
+                        })
+                    })
+            else {
+                continue;
+            };
+            let range = conflict.range.to_offset(&snapshot);
+            let buffer_edits = &mut edits
+                .entry(buffer_id)
+                .or_insert_with(|| (buffer.clone(), Vec::new()))
+                .1;
+            if !buffer_edits.iter().any(|(other, _)| *other == range) {
+                buffer_edits.push((range, conflict.resolved_text(resolution, &snapshot)));
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(cx, |editor, cx| {
+            for (_, (buffer, edits)) in edits {
+                buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            }
+            editor.change_selections(None, cx, |selections| selections.refresh());
+        });
+    }
+}
@@ -31,7 +31,8 @@ use fold_map::FoldMap;
 use gpui::{Font, HighlightStyle, Hsla, LineLayout, Model, ModelContext, Pixels, UnderlineStyle};
 use inlay_map::InlayMap;
 use language::{
-    language_settings::language_settings, OffsetUtf16, Point, Subscription as BufferSubscription,
+    language_settings::language_settings, BufferId, OffsetUtf16, Point,
+    Subscription as BufferSubscription, ToPoint as _,
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::{Anchor, AnchorRangeExt, MultiBuffer, MultiBufferSnapshot, ToOffset, ToPoint};
@@ -64,6 +65,9 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type SemanticHighlights = TreeMap<BufferId, Arc<Vec<(Range<Anchor>, HighlightStyle)>>>;
+type StyledHighlights = TreeMap<BufferId, Arc<Vec<(Range<Anchor>, HighlightStyle)>>>;
+type FoldableRanges = TreeMap<BufferId, Arc<[Range<text::Anchor>]>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -87,6 +91,14 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Highlights derived from language server semantic tokens, keyed by the buffer they belong to.
+    semantic_highlights: SemanticHighlights,
+    /// Highlights with styles of their own, such as the colors of the output of a program, keyed by the
+    /// buffer they belong to.
+    styled_highlights: StyledHighlights,
+    /// Ranges that can be folded, reported by language servers or by a language's folds query,
+    /// keyed by the buffer they belong to. Buffers without any fall back to indentation-based folding.
+    foldable_ranges: FoldableRanges,
     pub clip_at_line_ends: bool,
 }
 
@@ -119,6 +131,9 @@ impl DisplayMap {
             block_map,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_highlights: Default::default(),
+            styled_highlights: Default::default(),
+            foldable_ranges: Default::default(),
             clip_at_line_ends: false,
         }
     }
@@ -144,6 +159,9 @@ impl DisplayMap {
             block_snapshot,
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_highlights: self.semantic_highlights.clone(),
+            styled_highlights: self.styled_highlights.clone(),
+            foldable_ranges: self.foldable_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
         }
     }
@@ -279,6 +297,47 @@ impl DisplayMap {
         cleared
     }
 
+    /// Replaces the semantic token highlights of the given buffer.
+    /// The ranges must be sorted and must not overlap.
+    pub(crate) fn set_semantic_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        self.semantic_highlights
+            .insert(buffer_id, Arc::new(highlights));
+    }
+
+    pub(crate) fn clear_semantic_highlights(&mut self, buffer_id: BufferId) -> bool {
+        self.semantic_highlights.remove(&buffer_id).is_some()
+    }
+
+    /// Replaces the styled highlights of the given buffer.
+    /// The ranges must be sorted and must not overlap.
+    pub(crate) fn set_styled_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        self.styled_highlights
+            .insert(buffer_id, Arc::new(highlights));
+    }
+
+    /// Replaces the foldable ranges of the given buffer.
+    /// Each range spans from the end of its first line to the end of its last line,
+    /// and the ranges must be sorted by their start.
+    pub(crate) fn set_foldable_ranges(
+        &mut self,
+        buffer_id: BufferId,
+        ranges: Vec<Range<text::Anchor>>,
+    ) {
+        self.foldable_ranges.insert(buffer_id, ranges.into());
+    }
+
+    pub(crate) fn clear_foldable_ranges(&mut self, buffer_id: BufferId) -> bool {
+        self.foldable_ranges.remove(&buffer_id).is_some()
+    }
+
     pub fn set_font(&self, font: Font, font_size: Pixels, cx: &mut ModelContext<Self>) -> bool {
         self.wrap_map
             .update(cx, |map, cx| map.set_font_with_size(font, font_size, cx))
@@ -344,6 +403,8 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_highlights: Option<&'a SemanticHighlights>,
+    pub styled_highlights: Option<&'a StyledHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -369,6 +430,9 @@ pub struct DisplaySnapshot {
     block_snapshot: block_map::BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
+    styled_highlights: StyledHighlights,
+    foldable_ranges: FoldableRanges,
     clip_at_line_ends: bool,
 }
 
@@ -538,6 +602,8 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_highlights: Some(&self.semantic_highlights),
+                styled_highlights: Some(&self.styled_highlights),
                 styles: highlight_styles,
             },
         )
@@ -822,6 +888,10 @@ impl DisplaySnapshot {
     }
 
     pub fn is_foldable(&self, buffer_row: u32) -> bool {
+        if let Some(range) = self.foldable_range_for_row(buffer_row) {
+            return range.is_some();
+        }
+
         let max_row = self.buffer_snapshot.max_buffer_row();
         if buffer_row >= max_row {
             return false;
@@ -845,6 +915,10 @@ impl DisplaySnapshot {
     }
 
     pub fn foldable_range(&self, buffer_row: u32) -> Option<Range<Point>> {
+        if let Some(range) = self.foldable_range_for_row(buffer_row) {
+            return range.filter(|_| !self.is_line_folded(buffer_row));
+        }
+
         let start = Point::new(buffer_row, self.buffer_snapshot.line_len(buffer_row));
         if self.is_foldable(start.row) && !self.is_line_folded(start.row) {
             let (start_indent, _) = self.line_indent_for_buffer_row(buffer_row);
@@ -869,6 +943,43 @@ impl DisplaySnapshot {
         }
     }
 
+    /// Returns the outermost known foldable range starting on the given row, or `None`
+    /// if the row's buffer has no foldable ranges and indentation should be used instead.
+    fn foldable_range_for_row(&self, row: u32) -> Option<Option<Range<Point>>> {
+        let (buffer, line_range) = self.buffer_snapshot.buffer_line_for_row(row)?;
+        let ranges = self.foldable_ranges.get(&buffer.remote_id())?;
+        let buffer_row = line_range.start.row;
+
+        let first_ix =
+            ranges.partition_point(|range| range.start.to_point(buffer).row < buffer_row);
+        let end_row = ranges[first_ix..]
+            .iter()
+            .take_while(|range| range.start.to_point(buffer).row == buffer_row)
+            .map(|range| range.end.to_point(buffer).row)
+            .max()
+            .filter(|end_row| *end_row > buffer_row);
+        let Some(end_row) = end_row else {
+            return Some(None);
+        };
+
+        // Only fold rows that are displayed contiguously within the same excerpt.
+        let end_row = row + (end_row - buffer_row);
+        let is_contiguous = self.buffer_snapshot.buffer_line_for_row(end_row).map_or(
+            false,
+            |(end_buffer, end_line_range)| {
+                end_buffer.remote_id() == buffer.remote_id()
+                    && end_line_range.start.row == buffer_row + (end_row - row)
+            },
+        );
+        if !is_contiguous {
+            return Some(None);
+        }
+
+        let start = Point::new(row, self.buffer_snapshot.line_len(row));
+        let end = Point::new(end_row, self.buffer_snapshot.line_len(end_row));
+        Some(Some(start..end))
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn text_highlight_ranges<Tag: ?Sized + 'static>(
         &self,
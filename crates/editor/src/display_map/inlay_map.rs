@@ -12,7 +12,7 @@ use std::{
     vec,
 };
 use sum_tree::{Bias, Cursor, SumTree, TreeMap};
-use text::{Patch, Rope};
+use text::{BufferId, Patch, Rope};
 
 use super::Highlights;
 
@@ -67,6 +67,14 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn color<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::Color(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -185,11 +193,23 @@ pub struct InlayBufferRows<'a> {
     max_buffer_row: u32,
 }
 
+/// The highlights a highlight endpoint belongs to. An endpoint only ends the highlights with its own tag,
+/// and overlapping highlights are applied in the order of their tags.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightTag {
+    /// The semantic token highlights of a buffer.
+    Semantic(BufferId),
+    /// The highlights with styles of their own of a buffer.
+    Styled(BufferId),
+    /// Text highlights, keyed by the type that added them.
+    Text(Option<TypeId>),
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct HighlightEndpoint {
     offset: InlayOffset,
     is_start: bool,
-    tag: Option<TypeId>,
+    tag: HighlightTag,
     style: HighlightStyle,
 }
 
@@ -217,7 +237,7 @@ pub struct InlayChunks<'a> {
     max_output_offset: InlayOffset,
     highlight_styles: HighlightStyles,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    active_highlights: BTreeMap<HighlightTag, HighlightStyle>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -308,6 +328,7 @@ impl<'a> Iterator for InlayChunks<'a> {
                 let mut highlight_style = match inlay.id {
                     InlayId::Suggestion(_) => self.highlight_styles.suggestion,
                     InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Color(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -1039,6 +1060,26 @@ impl InlaySnapshot {
                 cursor.seek(&range.start, Bias::Right, &());
             }
         }
+        if let Some(semantic_highlights) = highlights.semantic_highlights {
+            if !semantic_highlights.is_empty() {
+                self.apply_buffer_highlights(
+                    &range,
+                    semantic_highlights,
+                    HighlightTag::Semantic,
+                    &mut highlight_endpoints,
+                );
+            }
+        }
+        if let Some(styled_highlights) = highlights.styled_highlights {
+            if !styled_highlights.is_empty() {
+                self.apply_buffer_highlights(
+                    &range,
+                    styled_highlights,
+                    HighlightTag::Styled,
+                    &mut highlight_endpoints,
+                );
+            }
+        }
         highlight_endpoints.sort();
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
@@ -1101,13 +1142,13 @@ impl InlaySnapshot {
                     highlight_endpoints.push(HighlightEndpoint {
                         offset: self.to_inlay_offset(range.start.to_offset(&self.buffer)),
                         is_start: true,
-                        tag: *tag,
+                        tag: HighlightTag::Text(*tag),
                         style,
                     });
                     highlight_endpoints.push(HighlightEndpoint {
                         offset: self.to_inlay_offset(range.end.to_offset(&self.buffer)),
                         is_start: false,
-                        tag: *tag,
+                        tag: HighlightTag::Text(*tag),
                         style,
                     });
                 }
@@ -1117,6 +1158,58 @@ impl InlaySnapshot {
         }
     }
 
+    /// Adds the endpoints of highlights with styles of their own, such as semantic token highlights,
+    /// tagging the highlights of each buffer with the given tag for its id.
+    fn apply_buffer_highlights(
+        &self,
+        range: &Range<InlayOffset>,
+        buffer_highlights: &TreeMap<BufferId, Arc<Vec<(Range<Anchor>, HighlightStyle)>>>,
+        tag: fn(BufferId) -> HighlightTag,
+        highlight_endpoints: &mut Vec<HighlightEndpoint>,
+    ) {
+        let range_start = self.buffer.anchor_after(self.to_buffer_offset(range.start));
+        let range_end = self.buffer.anchor_before(self.to_buffer_offset(range.end));
+        for (buffer_id, highlights) in buffer_highlights.iter() {
+            let tag = tag(*buffer_id);
+            let start_ix = match highlights.binary_search_by(|(probe, _)| {
+                if probe.end.cmp(&range_start, &self.buffer).is_gt() {
+                    cmp::Ordering::Greater
+                } else {
+                    cmp::Ordering::Less
+                }
+            }) {
+                Ok(i) | Err(i) => i,
+            };
+
+            let mut highlights = highlights[start_ix..]
+                .iter()
+                .take_while(|(range, _)| range.start.cmp(&range_end, &self.buffer).is_lt())
+                .peekable();
+            while let Some((range, style)) = highlights.next() {
+                let end = range.end.to_offset(&self.buffer);
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: self.to_inlay_offset(range.start.to_offset(&self.buffer)),
+                    is_start: true,
+                    tag,
+                    style: *style,
+                });
+                // A buffer's highlights share a tag, so adjacent ranges must not end the highlight
+                // that the next range starts at the same offset.
+                let next_starts_at_end = highlights
+                    .peek()
+                    .map_or(false, |(next, _)| next.start.to_offset(&self.buffer) == end);
+                if !next_starts_at_end {
+                    highlight_endpoints.push(HighlightEndpoint {
+                        offset: self.to_inlay_offset(end),
+                        is_start: false,
+                        tag,
+                        style: *style,
+                    });
+                }
+            }
+        }
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
@@ -1170,9 +1263,10 @@ mod tests {
     use crate::{
         display_map::{InlayHighlights, TextHighlights},
         hover_links::InlayHighlight,
-        InlayId, MultiBuffer,
+        ExcerptRange, InlayId, MultiBuffer,
     };
-    use gpui::AppContext;
+    use gpui::{AppContext, Context as _};
+    use language::{Buffer, Capability};
     use project::{InlayHint, InlayHintLabel, ResolveState};
     use rand::prelude::*;
     use settings::SettingsStore;
@@ -1551,6 +1645,92 @@ mod tests {
         assert_eq!(inlay_snapshot.text(), "abxJKLyDzefghi");
     }
 
+    #[gpui::test]
+    fn test_semantic_highlights_in_adjacent_excerpts(cx: &mut AppContext) {
+        let buffer_1 = cx.new_model(|cx| Buffer::local("one two", cx));
+        let buffer_2 = cx.new_model(|cx| Buffer::local("three", cx));
+        let buffer = cx.new_model(|cx| {
+            let mut multibuffer = MultiBuffer::new(0, Capability::ReadWrite);
+            multibuffer.push_excerpts(
+                buffer_1.clone(),
+                [ExcerptRange {
+                    context: 0..7,
+                    primary: None,
+                }],
+                cx,
+            );
+            multibuffer.push_excerpts(
+                buffer_2.clone(),
+                [ExcerptRange {
+                    context: 0..5,
+                    primary: None,
+                }],
+                cx,
+            );
+            multibuffer
+        });
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot.clone());
+        assert_eq!(inlay_snapshot.text(), "one two\nthree");
+
+        // The highlights of the first excerpt run up to its end, and the highlights of the second
+        // one start right away.
+        let red = HighlightStyle {
+            color: Some(gpui::red()),
+            ..Default::default()
+        };
+        let blue = HighlightStyle {
+            color: Some(gpui::blue()),
+            ..Default::default()
+        };
+        let mut semantic_highlights = TreeMap::default();
+        semantic_highlights.insert(
+            buffer_1.read(cx).remote_id(),
+            Arc::new(vec![(
+                buffer_snapshot.anchor_after(4)..buffer_snapshot.anchor_before(7),
+                red,
+            )]),
+        );
+        semantic_highlights.insert(
+            buffer_2.read(cx).remote_id(),
+            Arc::new(vec![
+                (
+                    buffer_snapshot.anchor_after(8)..buffer_snapshot.anchor_before(10),
+                    blue,
+                ),
+                (
+                    buffer_snapshot.anchor_after(10)..buffer_snapshot.anchor_before(13),
+                    red,
+                ),
+            ]),
+        );
+
+        let mut chunks: Vec<(String, Option<HighlightStyle>)> = Vec::new();
+        for chunk in inlay_snapshot.chunks(
+            InlayOffset(0)..inlay_snapshot.len(),
+            false,
+            Highlights {
+                semantic_highlights: Some(&semantic_highlights),
+                ..Highlights::default()
+            },
+        ) {
+            match chunks.last_mut() {
+                Some((text, style)) if *style == chunk.highlight_style => text.push_str(chunk.text),
+                _ => chunks.push((chunk.text.to_string(), chunk.highlight_style)),
+            }
+        }
+        assert_eq!(
+            chunks,
+            [
+                ("one ".to_string(), None),
+                ("two".to_string(), Some(red)),
+                ("\n".to_string(), None),
+                ("th".to_string(), Some(blue)),
+                ("ree".to_string(), Some(red)),
+            ]
+        );
+    }
+
     #[gpui::test]
     fn test_inlay_buffer_rows(cx: &mut AppContext) {
         let buffer = MultiBuffer::build_simple("abc\ndef\nghi", cx);
@@ -0,0 +1,295 @@
+use std::time::Duration;
+
+use collections::{HashMap, HashSet};
+use gpui::{HighlightStyle, Hsla, Model, Pixels, Point, Rgba, Task, ViewContext};
+use language::{Buffer, BufferId};
+use project::{ColorPresentation, DocumentColor};
+use settings::Settings;
+use util::{post_inc, ResultExt};
+
+use crate::{
+    display_map::Inlay, hover_links::InlayHighlight, mouse_context_menu::MouseContextMenu, Anchor,
+    Bias, Editor, EditorMode, EditorSettings, EditorSnapshot, InlayId, PointForPosition,
+};
+
+/// How long to wait after an edit before re-requesting document colors.
+const DOCUMENT_COLORS_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The text of the inlay shown before each color literal, of which the square gets its color.
+const SWATCH: &str = "■ ";
+const SWATCH_SQUARE_LEN: usize = "■".len();
+
+/// Marks the inlay highlights giving each swatch its color.
+enum DocumentColorSwatch {}
+
+/// Colors reported by the language servers of the editor's buffers, along with the inlays
+/// displaying them.
+#[derive(Default)]
+pub(crate) struct DocumentColorsState {
+    swatches: HashMap<BufferId, Vec<Swatch>>,
+    tasks: HashMap<BufferId, Task<Option<()>>>,
+}
+
+struct Swatch {
+    inlay_id: InlayId,
+    position: Anchor,
+    buffer: Model<Buffer>,
+    color: DocumentColor,
+}
+
+/// Re-requests the colors of every buffer in the editor, or removes all of the swatches
+/// if document colors are disabled.
+pub(crate) fn refresh_document_colors(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = if EditorSettings::get_global(cx).document_colors {
+        editor
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .map(|buffer| (buffer.read(cx).remote_id(), buffer))
+            .collect::<HashMap<_, _>>()
+    } else {
+        HashMap::default()
+    };
+
+    let stale_buffer_ids = editor
+        .document_colors_state
+        .swatches
+        .keys()
+        .chain(editor.document_colors_state.tasks.keys())
+        .filter(|buffer_id| !buffers.contains_key(buffer_id))
+        .copied()
+        .collect::<HashSet<_>>();
+    if !stale_buffer_ids.is_empty() {
+        let mut to_remove = Vec::new();
+        for buffer_id in stale_buffer_ids {
+            editor.document_colors_state.tasks.remove(&buffer_id);
+            if let Some(swatches) = editor.document_colors_state.swatches.remove(&buffer_id) {
+                to_remove.extend(swatches.into_iter().map(|swatch| swatch.inlay_id));
+            }
+        }
+        editor.splice_inlays(to_remove, Vec::new(), cx);
+        highlight_swatches(editor, cx);
+    }
+
+    for (buffer_id, buffer) in buffers {
+        let project = project.clone();
+        let task = cx.spawn(|editor, mut cx| {
+            async move {
+                if debounce {
+                    cx.background_executor()
+                        .timer(DOCUMENT_COLORS_DEBOUNCE)
+                        .await;
+                }
+                let colors = project
+                    .update(&mut cx, |project, cx| project.document_colors(&buffer, cx))?
+                    .await?;
+                editor.update(&mut cx, |editor, cx| {
+                    editor.document_colors_state.tasks.remove(&buffer_id);
+                    set_document_colors(editor, buffer, colors, cx);
+                })
+            }
+            .log_err()
+        });
+        editor.document_colors_state.tasks.insert(buffer_id, task);
+    }
+}
+
+fn set_document_colors(
+    editor: &mut Editor,
+    buffer: Model<Buffer>,
+    colors: Vec<DocumentColor>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let buffer_id = buffer.read(cx).remote_id();
+    let to_remove = editor
+        .document_colors_state
+        .swatches
+        .remove(&buffer_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|swatch| swatch.inlay_id)
+        .collect();
+
+    let multi_buffer = editor.buffer.read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+    let excerpts = multi_buffer.excerpts_for_buffer(&buffer, cx);
+    let buffer_snapshot = buffer.read(cx);
+    let mut swatches = Vec::new();
+    let mut to_insert = Vec::new();
+    for color in colors {
+        for (excerpt_id, excerpt_range) in &excerpts {
+            let start = color.range.start;
+            if excerpt_range
+                .context
+                .start
+                .cmp(&start, buffer_snapshot)
+                .is_gt()
+                || excerpt_range
+                    .context
+                    .end
+                    .cmp(&start, buffer_snapshot)
+                    .is_lt()
+            {
+                continue;
+            }
+            let Some(position) = snapshot.anchor_in_excerpt(*excerpt_id, start) else {
+                continue;
+            };
+            let inlay = Inlay::color(post_inc(&mut editor.next_inlay_id), position, SWATCH);
+            swatches.push(Swatch {
+                inlay_id: inlay.id,
+                position,
+                buffer: buffer.clone(),
+                color: color.clone(),
+            });
+            to_insert.push(inlay);
+        }
+    }
+
+    editor
+        .document_colors_state
+        .swatches
+        .insert(buffer_id, swatches);
+    editor.splice_inlays(to_remove, to_insert, cx);
+    highlight_swatches(editor, cx);
+}
+
+fn highlight_swatches(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_highlights::<DocumentColorSwatch>(cx);
+    let highlights = editor
+        .document_colors_state
+        .swatches
+        .values()
+        .flatten()
+        .map(|swatch| {
+            let highlight = InlayHighlight {
+                inlay: swatch.inlay_id,
+                inlay_position: swatch.position,
+                range: 0..SWATCH_SQUARE_LEN,
+            };
+            let style = HighlightStyle {
+                color: Some(color_to_hsla(swatch.color.color)),
+                ..Default::default()
+            };
+            (highlight, style)
+        })
+        .collect::<Vec<_>>();
+    for (highlight, style) in highlights {
+        editor.highlight_inlays::<DocumentColorSwatch>(vec![highlight], style, cx);
+    }
+}
+
+fn color_to_hsla(color: lsp::Color) -> Hsla {
+    Rgba {
+        r: color.red,
+        g: color.green,
+        b: color.blue,
+        a: color.alpha,
+    }
+    .into()
+}
+
+/// Returns the color whose swatch is at the given position, if any.
+pub(crate) fn color_swatch_at(
+    editor: &Editor,
+    snapshot: &EditorSnapshot,
+    point_for_position: &PointForPosition,
+    cx: &ViewContext<Editor>,
+) -> Option<(Model<Buffer>, DocumentColor)> {
+    if point_for_position.as_valid().is_some()
+        || point_for_position.column_overshoot_after_line_end > 0
+    {
+        return None;
+    }
+
+    let buffer_snapshot = editor.buffer.read(cx).snapshot(cx);
+    let previous_valid = buffer_snapshot.anchor_at(
+        point_for_position.previous_valid.to_point(snapshot),
+        Bias::Left,
+    );
+    let next_valid = buffer_snapshot.anchor_at(
+        point_for_position.next_valid.to_point(snapshot),
+        Bias::Right,
+    );
+    editor
+        .document_colors_state
+        .swatches
+        .values()
+        .flatten()
+        .find(|swatch| {
+            swatch
+                .position
+                .cmp(&previous_valid, &buffer_snapshot)
+                .is_ge()
+                && swatch.position.cmp(&next_valid, &buffer_snapshot).is_le()
+        })
+        .map(|swatch| (swatch.buffer.clone(), swatch.color.clone()))
+}
+
+/// Requests the ways the given color can be written and lists them in a context menu at the
+/// given position, rewriting the color in the picked presentation.
+pub(crate) fn show_color_presentations(
+    editor: &mut Editor,
+    buffer: Model<Buffer>,
+    color: DocumentColor,
+    position: Point<Pixels>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let presentations = project.update(cx, |project, cx| {
+        project.color_presentations(&buffer, color.range.clone(), color.color, cx)
+    });
+    cx.spawn(|editor, mut cx| async move {
+        let presentations = presentations.await?;
+        if presentations.is_empty() {
+            return Ok(());
+        }
+        editor.update(&mut cx, |editor, cx| {
+            let editor_handle = cx.view().downgrade();
+            let context_menu = ui::ContextMenu::build(cx, |mut menu, _| {
+                for presentation in presentations {
+                    let editor = editor_handle.clone();
+                    let buffer = buffer.clone();
+                    menu = menu.entry(presentation.label.clone(), None, move |cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                apply_color_presentation(editor, &buffer, &presentation, cx)
+                            })
+                            .ok();
+                    });
+                }
+                menu
+            });
+            editor.mouse_context_menu = Some(MouseContextMenu::new(position, context_menu, cx));
+            cx.notify();
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn apply_color_presentation(
+    editor: &mut Editor,
+    buffer: &Model<Buffer>,
+    presentation: &ColorPresentation,
+    cx: &mut ViewContext<Editor>,
+) {
+    editor.transact(cx, |_, cx| {
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit(presentation.edits.iter().cloned(), None, cx);
+        });
+    });
+}
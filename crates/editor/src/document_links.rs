@@ -0,0 +1,122 @@
+use std::{ops::Range, time::Duration};
+
+use collections::HashMap;
+use gpui::{AppContext, Model, Task, ViewContext};
+use language::{Buffer, BufferId, Point};
+use project::DocumentLink;
+use util::ResultExt;
+
+use crate::{hover_links::HoverLink, Editor, EditorMode};
+
+/// How long to wait after an edit before re-requesting document links.
+const DOCUMENT_LINKS_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Links to other documents reported by the language servers of the editor's buffers.
+#[derive(Default)]
+pub(crate) struct DocumentLinksState {
+    links: HashMap<BufferId, Vec<DocumentLink>>,
+    tasks: HashMap<BufferId, Task<Option<()>>>,
+}
+
+/// Re-requests the document links of every buffer in the editor.
+pub(crate) fn refresh_document_links(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .map(|buffer| (buffer.read(cx).remote_id(), buffer))
+        .collect::<HashMap<_, _>>();
+
+    let state = &mut editor.document_links_state;
+    state
+        .links
+        .retain(|buffer_id, _| buffers.contains_key(buffer_id));
+    state
+        .tasks
+        .retain(|buffer_id, _| buffers.contains_key(buffer_id));
+
+    for (buffer_id, buffer) in buffers {
+        let project = project.clone();
+        let task = cx.spawn(|editor, mut cx| {
+            async move {
+                if debounce {
+                    cx.background_executor()
+                        .timer(DOCUMENT_LINKS_DEBOUNCE)
+                        .await;
+                }
+                let links = project
+                    .update(&mut cx, |project, cx| project.document_links(&buffer, cx))?
+                    .await?;
+                editor.update(&mut cx, |editor, _| {
+                    editor.document_links_state.tasks.remove(&buffer_id);
+                    editor.document_links_state.links.insert(buffer_id, links);
+                })
+            }
+            .log_err()
+        });
+        editor.document_links_state.tasks.insert(buffer_id, task);
+    }
+}
+
+impl Editor {
+    /// Replaces the document links of a buffer that has no language server to report them,
+    /// such as links to the locations mentioned in the output of a program.
+    pub fn set_document_links(&mut self, buffer_id: BufferId, links: Vec<DocumentLink>) {
+        self.document_links_state.tasks.remove(&buffer_id);
+        self.document_links_state.links.insert(buffer_id, links);
+    }
+}
+
+/// Returns the document link at the given position of the buffer, if any, as a link to follow
+/// when the position is cmd-clicked.
+///
+/// Links to files open them in the workspace, while other links are opened externally.
+pub(crate) fn document_link_at(
+    editor: &Editor,
+    buffer: &Model<Buffer>,
+    position: text::Anchor,
+    cx: &AppContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let buffer = buffer.read(cx);
+    let link = editor
+        .document_links_state
+        .links
+        .get(&buffer.remote_id())?
+        .iter()
+        .find(|link| {
+            link.range.start.cmp(&position, buffer).is_le()
+                && link.range.end.cmp(&position, buffer).is_ge()
+        })?;
+
+    let target = link.target.as_ref()?;
+    let hover_link = match lsp::Url::parse(target) {
+        Ok(url) if url.scheme() == "file" => HoverLink::File(
+            url.to_file_path().ok()?,
+            url.fragment().and_then(parse_position_fragment),
+        ),
+        _ => HoverLink::Url(target.clone()),
+    };
+    Some((link.range.clone(), hover_link))
+}
+
+/// Parses the `L<line>` or `L<line>,<column>` fragment of a file link, with one-based numbers,
+/// into the position it points at.
+fn parse_position_fragment(fragment: &str) -> Option<Point> {
+    let position = fragment.strip_prefix('L')?;
+    let (line, column) = position.split_once(',').unwrap_or((position, "1"));
+    let row = line.parse::<u32>().ok()?.saturating_sub(1);
+    let column = column.parse::<u32>().ok()?.saturating_sub(1);
+    Some(Point::new(row, column))
+}
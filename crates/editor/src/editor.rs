@@ -14,9 +14,16 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behaviour.
 pub mod actions;
 mod blink_manager;
+mod bookmarks;
+mod breakpoints;
+mod code_lens;
+mod conflicts;
 pub mod display_map;
+mod document_colors;
+mod document_links;
 mod editor_settings;
 mod element;
+mod folding_ranges;
 mod inlay_hint_cache;
 
 mod debounced_delay;
@@ -24,14 +31,20 @@ mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
+mod hunk_diff;
 mod inline_completion_provider;
 pub mod items;
+mod linked_editing_ranges;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod runnables;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
+mod semantic_tokens;
+mod signature_help;
 
 #[cfg(test)]
 mod editor_tests;
@@ -45,16 +58,21 @@ use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
+use code_lens::{refresh_code_lenses, CodeLensState};
 use collections::{hash_map, BTreeMap, Bound, HashMap, HashSet, VecDeque};
+use conflicts::{refresh_conflicts, ConflictsState};
 use convert_case::{Case, Casing};
 use debounced_delay::DebouncedDelay;
 pub use display_map::DisplayPoint;
 use display_map::*;
+use document_colors::{refresh_document_colors, DocumentColorsState};
+use document_links::{refresh_document_links, DocumentLinksState};
 pub use editor_settings::EditorSettings;
 use element::LineWithInvisibles;
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
 };
+use folding_ranges::{refresh_foldable_ranges, FoldingRangesState};
 use futures::FutureExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::GitBlame;
@@ -70,6 +88,7 @@ use gpui::{
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
+use hunk_diff::{refresh_expanded_hunks_if_diff_changed, ExpandedHunks};
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use inline_completion_provider::*;
 pub use items::MAX_TAB_TITLE_LEN;
@@ -83,6 +102,9 @@ use language::{
 };
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
+use linked_editing_ranges::{
+    linked_edits, refresh_linked_editing_ranges, LinkedEditingRangesState,
+};
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
@@ -93,16 +115,24 @@ pub use multi_buffer::{
 };
 use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
+use persistence::DB;
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::{
     CodeAction, Completion, FormatTrigger, Item, Location, Project, ProjectPath, ProjectTransaction,
 };
 use rand::prelude::*;
 use rpc::proto::*;
+use runnables::{refresh_runnables, RunnablesState};
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
+use selection_ranges::{fetch_selection_ranges, selection_ranges_in_excerpt, SelectionRanges};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
+use semantic_tokens::{refresh_semantic_tokens, SemanticTokensState};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
+use signature_help::{
+    hide_signature_help, refresh_signature_help, trigger_signature_help_on_input,
+    SignatureHelpState,
+};
 use smallvec::SmallVec;
 use snippet::Snippet;
 use std::ops::Not as _;
@@ -217,6 +247,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Color(usize),
 }
 
 impl InlayId {
@@ -224,6 +255,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Color(id) => *id,
         }
     }
 }
@@ -406,6 +438,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_larger_syntax_node_stack: Vec<Box<[Selection<usize>]>>,
+    select_larger_syntax_node_task: Option<Task<()>>,
     ime_transaction: Option<TransactionId>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
@@ -420,6 +453,7 @@ pub struct Editor {
     show_breadcrumbs: bool,
     show_gutter: bool,
     show_wrap_guides: Option<bool>,
+    wrap_guides_override: Option<Vec<usize>>,
     placeholder_text: Option<Arc<str>>,
     highlight_order: usize,
     highlighted_rows: HashMap<TypeId, Vec<(usize, Range<Anchor>, Hsla)>>,
@@ -448,6 +482,15 @@ pub struct Editor {
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
+    signature_help_state: SignatureHelpState,
+    semantic_tokens_state: SemanticTokensState,
+    runnables_state: RunnablesState,
+    code_lens_state: CodeLensState,
+    folding_ranges_state: FoldingRangesState,
+    document_links_state: DocumentLinksState,
+    document_colors_state: DocumentColorsState,
+    conflicts_state: ConflictsState,
+    linked_editing_ranges_state: LinkedEditingRangesState,
     gutter_hovered: bool,
     hovered_link_state: Option<HoveredLinkState>,
     inline_completion_provider: Option<RegisteredInlineCompletionProvider>,
@@ -466,6 +509,7 @@ pub struct Editor {
     show_git_blame: bool,
     blame: Option<Model<GitBlame>>,
     blame_subscription: Option<Subscription>,
+    expanded_hunks: ExpandedHunks,
     custom_context_menu: Option<
         Box<
             dyn 'static
@@ -1407,9 +1451,41 @@ impl Editor {
                         cx.emit(EditorEvent::TitleChanged);
                     }));
                 }
+                let breakpoint_store = project.read(cx).breakpoint_store().clone();
+                project_subscriptions.push(cx.subscribe(
+                    &breakpoint_store,
+                    |_, _, _: &project::BreakpointStoreEvent, cx| cx.notify(),
+                ));
+                let bookmark_store = project.read(cx).bookmark_store().clone();
+                project_subscriptions.push(cx.subscribe(
+                    &bookmark_store,
+                    |_, _, _: &project::BookmarkStoreEvent, cx| cx.notify(),
+                ));
+                let test_results_store = project.read(cx).test_results_store().clone();
+                project_subscriptions.push(cx.subscribe(
+                    &test_results_store,
+                    |editor, _, _: &project::TestResultsStoreEvent, cx| {
+                        editor.runnables_state.test_results_changed();
+                        cx.notify();
+                    },
+                ));
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::RefreshSemanticTokens => {
+                            refresh_semantic_tokens(editor, false, cx);
+                        }
+                        project::Event::LanguageServerAdded(_) => {
+                            refresh_semantic_tokens(editor, false, cx);
+                            refresh_code_lenses(editor, false, cx);
+                            refresh_foldable_ranges(editor, false, cx);
+                            refresh_document_links(editor, false, cx);
+                            refresh_document_colors(editor, false, cx);
+                        }
+                        _ => {}
                     };
                 }));
             }
@@ -1439,6 +1515,7 @@ impl Editor {
             autoclose_regions: Default::default(),
             snippet_stack: Default::default(),
             select_larger_syntax_node_stack: Vec::new(),
+            select_larger_syntax_node_task: None,
             ime_transaction: Default::default(),
             active_diagnostics: None,
             soft_wrap_mode_override,
@@ -1451,6 +1528,7 @@ impl Editor {
             show_breadcrumbs: EditorSettings::get_global(cx).toolbar.breadcrumbs,
             show_gutter: mode == EditorMode::Full,
             show_wrap_guides: None,
+            wrap_guides_override: None,
             placeholder_text: None,
             highlight_order: 0,
             highlighted_rows: HashMap::default(),
@@ -1482,6 +1560,15 @@ impl Editor {
             leader_peer_id: None,
             remote_id: None,
             hover_state: Default::default(),
+            signature_help_state: Default::default(),
+            semantic_tokens_state: Default::default(),
+            runnables_state: Default::default(),
+            code_lens_state: Default::default(),
+            folding_ranges_state: Default::default(),
+            document_links_state: Default::default(),
+            document_colors_state: Default::default(),
+            conflicts_state: Default::default(),
+            linked_editing_ranges_state: Default::default(),
             hovered_link_state: Default::default(),
             inline_completion_provider: None,
             active_inline_completion: None,
@@ -1501,6 +1588,7 @@ impl Editor {
             show_git_blame: false,
             blame: None,
             blame_subscription: None,
+            expanded_hunks: ExpandedHunks::default(),
             _subscriptions: vec![
                 cx.observe(&buffer, Self::on_buffer_changed),
                 cx.subscribe(&buffer, Self::on_buffer_event),
@@ -1532,6 +1620,13 @@ impl Editor {
             cx.set_global(ScrollbarAutoHide(should_auto_hide_scrollbars));
         }
 
+        refresh_semantic_tokens(&mut this, false, cx);
+        refresh_code_lenses(&mut this, false, cx);
+        refresh_foldable_ranges(&mut this, false, cx);
+        refresh_document_links(&mut this, false, cx);
+        refresh_document_colors(&mut this, false, cx);
+        refresh_conflicts(&mut this, false, cx);
+        refresh_runnables(&mut this, false, cx);
         this.report_editor_event("open", None, cx);
         this
     }
@@ -1905,6 +2000,7 @@ impl Editor {
             }
 
             hide_hover(self, cx);
+            refresh_signature_help(self, cx);
 
             if old_cursor_position.to_display_point(&display_map).row()
                 != new_cursor_position.to_display_point(&display_map).row()
@@ -1914,6 +2010,7 @@ impl Editor {
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
             refresh_matching_bracket_highlights(self, cx);
+            refresh_linked_editing_ranges(self, cx);
             self.discard_inline_completion(cx);
         }
 
@@ -2336,6 +2433,10 @@ impl Editor {
             return true;
         }
 
+        if hide_signature_help(self, cx) {
+            return true;
+        }
+
         if self.hide_context_menu(cx).is_some() {
             return true;
         }
@@ -2549,10 +2650,12 @@ impl Editor {
         }
 
         drop(snapshot);
+        let linked_edits = linked_edits(self, &edits, cx);
         self.transact(cx, |this, cx| {
             this.buffer.update(cx, |buffer, cx| {
                 buffer.edit(edits, this.autoindent_mode.clone(), cx);
             });
+            linked_edits.apply(this, cx);
 
             let new_anchor_selections = new_selections.iter().map(|e| &e.0);
             let new_selection_deltas = new_selections.iter().map(|e| e.1);
@@ -2620,6 +2723,7 @@ impl Editor {
                 this.trigger_completion_on_input(&text, cx);
                 this.refresh_inline_completion(true, cx);
             }
+            trigger_signature_help_on_input(this, &text, cx);
         });
     }
 
@@ -2945,6 +3049,11 @@ impl Editor {
         let text: Arc<str> = text.into();
         self.transact(cx, |this, cx| {
             let old_selections = this.selections.all_adjusted(cx);
+            let edits = old_selections
+                .iter()
+                .map(|s| (s.start..s.end, text.clone()))
+                .collect::<Vec<_>>();
+            let linked_edits = linked_edits(this, &edits, cx);
             let selection_anchors = this.buffer.update(cx, |buffer, cx| {
                 let anchors = {
                     let snapshot = buffer.read(cx);
@@ -2956,15 +3065,10 @@ impl Editor {
                         })
                         .collect::<Vec<_>>()
                 };
-                buffer.edit(
-                    old_selections
-                        .iter()
-                        .map(|s| (s.start..s.end, text.clone())),
-                    autoindent_mode,
-                    cx,
-                );
+                buffer.edit(edits, autoindent_mode, cx);
                 anchors
             });
+            linked_edits.apply(this, cx);
 
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select_anchors(selection_anchors);
@@ -4876,6 +4980,14 @@ impl Editor {
 
     pub fn revert_selected_hunks(&mut self, _: &RevertSelectedHunks, cx: &mut ViewContext<Self>) {
         let revert_changes = self.gather_revert_changes(&self.selections.disjoint_anchors(), cx);
+        self.apply_revert_changes(revert_changes, cx);
+    }
+
+    fn apply_revert_changes(
+        &mut self,
+        revert_changes: HashMap<BufferId, Vec<(Range<text::Anchor>, Arc<str>)>>,
+        cx: &mut ViewContext<Self>,
+    ) {
         if !revert_changes.is_empty() {
             self.transact(cx, |editor, cx| {
                 editor.buffer().update(cx, |multi_buffer, cx| {
@@ -4892,6 +5004,68 @@ impl Editor {
         }
     }
 
+    pub fn stage_selected_hunks(&mut self, _: &StageSelectedHunks, cx: &mut ViewContext<Self>) {
+        self.update_index_of_selected_rows(true, cx);
+    }
+
+    pub fn unstage_selected_hunks(&mut self, _: &UnstageSelectedHunks, cx: &mut ViewContext<Self>) {
+        self.update_index_of_selected_rows(false, cx);
+    }
+
+    fn update_index_of_selected_rows(&mut self, stage: bool, cx: &mut ViewContext<Self>) {
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let ranges = self
+            .selections
+            .all::<Point>(cx)
+            .into_iter()
+            .map(|selection| {
+                Point::new(selection.start.row, 0)
+                    ..Point::new(
+                        selection.end.row,
+                        multi_buffer_snapshot.line_len(selection.end.row),
+                    )
+            })
+            .collect();
+        self.update_index_of_rows(ranges, stage, cx);
+    }
+
+    /// Stages or unstages the hunks intersecting the rows of the given ranges.
+    fn update_index_of_rows(
+        &mut self,
+        ranges: Vec<Range<Point>>,
+        stage: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let mut rows_by_buffer = HashMap::<BufferId, (Model<Buffer>, Vec<Range<u32>>)>::default();
+        let multi_buffer = self.buffer.read(cx);
+        for range in ranges {
+            for (buffer, range, _) in multi_buffer.range_to_buffer_ranges(range, cx) {
+                let buffer_snapshot = buffer.read(cx).snapshot();
+                let rows = buffer_snapshot.offset_to_point(range.start).row
+                    ..buffer_snapshot.offset_to_point(range.end).row + 1;
+                rows_by_buffer
+                    .entry(buffer_snapshot.remote_id())
+                    .or_insert_with(|| (buffer.clone(), Vec::new()))
+                    .1
+                    .push(rows);
+            }
+        }
+
+        for (_, (buffer, rows)) in rows_by_buffer {
+            project.update(cx, |project, cx| {
+                if stage {
+                    project.stage_hunks(&buffer, rows, cx)
+                } else {
+                    project.unstage_hunks(&buffer, rows, cx)
+                }
+                .detach_and_log_err(cx);
+            });
+        }
+    }
+
     fn gather_revert_changes(
         &mut self,
         selections: &[Selection<Anchor>],
@@ -7247,10 +7421,36 @@ impl Editor {
         &mut self,
         _: &SelectLargerSyntaxNode,
         cx: &mut ViewContext<Self>,
+    ) {
+        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
+        let Some(fetch_selection_ranges) = fetch_selection_ranges(self, &old_selections, cx) else {
+            self.select_larger_ranges(old_selections, &SelectionRanges::default(), cx);
+            return;
+        };
+
+        self.select_larger_syntax_node_task = Some(cx.spawn(|editor, mut cx| async move {
+            let selection_ranges = fetch_selection_ranges.await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    // Drop the ranges if the selections changed while they were being requested.
+                    if editor.selections.all::<usize>(cx)[..] == old_selections[..] {
+                        editor.select_larger_ranges(old_selections, &selection_ranges, cx);
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    /// Expands each selection to the smallest enclosing range reported by a language server,
+    /// or to its enclosing syntax node if there is none.
+    fn select_larger_ranges(
+        &mut self,
+        old_selections: Box<[Selection<usize>]>,
+        selection_ranges: &SelectionRanges,
+        cx: &mut ViewContext<Self>,
     ) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
-        let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
 
         let mut stack = mem::take(&mut self.select_larger_syntax_node_stack);
         let mut selected_larger_node = false;
@@ -7259,8 +7459,18 @@ impl Editor {
             .map(|selection| {
                 let old_range = selection.start..selection.end;
                 let mut new_range = old_range.clone();
-                while let Some(containing_range) =
-                    buffer.range_for_syntax_ancestor(new_range.clone())
+                let mut lsp_ranges = selection_ranges
+                    .get(&selection.id)
+                    .map(|ranges| selection_ranges_in_excerpt(&buffer, old_range.clone(), ranges))
+                    .unwrap_or_default()
+                    .into_iter();
+                while let Some(containing_range) = lsp_ranges
+                    .find(|range| {
+                        range.start <= new_range.start
+                            && range.end >= new_range.end
+                            && *range != new_range
+                    })
+                    .or_else(|| buffer.range_for_syntax_ancestor(new_range.clone()))
                 {
                     new_range = containing_range;
                     if !display_map.intersects_fold(new_range.start)
@@ -7701,6 +7911,32 @@ impl Editor {
                     cx.open_url(&url);
                     Task::ready(Ok(None))
                 }
+                HoverLink::File(path, position) => {
+                    if let Some(workspace) = self.workspace() {
+                        let open_path = workspace
+                            .update(cx, |workspace, cx| workspace.open_abs_path(path, false, cx));
+                        cx.spawn(|_, mut cx| async move {
+                            let item = open_path.await?;
+                            if let Some((position, editor)) =
+                                position.zip(item.downcast::<Editor>())
+                            {
+                                editor.update(&mut cx, |editor, cx| {
+                                    let position = editor
+                                        .buffer
+                                        .read(cx)
+                                        .snapshot(cx)
+                                        .clip_point(position, Bias::Left);
+                                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                        s.select_ranges([position..position])
+                                    });
+                                })?;
+                            }
+                            anyhow::Ok(())
+                        })
+                        .detach_and_log_err(cx);
+                    }
+                    Task::ready(Ok(None))
+                }
             };
             cx.spawn(|editor, mut cx| async move {
                 let target = target_task.await.context("target resolution task")?;
@@ -7773,7 +8009,7 @@ impl Editor {
                                     )
                                 }),
                                 HoverLink::InlayHint(_, _) => None,
-                                HoverLink::Url(_) => None,
+                                HoverLink::Url(_) | HoverLink::File(..) => None,
                             })
                             .unwrap_or(tab_kind.to_string());
                         let location_tasks = definitions
@@ -7783,7 +8019,7 @@ impl Editor {
                                 HoverLink::InlayHint(lsp_location, server_id) => {
                                     editor.compute_target_location(lsp_location, server_id, cx)
                                 }
-                                HoverLink::Url(_) => Task::ready(Ok(None)),
+                                HoverLink::Url(_) | HoverLink::File(..) => Task::ready(Ok(None)),
                             })
                             .collect::<Vec<_>>();
                         (title, location_tasks, editor.workspace().clone())
@@ -8538,6 +8774,36 @@ impl Editor {
         }
     }
 
+    pub fn fold_all(&mut self, _: &actions::FoldAll, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let fold_ranges = (0..=display_map.buffer_snapshot.max_buffer_row())
+            .filter_map(|row| display_map.foldable_range(row))
+            .collect::<Vec<_>>();
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    pub fn fold_level(&mut self, fold_level: &FoldLevel, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let mut enclosing_ranges = Vec::<Range<Point>>::new();
+        let mut fold_ranges = Vec::new();
+        for row in 0..=display_map.buffer_snapshot.max_buffer_row() {
+            let Some(fold_range) = display_map.foldable_range(row) else {
+                continue;
+            };
+            while enclosing_ranges.last().map_or(false, |enclosing_range| {
+                enclosing_range.end < fold_range.end
+            }) {
+                enclosing_ranges.pop();
+            }
+            if enclosing_ranges.len() + 1 == fold_level.0 as usize {
+                fold_ranges.push(fold_range.clone());
+            }
+            enclosing_ranges.push(fold_range);
+        }
+
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
     pub fn unfold_lines(&mut self, _: &UnfoldLines, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = &display_map.buffer_snapshot;
@@ -8575,6 +8841,11 @@ impl Editor {
         self.unfold_ranges(std::iter::once(intersection_range), true, autoscroll, cx)
     }
 
+    pub fn unfold_all(&mut self, _: &actions::UnfoldAll, cx: &mut ViewContext<Self>) {
+        let len = self.buffer.read(cx).snapshot(cx).len();
+        self.unfold_ranges([0..len], true, true, cx);
+    }
+
     pub fn fold_selected_ranges(&mut self, _: &FoldSelectedRanges, cx: &mut ViewContext<Self>) {
         let selections = self.selections.all::<Point>(cx);
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
@@ -8605,6 +8876,7 @@ impl Editor {
                 self.request_autoscroll(Autoscroll::fit(), cx);
             }
 
+            self.serialize_folds(cx);
             cx.notify();
         }
     }
@@ -8624,10 +8896,81 @@ impl Editor {
                 self.request_autoscroll(Autoscroll::fit(), cx);
             }
 
+            self.serialize_folds(cx);
             cx.notify();
         }
     }
 
+    /// Stores the folds of a file-backed singleton editor, so they can be restored when the file is reopened.
+    fn serialize_folds(&mut self, cx: &mut ViewContext<Self>) {
+        let Some((_, workspace_id)) = self.workspace else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let Some(path) = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return;
+        };
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer_snapshot = &display_map.buffer_snapshot;
+        let folds = display_map
+            .folds_in_range(0..buffer_snapshot.len())
+            .map(|fold| {
+                (
+                    fold.range.start.to_offset(buffer_snapshot),
+                    fold.range.end.to_offset(buffer_snapshot),
+                )
+            })
+            .collect::<Vec<_>>();
+        cx.background_executor()
+            .spawn(async move { DB.save_folds(workspace_id, path, folds).await.log_err() })
+            .detach();
+    }
+
+    pub(crate) fn read_folds_from_db(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let Some(path) = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return;
+        };
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        if display_map
+            .folds_in_range(0..display_map.buffer_snapshot.len())
+            .next()
+            .is_some()
+        {
+            return;
+        }
+        let len = display_map.buffer_snapshot.len();
+        if let Some(folds) = DB.get_folds(workspace_id, path).log_err() {
+            let fold_ranges = folds
+                .into_iter()
+                .filter(|(start, end)| start < end && *end <= len)
+                .map(|(start, end)| start..end)
+                .collect::<Vec<_>>();
+            self.display_map
+                .update(cx, |map, cx| map.fold(fold_ranges, cx));
+        }
+    }
+
     pub fn set_gutter_hovered(&mut self, hovered: bool, cx: &mut ViewContext<Self>) {
         if hovered != self.gutter_hovered {
             self.gutter_hovered = hovered;
@@ -8726,6 +9069,10 @@ impl Editor {
         if self.show_wrap_guides == Some(false) {
             return wrap_guides;
         }
+        if let Some(columns) = &self.wrap_guides_override {
+            wrap_guides.extend(columns.iter().map(|column| (*column, true)));
+            return wrap_guides;
+        }
 
         let settings = self.buffer.read(cx).settings_at(0, cx);
         if settings.show_wrap_guides {
@@ -8814,6 +9161,12 @@ impl Editor {
         cx.notify();
     }
 
+    /// Shows wrap guides at the given columns instead of the ones of the language settings.
+    pub fn set_wrap_guides(&mut self, columns: Vec<usize>, cx: &mut ViewContext<Self>) {
+        self.wrap_guides_override = Some(columns);
+        cx.notify();
+    }
+
     pub fn reveal_in_finder(&mut self, _: &RevealInFinder, cx: &mut ViewContext<Self>) {
         if let Some(buffer) = self.buffer().read(cx).as_singleton() {
             if let Some(file) = buffer.read(cx).file().and_then(|f| f.as_local()) {
@@ -9299,6 +9652,32 @@ impl Editor {
         cx.notify();
     }
 
+    /// Highlights ranges of a buffer with styles of their own, such as the colors of the output of a
+    /// program, replacing the previous ones of that buffer. The ranges must be sorted and must not overlap.
+    pub fn set_styled_highlights(
+        &mut self,
+        buffer: &Model<Buffer>,
+        highlights: &[(Range<text::Anchor>, HighlightStyle)],
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut editor_highlights = Vec::new();
+        for (excerpt_id, _) in multi_buffer.excerpts_for_buffer(buffer, cx) {
+            editor_highlights.extend(highlights.iter().filter_map(|(range, style)| {
+                let start = multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                let end = multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                Some((start..end, *style))
+            }));
+        }
+
+        self.display_map.update(cx, |display_map, _| {
+            display_map.set_styled_highlights(buffer_id, editor_highlights)
+        });
+        cx.notify();
+    }
+
     pub(crate) fn highlight_inlays<T: 'static>(
         &mut self,
         highlights: Vec<InlayHighlight>,
@@ -9333,6 +9712,7 @@ impl Editor {
     }
 
     fn on_buffer_changed(&mut self, _: Model<MultiBuffer>, cx: &mut ViewContext<Self>) {
+        refresh_expanded_hunks_if_diff_changed(self, cx);
         cx.notify();
     }
 
@@ -9349,6 +9729,13 @@ impl Editor {
                 self.scrollbar_marker_state.dirty = true;
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                refresh_semantic_tokens(self, true, cx);
+                refresh_code_lenses(self, true, cx);
+                refresh_foldable_ranges(self, true, cx);
+                refresh_document_links(self, true, cx);
+                refresh_document_colors(self, true, cx);
+                refresh_conflicts(self, true, cx);
+                refresh_runnables(self, true, cx);
                 if self.has_active_inline_completion(cx) {
                     self.update_visible_inline_completion(cx);
                 }
@@ -9399,22 +9786,47 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_semantic_tokens(self, true, cx);
+                refresh_code_lenses(self, true, cx);
+                refresh_foldable_ranges(self, true, cx);
+                refresh_document_links(self, true, cx);
+                refresh_document_colors(self, true, cx);
+                refresh_conflicts(self, true, cx);
+                refresh_runnables(self, true, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                refresh_semantic_tokens(self, true, cx);
+                refresh_code_lenses(self, true, cx);
+                refresh_foldable_ranges(self, true, cx);
+                refresh_document_links(self, true, cx);
+                refresh_document_colors(self, true, cx);
+                refresh_conflicts(self, true, cx);
+                refresh_runnables(self, true, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
-            multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
+            multi_buffer::Event::Reparsed => {
+                refresh_foldable_ranges(self, true, cx);
+                refresh_runnables(self, true, cx);
+                cx.emit(EditorEvent::Reparsed);
+            }
             multi_buffer::Event::LanguageChanged => {
+                refresh_foldable_ranges(self, false, cx);
+                refresh_runnables(self, false, cx);
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
-            multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
+            multi_buffer::Event::Saved => {
+                // Folds move along with edits, so store their latest positions with the file.
+                self.serialize_folds(cx);
+                cx.emit(EditorEvent::Saved);
+            }
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
                 cx.emit(EditorEvent::TitleChanged)
             }
             multi_buffer::Event::DiffBaseChanged => {
+                refresh_expanded_hunks_if_diff_changed(self, cx);
                 self.scrollbar_marker_state.dirty = true;
                 cx.emit(EditorEvent::DiffBaseChanged);
                 cx.notify();
@@ -9443,6 +9855,10 @@ impl Editor {
             )),
             cx,
         );
+        refresh_semantic_tokens(self, false, cx);
+        refresh_code_lenses(self, false, cx);
+        refresh_document_colors(self, false, cx);
+        refresh_conflicts(self, false, cx);
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
@@ -10,6 +10,9 @@ pub struct EditorSettings {
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
     pub completion_documentation_secondary_query_debounce: u64,
+    pub auto_signature_help: bool,
+    pub code_lens: bool,
+    pub document_colors: bool,
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
@@ -67,6 +70,7 @@ pub struct Gutter {
     pub line_numbers: bool,
     pub code_actions: bool,
     pub folds: bool,
+    pub runnables: bool,
 }
 
 /// When to show the scrollbar in the editor.
@@ -123,6 +127,21 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 300 ms
     pub completion_documentation_secondary_query_debounce: Option<u64>,
+    /// Whether to show the signature help popover when typing a character
+    /// that the language server reports as a signature help trigger.
+    ///
+    /// Default: true
+    pub auto_signature_help: Option<bool>,
+    /// Whether to show the code lenses reported by language servers,
+    /// such as "Run test" or "3 references", above the items they refer to.
+    ///
+    /// Default: false
+    pub code_lens: Option<bool>,
+    /// Whether to show a swatch before each color literal reported by language servers,
+    /// which can be clicked to rewrite the color in another format.
+    ///
+    /// Default: true
+    pub document_colors: Option<bool>,
     /// Whether to use additional LSP queries to format (and amend) the code after
     /// every "trigger" symbol input, defined by LSP server capabilities.
     ///
@@ -217,6 +236,11 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub folds: Option<bool>,
+    /// Whether to show run buttons in the gutter next to the runnables, such as tests,
+    /// found by the runnables query of the language.
+    ///
+    /// Default: true
+    pub runnables: Option<bool>,
 }
 
 impl Settings for EditorSettings {
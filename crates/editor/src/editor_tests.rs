@@ -799,6 +799,63 @@ fn test_fold_action(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_fold_all_and_fold_level(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(
+            &"
+                impl Foo {
+                    fn a() {
+                        1
+                    }
+
+                    fn b() {
+                        2
+                    }
+                }
+            "
+            .unindent(),
+            cx,
+        );
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.fold_level(&FoldLevel(2), cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    fn a() {⋯
+                    }
+
+                    fn b() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        assert_eq!(view.display_text(cx), view.buffer.read(cx).read(cx).text());
+
+        view.fold_all(&FoldAll, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {⋯
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        assert_eq!(view.display_text(cx), view.buffer.read(cx).read(cx).text());
+    });
+}
+
 #[gpui::test]
 fn test_move_cursor(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4414,6 +4471,159 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_select_larger_syntax_node_with_lsp_selection_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state("fn a() { b!(ˇc, d) }");
+    cx.lsp
+        .handle_request::<lsp::request::SelectionRangeRequest, _, _>(|params, _| async move {
+            assert_eq!(params.positions, [lsp::Position::new(0, 12)]);
+            Ok(Some(vec![lsp::SelectionRange {
+                range: lsp::Range::new(lsp::Position::new(0, 12), lsp::Position::new(0, 13)),
+                parent: Some(Box::new(lsp::SelectionRange {
+                    range: lsp::Range::new(lsp::Position::new(0, 12), lsp::Position::new(0, 16)),
+                    parent: None,
+                })),
+            }]))
+        });
+
+    // Ranges from the language server take precedence over the macro's token tree.
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("fn a() { b!(«cˇ», d) }");
+
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("fn a() { b!(«c, dˇ») }");
+
+    // Once the language server's ranges are exhausted, syntax nodes are used instead.
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("fn a() { b!«(c, d)ˇ» }");
+
+    cx.update_editor(|editor, cx| editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx));
+    cx.assert_editor_state("fn a() { b!(«c, dˇ») }");
+}
+
+#[gpui::test]
+async fn test_linked_editing_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            linked_editing_range_provider: Some(lsp::LinkedEditingRangeServerCapabilities::Simple(
+                true,
+            )),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.lsp
+        .handle_request::<lsp::request::LinkedEditingRange, _, _>(|params, _| async move {
+            let position = params.text_document_position_params.position;
+            let ranges = if (7..=10).contains(&position.character) {
+                vec![
+                    lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 10)),
+                    lsp::Range::new(lsp::Position::new(0, 17), lsp::Position::new(0, 20)),
+                ]
+            } else {
+                Vec::new()
+            };
+            Ok(Some(lsp::LinkedEditingRanges {
+                ranges,
+                word_pattern: None,
+            }))
+        });
+
+    cx.set_state("struct Fooˇ; impl Foo {}");
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+
+    // Edits within a linked range are mirrored into the ranges linked to it, as part of the
+    // same transaction.
+    cx.update_editor(|editor, cx| editor.handle_input("Bar", cx));
+    cx.assert_editor_state("struct FooBarˇ; impl FooBar {}");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("struct Fooˇ; impl Foo {}");
+    cx.update_editor(|editor, cx| editor.backspace(&Backspace, cx));
+    cx.assert_editor_state("struct Foˇ; impl Fo {}");
+
+    // Inserting whitespace unlinks the ranges.
+    cx.update_editor(|editor, cx| editor.handle_input(" ", cx));
+    cx.assert_editor_state("struct Fo ˇ; impl Fo {}");
+    cx.update_editor(|editor, cx| editor.handle_input("x", cx));
+    cx.assert_editor_state("struct Fo xˇ; impl Fo {}");
+
+    // Edits outside of any linked range are left alone.
+    cx.set_state("struct Foo; ˇimpl Foo {}");
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.handle_input("x", cx));
+    cx.assert_editor_state("struct Foo; xˇimpl Foo {}");
+}
+
+#[gpui::test]
+async fn test_document_colors(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.lsp
+        .handle_request::<lsp::request::DocumentColor, _, _>(|_, _| async move {
+            Ok(vec![lsp::ColorInformation {
+                range: lsp::Range::new(lsp::Position::new(0, 19), lsp::Position::new(0, 26)),
+                color: lsp::Color {
+                    red: 1.,
+                    green: 0.,
+                    blue: 0.,
+                    alpha: 1.,
+                },
+            }])
+        });
+
+    cx.set_state(r##"const RED: &str = "#ff0000";ˇ"##);
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            r##"const RED: &str = "■ #ff0000";"##
+        );
+    });
+
+    // Swatches are removed when document colors are disabled.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.document_colors = Some(false);
+            });
+        })
+    });
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert_eq!(editor.display_text(cx), r##"const RED: &str = "#ff0000";"##);
+    });
+}
+
 #[gpui::test]
 async fn test_autoindent_selections(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7922,6 +8132,202 @@ async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_expanded_hunks(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let diff_base = "one\ntwo\nthree\nfour\nfive\nsix\n";
+    // Edits are modified, removed, added
+    cx.set_state("ˇone\nTWO\nthree\nfive\nsix\nseven\n");
+    cx.set_diff_base(Some(diff_base));
+    executor.run_until_parked();
+
+    cx.update_editor(|editor, cx| {
+        editor.go_to_expanded_hunk(&GoToExpandedHunk, cx);
+    });
+    cx.assert_editor_state("ˇone\nTWO\nthree\nfive\nsix\nseven\n");
+
+    cx.update_editor(|editor, cx| {
+        editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+        editor.go_to_expanded_hunk(&GoToExpandedHunk, cx);
+    });
+    cx.assert_editor_state("one\nˇTWO\nthree\nfive\nsix\nseven\n");
+    cx.update_editor(|editor, cx| editor.go_to_expanded_hunk(&GoToExpandedHunk, cx));
+    cx.assert_editor_state("one\nTWO\nthree\nˇfive\nsix\nseven\n");
+    cx.update_editor(|editor, cx| editor.go_to_expanded_hunk(&GoToExpandedHunk, cx));
+    cx.assert_editor_state("one\nTWO\nthree\nfive\nsix\nˇseven\n");
+    cx.update_editor(|editor, cx| editor.go_to_expanded_hunk(&GoToExpandedHunk, cx));
+    cx.assert_editor_state("one\nˇTWO\nthree\nfive\nsix\nseven\n");
+
+    // Collapsing a hunk keeps the other ones expanded.
+    cx.update_editor(|editor, cx| {
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+        editor.go_to_prev_expanded_hunk(&GoToPrevExpandedHunk, cx);
+    });
+    cx.assert_editor_state("one\nTWO\nthree\nfive\nsix\nˇseven\n");
+    cx.update_editor(|editor, cx| editor.go_to_expanded_hunk(&GoToExpandedHunk, cx));
+    cx.assert_editor_state("one\nTWO\nthree\nˇfive\nsix\nseven\n");
+
+    // Expanded hunks that are reverted are forgotten.
+    cx.update_editor(|editor, cx| {
+        editor.revert_selected_hunks(&RevertSelectedHunks, cx);
+    });
+    cx.set_diff_base(Some(diff_base));
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        editor.go_to_expanded_hunk(&GoToExpandedHunk, cx);
+        editor.go_to_expanded_hunk(&GoToExpandedHunk, cx);
+    });
+    cx.assert_editor_state("one\nTWO\nthree\nfour\nfive\nsix\nˇseven\n");
+
+    cx.update_editor(|editor, cx| {
+        editor.collapse_all_hunk_diffs(&CollapseAllHunkDiffs, cx);
+        editor.go_to_expanded_hunk(&GoToExpandedHunk, cx);
+    });
+    cx.assert_editor_state("one\nTWO\nthree\nfour\nfive\nsix\nˇseven\n");
+}
+
+#[gpui::test]
+async fn test_resolving_conflicts(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        one
+        <<<<<<< HEAD
+        twˇo
+        ||||||| base
+        deux
+        =======
+        dos
+        >>>>>>> branch
+        three
+        <<<<<<< HEAD
+        four
+        =======
+        ˇcuatro
+        >>>>>>> branch
+    "});
+    executor.advance_clock(Duration::from_millis(500));
+    executor.run_until_parked();
+
+    // Conflicts are resolved for every selection.
+    cx.update_editor(|editor, cx| editor.accept_both(&AcceptBoth, cx));
+    assert_eq!(
+        cx.buffer_text(),
+        indoc! {"
+            one
+            two
+            dos
+            three
+            four
+            cuatro
+        "}
+    );
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    executor.advance_clock(Duration::from_millis(500));
+    executor.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(8, 0)..Point::new(8, 0)])
+        });
+        editor.accept_ours(&AcceptOurs, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        one
+        <<<<<<< HEAD
+        two
+        ||||||| base
+        deux
+        =======
+        dos
+        >>>>>>> branch
+        ˇthree
+        <<<<<<< HEAD
+        four
+        =======
+        cuatro
+        >>>>>>> branch
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+        });
+        editor.accept_theirs(&AcceptTheirs, cx);
+    });
+    assert_eq!(
+        cx.buffer_text(),
+        indoc! {"
+            one
+            dos
+            three
+            <<<<<<< HEAD
+            four
+            =======
+            cuatro
+            >>>>>>> branch
+        "}
+    );
+}
+
+#[gpui::test]
+async fn test_runnables_after_edits(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_runnable_query(
+            r#"
+            (
+                (attribute_item (attribute (identifier) @_attribute))
+                .
+                (function_item name: (_) @run @test_name)
+                (#eq? @_attribute "test")
+                (#set! tag rust-test)
+            )
+            (
+                (function_item name: (_) @run)
+                (#set! tag rust-function)
+            )
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        ˇfn main() {}
+
+        #[test]
+        fn a() {}
+    "});
+    executor.advance_clock(Duration::from_millis(500));
+    executor.run_until_parked();
+    let runnable_rows = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            crate::runnables::runnable_display_rows(editor, &snapshot, 0..10, cx)
+                .into_iter()
+                .map(|(row, _, _)| row)
+                .collect::<Vec<_>>()
+        })
+    };
+    // Both matches of the test function are shown as one runnable.
+    assert_eq!(runnable_rows(&mut cx), [0, 3]);
+
+    cx.update_editor(|editor, cx| editor.newline(&Newline, cx));
+    executor.advance_clock(Duration::from_millis(500));
+    executor.run_until_parked();
+    assert_eq!(runnable_rows(&mut cx), [1, 4]);
+}
+
 #[test]
 fn test_split_words() {
     fn split(text: &str) -> Vec<&str> {
@@ -1,8 +1,11 @@
 use crate::{
+    bookmarks::bookmark_display_rows,
+    breakpoints::breakpoint_display_rows,
     display_map::{
         BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk, ToDisplayPoint,
         TransformBlock,
     },
+    document_colors::{color_swatch_at, show_color_presentations},
     editor_settings::{DoubleClickInMultibuffer, MultiCursorModifier, ShowScrollbar},
     git::{blame::GitBlame, diff_hunk_to_display, DisplayDiffHunk},
     hover_popover::{
@@ -10,11 +13,13 @@ use crate::{
     },
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MouseContextMenu},
+    runnables::runnable_display_rows,
     scroll::scroll_amount::ScrollAmount,
-    CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode,
-    EditorSettings, EditorSnapshot, EditorStyle, GutterDimensions, HalfPageDown, HalfPageUp,
-    HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Point, SelectPhase, Selection,
-    SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
+    signature_help, CursorShape, DisplayPoint, DocumentHighlightRead, DocumentHighlightWrite,
+    Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle, GutterDimensions,
+    HalfPageDown, HalfPageUp, HoveredCursor, LineDown, LineUp, OpenExcerpts, PageDown, PageUp,
+    Point, SelectPhase, Selection, ShowCommit, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR,
+    MAX_LINE_LEN,
 };
 use anyhow::Result;
 use collections::{BTreeMap, HashMap};
@@ -52,6 +57,7 @@ use sum_tree::Bias;
 use theme::{ActiveTheme, PlayerColor};
 use ui::{h_flex, ButtonLike, ButtonStyle, ContextMenu, Tooltip};
 use ui::{prelude::*, tooltip_container};
+use url::Url;
 use util::ResultExt;
 use workspace::item::Item;
 
@@ -260,6 +266,8 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::go_to_expanded_hunk);
+        register_action(view, cx, Editor::go_to_prev_expanded_hunk);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -285,8 +293,11 @@ impl EditorElement {
         register_action(view, cx, Editor::open_url);
         register_action(view, cx, Editor::fold);
         register_action(view, cx, Editor::fold_at);
+        register_action(view, cx, Editor::fold_all);
+        register_action(view, cx, Editor::fold_level);
         register_action(view, cx, Editor::unfold_lines);
         register_action(view, cx, Editor::unfold_at);
+        register_action(view, cx, Editor::unfold_all);
         register_action(view, cx, Editor::fold_selected_ranges);
         register_action(view, cx, Editor::show_completions);
         register_action(view, cx, Editor::toggle_code_actions);
@@ -296,6 +307,7 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_line_numbers);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, signature_help::show_signature_help);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
@@ -303,6 +315,8 @@ impl EditorElement {
         register_action(view, cx, Editor::copy_permalink_to_line);
         register_action(view, cx, Editor::open_permalink_to_line);
         register_action(view, cx, Editor::toggle_git_blame);
+        register_action(view, cx, Editor::toggle_breakpoint);
+        register_action(view, cx, Editor::toggle_bookmark);
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.format(action, cx) {
                 task.detach_and_log_err(cx);
@@ -359,6 +373,14 @@ impl EditorElement {
         register_action(view, cx, Editor::unique_lines_case_sensitive);
         register_action(view, cx, Editor::accept_partial_inline_completion);
         register_action(view, cx, Editor::revert_selected_hunks);
+        register_action(view, cx, Editor::stage_selected_hunks);
+        register_action(view, cx, Editor::unstage_selected_hunks);
+        register_action(view, cx, Editor::toggle_hunk_diff);
+        register_action(view, cx, Editor::expand_all_hunk_diffs);
+        register_action(view, cx, Editor::collapse_all_hunk_diffs);
+        register_action(view, cx, Editor::accept_ours);
+        register_action(view, cx, Editor::accept_theirs);
+        register_action(view, cx, Editor::accept_both);
     }
 
     fn register_key_listeners(&self, cx: &mut ElementContext, layout: &EditorLayout) {
@@ -440,6 +462,16 @@ impl EditorElement {
 
         let point_for_position =
             position_map.point_for_position(text_hitbox.bounds, event.position);
+        if click_count == 1 && !modifiers.modified() {
+            if let Some((buffer, color)) =
+                color_swatch_at(editor, &position_map.snapshot, &point_for_position, cx)
+            {
+                show_color_presentations(editor, buffer, color, event.position, cx);
+                cx.stop_propagation();
+                return;
+            }
+        }
+
         let position = point_for_position.previous_valid;
         if modifiers.shift && modifiers.alt {
             editor.select(
@@ -1195,6 +1227,72 @@ impl EditorElement {
         Some(button)
     }
 
+    /// Lays out the breakpoint, bookmark and run indicators of the visible rows, with breakpoints
+    /// taking precedence over bookmarks, and bookmarks over runnables, on the same row.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_line_indicators(
+        &self,
+        snapshot: &EditorSnapshot,
+        rows: Range<u32>,
+        show_runnables: bool,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        cx: &mut ElementContext,
+    ) -> Vec<AnyElement> {
+        let buttons = self.editor.update(cx, |editor, cx| {
+            let mut buttons = breakpoint_display_rows(editor, snapshot, rows.clone(), cx)
+                .into_iter()
+                .map(|(row, position)| {
+                    let button = editor.render_breakpoint_indicator(row, position, cx);
+                    (row, button)
+                })
+                .collect::<Vec<_>>();
+            for (row, position, label) in bookmark_display_rows(editor, snapshot, rows.clone(), cx)
+            {
+                if let Err(ix) = buttons.binary_search_by_key(&row, |(row, _)| *row) {
+                    let button = editor.render_bookmark_indicator(row, position, label, cx);
+                    buttons.insert(ix, (row, button));
+                }
+            }
+            if show_runnables {
+                for (row, position, status) in runnable_display_rows(editor, snapshot, rows, cx) {
+                    if let Err(ix) = buttons.binary_search_by_key(&row, |(row, _)| *row) {
+                        let button = editor.render_run_indicator(row, position, status, cx);
+                        buttons.insert(ix, (row, button));
+                    }
+                }
+            }
+            buttons
+        });
+
+        let blame_width = gutter_dimensions
+            .git_blame_entries_width
+            .unwrap_or(Pixels::ZERO);
+        buttons
+            .into_iter()
+            .map(|(row, button)| {
+                let mut button = button.into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let indicator_size = button.measure(available_space, cx);
+
+                let available_width = gutter_dimensions.margin + gutter_dimensions.left_padding
+                    - indicator_size.width
+                    - blame_width;
+                let x = blame_width + available_width / 2.;
+                let y = row as f32 * line_height - scroll_pixel_position.y
+                    + (line_height - indicator_size.height) / 2.;
+
+                button.layout(gutter_hitbox.origin + point(x, y), available_space, cx);
+                button
+            })
+            .collect()
+    }
+
     fn calculate_relative_line_numbers(
         &self,
         buffer_rows: Vec<Option<u32>>,
@@ -1945,6 +2043,67 @@ impl EditorElement {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_signature_help(
+        &self,
+        snapshot: &EditorSnapshot,
+        hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        visible_display_row_range: Range<u32>,
+        content_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_layouts: &[LineWithInvisibles],
+        line_height: Pixels,
+        em_width: Pixels,
+        cx: &mut ElementContext,
+    ) {
+        let max_size = size(
+            (120. * em_width)
+                .min(hitbox.size.width / 2.)
+                .max(MIN_POPOVER_CHARACTER_WIDTH * em_width),
+            (8. * line_height)
+                .min(hitbox.size.height / 2.)
+                .max(MIN_POPOVER_LINE_HEIGHT * line_height),
+        );
+
+        let Some((position, mut popover)) = self.editor.update(cx, |editor, cx| {
+            editor.signature_help_state.render(
+                &self.style,
+                max_size,
+                editor.workspace.as_ref().map(|(w, _)| w.clone()),
+                cx,
+            )
+        }) else {
+            return;
+        };
+
+        let position = position.to_display_point(&snapshot.display_snapshot);
+        if !visible_display_row_range.contains(&position.row()) {
+            return;
+        }
+
+        let row_layout =
+            &line_layouts[(position.row() - visible_display_row_range.start) as usize].line;
+        let x = row_layout.x_for_index(position.column() as usize) - scroll_pixel_position.x;
+        let y = position.row() as f32 * line_height - scroll_pixel_position.y;
+        let cursor_point = content_origin + point(x, y);
+
+        let available_space = size(AvailableSpace::MinContent, AvailableSpace::MinContent);
+        let popover_size = popover.measure(available_space, cx);
+        let horizontal_offset =
+            (text_hitbox.upper_right().x - (cursor_point.x + popover_size.width)).min(Pixels::ZERO);
+
+        // Prefer rendering above the cursor, so that the completions menu below it stays visible.
+        let above_y = cursor_point.y - popover_size.height - HOVER_POPOVER_GAP;
+        let popover_y = if above_y > text_hitbox.origin.y {
+            above_y
+        } else {
+            cursor_point.y + line_height + HOVER_POPOVER_GAP
+        };
+        let popover_origin = point(cursor_point.x + horizontal_offset, popover_y);
+        cx.defer_draw(popover, popover_origin, 2);
+    }
+
     fn paint_background(&self, layout: &EditorLayout, cx: &mut ElementContext) {
         cx.paint_layer(layout.hitbox.bounds, |cx| {
             let scroll_top = layout.position_map.snapshot.scroll_position().y;
@@ -2108,6 +2267,12 @@ impl EditorElement {
                 }
             });
 
+            cx.with_element_id(Some("gutter_line_indicators"), |cx| {
+                for line_indicator in layout.line_indicators.iter_mut() {
+                    line_indicator.paint(cx);
+                }
+            });
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
@@ -2958,16 +3123,23 @@ fn render_blame_entry(
         .on_mouse_down(MouseButton::Right, {
             let blame_entry = blame_entry.clone();
             move |event, cx| {
-                deploy_blame_entry_context_menu(&blame_entry, editor.clone(), event.position, cx);
+                deploy_blame_entry_context_menu(
+                    &blame_entry,
+                    permalink.clone(),
+                    editor.clone(),
+                    event.position,
+                    cx,
+                );
             }
         })
         .hover(|style| style.bg(cx.theme().colors().element_hover))
-        .when_some(permalink, |this, url| {
-            let url = url.clone();
-            this.cursor_pointer().on_click(move |_, cx| {
+        .cursor_pointer()
+        .on_click({
+            let sha = pretty_commit_id.clone();
+            move |_, cx| {
                 cx.stop_propagation();
-                cx.open_url(url.as_str())
-            })
+                cx.dispatch_action(Box::new(ShowCommit { sha: sha.clone() }))
+            }
         })
         .hoverable_tooltip(move |cx| {
             BlameEntryTooltip::new(
@@ -2982,15 +3154,27 @@ fn render_blame_entry(
 
 fn deploy_blame_entry_context_menu(
     blame_entry: &BlameEntry,
+    permalink: Option<Url>,
     editor: View<Editor>,
     position: gpui::Point<Pixels>,
     cx: &mut WindowContext<'_>,
 ) {
     let context_menu = ContextMenu::build(cx, move |this, _| {
         let sha = format!("{}", blame_entry.sha);
-        this.entry("Copy commit SHA", None, move |cx| {
-            cx.write_to_clipboard(ClipboardItem::new(sha.clone()));
-        })
+        let menu = this
+            .action(
+                "Show in Commit Log",
+                Box::new(ShowCommit { sha: sha.clone() }),
+            )
+            .entry("Copy commit SHA", None, move |cx| {
+                cx.write_to_clipboard(ClipboardItem::new(sha.clone()));
+            });
+        match permalink {
+            Some(permalink) => menu.entry("Open Permalink", None, move |cx| {
+                cx.open_url(permalink.as_str())
+            }),
+            None => menu,
+        }
     });
 
     editor.update(cx, move |editor, cx| {
@@ -3635,6 +3819,21 @@ impl Element for EditorElement {
                     }
                 }
 
+                if !cx.has_active_drag() {
+                    self.layout_signature_help(
+                        &snapshot,
+                        &hitbox,
+                        &text_hitbox,
+                        start_row..end_row,
+                        content_origin,
+                        scroll_pixel_position,
+                        &line_layouts,
+                        line_height,
+                        em_width,
+                        cx,
+                    );
+                }
+
                 if !context_menu_visible && !cx.has_active_drag() {
                     self.layout_hover_popovers(
                         &snapshot,
@@ -3652,6 +3851,19 @@ impl Element for EditorElement {
 
                 let mouse_context_menu = self.layout_mouse_context_menu(cx);
 
+                let line_indicators = cx.with_element_id(Some("gutter_line_indicators"), |cx| {
+                    self.layout_line_indicators(
+                        &snapshot,
+                        start_row..end_row,
+                        gutter_settings.runnables,
+                        line_height,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        cx,
+                    )
+                });
+
                 let fold_indicators = if gutter_settings.folds {
                     cx.with_element_id(Some("gutter_fold_indicators"), |cx| {
                         self.layout_gutter_fold_indicators(
@@ -3734,6 +3946,7 @@ impl Element for EditorElement {
                     selections,
                     mouse_context_menu,
                     code_actions_indicator,
+                    line_indicators,
                     fold_indicators,
                     tab_invisible,
                     space_invisible,
@@ -3823,6 +4036,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     max_row: u32,
     code_actions_indicator: Option<AnyElement>,
+    line_indicators: Vec<AnyElement>,
     fold_indicators: Vec<Option<AnyElement>>,
     mouse_context_menu: Option<AnyElement>,
     tab_invisible: ShapedLine,
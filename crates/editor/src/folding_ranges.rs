@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::{Task, ViewContext};
+use language::BufferId;
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+/// How long to wait after an edit before re-requesting folding ranges.
+const FOLDING_RANGES_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Pending folding range requests, one per buffer in the editor.
+#[derive(Default)]
+pub(crate) struct FoldingRangesState {
+    tasks: HashMap<BufferId, Task<Option<()>>>,
+}
+
+/// Recomputes the foldable ranges of every buffer in the editor, preferring the ranges reported
+/// by the buffer's language server over the ones matched by its language's folds query.
+///
+/// Buffers for which neither is available fold by indentation.
+pub(crate) fn refresh_foldable_ranges(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .map(|buffer| (buffer.read(cx).remote_id(), buffer))
+        .collect::<HashMap<_, _>>();
+
+    let stale_buffer_ids = editor
+        .folding_ranges_state
+        .tasks
+        .keys()
+        .filter(|buffer_id| !buffers.contains_key(buffer_id))
+        .copied()
+        .collect::<Vec<_>>();
+    if !stale_buffer_ids.is_empty() {
+        editor.display_map.update(cx, |display_map, _| {
+            for buffer_id in &stale_buffer_ids {
+                display_map.clear_foldable_ranges(*buffer_id);
+            }
+        });
+        for buffer_id in stale_buffer_ids {
+            editor.folding_ranges_state.tasks.remove(&buffer_id);
+        }
+    }
+
+    for (buffer_id, buffer) in buffers {
+        let project = editor.project.clone();
+        let task = cx.spawn(|editor, mut cx| {
+            async move {
+                if debounce {
+                    cx.background_executor()
+                        .timer(FOLDING_RANGES_DEBOUNCE)
+                        .await;
+                }
+
+                let mut ranges = Vec::new();
+                if let Some(project) = project {
+                    ranges = project
+                        .update(&mut cx, |project, cx| project.folding_ranges(&buffer, cx))?
+                        .await
+                        .log_err()
+                        .unwrap_or_default();
+                }
+                if ranges.is_empty() {
+                    let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+                    ranges = cx
+                        .background_executor()
+                        .spawn(async move {
+                            snapshot
+                                .foldable_ranges()
+                                .into_iter()
+                                .map(|range| {
+                                    snapshot.anchor_after(range.start)
+                                        ..snapshot.anchor_before(range.end)
+                                })
+                                .collect()
+                        })
+                        .await;
+                }
+
+                editor.update(&mut cx, |editor, cx| {
+                    editor.display_map.update(cx, |display_map, _| {
+                        if ranges.is_empty() {
+                            display_map.clear_foldable_ranges(buffer_id);
+                        } else {
+                            display_map.set_foldable_ranges(buffer_id, ranges);
+                        }
+                    });
+                    cx.notify();
+                })
+            }
+            .log_err()
+        });
+        editor.folding_ranges_state.tasks.insert(buffer_id, task);
+    }
+}
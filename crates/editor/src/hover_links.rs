@@ -1,4 +1,5 @@
 use crate::{
+    document_links::document_link_at,
     hover_popover::{self, InlayHover},
     Anchor, Editor, EditorSnapshot, FindAllReferences, GoToDefinition, GoToTypeDefinition, InlayId,
     PointForPosition, SelectPhase,
@@ -11,7 +12,7 @@ use project::{
     HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink,
     ResolveState,
 };
-use std::{cmp, ops::Range};
+use std::{cmp, ops::Range, path::PathBuf};
 use text::Point;
 use theme::ActiveTheme as _;
 use util::{maybe, ResultExt, TryFutureExt};
@@ -59,6 +60,8 @@ impl RangeInEditor {
 #[derive(Debug, Clone)]
 pub enum HoverLink {
     Url(String),
+    /// A file to open, at the given position if the link points at one.
+    File(PathBuf, Option<Point>),
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
 }
@@ -506,13 +509,26 @@ pub fn show_link_definition(
         editor.hide_hovered_link(cx)
     }
     let project = editor.project.clone();
+    let document_link = match &trigger_point {
+        TriggerPoint::Text(_) => document_link_at(editor, &buffer, buffer_position, cx),
+        TriggerPoint::InlayHint(..) => None,
+    };
 
     let snapshot = snapshot.buffer_snapshot.clone();
     hovered_link_state.task = Some(cx.spawn(|this, mut cx| {
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some((link_range, link)) = document_link {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(&mut cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -703,6 +719,7 @@ mod tests {
     use indoc::indoc;
     use language::language_settings::InlayHintSettings;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
+    use std::time::Duration;
     use util::assert_set_eq;
     use workspace::item::Item;
 
@@ -1218,6 +1235,43 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.lsp
+            .handle_request::<lsp::request::DocumentLinkRequest, _, _>(|_, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 9)),
+                    target: Some(lsp::Url::parse("https://docs.rs/serde").unwrap()),
+                    tooltip: None,
+                    data: None,
+                }]))
+            });
+
+        cx.set_state(indoc! {"use serde;ˇ"});
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.executor().run_until_parked();
+
+        let screen_coord = cx.pixel_position(indoc! {"use seˇrde;"});
+        cx.simulate_mouse_move(screen_coord, Modifiers::secondary_key());
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"use «serdeˇ»;"});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -361,7 +361,7 @@ fn show_hover(
     editor.hover_state.info_task = Some(task);
 }
 
-async fn parse_blocks(
+pub(crate) async fn parse_blocks(
     blocks: &[HoverBlock],
     language_registry: &Arc<LanguageRegistry>,
     language: Option<Arc<Language>>,
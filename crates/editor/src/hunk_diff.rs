@@ -0,0 +1,520 @@
+use std::{mem, ops::Range, sync::Arc};
+
+use ::git::diff::{DiffHunk, DiffHunkStatus};
+use collections::{HashMap, HashSet};
+use gpui::{Div, View, ViewContext, WeakView};
+use language::{language_settings::SoftWrap, Buffer, BufferId, Language, LanguageRegistry, Point};
+use multi_buffer::{Anchor, MultiBuffer, MultiBufferSnapshot, ToPoint as _};
+use ui::prelude::*;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    scroll::Autoscroll,
+    CollapseAllHunkDiffs, Editor, EditorMode, ExpandAllHunkDiffs, GoToExpandedHunk,
+    GoToPrevExpandedHunk, ToggleHunkDiff,
+};
+
+/// The git diff hunks of an editor that show the text they deleted inline, along with the blocks
+/// displaying it.
+#[derive(Default)]
+pub(crate) struct ExpandedHunks {
+    /// Whether every hunk is expanded, including the ones appearing after the expansion.
+    all: bool,
+    /// Positions within the hunks that were expanded one by one. A hunk stays expanded as long as
+    /// it contains one of them, so that expansions survive the recomputation of the diff.
+    positions: Vec<Anchor>,
+    /// The currently expanded hunks, in order.
+    hunks: Vec<ExpandedHunk>,
+    git_diff_update_count: usize,
+}
+
+impl ExpandedHunks {
+    fn is_empty(&self) -> bool {
+        !self.all && self.positions.is_empty()
+    }
+}
+
+/// An expanded hunk, along with the blocks displaying the text it deleted. Its blocks are kept as
+/// long as the diff keeps the hunk unchanged.
+struct ExpandedHunk {
+    /// The rows added by the hunk, from the start of the first one to the start of the last one.
+    rows: Range<Anchor>,
+    row_count: u32,
+    buffer_id: BufferId,
+    status: DiffHunkStatus,
+    deleted_text: String,
+    blocks: Vec<BlockId>,
+}
+
+/// Blocks are at most `u8::MAX` rows high, so the deleted text of taller hunks is split across
+/// several blocks.
+const MAX_BLOCK_LINES: usize = u8::MAX as usize;
+
+struct ExpandedHunkRows;
+
+#[derive(Clone, Copy)]
+enum HunkAction {
+    Stage,
+    Revert,
+    Collapse,
+}
+
+impl Editor {
+    pub fn toggle_hunk_diff(&mut self, _: &ToggleHunkDiff, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut hunks = Vec::<DiffHunk<u32>>::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let row = selection.head().row;
+            if let Some(hunk) = hunk_at_row(&snapshot, row) {
+                if !hunks
+                    .iter()
+                    .any(|other| other.associated_range == hunk.associated_range)
+                {
+                    hunks.push(hunk);
+                }
+            }
+        }
+        for hunk in hunks {
+            self.toggle_hunk_expansion(&hunk, &snapshot);
+        }
+        refresh_expanded_hunks(self, cx);
+    }
+
+    pub fn expand_all_hunk_diffs(&mut self, _: &ExpandAllHunkDiffs, cx: &mut ViewContext<Self>) {
+        self.expanded_hunks.all = true;
+        refresh_expanded_hunks(self, cx);
+    }
+
+    pub fn collapse_all_hunk_diffs(
+        &mut self,
+        _: &CollapseAllHunkDiffs,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.expanded_hunks.all = false;
+        self.expanded_hunks.positions.clear();
+        refresh_expanded_hunks(self, cx);
+    }
+
+    pub fn go_to_expanded_hunk(&mut self, _: &GoToExpandedHunk, cx: &mut ViewContext<Self>) {
+        self.go_to_expanded_hunk_in_direction(true, cx);
+    }
+
+    pub fn go_to_prev_expanded_hunk(
+        &mut self,
+        _: &GoToPrevExpandedHunk,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.go_to_expanded_hunk_in_direction(false, cx);
+    }
+
+    fn go_to_expanded_hunk_in_direction(&mut self, next: bool, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let head_row = self.selections.newest::<Point>(cx).head().row;
+        let rows = self
+            .expanded_hunks
+            .hunks
+            .iter()
+            .map(|hunk| hunk.rows.start.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        let row = if next {
+            rows.iter()
+                .find(|row| **row > head_row)
+                .or_else(|| rows.first())
+        } else {
+            rows.iter()
+                .rev()
+                .find(|row| **row < head_row)
+                .or_else(|| rows.last())
+        };
+        if let Some(row) = row {
+            let point = Point::new(*row, 0);
+            // Centering keeps the deleted lines displayed above the hunk in view.
+            self.change_selections(Some(Autoscroll::center()), cx, |s| {
+                s.select_ranges([point..point])
+            });
+        }
+    }
+
+    /// Collapses the hunk if it is expanded, or expands it otherwise.
+    fn toggle_hunk_expansion(&mut self, hunk: &DiffHunk<u32>, snapshot: &MultiBufferSnapshot) {
+        let expanded_hunks = &mut self.expanded_hunks;
+        if expanded_hunks.all {
+            // Keep the other hunks expanded, one by one.
+            expanded_hunks.all = false;
+            expanded_hunks.positions = snapshot
+                .git_diff_hunks_in_range(0..u32::MAX)
+                .filter(|other| other.associated_range != hunk.associated_range)
+                .map(|other| snapshot.anchor_before(Point::new(other.associated_range.start, 0)))
+                .collect();
+            return;
+        }
+
+        let positions_len = expanded_hunks.positions.len();
+        expanded_hunks
+            .positions
+            .retain(|position| !hunk_contains_row(hunk, position.to_point(snapshot).row));
+        if expanded_hunks.positions.len() == positions_len {
+            expanded_hunks
+                .positions
+                .push(snapshot.anchor_before(Point::new(hunk.associated_range.start, 0)));
+        }
+    }
+
+    fn apply_hunk_action(
+        &mut self,
+        position: Anchor,
+        action: HunkAction,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let Some(hunk) = hunk_at_row(&snapshot, position.to_point(&snapshot).row) else {
+            return;
+        };
+        match action {
+            HunkAction::Stage => {
+                let start = Point::new(hunk.associated_range.start, 0);
+                let end = if hunk.associated_range.is_empty() {
+                    start
+                } else {
+                    let row = hunk.associated_range.end - 1;
+                    Point::new(row, snapshot.line_len(row))
+                };
+                self.update_index_of_rows(vec![start..end], true, cx);
+            }
+            HunkAction::Revert => {
+                let mut revert_changes = HashMap::default();
+                self.buffer.update(cx, |multi_buffer, cx| {
+                    Self::prepare_revert_change(&mut revert_changes, multi_buffer, &hunk, cx);
+                });
+                self.apply_revert_changes(revert_changes, cx);
+            }
+            HunkAction::Collapse => {
+                self.toggle_hunk_expansion(&hunk, &snapshot);
+                refresh_expanded_hunks(self, cx);
+            }
+        }
+    }
+}
+
+/// Re-renders the expanded hunks when the diff of the buffers was recomputed since they were last
+/// rendered.
+pub(crate) fn refresh_expanded_hunks_if_diff_changed(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.expanded_hunks.is_empty() && editor.expanded_hunks.hunks.is_empty() {
+        return;
+    }
+    let git_diff_update_count = editor.buffer.read(cx).snapshot(cx).git_diff_update_count();
+    if git_diff_update_count != editor.expanded_hunks.git_diff_update_count {
+        refresh_expanded_hunks(editor, cx);
+    }
+}
+
+/// Updates the blocks showing the deleted text of the expanded hunks, replacing the ones of the hunks
+/// that changed, and highlights the rows they added.
+fn refresh_expanded_hunks(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_row_highlights::<ExpandedHunkRows>();
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    editor.expanded_hunks.git_diff_update_count = snapshot.git_diff_update_count();
+    let all_hunks = snapshot
+        .git_diff_hunks_in_range(0..u32::MAX)
+        .collect::<Vec<_>>();
+
+    // Forget the positions whose hunk is gone, for instance after it was reverted or committed.
+    editor.expanded_hunks.positions.retain(|position| {
+        let row = position.to_point(&snapshot).row;
+        all_hunks.iter().any(|hunk| hunk_contains_row(hunk, row))
+    });
+    let expanded_rows = editor
+        .expanded_hunks
+        .positions
+        .iter()
+        .map(|position| position.to_point(&snapshot).row)
+        .collect::<Vec<_>>();
+    let all = editor.expanded_hunks.all;
+    let expanded_hunks = all_hunks.into_iter().filter(|hunk| {
+        all || expanded_rows
+            .iter()
+            .any(|row| hunk_contains_row(hunk, *row))
+    });
+
+    let languages = editor
+        .project
+        .as_ref()
+        .map(|project| project.read(cx).languages().clone());
+    let created_background = cx.theme().status().created_background;
+    let deleted_background = cx.theme().status().deleted_background;
+    let editor_handle = cx.view().downgrade();
+    let max_point = snapshot.max_point();
+    let mut old_hunks = mem::take(&mut editor.expanded_hunks.hunks);
+    let mut hunks = Vec::new();
+    let mut blocks = Vec::new();
+    for hunk in expanded_hunks {
+        let rows = hunk.associated_range.clone();
+        let start = Point::new(rows.start.min(max_point.row), 0);
+        let hunk_start = snapshot.anchor_before(start);
+        let hunk_end = if rows.is_empty() {
+            hunk_start
+        } else {
+            snapshot.anchor_before(Point::new(rows.end - 1, 0))
+        };
+        if !rows.is_empty() {
+            editor.highlight_rows::<ExpandedHunkRows>(
+                hunk_start..hunk_end,
+                Some(created_background),
+                cx,
+            );
+        }
+
+        let buffer = editor
+            .buffer
+            .read(cx)
+            .buffer(hunk.buffer_id)
+            .map(|buffer| buffer.read(cx));
+        let deleted_text = buffer
+            .and_then(|buffer| buffer.diff_base())
+            .and_then(|diff_base| diff_base.get(hunk.diff_base_byte_range.clone()))
+            .unwrap_or_default()
+            .trim_end_matches('\n')
+            .to_string();
+        let row_count = rows.len() as u32;
+        let unchanged_hunk = old_hunks.iter().position(|old_hunk| {
+            old_hunk.buffer_id == hunk.buffer_id
+                && old_hunk.status == hunk.status()
+                && old_hunk.row_count == row_count
+                && old_hunk.rows.start.to_point(&snapshot).row == start.row
+                && old_hunk.deleted_text == deleted_text
+        });
+        if let Some(ix) = unchanged_hunk {
+            hunks.push(old_hunks.swap_remove(ix));
+            continue;
+        }
+        let Some(buffer) = buffer else {
+            continue;
+        };
+
+        let language = buffer.language().cloned();
+        let deleted_lines = if hunk.diff_base_byte_range.is_empty() {
+            Vec::new()
+        } else if deleted_text.is_empty() {
+            vec![""]
+        } else {
+            deleted_text.lines().collect()
+        };
+        // The first block starts with a line holding the buttons acting on the hunk.
+        let mut chunks = Vec::new();
+        let mut chunk_start = 0;
+        let mut chunk_len = MAX_BLOCK_LINES - 1;
+        while chunk_start < deleted_lines.len() {
+            let chunk_end = (chunk_start + chunk_len).min(deleted_lines.len());
+            chunks.push(&deleted_lines[chunk_start..chunk_end]);
+            chunk_start = chunk_end;
+            chunk_len = MAX_BLOCK_LINES;
+        }
+        let mut deleted_editors = chunks
+            .into_iter()
+            .map(|lines| {
+                let editor = deleted_text_editor(
+                    lines.join("\n"),
+                    language.clone(),
+                    languages.clone(),
+                    lines.len(),
+                    deleted_background,
+                    cx,
+                );
+                (editor, lines.len())
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let (position, disposition) = if rows.start > max_point.row {
+            (snapshot.anchor_after(max_point), BlockDisposition::Below)
+        } else {
+            (hunk_start, BlockDisposition::Above)
+        };
+        let first_deleted_editor = deleted_editors.next();
+        let mut hunk_blocks = vec![BlockProperties {
+            position,
+            height: 1 + first_deleted_editor.as_ref().map_or(0, |(_, lines)| *lines) as u8,
+            style: BlockStyle::Flex,
+            render: render_hunk_block(
+                editor_handle.clone(),
+                hunk_start,
+                hunk.status(),
+                rows.len(),
+                deleted_lines.len(),
+                first_deleted_editor,
+            ),
+            disposition,
+        }];
+        hunk_blocks.extend(
+            deleted_editors.map(|(deleted_editor, lines)| BlockProperties {
+                position,
+                height: lines as u8,
+                style: BlockStyle::Flex,
+                render: Box::new(move |cx: &mut BlockContext| {
+                    render_deleted_text(deleted_editor.clone(), lines, cx).into_any_element()
+                }),
+                disposition,
+            }),
+        );
+        blocks.push((hunks.len(), hunk_blocks));
+        hunks.push(ExpandedHunk {
+            rows: hunk_start..hunk_end,
+            row_count,
+            buffer_id: hunk.buffer_id,
+            status: hunk.status(),
+            deleted_text,
+            blocks: Vec::new(),
+        });
+    }
+
+    let old_blocks = old_hunks
+        .into_iter()
+        .flat_map(|old_hunk| old_hunk.blocks)
+        .collect::<HashSet<_>>();
+    if !old_blocks.is_empty() {
+        editor.remove_blocks(old_blocks, None, cx);
+    }
+    let block_counts = blocks
+        .iter()
+        .map(|(ix, hunk_blocks)| (*ix, hunk_blocks.len()))
+        .collect::<Vec<_>>();
+    let mut block_ids = editor
+        .insert_blocks(
+            blocks.into_iter().flat_map(|(_, hunk_blocks)| hunk_blocks),
+            None,
+            cx,
+        )
+        .into_iter();
+    for (ix, count) in block_counts {
+        hunks[ix].blocks = block_ids.by_ref().take(count).collect();
+    }
+    editor.expanded_hunks.hunks = hunks;
+    cx.notify();
+}
+
+/// Returns the hunk of the diff that the row belongs to, deleted hunks belonging to the row
+/// following the deleted lines.
+fn hunk_at_row(snapshot: &MultiBufferSnapshot, row: u32) -> Option<DiffHunk<u32>> {
+    snapshot
+        .git_diff_hunks_in_range(row.saturating_sub(1)..row + 1)
+        .find(|hunk| hunk_contains_row(hunk, row))
+}
+
+fn hunk_contains_row(hunk: &DiffHunk<u32>, row: u32) -> bool {
+    if hunk.status() == DiffHunkStatus::Removed {
+        hunk.associated_range.start == row
+    } else {
+        hunk.associated_range.contains(&row)
+    }
+}
+
+/// Creates a read-only editor showing the deleted text of a hunk, syntax highlighted with the
+/// language of its buffer.
+fn deleted_text_editor(
+    text: String,
+    language: Option<Arc<Language>>,
+    languages: Option<Arc<LanguageRegistry>>,
+    line_count: usize,
+    background: Hsla,
+    cx: &mut ViewContext<Editor>,
+) -> View<Editor> {
+    cx.new_view(|cx| {
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            if let Some(languages) = languages {
+                buffer.set_language_registry(languages);
+            }
+            buffer.set_language(language, cx);
+            buffer
+        });
+        let multi_buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let mut editor = Editor::new(
+            EditorMode::AutoHeight {
+                max_lines: line_count,
+            },
+            multi_buffer,
+            None,
+            cx,
+        );
+        editor.set_read_only(true);
+        editor.set_show_gutter(false, cx);
+        editor.set_soft_wrap_mode(SoftWrap::None, cx);
+        editor.highlight_rows::<ExpandedHunkRows>(
+            Anchor::min()..Anchor::max(),
+            Some(background),
+            cx,
+        );
+        editor
+    })
+}
+
+/// Renders the first block of a hunk, with the buttons acting on it and the first lines it deleted.
+fn render_hunk_block(
+    editor: WeakView<Editor>,
+    hunk_start: Anchor,
+    status: DiffHunkStatus,
+    added_lines: usize,
+    deleted_lines: usize,
+    deleted_text: Option<(View<Editor>, usize)>,
+) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        let button = |label: &'static str, action: HunkAction, cx: &BlockContext| {
+            let editor = editor.clone();
+            Button::new((label, cx.block_id), label)
+                .label_size(LabelSize::Small)
+                .size(ButtonSize::Compact)
+                .style(ButtonStyle::Subtle)
+                .on_click(move |_, cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.apply_hunk_action(hunk_start, action, cx)
+                        })
+                        .ok();
+                })
+        };
+        let summary = match status {
+            DiffHunkStatus::Added => format!("+{added_lines}"),
+            DiffHunkStatus::Modified => format!("-{deleted_lines} +{added_lines}"),
+            DiffHunkStatus::Removed => format!("-{deleted_lines}"),
+        };
+        let text_x = cx.gutter_dimensions.width + cx.gutter_dimensions.margin;
+        let line_height = cx.line_height;
+
+        v_flex()
+            .id(cx.block_id)
+            .size_full()
+            .child(
+                h_flex()
+                    .h(line_height)
+                    .pl(text_x)
+                    .gap_1()
+                    .child(
+                        Label::new(summary)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(button("Stage", HunkAction::Stage, cx))
+                    .child(button("Revert", HunkAction::Revert, cx))
+                    .child(button("Collapse", HunkAction::Collapse, cx)),
+            )
+            .when_some(deleted_text.clone(), |block, (deleted_editor, lines)| {
+                block.child(render_deleted_text(deleted_editor, lines, cx))
+            })
+            .into_any_element()
+    })
+}
+
+fn render_deleted_text(deleted_editor: View<Editor>, lines: usize, cx: &mut BlockContext) -> Div {
+    div()
+        .pl(cx.gutter_dimensions.width + cx.gutter_dimensions.margin)
+        .w(cx.max_width + cx.gutter_dimensions.width)
+        .h(cx.line_height * lines as f32)
+        .bg(cx.theme().status().deleted_background)
+        .child(deleted_editor)
+}
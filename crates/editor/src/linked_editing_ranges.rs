@@ -0,0 +1,196 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{AppContext, Task, ViewContext};
+use language::{BufferId, OffsetRangeExt as _, ToOffset as _};
+use multi_buffer::ToOffset;
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+/// How long to wait after the cursors move before re-requesting linked editing ranges.
+const LINKED_EDITING_RANGES_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Groups of ranges around the cursors whose text is edited together, such as the names of
+/// an opening and a closing tag.
+#[derive(Default)]
+pub(crate) struct LinkedEditingRangesState {
+    groups: Vec<(BufferId, Vec<Range<text::Anchor>>)>,
+    fetch_task: Option<Task<Option<()>>>,
+}
+
+/// Edits mirrored into the ranges linked to the ranges being edited, keyed by buffer.
+#[derive(Default)]
+pub(crate) struct LinkedEdits(HashMap<BufferId, Vec<(Range<text::Anchor>, Arc<str>)>>);
+
+impl LinkedEdits {
+    /// Applies the mirrored edits to their buffers directly, so that they become part of the
+    /// editor's current transaction.
+    pub(crate) fn apply(self, editor: &Editor, cx: &mut ViewContext<Editor>) {
+        for (buffer_id, edits) in self.0 {
+            let Some(buffer) = editor.buffer.read(cx).buffer(buffer_id) else {
+                continue;
+            };
+            buffer.update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        }
+    }
+}
+
+/// Requests the linked editing ranges around each cursor from the language server of its
+/// buffer, falling back to the linked editing query of the buffer's language.
+pub(crate) fn refresh_linked_editing_ranges(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full || editor.pending_rename.is_some() {
+        return;
+    }
+
+    let multi_buffer = editor.buffer.read(cx);
+    let positions = editor
+        .selections
+        .disjoint_anchors()
+        .iter()
+        .filter_map(|selection| multi_buffer.text_anchor_for_position(selection.head(), cx))
+        .collect::<Vec<_>>();
+    let project = editor.project.clone();
+
+    editor.linked_editing_ranges_state.fetch_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(LINKED_EDITING_RANGES_DEBOUNCE)
+            .await;
+
+        let requests = positions
+            .into_iter()
+            .map(|(buffer, position)| {
+                let request = project.as_ref().map(|project| {
+                    project.update(&mut cx, |project, cx| {
+                        project.linked_editing_ranges(&buffer, position, cx)
+                    })
+                });
+                let mut cx = cx.clone();
+                async move {
+                    let mut ranges = match request {
+                        Some(Ok(request)) => request.await.log_err().unwrap_or_default(),
+                        _ => Vec::new(),
+                    };
+                    let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot()).ok()?;
+                    let offset = position.to_offset(&snapshot);
+                    if ranges.is_empty() {
+                        ranges = snapshot
+                            .linked_editing_ranges(offset)
+                            .into_iter()
+                            .map(|range| {
+                                snapshot.anchor_before(range.start)
+                                    ..snapshot.anchor_after(range.end)
+                            })
+                            .collect();
+                    }
+
+                    let contains_cursor = ranges.iter().any(|range| {
+                        let range = range.to_offset(&snapshot);
+                        range.start <= offset && offset <= range.end
+                    });
+                    (ranges.len() > 1 && contains_cursor).then(|| (snapshot.remote_id(), ranges))
+                }
+            })
+            .collect::<Vec<_>>();
+        let groups = join_all(requests).await.into_iter().flatten().collect();
+
+        editor
+            .update(&mut cx, |editor, _| {
+                editor.linked_editing_ranges_state.groups = groups;
+            })
+            .ok()
+    }));
+}
+
+/// Returns the edits to mirror into linked editing ranges for the given edits of the
+/// multi-buffer.
+///
+/// Edits that insert whitespace, such as the one separating a tag's name from its attributes,
+/// unlink the ranges they touch instead of being mirrored.
+pub(crate) fn linked_edits<T: ToOffset>(
+    editor: &mut Editor,
+    edits: &[(Range<T>, Arc<str>)],
+    cx: &AppContext,
+) -> LinkedEdits {
+    let mut linked_edits = LinkedEdits::default();
+    if editor.linked_editing_ranges_state.groups.is_empty() {
+        return linked_edits;
+    }
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let buffer_edits = edits
+        .iter()
+        .filter_map(|(range, text)| {
+            let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+            let excerpt = snapshot.excerpt_containing(range.clone())?;
+            Some((excerpt.buffer(), excerpt.map_range_to_buffer(range), text))
+        })
+        .collect::<Vec<_>>();
+
+    let groups = &mut editor.linked_editing_ranges_state.groups;
+    let mut unlinked_group_ixs = Vec::new();
+    for (buffer, edit_range, text) in &buffer_edits {
+        let buffer_id = buffer.remote_id();
+        for (group_ix, (group_buffer_id, ranges)) in groups.iter().enumerate() {
+            if *group_buffer_id != buffer_id {
+                continue;
+            }
+            let ranges = ranges
+                .iter()
+                .map(|range| range.to_offset(buffer))
+                .collect::<Vec<_>>();
+            let Some(edited_ix) = ranges
+                .iter()
+                .position(|range| range.start <= edit_range.start && edit_range.end <= range.end)
+            else {
+                continue;
+            };
+
+            let edited_range = ranges[edited_ix].clone();
+            let edited_text = || {
+                buffer
+                    .text_for_range(edited_range.clone())
+                    .flat_map(str::chars)
+            };
+            let ranges_match = ranges.iter().all(|range| {
+                buffer
+                    .text_for_range(range.clone())
+                    .flat_map(str::chars)
+                    .eq(edited_text())
+            });
+            if !ranges_match || text.chars().any(char::is_whitespace) {
+                unlinked_group_ixs.push(group_ix);
+                break;
+            }
+
+            for (ix, range) in ranges.iter().enumerate() {
+                if ix == edited_ix {
+                    continue;
+                }
+                let start = range.start + (edit_range.start - edited_range.start);
+                let end = range.start + (edit_range.end - edited_range.start);
+                // Skip ranges that are already being edited, e.g. with a cursor in each of them.
+                let already_edited = buffer_edits.iter().any(|(other_buffer, other_range, _)| {
+                    other_buffer.remote_id() == buffer_id
+                        && other_range.start <= end
+                        && start <= other_range.end
+                });
+                if !already_edited {
+                    linked_edits.0.entry(buffer_id).or_default().push((
+                        buffer.anchor_after(start)..buffer.anchor_before(end),
+                        (*text).clone(),
+                    ));
+                }
+            }
+            break;
+        }
+    }
+
+    unlinked_group_ixs.sort_unstable();
+    unlinked_group_ixs.dedup();
+    for group_ix in unlinked_group_ixs.into_iter().rev() {
+        groups.remove(group_ix);
+    }
+    linked_edits
+}
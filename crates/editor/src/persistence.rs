@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use anyhow::Result;
 use db::sqlez_macros::sql;
 use db::{define_connection, query};
 
@@ -15,6 +16,13 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    //
+    // editor_folds(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   start_offset: usize,
+    //   end_offset: usize,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +39,17 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE editor_folds(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
         )];
 );
 
@@ -80,4 +99,39 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    query! {
+        pub fn get_folds(workspace_id: WorkspaceId, path: PathBuf) -> Result<Vec<(usize, usize)>> {
+            SELECT start_offset, end_offset
+            FROM editor_folds
+            WHERE workspace_id = ? AND path = ?
+            ORDER BY start_offset
+        }
+    }
+
+    /// Replaces the folds stored for the file, given as offset ranges.
+    pub async fn save_folds(
+        &self,
+        workspace_id: WorkspaceId,
+        path: PathBuf,
+        folds: Vec<(usize, usize)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_folds", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM editor_folds WHERE workspace_id = ? AND path = ?
+                ))?((workspace_id, path.as_path()))?;
+
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO editor_folds(workspace_id, path, start_offset, end_offset)
+                    VALUES (?, ?, ?, ?)
+                ))?;
+                for (start, end) in folds {
+                    insert((workspace_id, path.as_path(), start, end))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
 }
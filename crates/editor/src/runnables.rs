@@ -0,0 +1,312 @@
+use std::{
+    ops::{Range, RangeInclusive},
+    path::Path,
+    time::Duration,
+};
+
+use collections::{btree_map, BTreeMap, HashMap, HashSet};
+use gpui::{AppContext, Model, Task, ViewContext};
+use language::{
+    Bias, Buffer, BufferId, BufferSnapshot, Point, RunnableRange, ToOffset as _, ToPoint as _,
+};
+use multi_buffer::Anchor;
+use project::TestResultsStore;
+use task::TestStatus;
+use ui::{prelude::*, IconButton, IconName, IconSize};
+use util::ResultExt;
+
+use crate::{
+    display_map::ToDisplayPoint, scroll::Autoscroll, DisplayPoint, Editor, EditorMode,
+    EditorSnapshot, SpawnRunnable,
+};
+
+/// How long to wait after an edit before finding the runnables of the buffers again.
+const RUNNABLES_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The runnables of the buffers of an editor, found again once a buffer is edited or reparsed,
+/// along with the status of the latest run of those that are tests, found again once test results change.
+#[derive(Default)]
+pub(crate) struct RunnablesState {
+    buffers: HashMap<BufferId, BufferRunnables>,
+    tasks: HashMap<BufferId, Task<Option<()>>>,
+}
+
+struct BufferRunnables {
+    /// The runnables starting on each row, as of when they were found, merged into one.
+    runnables: BTreeMap<u32, (text::Anchor, RunnableRange)>,
+    /// The status of the latest run of the runnable on each row, for those that are tests that ran.
+    statuses: Option<HashMap<u32, TestStatus>>,
+}
+
+impl RunnablesState {
+    pub(crate) fn test_results_changed(&mut self) {
+        for buffer_runnables in self.buffers.values_mut() {
+            buffer_runnables.statuses = None;
+        }
+    }
+
+    /// Returns the positions of the runnables of the given buffer on the given rows, with the status
+    /// of the latest run of each, finding the statuses again if the test results changed.
+    fn runnables_for_buffer(
+        &mut self,
+        buffer: &BufferSnapshot,
+        rows: RangeInclusive<u32>,
+        test_results_store: Option<&TestResultsStore>,
+        cx: &AppContext,
+    ) -> impl Iterator<Item = (&text::Anchor, Option<TestStatus>)> {
+        let buffer_runnables = self
+            .buffers
+            .get_mut(&buffer.remote_id())
+            .map(|buffer_runnables| {
+                let runnables = &buffer_runnables.runnables;
+                buffer_runnables.statuses.get_or_insert_with(|| {
+                    let abs_path = buffer
+                        .file()
+                        .and_then(|file| Some(file.as_local()?.abs_path(cx)));
+                    runnables
+                        .iter()
+                        .filter_map(|(row, (_, runnable))| {
+                            let status =
+                                test_status(runnable, abs_path.as_deref()?, test_results_store?)?;
+                            Some((*row, status))
+                        })
+                        .collect()
+                });
+                &*buffer_runnables
+            });
+        buffer_runnables
+            .into_iter()
+            .flat_map(move |buffer_runnables| {
+                let statuses = buffer_runnables.statuses.as_ref();
+                buffer_runnables
+                    .runnables
+                    .range(rows.clone())
+                    .map(move |(row, (position, _))| {
+                        (
+                            position,
+                            statuses.and_then(|statuses| statuses.get(row).copied()),
+                        )
+                    })
+            })
+    }
+}
+
+/// Finds the runnables of every buffer of the editor again.
+pub(crate) fn refresh_runnables(editor: &mut Editor, debounce: bool, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .map(|buffer| (buffer.read(cx).remote_id(), buffer))
+        .collect::<HashMap<_, _>>();
+    let stale_buffer_ids = editor
+        .runnables_state
+        .buffers
+        .keys()
+        .chain(editor.runnables_state.tasks.keys())
+        .filter(|buffer_id| !buffers.contains_key(buffer_id))
+        .copied()
+        .collect::<HashSet<_>>();
+    for buffer_id in stale_buffer_ids {
+        editor.runnables_state.tasks.remove(&buffer_id);
+        editor.runnables_state.buffers.remove(&buffer_id);
+    }
+
+    for (buffer_id, buffer) in buffers {
+        let snapshot = buffer.read(cx).snapshot();
+        let task = cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(RUNNABLES_DEBOUNCE).await;
+            }
+            let runnables = cx
+                .background_executor()
+                .spawn(async move { find_buffer_runnables(&snapshot) })
+                .await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.runnables_state.tasks.remove(&buffer_id);
+                    let had_runnables = editor.runnables_state.buffers.contains_key(&buffer_id);
+                    if runnables.is_empty() {
+                        editor.runnables_state.buffers.remove(&buffer_id);
+                        if !had_runnables {
+                            return;
+                        }
+                    } else {
+                        editor.runnables_state.buffers.insert(
+                            buffer_id,
+                            BufferRunnables {
+                                runnables,
+                                statuses: None,
+                            },
+                        );
+                    }
+                    cx.notify();
+                })
+                .log_err()
+        });
+        editor.runnables_state.tasks.insert(buffer_id, task);
+    }
+}
+
+/// Finds the runnables of the buffer, merging the tags and captures of those starting on the same row.
+fn find_buffer_runnables(buffer: &BufferSnapshot) -> BTreeMap<u32, (text::Anchor, RunnableRange)> {
+    let mut runnables = BTreeMap::<u32, (text::Anchor, RunnableRange)>::new();
+    for runnable in buffer.runnable_ranges(0..buffer.len()) {
+        let row = buffer.offset_to_point(runnable.run_range.start).row;
+        match runnables.entry(row) {
+            btree_map::Entry::Occupied(mut entry) => {
+                merge_runnable(&mut entry.get_mut().1, runnable)
+            }
+            btree_map::Entry::Vacant(entry) => {
+                let position = buffer.anchor_after(runnable.run_range.start);
+                entry.insert((position, runnable));
+            }
+        }
+    }
+    runnables
+}
+
+/// Returns the display rows in the given range on which a runnable starts, along with the
+/// position of each runnable and the status of its latest run, if it is a test that ran.
+pub(crate) fn runnable_display_rows(
+    editor: &mut Editor,
+    snapshot: &EditorSnapshot,
+    display_rows: Range<u32>,
+    cx: &AppContext,
+) -> Vec<(u32, Anchor, Option<TestStatus>)> {
+    let buffer_snapshot = &snapshot.buffer_snapshot;
+    let start = DisplayPoint::new(display_rows.start, 0).to_offset(snapshot, Bias::Left);
+    let end = if display_rows.end > snapshot.max_point().row() {
+        buffer_snapshot.len()
+    } else {
+        DisplayPoint::new(display_rows.end, 0).to_offset(snapshot, Bias::Right)
+    };
+    let test_results_store = editor
+        .project
+        .as_ref()
+        .map(|project| project.read(cx).test_results_store().read(cx));
+
+    let mut runnables = Vec::new();
+    for (excerpt_id, buffer, range) in buffer_snapshot.excerpts() {
+        let (Some(excerpt_start), Some(excerpt_end)) = (
+            buffer_snapshot.anchor_in_excerpt(excerpt_id, range.context.start),
+            buffer_snapshot.anchor_in_excerpt(excerpt_id, range.context.end),
+        ) else {
+            continue;
+        };
+        if excerpt_end.to_offset(buffer_snapshot) < start
+            || excerpt_start.to_offset(buffer_snapshot) > end
+        {
+            continue;
+        }
+        let context_rows =
+            range.context.start.to_point(buffer).row..=range.context.end.to_point(buffer).row;
+        for (position, status) in editor.runnables_state.runnables_for_buffer(
+            buffer,
+            context_rows,
+            test_results_store,
+            cx,
+        ) {
+            if position.cmp(&range.context.start, buffer).is_lt()
+                || position.cmp(&range.context.end, buffer).is_gt()
+            {
+                continue;
+            }
+            let Some(anchor) = buffer_snapshot.anchor_in_excerpt(excerpt_id, *position) else {
+                continue;
+            };
+            let row = anchor.to_display_point(snapshot).row();
+            if display_rows.contains(&row) {
+                runnables.push((row, anchor, status));
+            }
+        }
+    }
+    runnables.sort_by_key(|(row, _, _)| *row);
+    runnables.dedup_by_key(|(row, _, _)| *row);
+    runnables
+}
+
+/// Returns the runnable starting on the given row, merging the tags and captures of several
+/// matches starting on it, such as a test function matched both on its own and within its test
+/// class.
+fn runnable_on_row(snapshot: &BufferSnapshot, row: u32) -> Option<RunnableRange> {
+    let line_start = Point::new(row, 0).to_offset(snapshot);
+    let line_end = Point::new(row, snapshot.line_len(row)).to_offset(snapshot);
+    let mut runnables = snapshot.runnable_ranges(line_start..line_end);
+    let mut runnable = runnables.next()?;
+    for other in runnables {
+        merge_runnable(&mut runnable, other);
+    }
+    Some(runnable)
+}
+
+fn merge_runnable(runnable: &mut RunnableRange, other: RunnableRange) {
+    for tag in other.tags {
+        if !runnable.tags.contains(&tag) {
+            runnable.tags.push(tag);
+        }
+    }
+    for (capture_name, text) in other.extra_captures {
+        runnable.extra_captures.entry(capture_name).or_insert(text);
+    }
+}
+
+/// Returns the status of the latest run of the given runnable, if it is a test that ran.
+fn test_status(
+    runnable: &RunnableRange,
+    abs_path: &Path,
+    test_results_store: &TestResultsStore,
+) -> Option<TestStatus> {
+    let test_name = runnable.extra_captures.get("test_name")?;
+    let suite_name = runnable
+        .extra_captures
+        .get("test_module")
+        .or_else(|| runnable.extra_captures.get("test_class"));
+    let result =
+        test_results_store.result_for(abs_path, test_name, suite_name.map(String::as_str))?;
+    Some(result.status)
+}
+
+impl Editor {
+    /// Returns the runnable starting on the line of the newest cursor, along with its buffer, with
+    /// its range in that buffer's coordinates.
+    pub fn runnable_at_cursor(&self, cx: &AppContext) -> Option<(Model<Buffer>, RunnableRange)> {
+        let head = self.selections.newest::<Point>(cx).head();
+        let (buffer, position) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let snapshot = buffer.read(cx).snapshot();
+        let runnable = runnable_on_row(&snapshot, position.to_point(&snapshot).row)?;
+        Some((buffer, runnable))
+    }
+
+    pub(crate) fn render_run_indicator(
+        &self,
+        row: u32,
+        position: Anchor,
+        status: Option<TestStatus>,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        // Tests that ran show the outcome of their latest run instead of the run button.
+        let (icon, color) = match status {
+            Some(TestStatus::Passed) => (IconName::Check, Color::Success),
+            Some(TestStatus::Failed) => (IconName::XCircle, Color::Error),
+            Some(TestStatus::Skipped) => (IconName::Dash, Color::Muted),
+            None => (IconName::Play, Color::Success),
+        };
+        IconButton::new(("run_indicator", row as usize), icon)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(color)
+            .on_click(cx.listener(move |editor, _, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                    selections.select_anchor_ranges([position..position])
+                });
+                cx.focus(&editor.focus_handle);
+                cx.dispatch_action(Box::new(SpawnRunnable));
+            }))
+    }
+}
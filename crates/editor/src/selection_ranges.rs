@@ -0,0 +1,77 @@
+use std::ops::Range;
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{Model, Task, ViewContext};
+use language::{Buffer, BufferId, OffsetRangeExt as _, Selection};
+use multi_buffer::MultiBufferSnapshot;
+use util::ResultExt;
+
+use crate::Editor;
+
+/// Ranges reported by language servers around the start of each selection, ordered from the
+/// innermost to the outermost one and keyed by selection id.
+pub(crate) type SelectionRanges = HashMap<usize, Vec<Range<text::Anchor>>>;
+
+/// Requests selection ranges for the given selections from the language servers of their buffers.
+///
+/// Returns `None` when the editor isn't backed by a project.
+pub(crate) fn fetch_selection_ranges(
+    editor: &Editor,
+    selections: &[Selection<usize>],
+    cx: &mut ViewContext<Editor>,
+) -> Option<Task<SelectionRanges>> {
+    let project = editor.project.clone()?;
+
+    let multi_buffer = editor.buffer.read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+    let mut selections_by_buffer =
+        HashMap::<BufferId, (Model<Buffer>, Vec<(usize, usize)>)>::default();
+    for selection in selections {
+        let Some(excerpt) = snapshot.excerpt_containing(selection.range()) else {
+            continue;
+        };
+        let buffer_id = excerpt.buffer().remote_id();
+        let Some(buffer) = multi_buffer.buffer(buffer_id) else {
+            continue;
+        };
+        selections_by_buffer
+            .entry(buffer_id)
+            .or_insert_with(|| (buffer, Vec::new()))
+            .1
+            .push((selection.id, excerpt.map_offset_to_buffer(selection.start)));
+    }
+
+    let requests = selections_by_buffer
+        .into_values()
+        .map(|(buffer, selections)| {
+            let (selection_ids, positions): (Vec<_>, Vec<_>) = selections.into_iter().unzip();
+            let request = project.update(cx, |project, cx| {
+                project.selection_ranges(&buffer, positions, cx)
+            });
+            async move {
+                let selection_ranges = request.await.log_err().unwrap_or_default();
+                selection_ids.into_iter().zip(selection_ranges)
+            }
+        })
+        .collect::<Vec<_>>();
+    Some(cx.spawn(|_, _| async move { join_all(requests).await.into_iter().flatten().collect() }))
+}
+
+/// Maps the language server ranges of a selection into the multi-buffer, keeping only those
+/// that fit within the selection's excerpt.
+pub(crate) fn selection_ranges_in_excerpt(
+    snapshot: &MultiBufferSnapshot,
+    selection_range: Range<usize>,
+    ranges: &[Range<text::Anchor>],
+) -> Vec<Range<usize>> {
+    let Some(excerpt) = snapshot.excerpt_containing(selection_range) else {
+        return Vec::new();
+    };
+    ranges
+        .iter()
+        .map(|range| range.to_offset(excerpt.buffer()))
+        .take_while(|range| excerpt.contains_buffer_range(range.clone()))
+        .map(|range| excerpt.map_range_from_buffer(range))
+        .collect()
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::{HighlightStyle, Model, Task, ViewContext};
+use language::{language_settings::language_settings, Buffer, BufferId};
+use project::{SemanticToken, SemanticTokens};
+use theme::{ActiveTheme, SyntaxTheme};
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+/// How long to wait after an edit before re-requesting semantic tokens.
+const SEMANTIC_TOKENS_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Pending semantic token requests, one per buffer that is highlighted with semantic tokens.
+#[derive(Default)]
+pub(crate) struct SemanticTokensState {
+    tasks: HashMap<BufferId, Task<Option<()>>>,
+}
+
+/// Re-requests semantic tokens for every buffer in the editor that has them enabled,
+/// and drops the highlights of buffers that were removed or had them disabled.
+pub(crate) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let mut buffers = HashMap::default();
+    for buffer in editor.buffer.read(cx).all_buffers() {
+        let enabled = {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx).semantic_tokens
+        };
+        if enabled {
+            buffers.insert(buffer.read(cx).remote_id(), buffer);
+        }
+    }
+
+    let stale_buffer_ids = editor
+        .semantic_tokens_state
+        .tasks
+        .keys()
+        .filter(|buffer_id| !buffers.contains_key(buffer_id))
+        .copied()
+        .collect::<Vec<_>>();
+    if !stale_buffer_ids.is_empty() {
+        editor.display_map.update(cx, |display_map, _| {
+            for buffer_id in &stale_buffer_ids {
+                display_map.clear_semantic_highlights(*buffer_id);
+            }
+        });
+        for buffer_id in stale_buffer_ids {
+            editor.semantic_tokens_state.tasks.remove(&buffer_id);
+        }
+        cx.notify();
+    }
+
+    for (buffer_id, buffer) in buffers {
+        let project = project.clone();
+        let task = cx.spawn(|editor, mut cx| {
+            async move {
+                if debounce {
+                    cx.background_executor()
+                        .timer(SEMANTIC_TOKENS_DEBOUNCE)
+                        .await;
+                }
+                let tokens = project
+                    .update(&mut cx, |project, cx| project.semantic_tokens(&buffer, cx))?
+                    .await?;
+                editor.update(&mut cx, |editor, cx| {
+                    apply_semantic_tokens(editor, &buffer, tokens.unwrap_or_default(), cx);
+                })
+            }
+            .log_err()
+        });
+        editor.semantic_tokens_state.tasks.insert(buffer_id, task);
+    }
+}
+
+fn apply_semantic_tokens(
+    editor: &mut Editor,
+    buffer: &Model<Buffer>,
+    tokens: SemanticTokens,
+    cx: &mut ViewContext<Editor>,
+) {
+    let syntax_theme = cx.theme().syntax().clone();
+    let buffer_id = buffer.read(cx).remote_id();
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    let mapping = &language_settings(buffer_snapshot.language(), buffer_snapshot.file(), cx)
+        .semantic_token_highlights;
+
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let mut styles = HashMap::default();
+    let mut highlights = Vec::new();
+    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(buffer, cx) {
+        let context = excerpt_range.context;
+        // Tokens are sorted and don't overlap, so their ends are sorted too.
+        let first_token_ix = tokens.tokens.partition_point(|token| {
+            token
+                .range
+                .end
+                .cmp(&context.start, &buffer_snapshot)
+                .is_le()
+        });
+        for token in &tokens.tokens[first_token_ix..] {
+            if token
+                .range
+                .start
+                .cmp(&context.end, &buffer_snapshot)
+                .is_ge()
+            {
+                break;
+            }
+
+            let style = *styles
+                .entry((token.token_type, token.token_modifiers))
+                .or_insert_with(|| token_highlight(&tokens, token, mapping, &syntax_theme));
+            let Some(style) = style else {
+                continue;
+            };
+            let (Some(start), Some(end)) = (
+                multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+            ) else {
+                continue;
+            };
+            highlights.push((start..end, style));
+        }
+    }
+
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_semantic_highlights(buffer_id, highlights)
+    });
+    cx.notify();
+}
+
+/// Resolves the style of a token, preferring a `type.modifier` mapping over the plain `type` one.
+fn token_highlight(
+    tokens: &SemanticTokens,
+    token: &SemanticToken,
+    mapping: &HashMap<String, String>,
+    syntax_theme: &SyntaxTheme,
+) -> Option<HighlightStyle> {
+    let token_type = tokens.token_type(token)?;
+    let highlight_name = tokens
+        .token_modifiers(token)
+        .find_map(|modifier| mapping.get(&format!("{token_type}.{modifier}")))
+        .or_else(|| mapping.get(token_type))?;
+    theme_highlight(syntax_theme, highlight_name)
+}
+
+/// Looks up a highlight in the theme, falling back to less specific names,
+/// e.g. `string.regex` falls back to `string`.
+fn theme_highlight(syntax_theme: &SyntaxTheme, mut name: &str) -> Option<HighlightStyle> {
+    loop {
+        if let Some((_, style)) = syntax_theme
+            .highlights
+            .iter()
+            .find(|(highlight_name, _)| highlight_name == name)
+        {
+            return Some(*style);
+        }
+        name = &name[..name.rfind('.')?];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{hsla, FontWeight};
+
+    #[test]
+    fn test_theme_highlight_fallback() {
+        let string = HighlightStyle {
+            color: Some(hsla(0.1, 0.5, 0.5, 1.)),
+            ..Default::default()
+        };
+        let variable = HighlightStyle {
+            font_weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        };
+        let syntax_theme = SyntaxTheme {
+            highlights: vec![
+                ("string".to_string(), string),
+                ("variable".to_string(), variable),
+            ],
+        };
+
+        assert_eq!(theme_highlight(&syntax_theme, "string"), Some(string));
+        assert_eq!(theme_highlight(&syntax_theme, "string.regex"), Some(string));
+        assert_eq!(
+            theme_highlight(&syntax_theme, "variable.special.mutable"),
+            Some(variable)
+        );
+        assert_eq!(theme_highlight(&syntax_theme, "function"), None);
+    }
+}
@@ -0,0 +1,409 @@
+use crate::{
+    hover_popover::parse_blocks, Anchor, Editor, EditorSettings, EditorStyle, ShowSignatureHelp,
+};
+use gpui::{
+    div, AnyElement, FontWeight, HighlightStyle, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, Pixels, Size, StatefulInteractiveElement, Styled, StyledText, Task,
+    UnderlineStyle, ViewContext, WeakView,
+};
+use language::ParsedMarkdown;
+use project::{HoverBlock, MarkupContent, SignatureHelp};
+use settings::Settings;
+use ui::prelude::*;
+use util::TryFutureExt;
+use workspace::Workspace;
+
+/// Bindable action which requests signature help at the newest selection head.
+pub fn show_signature_help(
+    editor: &mut Editor,
+    _: &ShowSignatureHelp,
+    cx: &mut ViewContext<Editor>,
+) {
+    request_signature_help(editor, cx);
+}
+
+/// Requests signature help when `text` was typed and it is one of the
+/// language server's trigger characters.
+///
+/// While the popover is visible, it's refreshed on every selection change instead,
+/// so that the active parameter follows the cursor.
+pub fn trigger_signature_help_on_input(
+    editor: &mut Editor,
+    text: &str,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !EditorSettings::get_global(cx).auto_signature_help || editor.signature_help_state.is_shown()
+    {
+        return;
+    }
+
+    let Some(project) = editor.project.as_ref() else {
+        return;
+    };
+    let position = editor.selections.newest_anchor().head();
+    let Some((buffer, _)) = editor
+        .buffer
+        .read(cx)
+        .text_anchor_for_position(position, cx)
+    else {
+        return;
+    };
+    let is_trigger = project
+        .read(cx)
+        .signature_help_trigger_characters(&buffer, cx)
+        .iter()
+        .any(|trigger| trigger == text);
+    if is_trigger {
+        request_signature_help(editor, cx);
+    }
+}
+
+/// Re-queries the signature help for the new cursor position, if the popover is shown.
+pub fn refresh_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.signature_help_state.is_shown() {
+        request_signature_help(editor, cx);
+    }
+}
+
+/// Hides the signature help popover and cancels any pending request.
+pub fn hide_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
+    editor.signature_help_state.task = None;
+    if editor.signature_help_state.popover.take().is_some() {
+        cx.notify();
+        true
+    } else {
+        false
+    }
+}
+
+fn request_signature_help(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.pending_rename.is_some() {
+        return;
+    }
+
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let position = editor.selections.newest_anchor().head();
+    let Some((buffer, buffer_position)) = editor
+        .buffer
+        .read(cx)
+        .text_anchor_for_position(position, cx)
+    else {
+        return;
+    };
+
+    let signature_help_request = project.update(cx, |project, cx| {
+        project.signature_help(&buffer, buffer_position, cx)
+    });
+    let task = cx.spawn(|editor, mut cx| {
+        async move {
+            let signature_help = signature_help_request.await?;
+            let Some(signature_help) = signature_help else {
+                editor.update(&mut cx, |editor, cx| {
+                    hide_signature_help(editor, cx);
+                })?;
+                return anyhow::Ok(());
+            };
+
+            let language_registry = project.update(&mut cx, |p, _| p.languages().clone())?;
+            let language = buffer.update(&mut cx, |buffer, _| buffer.language().cloned())?;
+            let documentation_blocks = signature_help
+                .active_signature()
+                .and_then(|signature| signature.documentation.clone())
+                .map(markup_to_hover_block)
+                .into_iter()
+                .collect::<Vec<_>>();
+            let documentation = if documentation_blocks.is_empty() {
+                None
+            } else {
+                Some(parse_blocks(&documentation_blocks, &language_registry, language).await)
+            };
+
+            editor.update(&mut cx, |editor, cx| {
+                editor.signature_help_state.popover = Some(SignatureHelpPopover {
+                    position,
+                    signature_help,
+                    documentation,
+                });
+                cx.notify();
+            })?;
+            anyhow::Ok(())
+        }
+        .log_err()
+    });
+    editor.signature_help_state.task = Some(task);
+}
+
+fn markup_to_hover_block(markup: MarkupContent) -> HoverBlock {
+    HoverBlock {
+        text: markup.value,
+        kind: markup.kind,
+    }
+}
+
+#[derive(Default)]
+pub struct SignatureHelpState {
+    popover: Option<SignatureHelpPopover>,
+    task: Option<Task<Option<()>>>,
+}
+
+impl SignatureHelpState {
+    pub fn is_shown(&self) -> bool {
+        self.popover.is_some()
+    }
+
+    pub fn popover(&self) -> Option<&SignatureHelpPopover> {
+        self.popover.as_ref()
+    }
+
+    pub fn render(
+        &mut self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<(Anchor, AnyElement)> {
+        let popover = self.popover.as_mut()?;
+        Some((
+            popover.position,
+            popover.render(style, max_size, workspace, cx),
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignatureHelpPopover {
+    position: Anchor,
+    signature_help: SignatureHelp,
+    documentation: Option<ParsedMarkdown>,
+}
+
+impl SignatureHelpPopover {
+    pub fn signature_help(&self) -> &SignatureHelp {
+        &self.signature_help
+    }
+
+    pub fn render(
+        &mut self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> AnyElement {
+        let Some(signature) = self.signature_help.active_signature() else {
+            return div().into_any_element();
+        };
+
+        let highlights = self
+            .signature_help
+            .active_parameter_range()
+            .filter(|range| signature.label.get(range.clone()).is_some())
+            .map(|range| {
+                (
+                    range,
+                    HighlightStyle {
+                        font_weight: Some(FontWeight::BOLD),
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                )
+            });
+        let label =
+            StyledText::new(signature.label.clone()).with_highlights(&style.text, highlights);
+
+        let signature_count = self.signature_help.signatures.len();
+        let counter = (signature_count > 1).then(|| {
+            Label::new(format!(
+                "{}/{}",
+                self.signature_help
+                    .active_signature
+                    .min(signature_count - 1)
+                    + 1,
+                signature_count
+            ))
+            .size(LabelSize::Small)
+            .color(Color::Muted)
+        });
+
+        div()
+            .id("signature_help_popover")
+            .elevation_2(cx)
+            .p_2()
+            .overflow_y_scroll()
+            .max_w(max_size.width)
+            .max_h(max_size.height)
+            // Prevent a mouse down/move on the popover from being propagated to the editor,
+            // because that would dismiss the popover.
+            .on_mouse_move(|_, cx| cx.stop_propagation())
+            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+            .child(h_flex().gap_2().child(label).children(counter))
+            .children(self.documentation.as_ref().map(|documentation| {
+                div().pt_1().child(crate::render_parsed_markdown(
+                    "signature_help_documentation",
+                    documentation,
+                    style,
+                    workspace,
+                    cx,
+                ))
+            }))
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt;
+    use indoc::indoc;
+    use project::{ParameterInformation, SignatureInformation};
+
+    fn fake_signature_help(active_parameter: u32) -> lsp::SignatureHelp {
+        lsp::SignatureHelp {
+            signatures: vec![lsp::SignatureInformation {
+                label: "fn add(a: u32, b: u32) -> u32".to_string(),
+                documentation: Some(lsp::Documentation::String("Adds numbers".to_string())),
+                parameters: Some(vec![
+                    lsp::ParameterInformation {
+                        label: lsp::ParameterLabel::Simple("a: u32".to_string()),
+                        documentation: None,
+                    },
+                    lsp::ParameterInformation {
+                        label: lsp::ParameterLabel::LabelOffsets([15, 21]),
+                        documentation: None,
+                    },
+                ]),
+                active_parameter: None,
+            }],
+            active_signature: Some(0),
+            active_parameter: Some(active_parameter),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_signature_help_on_trigger_characters(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { addˇ }
+        "});
+
+        let mut requests =
+            cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, _, _| async move {
+                Ok(Some(fake_signature_help(0)))
+            });
+        cx.update_editor(|editor, cx| editor.handle_input("(", cx));
+        requests.next().await;
+        cx.condition(|editor, _| editor.signature_help_state.is_shown())
+            .await;
+        cx.editor(|editor, _| {
+            let signature_help = editor
+                .signature_help_state
+                .popover()
+                .unwrap()
+                .signature_help();
+            assert_eq!(
+                signature_help.signatures,
+                vec![SignatureInformation {
+                    label: "fn add(a: u32, b: u32) -> u32".to_string(),
+                    documentation: Some(MarkupContent {
+                        kind: project::HoverBlockKind::PlainText,
+                        value: "Adds numbers".to_string(),
+                    }),
+                    parameters: vec![
+                        ParameterInformation {
+                            label_range: 7..13,
+                            documentation: None,
+                        },
+                        ParameterInformation {
+                            label_range: 15..21,
+                            documentation: None,
+                        },
+                    ],
+                    active_parameter: None,
+                }]
+            );
+            assert_eq!(signature_help.active_parameter_range(), Some(7..13));
+        });
+
+        // Typing the next argument refreshes the active parameter.
+        let mut requests =
+            cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, _, _| async move {
+                Ok(Some(fake_signature_help(1)))
+            });
+        cx.update_editor(|editor, cx| editor.handle_input("1,", cx));
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| {
+            let signature_help = editor
+                .signature_help_state
+                .popover()
+                .unwrap()
+                .signature_help();
+            assert_eq!(signature_help.active_parameter_range(), Some(15..21));
+        });
+
+        // Leaving the call hides the popover once the server stops reporting a signature.
+        let mut requests =
+            cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, _, _| async move {
+                Ok(None)
+            });
+        cx.update_editor(|editor, cx| editor.handle_input("2)", cx));
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_shown()));
+    }
+
+    #[gpui::test]
+    async fn test_signature_help_not_requested_for_other_input(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { ˇ }
+        "});
+        cx.update_editor(|editor, cx| editor.handle_input("a", cx));
+        cx.run_until_parked();
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_shown()));
+
+        // Explicitly requesting it works regardless of the typed text.
+        let mut requests =
+            cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(|_, _, _| async move {
+                Ok(Some(fake_signature_help(0)))
+            });
+        cx.update_editor(|editor, cx| show_signature_help(editor, &ShowSignatureHelp, cx));
+        requests.next().await;
+        cx.condition(|editor, _| editor.signature_help_state.is_shown())
+            .await;
+
+        cx.update_editor(|editor, cx| editor.cancel(&crate::Cancel, cx));
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.is_shown()));
+    }
+}
@@ -836,6 +836,53 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
+    pub fn set_commit_contents_for_repo(
+        &self,
+        dot_git: &Path,
+        sha: &str,
+        contents: &[(&Path, String)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commit_contents.insert(
+                sha.to_string(),
+                contents
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone()))
+                    .collect(),
+            );
+        });
+    }
+
+    /// Sets the contents of the working directory files that the fake repository stages, and lists as changed.
+    pub fn set_worktree_contents_for_repo(&self, dot_git: &Path, contents: &[(&Path, String)]) {
+        self.with_git_state(dot_git, false, |state| {
+            state.worktree_contents.clear();
+            state.worktree_contents.extend(
+                contents
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
+    /// Sets the commits that the fake repository lists in its log, newest first.
+    pub fn set_history_for_repo(&self, dot_git: &Path, history: Vec<git::log::CommitDetails>) {
+        self.with_git_state(dot_git, true, |state| {
+            state.history = history;
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1,12 +1,13 @@
 use anyhow::{Context, Result};
 use collections::HashMap;
-use git::blame::Blame;
+use git::{blame::Blame, log::CommitDetails};
 use git2::{BranchType, StatusShow};
 use parking_lot::Mutex;
 use rope::Rope;
 use serde_derive::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    collections::BTreeSet,
     path::{Component, Path, PathBuf},
     sync::Arc,
     time::SystemTime,
@@ -60,6 +61,45 @@ pub trait GitRepository: Send {
     fn create_branch(&self, _: &str) -> Result<()>;
 
     fn blame(&self, path: &Path, content: Rope) -> Result<git::blame::Blame>;
+
+    /// Loads the contents of a file in the HEAD commit.
+    fn load_head_text(&self, relative_file_path: &Path) -> Option<String>;
+
+    /// Lists the files that have changes in the index or in the working directory, excluding the ignored ones.
+    fn status_entries(&self) -> Result<Vec<GitStatusEntry>>;
+
+    /// Updates the index entries of the given paths to match the working directory, removing the entries
+    /// of the files that do not exist anymore.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the index entries of the given paths to match the HEAD commit.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Loads the contents of a file in the working directory as staging it would add them to the index, with its
+    /// line endings converted according to its `text` and `eol` attributes and the `core.autocrlf` setting.
+    /// Returns `None` if the file does not exist.
+    fn load_working_text(&self, path: &RepoPath) -> Result<Option<String>>;
+
+    /// Replaces the contents of a file in the index, removing its entry when `content` is `None`.
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()>;
+
+    /// Commits the index with the given message, replacing the HEAD commit instead when `amend` is set.
+    fn commit(&self, message: &str, amend: bool) -> Result<()>;
+
+    /// Returns the message of the HEAD commit.
+    fn last_commit_message(&self) -> Option<String>;
+
+    /// Loads the contents of a file in the given commit, or `None` if the file does not exist in it.
+    fn load_commit_text(&self, relative_file_path: &Path, commit: &str) -> Result<Option<String>>;
+
+    /// Lists at most `max_count` commits reachable from the revision, newest first. When a path is given, only the
+    /// commits that changed the file are listed, following its renames.
+    fn log(
+        &self,
+        revision: &str,
+        relative_file_path: Option<&Path>,
+        max_count: usize,
+    ) -> Result<Vec<CommitDetails>>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -82,6 +122,39 @@ impl RealGitRepository {
     }
 }
 
+impl RealGitRepository {
+    /// Whether staging the file converts its CRLF line endings into LF, as git does for the files that its `text`
+    /// and `eol` attributes mark as text, or for all files when `core.autocrlf` is set. Like git, files detected as
+    /// text rather than marked as such keep the CRLF line endings they already have in the index.
+    fn normalizes_line_endings(&self, path: &RepoPath) -> Result<bool> {
+        let flags = git2::AttrCheckFlags::FILE_THEN_INDEX;
+        let detected_as_text = || {
+            !self
+                .load_index_text(path)
+                .map_or(false, |index_text| index_text.contains("\r\n"))
+        };
+        match git2::AttrValue::from_string(self.repository.get_attr(path, "text", flags)?) {
+            git2::AttrValue::True => return Ok(true),
+            git2::AttrValue::False => return Ok(false),
+            git2::AttrValue::String("auto") => return Ok(detected_as_text()),
+            _ => {}
+        }
+        if self.repository.get_attr(path, "eol", flags)?.is_some() {
+            return Ok(true);
+        }
+
+        // `core.autocrlf` is either a boolean or `input`, which only converts line endings when staging.
+        let config = self.repository.config()?;
+        let autocrlf = match config.get_bool("core.autocrlf") {
+            Ok(autocrlf) => autocrlf,
+            Err(_) => config
+                .get_string("core.autocrlf")
+                .map_or(false, |autocrlf| autocrlf.eq_ignore_ascii_case("input")),
+        };
+        Ok(autocrlf && detected_as_text())
+    }
+}
+
 impl GitRepository for RealGitRepository {
     fn reload_index(&self) {
         if let Ok(mut index) = self.repository.index() {
@@ -248,6 +321,237 @@ impl GitRepository for RealGitRepository {
             remote_url,
         )
     }
+
+    fn load_head_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &LibGitRepository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+            let tree = repo.head()?.peel_to_tree()?;
+            let entry = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry,
+                Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(error) => return Err(error.into()),
+            };
+            let content = entry.to_object(repo)?.peel_to_blob()?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(&self.repository, relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading head text: {:?}", err),
+        }
+        None
+    }
+
+    fn status_entries(&self) -> Result<Vec<GitStatusEntry>> {
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true);
+        options.recurse_untracked_dirs(true);
+        options.include_ignored(false);
+        options.renames_head_to_index(false);
+
+        let statuses = self.repository.statuses(Some(&mut options))?;
+        let mut entries = Vec::new();
+        for status in statuses.iter() {
+            let repo_path = RepoPath(PathBuf::try_from_bytes(status.path_bytes())?);
+            let status = status.status();
+            let entry = if status.contains(git2::Status::CONFLICTED) {
+                GitStatusEntry {
+                    repo_path,
+                    staged: None,
+                    unstaged: Some(GitChange::Conflict),
+                }
+            } else {
+                let staged = if status.contains(git2::Status::INDEX_NEW) {
+                    Some(GitChange::Added)
+                } else if status.contains(git2::Status::INDEX_DELETED) {
+                    Some(GitChange::Deleted)
+                } else if status.intersects(
+                    git2::Status::INDEX_MODIFIED
+                        | git2::Status::INDEX_RENAMED
+                        | git2::Status::INDEX_TYPECHANGE,
+                ) {
+                    Some(GitChange::Modified)
+                } else {
+                    None
+                };
+                let unstaged = if status.contains(git2::Status::WT_NEW) {
+                    Some(GitChange::Untracked)
+                } else if status.contains(git2::Status::WT_DELETED) {
+                    Some(GitChange::Deleted)
+                } else if status.intersects(
+                    git2::Status::WT_MODIFIED
+                        | git2::Status::WT_RENAMED
+                        | git2::Status::WT_TYPECHANGE,
+                ) {
+                    Some(GitChange::Modified)
+                } else {
+                    None
+                };
+                GitStatusEntry {
+                    repo_path,
+                    staged,
+                    unstaged,
+                }
+            };
+            if entry.staged.is_some() || entry.unstaged.is_some() {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| a.repo_path.cmp(&b.repo_path));
+        Ok(entries)
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let working_directory = self
+            .repository
+            .workdir()
+            .context("failed to get git working directory")?;
+        let mut index = self.repository.index()?;
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+            if working_directory.join(path).exists() {
+                index.add_path(path)?;
+            } else {
+                index.remove_path(path)?;
+            }
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+        }
+        match self.repository.head() {
+            Ok(head) => {
+                let head = head.peel_to_commit()?;
+                self.repository.reset_default(
+                    Some(head.as_object()),
+                    paths.iter().map(|path| path.as_path()),
+                )?;
+            }
+            // Before the first commit, there is nothing to reset the entries to.
+            Err(error) if error.code() == git2::ErrorCode::UnbornBranch => {
+                let mut index = self.repository.index()?;
+                for path in paths {
+                    index.remove_path(path)?;
+                }
+                index.write()?;
+            }
+            Err(error) => return Err(error.into()),
+        }
+        Ok(())
+    }
+
+    fn load_working_text(&self, path: &RepoPath) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(path)?;
+        let working_directory = self
+            .repository
+            .workdir()
+            .context("failed to get git working directory")?;
+        let text = match std::fs::read_to_string(working_directory.join(path)) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        if self.normalizes_line_endings(path)? {
+            Ok(Some(text.replace("\r\n", "\n")))
+        } else {
+            Ok(Some(text))
+        }
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        check_path_to_repo_path_errors(path)?;
+        let mut index = self.repository.index()?;
+        match content {
+            Some(content) => {
+                let id = self.repository.blob(content.as_bytes())?;
+                let mode = index.get_path(path, 0).map_or(0o100644, |entry| entry.mode);
+                // Index paths are separated by slashes on every platform.
+                let path_bytes = path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+                    .into_bytes();
+                index.add(&git2::IndexEntry {
+                    ctime: git2::IndexTime::new(0, 0),
+                    mtime: git2::IndexTime::new(0, 0),
+                    dev: 0,
+                    ino: 0,
+                    mode,
+                    uid: 0,
+                    gid: 0,
+                    file_size: content.len() as u32,
+                    id,
+                    flags: path_bytes.len().min(0xfff) as u16,
+                    flags_extended: 0,
+                    path: path_bytes,
+                })?;
+            }
+            None => index.remove_path(path)?,
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let working_directory = self
+            .repository
+            .workdir()
+            .context("failed to get git working directory")?;
+        git::commit::commit(&self.git_binary_path, working_directory, message, amend)
+    }
+
+    fn last_commit_message(&self) -> Option<String> {
+        let head = self.repository.head().ok()?.peel_to_commit().ok()?;
+        head.message().map(|message| message.to_string())
+    }
+
+    fn load_commit_text(&self, relative_file_path: &Path, commit: &str) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+        let tree = self
+            .repository
+            .revparse_single(commit)
+            .with_context(|| format!("failed to find commit {commit}"))?
+            .peel_to_commit()?
+            .tree()?;
+        let entry = match tree.get_path(relative_file_path) {
+            Ok(entry) => entry,
+            Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        let content = entry
+            .to_object(&self.repository)?
+            .peel_to_blob()?
+            .content()
+            .to_owned();
+        Ok(Some(String::from_utf8(content)?))
+    }
+
+    fn log(
+        &self,
+        revision: &str,
+        relative_file_path: Option<&Path>,
+        max_count: usize,
+    ) -> Result<Vec<CommitDetails>> {
+        if let Some(relative_file_path) = relative_file_path {
+            check_path_to_repo_path_errors(relative_file_path)?;
+        }
+        let working_directory = self
+            .repository
+            .workdir()
+            .context("failed to get git working directory")?;
+        git::log::log(
+            &self.git_binary_path,
+            working_directory,
+            revision,
+            relative_file_path,
+            max_count,
+        )
+    }
 }
 
 fn matches_index(repo: &LibGitRepository, path: &RepoPath, mtime: SystemTime) -> bool {
@@ -290,7 +594,17 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    /// The contents of the files in the HEAD commit.
+    pub head_contents: HashMap<PathBuf, String>,
+    /// The contents of the files in the working directory, that staging copies to the index.
+    pub worktree_contents: HashMap<PathBuf, String>,
+    /// The messages of the commits, from the first one to HEAD.
+    pub commit_messages: Vec<String>,
+    /// The contents of the files of the commits other than HEAD, by SHA.
+    pub commit_contents: HashMap<String, HashMap<PathBuf, String>>,
     pub blames: HashMap<PathBuf, Blame>,
+    /// The commits listed by `log`, newest first.
+    pub history: Vec<CommitDetails>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
 }
@@ -366,6 +680,163 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn load_head_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
+    fn status_entries(&self) -> Result<Vec<GitStatusEntry>> {
+        let state = self.state.lock();
+        let conflicted_paths = state
+            .worktree_statuses
+            .iter()
+            .filter(|(_, status)| **status == GitFileStatus::Conflict)
+            .map(|(repo_path, _)| &repo_path.0)
+            .collect::<BTreeSet<_>>();
+        let paths = state
+            .head_contents
+            .keys()
+            .chain(state.index_contents.keys())
+            .chain(state.worktree_contents.keys())
+            .chain(conflicted_paths.iter().copied())
+            .collect::<BTreeSet<_>>();
+        let change = |old: Option<&String>, new: Option<&String>| match (old, new) {
+            (None, Some(_)) => Some(GitChange::Added),
+            (Some(_), None) => Some(GitChange::Deleted),
+            (Some(old), Some(new)) if old != new => Some(GitChange::Modified),
+            _ => None,
+        };
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| {
+                if conflicted_paths.contains(&path) {
+                    return Some(GitStatusEntry {
+                        repo_path: RepoPath(path.clone()),
+                        staged: None,
+                        unstaged: Some(GitChange::Conflict),
+                    });
+                }
+                let head = state.head_contents.get(path);
+                let index = state.index_contents.get(path);
+                let worktree = state.worktree_contents.get(path);
+                let staged = change(head, index);
+                let unstaged = match change(index, worktree) {
+                    Some(GitChange::Added) => Some(GitChange::Untracked),
+                    change => change,
+                };
+                (staged.is_some() || unstaged.is_some()).then(|| GitStatusEntry {
+                    repo_path: RepoPath(path.clone()),
+                    staged,
+                    unstaged,
+                })
+            })
+            .collect())
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            match state.worktree_contents.get(&path.0).cloned() {
+                Some(content) => state.index_contents.insert(path.0.clone(), content),
+                None => state.index_contents.remove(&path.0),
+            };
+        }
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            match state.head_contents.get(&path.0).cloned() {
+                Some(content) => state.index_contents.insert(path.0.clone(), content),
+                None => state.index_contents.remove(&path.0),
+            };
+        }
+        Ok(())
+    }
+
+    fn load_working_text(&self, path: &RepoPath) -> Result<Option<String>> {
+        let state = self.state.lock();
+        Ok(state.worktree_contents.get(&path.0).cloned())
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        let mut state = self.state.lock();
+        match content {
+            Some(content) => state.index_contents.insert(path.0.clone(), content),
+            None => state.index_contents.remove(&path.0),
+        };
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        if amend {
+            anyhow::ensure!(
+                state.commit_messages.pop().is_some(),
+                "there is no commit to amend"
+            );
+        }
+        state.head_contents = state.index_contents.clone();
+        state.commit_messages.push(message.to_owned());
+        Ok(())
+    }
+
+    fn last_commit_message(&self) -> Option<String> {
+        let state = self.state.lock();
+        state.commit_messages.last().cloned()
+    }
+
+    fn load_commit_text(&self, path: &Path, commit: &str) -> Result<Option<String>> {
+        let state = self.state.lock();
+        let contents = if commit == "HEAD" {
+            &state.head_contents
+        } else {
+            state
+                .commit_contents
+                .get(commit)
+                .with_context(|| format!("failed to find commit {commit}"))?
+        };
+        Ok(contents.get(path).cloned())
+    }
+
+    fn log(
+        &self,
+        revision: &str,
+        path: Option<&Path>,
+        max_count: usize,
+    ) -> Result<Vec<CommitDetails>> {
+        let state = self.state.lock();
+        let start = if revision == "HEAD" {
+            0
+        } else {
+            state
+                .history
+                .iter()
+                .position(|commit| commit.sha.to_string() == revision)
+                .with_context(|| format!("failed to find commit {revision}"))?
+        };
+        let mut path = path.map(Path::to_path_buf);
+        let mut commits = Vec::new();
+        for commit in &state.history[start..] {
+            if commits.len() == max_count {
+                break;
+            }
+            let Some(followed_path) = path.as_mut() else {
+                commits.push(commit.clone());
+                continue;
+            };
+            let Some(file) = commit.files.iter().find(|file| &file.path == followed_path) else {
+                continue;
+            };
+            let mut commit = commit.clone();
+            commit.files = vec![file.clone()];
+            *followed_path = file.path_before().to_path_buf();
+            commits.push(commit);
+        }
+        Ok(commits)
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -429,6 +900,26 @@ impl GitFileStatus {
     }
 }
 
+/// A file with changes in the index or in the working directory of a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatusEntry {
+    pub repo_path: RepoPath,
+    /// The change of the file in the index, relative to the HEAD commit.
+    pub staged: Option<GitChange>,
+    /// The change of the file in the working directory, relative to the index.
+    pub unstaged: Option<GitChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitChange {
+    Added,
+    Modified,
+    Deleted,
+    /// A file in the working directory that is not in the index.
+    Untracked,
+    Conflict,
+}
+
 #[derive(Clone, Debug, Ord, Hash, PartialOrd, Eq, PartialEq)]
 pub struct RepoPath(pub PathBuf);
 
@@ -484,3 +975,56 @@ impl<'a> MapSeekTarget<RepoPath> for RepoPathDescendants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_working_text_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = LibGitRepository::init(dir.path()).unwrap();
+        repository
+            .config()
+            .unwrap()
+            .set_str("core.autocrlf", "input")
+            .unwrap();
+        std::fs::write(dir.path().join(".gitattributes"), "*.bat -text\n").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\r\ntwo\r\n").unwrap();
+        std::fs::write(dir.path().join("b.bat"), "one\r\ntwo\r\n").unwrap();
+        let repo = RealGitRepository::new(repository, None);
+
+        // Text files are staged with LF line endings, unless they're marked otherwise.
+        assert_eq!(
+            repo.load_working_text(&RepoPath::new("a.txt".into()))
+                .unwrap()
+                .as_deref(),
+            Some("one\ntwo\n")
+        );
+        assert_eq!(
+            repo.load_working_text(&RepoPath::new("b.bat".into()))
+                .unwrap()
+                .as_deref(),
+            Some("one\r\ntwo\r\n")
+        );
+        assert_eq!(
+            repo.load_working_text(&RepoPath::new("c.txt".into()))
+                .unwrap(),
+            None
+        );
+
+        // Files that already have CRLF line endings in the index keep them.
+        repo.set_index_text(&RepoPath::new("a.txt".into()), Some("one\r\n".to_string()))
+            .unwrap();
+        assert_eq!(
+            repo.load_working_text(&RepoPath::new("a.txt".into()))
+                .unwrap()
+                .as_deref(),
+            Some("one\r\ntwo\r\n")
+        );
+
+        assert!(repo
+            .unstage_paths(&[RepoPath::new("../a.txt".into())])
+            .is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use crate::Oid;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 pub fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<HashMap<Oid, String>> {
     const MARKER: &'static str = "<MARKER>";
@@ -33,3 +34,46 @@ pub fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<HashMap<Oi
         )
         .collect::<HashMap<Oid, String>>())
 }
+
+/// Commits the index of the repository with the given message, amending the HEAD commit if `amend` is set.
+/// Runs `git commit`, so that the hooks and the signing configuration of the repository apply.
+pub fn commit(
+    git_binary: &Path,
+    working_directory: &Path,
+    message: &str,
+    amend: bool,
+) -> Result<()> {
+    let mut command = Command::new(git_binary);
+    command.current_dir(working_directory).args([
+        "commit",
+        "--quiet",
+        "--cleanup=strip",
+        "--file",
+        "-",
+    ]);
+    if amend {
+        command.arg("--amend");
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start git commit process: {}", e))?;
+
+    child
+        .stdin
+        .take()
+        .context("failed to get pipe to stdin of git commit command")?
+        .write_all(message.as_bytes())?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| anyhow!("Failed to read git commit output: {}", e))?;
+
+    anyhow::ensure!(
+        output.status.success(),
+        "git commit failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(())
+}
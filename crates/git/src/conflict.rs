@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+const MARKER_LEN: usize = 7;
+
+/// A conflict left in a file by a merge, a rebase or a cherry-pick, delimited by conflict markers.
+///
+/// The ranges are byte ranges of whole lines, including their trailing newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// From the start of the `<<<<<<<` line to the end of the `>>>>>>>` line.
+    pub range: Range<usize>,
+    /// Our side of the conflict, between the `<<<<<<<` line and the `|||||||` or `=======` one.
+    pub ours: Range<usize>,
+    /// The common ancestor of diff3-style conflicts, between the `|||||||` and `=======` lines.
+    pub base: Option<Range<usize>>,
+    /// Their side of the conflict, between the `=======` and `>>>>>>>` lines.
+    pub theirs: Range<usize>,
+    /// The text following the `<<<<<<<` marker, usually the name of our branch.
+    pub ours_label: String,
+    /// The text following the `>>>>>>>` marker, usually the name or commit of their branch.
+    pub theirs_label: String,
+}
+
+impl Conflict {
+    /// The lines of our side, including the `<<<<<<<` marker.
+    pub fn ours_section(&self) -> Range<usize> {
+        self.range.start..self.ours.end
+    }
+
+    /// The lines of the common ancestor, including the `|||||||` marker.
+    pub fn base_section(&self) -> Option<Range<usize>> {
+        let base = self.base.as_ref()?;
+        Some(self.ours.end..base.end)
+    }
+
+    /// The lines of their side, including the `=======` and `>>>>>>>` markers.
+    pub fn theirs_section(&self) -> Range<usize> {
+        let start = self.base.as_ref().map_or(self.ours.end, |base| base.end);
+        start..self.range.end
+    }
+}
+
+enum State {
+    Ours {
+        start: usize,
+        ours_start: usize,
+        ours_label: String,
+    },
+    Base {
+        start: usize,
+        ours: Range<usize>,
+        base_start: usize,
+        ours_label: String,
+    },
+    Theirs {
+        start: usize,
+        ours: Range<usize>,
+        base: Option<Range<usize>>,
+        theirs_start: usize,
+        ours_label: String,
+    },
+}
+
+/// Returns the conflicts delimited by conflict markers in the text, in order.
+///
+/// Conflicts missing some of their markers are ignored.
+pub fn parse_conflicts(text: &str) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut state = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        // A new conflict starting before the current one ended means the current one was malformed.
+        if let Some(label) = marker_label(line, '<') {
+            state = Some(State::Ours {
+                start: line_start,
+                ours_start: offset,
+                ours_label: label,
+            });
+            continue;
+        }
+
+        state = match state.take() {
+            None => None,
+            Some(State::Ours {
+                start,
+                ours_start,
+                ours_label,
+            }) => {
+                if marker_label(line, '|').is_some() {
+                    Some(State::Base {
+                        start,
+                        ours: ours_start..line_start,
+                        base_start: offset,
+                        ours_label,
+                    })
+                } else if is_separator(line) {
+                    Some(State::Theirs {
+                        start,
+                        ours: ours_start..line_start,
+                        base: None,
+                        theirs_start: offset,
+                        ours_label,
+                    })
+                } else {
+                    Some(State::Ours {
+                        start,
+                        ours_start,
+                        ours_label,
+                    })
+                }
+            }
+            Some(State::Base {
+                start,
+                ours,
+                base_start,
+                ours_label,
+            }) => {
+                if is_separator(line) {
+                    Some(State::Theirs {
+                        start,
+                        ours,
+                        base: Some(base_start..line_start),
+                        theirs_start: offset,
+                        ours_label,
+                    })
+                } else {
+                    Some(State::Base {
+                        start,
+                        ours,
+                        base_start,
+                        ours_label,
+                    })
+                }
+            }
+            Some(State::Theirs {
+                start,
+                ours,
+                base,
+                theirs_start,
+                ours_label,
+            }) => {
+                if let Some(theirs_label) = marker_label(line, '>') {
+                    conflicts.push(Conflict {
+                        range: start..offset,
+                        ours,
+                        base,
+                        theirs: theirs_start..line_start,
+                        ours_label,
+                        theirs_label,
+                    });
+                    None
+                } else {
+                    Some(State::Theirs {
+                        start,
+                        ours,
+                        base,
+                        theirs_start,
+                        ours_label,
+                    })
+                }
+            }
+        };
+    }
+    conflicts
+}
+
+/// Returns the label following the marker if the line starts with one made of the character.
+fn marker_label(line: &str, marker: char) -> Option<String> {
+    let rest = line.strip_prefix(&marker.to_string().repeat(MARKER_LEN))?;
+    if rest.starts_with(marker) {
+        return None;
+    }
+    let label = rest.trim_end_matches(['\n', '\r']);
+    if !label.is_empty() && !label.starts_with(' ') {
+        return None;
+    }
+    Some(label.trim().to_string())
+}
+
+fn is_separator(line: &str) -> bool {
+    line.trim_end_matches(['\n', '\r']) == "=".repeat(MARKER_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use unindent::Unindent as _;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_conflicts() {
+        let text = "
+            one
+            <<<<<<< HEAD
+            two
+            =======
+            three
+            >>>>>>> feature
+            four
+            <<<<<<< ours
+            five
+            ||||||| base
+            six
+            =======
+            >>>>>>> theirs
+            <<<<<<< unterminated
+            seven
+            "
+        .unindent();
+
+        let conflicts = parse_conflicts(&text);
+        assert_eq!(conflicts.len(), 2);
+
+        let first = &conflicts[0];
+        assert_eq!(
+            &text[first.range.clone()],
+            "<<<<<<< HEAD\ntwo\n=======\nthree\n>>>>>>> feature\n"
+        );
+        assert_eq!(&text[first.ours.clone()], "two\n");
+        assert_eq!(first.base, None);
+        assert_eq!(&text[first.theirs.clone()], "three\n");
+        assert_eq!(first.ours_label, "HEAD");
+        assert_eq!(first.theirs_label, "feature");
+        assert_eq!(&text[first.ours_section()], "<<<<<<< HEAD\ntwo\n");
+        assert_eq!(
+            &text[first.theirs_section()],
+            "=======\nthree\n>>>>>>> feature\n"
+        );
+
+        let second = &conflicts[1];
+        assert_eq!(&text[second.ours.clone()], "five\n");
+        assert_eq!(&text[second.base.clone().unwrap()], "six\n");
+        assert_eq!(&text[second.base_section().unwrap()], "||||||| base\nsix\n");
+        assert_eq!(&text[second.theirs.clone()], "");
+        assert_eq!(&text[second.theirs_section()], "=======\n>>>>>>> theirs\n");
+    }
+
+    #[test]
+    fn test_marker_label() {
+        assert_eq!(marker_label("<<<<<<< HEAD\n", '<'), Some("HEAD".into()));
+        assert_eq!(marker_label("<<<<<<<\r\n", '<'), Some("".into()));
+        assert_eq!(marker_label("<<<<<<<< HEAD\n", '<'), None);
+        assert_eq!(marker_label("<<<<<<<HEAD\n", '<'), None);
+        assert!(is_separator("=======\n"));
+        assert!(!is_separator("======== \n"));
+    }
+}
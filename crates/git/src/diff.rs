@@ -178,7 +178,8 @@ impl BufferDiff {
         self.tree = SumTree::new();
     }
 
-    pub async fn update(&mut self, diff_base: &str, buffer: &text::BufferSnapshot) {
+    /// Computes the diff of the buffer against the diff base, for when it doesn't need to be kept up to date.
+    pub fn build(diff_base: &str, buffer: &text::BufferSnapshot) -> Self {
         let mut tree = SumTree::new();
 
         let buffer_text = buffer.as_rope().to_string();
@@ -192,8 +193,87 @@ impl BufferDiff {
             }
         }
 
-        self.tree = tree;
-        self.last_buffer_version = Some(buffer.version().clone());
+        Self {
+            last_buffer_version: Some(buffer.version().clone()),
+            tree,
+        }
+    }
+
+    pub async fn update(&mut self, diff_base: &str, buffer: &text::BufferSnapshot) {
+        *self = Self::build(diff_base, buffer);
+    }
+
+    /// Returns the diff base with the changes of the hunks accepted by `filter` applied to it, e.g. the text
+    /// of the index after staging these hunks when the diff base is the index text.
+    pub fn apply_hunks(
+        &self,
+        diff_base: &str,
+        buffer: &BufferSnapshot,
+        mut filter: impl FnMut(&DiffHunk<u32>) -> bool,
+    ) -> String {
+        let mut applied = String::with_capacity(diff_base.len());
+        let mut base_offset = 0;
+        for (hunk, base_byte_range, _) in self.hunks_with_base_ranges(diff_base, buffer) {
+            if !filter(&hunk) {
+                continue;
+            }
+            applied.push_str(&diff_base[base_offset..base_byte_range.start]);
+            applied.extend(
+                buffer.text_for_range(row_range_to_points(hunk.associated_range.clone(), buffer)),
+            );
+            base_offset = base_byte_range.end;
+        }
+        applied.push_str(&diff_base[base_offset..]);
+        applied
+    }
+
+    /// Converts a row of the buffer into the corresponding row of the diff base.
+    /// Rows inside a changed hunk are converted into the rows of the hunk in the diff base, as far as it extends.
+    pub fn base_row_for_buffer_row(
+        &self,
+        row: u32,
+        diff_base: &str,
+        buffer: &BufferSnapshot,
+    ) -> u32 {
+        let mut row_divergence = 0i64;
+        for (hunk, _, base_row_range) in self.hunks_with_base_ranges(diff_base, buffer) {
+            if hunk.associated_range.start > row {
+                break;
+            }
+            if row < hunk.associated_range.end {
+                let base_row = base_row_range.start + (row - hunk.associated_range.start);
+                return base_row.min(base_row_range.end);
+            }
+            row_divergence = hunk.associated_range.end as i64 - base_row_range.end as i64;
+        }
+        (row as i64 - row_divergence).max(0) as u32
+    }
+
+    /// Iterates over all the hunks, along with the byte range and the row range that each of them replaces in the diff base.
+    pub fn hunks_with_base_ranges<'a>(
+        &'a self,
+        diff_base: &'a str,
+        buffer: &'a BufferSnapshot,
+    ) -> impl 'a + Iterator<Item = (DiffHunk<u32>, Range<usize>, Range<u32>)> {
+        let mut row_divergence = 0i64;
+        self.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, buffer)
+            .map(move |hunk| {
+                let (base_byte_range, base_row_range) = if hunk.diff_base_byte_range.is_empty() {
+                    // Added hunks do not replace anything in the diff base, their position is only known by their rows.
+                    let base_row =
+                        (hunk.associated_range.start as i64 - row_divergence).max(0) as u32;
+                    let offset = row_offset(diff_base, base_row);
+                    (offset..offset, base_row..base_row)
+                } else {
+                    let range = hunk.diff_base_byte_range.clone();
+                    let start_row = diff_base[..range.start].matches('\n').count() as u32;
+                    let row_count = diff_base[range.clone()].matches('\n').count() as u32
+                        + u32::from(!diff_base[range.clone()].ends_with('\n'));
+                    (range, start_row..start_row + row_count)
+                };
+                row_divergence = hunk.associated_range.end as i64 - base_row_range.end as i64;
+                (hunk, base_byte_range, base_row_range)
+            })
     }
 
     #[cfg(test)]
@@ -294,6 +374,29 @@ impl BufferDiff {
     }
 }
 
+/// Converts a range of rows into the range of points spanning them, clipped to the end of the buffer.
+fn row_range_to_points(rows: Range<u32>, buffer: &BufferSnapshot) -> Range<Point> {
+    let max_point = buffer.max_point();
+    let clip = |row: u32| {
+        if row > max_point.row {
+            max_point
+        } else {
+            Point::new(row, 0)
+        }
+    };
+    clip(rows.start)..clip(rows.end)
+}
+
+/// Returns the byte offset of the start of the row in the text, or the length of the text past its last row.
+fn row_offset(text: &str, row: u32) -> usize {
+    if row == 0 {
+        return 0;
+    }
+    text.match_indices('\n')
+        .nth(row as usize - 1)
+        .map_or(text.len(), |(offset, _)| offset + 1)
+}
+
 /// Range (crossing new lines), old, new
 #[cfg(any(test, feature = "test-support"))]
 #[track_caller]
@@ -375,6 +478,63 @@ mod tests {
         assert_hunks(diff.hunks(&buffer), &buffer, &diff_base, &[]);
     }
 
+    #[test]
+    fn test_apply_hunks() {
+        let diff_base = "
+            one
+            two
+            three
+            four
+            five
+        "
+        .unindent();
+        let buffer_text = "
+            zero
+            one
+            TWO
+            three
+            five
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text.clone());
+        let mut diff = BufferDiff::new();
+        smol::block_on(diff.update(&diff_base, &buffer));
+        assert_eq!(diff.apply_hunks(&diff_base, &buffer, |_| true), buffer_text);
+        assert_eq!(diff.apply_hunks(&diff_base, &buffer, |_| false), diff_base);
+        assert_eq!(
+            diff.apply_hunks(&diff_base, &buffer, |hunk| hunk.associated_range.start == 2),
+            "
+            one
+            TWO
+            three
+            four
+            five
+            "
+            .unindent(),
+            "Only the modification of the second row should be applied"
+        );
+        assert_eq!(
+            diff.apply_hunks(&diff_base, &buffer, |hunk| {
+                hunk.status() != DiffHunkStatus::Modified
+            }),
+            "
+            zero
+            one
+            two
+            three
+            five
+            "
+            .unindent(),
+        );
+
+        assert_eq!(diff.base_row_for_buffer_row(0, &diff_base, &buffer), 0);
+        assert_eq!(diff.base_row_for_buffer_row(1, &diff_base, &buffer), 0);
+        assert_eq!(diff.base_row_for_buffer_row(2, &diff_base, &buffer), 1);
+        assert_eq!(diff.base_row_for_buffer_row(3, &diff_base, &buffer), 2);
+        assert_eq!(diff.base_row_for_buffer_row(4, &diff_base, &buffer), 4);
+    }
+
     #[test]
     fn test_buffer_diff_range() {
         let diff_base = "
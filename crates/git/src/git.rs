@@ -9,7 +9,9 @@ pub use lazy_static::lazy_static;
 
 pub mod blame;
 pub mod commit;
+pub mod conflict;
 pub mod diff;
+pub mod log;
 pub mod permalink;
 
 lazy_static! {
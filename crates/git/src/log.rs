@@ -0,0 +1,269 @@
+use crate::Oid;
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
+
+const COMMIT_SEPARATOR: char = '\x1e';
+const FIELD_COUNT: usize = 7;
+
+/// A commit of the history of a repository, along with the files it changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitDetails {
+    pub sha: Oid,
+    pub parent_shas: Vec<Oid>,
+    pub author: String,
+    pub author_mail: String,
+    /// The time of the commit in seconds since the Unix epoch.
+    pub author_time: i64,
+    /// The offset of the author's timezone, such as `+0200`.
+    pub author_tz: String,
+    pub message: String,
+    /// The files changed by the commit, compared to its first parent.
+    pub files: Vec<CommitFile>,
+}
+
+impl CommitDetails {
+    /// The first line of the message.
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    pub fn author_offset_date_time(&self) -> Result<OffsetDateTime> {
+        let format = format_description!("[offset_hour][offset_minute]");
+        let offset = UtcOffset::parse(&self.author_tz, &format)?;
+        Ok(OffsetDateTime::from_unix_timestamp(self.author_time)?.to_offset(offset))
+    }
+}
+
+/// A file changed by a commit, with paths relative to the working directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitFile {
+    pub status: CommitFileStatus,
+    pub path: PathBuf,
+    /// The path of the file before the commit, if it was renamed or copied.
+    pub old_path: Option<PathBuf>,
+}
+
+impl CommitFile {
+    /// The path of the file in the parent of the commit.
+    pub fn path_before(&self) -> &Path {
+        self.old_path.as_deref().unwrap_or(&self.path)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitFileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+}
+
+impl CommitFileStatus {
+    fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'A' => Some(Self::Added),
+            'M' => Some(Self::Modified),
+            'D' => Some(Self::Deleted),
+            'R' => Some(Self::Renamed),
+            'C' => Some(Self::Copied),
+            'T' => Some(Self::TypeChanged),
+            _ => None,
+        }
+    }
+
+    /// The letter `git status` uses for the change.
+    pub fn letter(self) -> &'static str {
+        match self {
+            Self::Added => "A",
+            Self::Modified => "M",
+            Self::Deleted => "D",
+            Self::Renamed => "R",
+            Self::Copied => "C",
+            Self::TypeChanged => "T",
+        }
+    }
+}
+
+/// Lists the commits reachable from `revision`, newest first.
+///
+/// When a path is given, only the commits that changed it are listed, following its renames, and their files are
+/// limited to it.
+pub fn log(
+    git_binary: &Path,
+    working_directory: &Path,
+    revision: &str,
+    path: Option<&Path>,
+    max_count: usize,
+) -> Result<Vec<CommitDetails>> {
+    let mut command = Command::new(git_binary);
+    command
+        .current_dir(working_directory)
+        .args(["log", "-z", "-M", "--name-status"])
+        .arg(format!("--max-count={max_count}"))
+        .arg("--format=%x1e%H%x00%P%x00%an%x00%ae%x00%at%x00%ai%x00%B%x00");
+    if path.is_some() {
+        command.arg("--follow");
+    }
+    // Revisions starting with `-` must not be taken for options.
+    command.arg("--end-of-options").arg(revision).arg("--");
+    if let Some(path) = path {
+        command.arg(path);
+    }
+    let output = command
+        .output()
+        .map_err(|e| anyhow!("Failed to start git log process: {}", e))?;
+
+    anyhow::ensure!(
+        output.status.success(),
+        "git log failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    parse_log(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses the output of `git log -z --name-status` with the format used by [`log`].
+fn parse_log(output: &str) -> Result<Vec<CommitDetails>> {
+    output
+        .split(COMMIT_SEPARATOR)
+        .filter(|commit| !commit.trim().is_empty())
+        .map(parse_commit)
+        .collect()
+}
+
+fn parse_commit(commit: &str) -> Result<CommitDetails> {
+    let mut fields = commit.split('\0');
+    let mut header = Vec::with_capacity(FIELD_COUNT);
+    for _ in 0..FIELD_COUNT {
+        header.push(
+            fields
+                .next()
+                .context("incomplete commit in git log output")?,
+        );
+    }
+    let sha = header[0].parse()?;
+    let parent_shas = header[1]
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<Oid>>>()?;
+    let author_time = header[4]
+        .parse()
+        .with_context(|| format!("invalid commit time {:?}", header[4]))?;
+    let author_tz = header[5]
+        .split_whitespace()
+        .last()
+        .unwrap_or("+0000")
+        .to_string();
+
+    // The file changes follow the message as a status letter, with a similarity score for renames and copies,
+    // and then one path, or two for renames and copies.
+    let mut tokens = fields
+        .map(|token| token.trim_start_matches('\n'))
+        .filter(|token| !token.is_empty());
+    let mut files = Vec::new();
+    while let Some(status) = tokens.next() {
+        let status = status
+            .chars()
+            .next()
+            .and_then(CommitFileStatus::from_letter)
+            .with_context(|| format!("invalid file status {status:?} in git log output"))?;
+        let path = tokens.next().context("missing path in git log output")?;
+        let file = match status {
+            CommitFileStatus::Renamed | CommitFileStatus::Copied => {
+                let new_path = tokens.next().context("missing path in git log output")?;
+                CommitFile {
+                    status,
+                    path: new_path.into(),
+                    old_path: Some(path.into()),
+                }
+            }
+            _ => CommitFile {
+                status,
+                path: path.into(),
+                old_path: None,
+            },
+        };
+        files.push(file);
+    }
+
+    Ok(CommitDetails {
+        sha,
+        parent_shas,
+        author: header[2].to_string(),
+        author_mail: header[3].to_string(),
+        author_time,
+        author_tz,
+        message: header[6].trim().to_string(),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = concat!(
+            "\x1e1111111111111111111111111111111111111111\x002222222222222222222222222222222222222222\x00",
+            "Jane Doe\x00jane@example.com\x001714557600\x002024-05-01 12:00:00 +0200\x00",
+            "Rename the parser\n\nAnd fix its tests.\n\x00\x00\n",
+            "R087\x00src/old.rs\x00src/new.rs\x00M\x00src/lib.rs\x00",
+            "\x1e2222222222222222222222222222222222222222\x00\x00",
+            "John Doe\x00john@example.com\x001714471200\x002024-04-30 10:00:00 -0100\x00",
+            "Initial commit\n\x00\x00\nA\x00src/old.rs\x00",
+        );
+
+        let commits = parse_log(output).unwrap();
+        assert_eq!(commits.len(), 2);
+
+        let first = &commits[0];
+        assert_eq!(
+            first.sha.to_string(),
+            "1111111111111111111111111111111111111111"
+        );
+        assert_eq!(first.parent_shas, vec![commits[1].sha]);
+        assert_eq!(first.author, "Jane Doe");
+        assert_eq!(first.author_mail, "jane@example.com");
+        assert_eq!(first.author_time, 1714557600);
+        assert_eq!(first.author_tz, "+0200");
+        assert_eq!(first.message, "Rename the parser\n\nAnd fix its tests.");
+        assert_eq!(first.summary(), "Rename the parser");
+        assert_eq!(
+            first.files,
+            vec![
+                CommitFile {
+                    status: CommitFileStatus::Renamed,
+                    path: "src/new.rs".into(),
+                    old_path: Some("src/old.rs".into()),
+                },
+                CommitFile {
+                    status: CommitFileStatus::Modified,
+                    path: "src/lib.rs".into(),
+                    old_path: None,
+                },
+            ]
+        );
+        assert_eq!(first.files[0].path_before(), Path::new("src/old.rs"));
+
+        let second = &commits[1];
+        assert!(second.parent_shas.is_empty());
+        assert_eq!(second.author_tz, "-0100");
+        assert_eq!(
+            second.files,
+            vec![CommitFile {
+                status: CommitFileStatus::Added,
+                path: "src/old.rs".into(),
+                old_path: None,
+            }]
+        );
+        assert_eq!(
+            second.author_offset_date_time().unwrap().offset(),
+            UtcOffset::from_hms(-1, 0, 0).unwrap()
+        );
+    }
+}
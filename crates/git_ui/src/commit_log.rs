@@ -0,0 +1,633 @@
+use std::{any::TypeId, path::Path};
+
+use anyhow::Result;
+use editor::{actions::ShowCommit, Editor, MultiBuffer};
+use git::log::{CommitDetails, CommitFile, CommitFileStatus};
+use gpui::{
+    actions, uniform_list, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle,
+    FocusableView, KeyContext, Model, Subscription, Task, UniformListScrollHandle, View, WeakView,
+};
+use language::Buffer;
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{GitLog, GitRevision, Item as _, Project, ProjectPath};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, ListItem, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    Workspace,
+};
+
+actions!(commit_log, [ShowRepositoryLog, ShowFileHistory]);
+
+const MAX_COMMIT_COUNT: usize = 1000;
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace, _: &ShowRepositoryLog, cx| {
+        let project = workspace.project().read(cx);
+        let work_directories = project.git_work_directories(cx);
+        // Prefer the innermost repository of the active file.
+        let active_path = active_project_path(workspace, cx);
+        let work_directory = work_directories
+            .iter()
+            .filter(|work_directory| {
+                active_path.as_ref().map_or(false, |active_path| {
+                    active_path.worktree_id == work_directory.worktree_id
+                        && active_path.path.starts_with(&work_directory.path)
+                })
+            })
+            .max_by_key(|work_directory| work_directory.path.components().count())
+            .or(work_directories.first())
+            .cloned();
+        if let Some(work_directory) = work_directory {
+            CommitLog::deploy(workspace, work_directory, None, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &ShowFileHistory, cx| {
+        if let Some(path) = active_project_path(workspace, cx) {
+            CommitLog::deploy(workspace, path, None, cx);
+        }
+    });
+    workspace.register_action(|workspace, action: &ShowCommit, cx| {
+        if let Some(path) = active_project_path(workspace, cx) {
+            CommitLog::deploy(workspace, path, Some(action.sha.clone()), cx);
+        }
+    });
+}
+
+fn active_project_path(workspace: &Workspace, cx: &AppContext) -> Option<ProjectPath> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let project_path = buffer.read(cx).project_path(cx);
+    project_path
+}
+
+/// A workspace item listing the commits of a repository, or the history of a file, with the details of the
+/// selected commit and the files it changed.
+pub struct CommitLog {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    /// The working directory of the repository, or the file whose history is listed.
+    path: ProjectPath,
+    log: Option<GitLog>,
+    selected_ix: Option<usize>,
+    /// The SHA of the commit to select once the log is loaded.
+    sha_to_select: Option<String>,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_load: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CommitLog {
+    fn deploy(
+        workspace: &mut Workspace,
+        path: ProjectPath,
+        sha: Option<String>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<CommitLog>(cx)
+            .find(|commit_log| commit_log.read(cx).path == path);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, cx);
+            if let Some(sha) = sha {
+                existing.update(cx, |commit_log, cx| commit_log.select_sha(sha, cx));
+            }
+            return;
+        }
+
+        let project = workspace.project().clone();
+        let workspace_handle = cx.view().downgrade();
+        let commit_log = cx.new_view(|cx| Self::new(workspace_handle, project, path, sha, cx));
+        workspace.add_item_to_active_pane(Box::new(commit_log), cx);
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        path: ProjectPath,
+        sha_to_select: Option<String>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let subscriptions = vec![cx.subscribe(&project, |this, _, event, cx| {
+            if let project::Event::WorktreeUpdatedGitRepositories = event {
+                this.load(cx);
+            }
+        })];
+        let mut this = Self {
+            workspace,
+            project,
+            path,
+            log: None,
+            selected_ix: None,
+            sha_to_select,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            pending_load: None,
+            _subscriptions: subscriptions,
+        };
+        this.load(cx);
+        this
+    }
+
+    fn is_file_history(&self, cx: &AppContext) -> bool {
+        self.project
+            .read(cx)
+            .entry_for_path(&self.path, cx)
+            .map_or(false, |entry| entry.is_file())
+    }
+
+    fn commits(&self) -> &[CommitDetails] {
+        self.log.as_ref().map_or(&[], |log| &log.commits)
+    }
+
+    fn selected_commit(&self) -> Option<&CommitDetails> {
+        self.commits().get(self.selected_ix?)
+    }
+
+    /// Lists the commits again, keeping the selected commit selected.
+    fn load(&mut self, cx: &mut ViewContext<Self>) {
+        if self.sha_to_select.is_none() {
+            self.sha_to_select = self.selected_commit().map(|commit| commit.sha.to_string());
+        }
+        let log = self
+            .project
+            .read(cx)
+            .git_log(&self.path, "HEAD".into(), MAX_COMMIT_COUNT, cx);
+        self.pending_load = Some(cx.spawn(|this, mut cx| async move {
+            let log = log.await;
+            this.update(&mut cx, |this, cx| {
+                match log {
+                    Ok(log) => {
+                        this.log = Some(log);
+                        this.error = None;
+                    }
+                    Err(error) => {
+                        this.log = None;
+                        this.error = Some(format!("{error:#}").into());
+                    }
+                }
+                this.selected_ix = None;
+                this.pending_load = None;
+                match this.sha_to_select.take() {
+                    Some(sha) => this.select_sha(sha, cx),
+                    None => {
+                        if !this.commits().is_empty() {
+                            this.select(0, cx);
+                        }
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Selects the commit with the SHA, loading it when it is older than the listed commits.
+    fn select_sha(&mut self, sha: String, cx: &mut ViewContext<Self>) {
+        if self.pending_load.is_some() {
+            self.sha_to_select = Some(sha);
+            return;
+        }
+        if let Some(ix) = self
+            .commits()
+            .iter()
+            .position(|commit| commit.sha.to_string() == sha)
+        {
+            self.select(ix, cx);
+            return;
+        }
+        let Some(work_directory) = self.log.as_ref().map(|log| log.work_directory.clone()) else {
+            return;
+        };
+        let commit = self.project.read(cx).git_log(&work_directory, sha, 1, cx);
+        cx.spawn(|this, mut cx| async move {
+            let commit = commit.await?.commits.into_iter().next();
+            this.update(&mut cx, |this, cx| {
+                let (Some(commit), Some(log)) = (commit, this.log.as_mut()) else {
+                    return;
+                };
+                let ix = log
+                    .commits
+                    .iter()
+                    .position(|listed| listed.author_time < commit.author_time)
+                    .unwrap_or(log.commits.len());
+                log.commits.insert(ix, commit);
+                this.select(ix, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_ix = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let count = self.commits().len();
+        if count > 0 {
+            let ix = self.selected_ix.map_or(0, |ix| (ix + 1).min(count - 1));
+            self.select(ix, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if !self.commits().is_empty() {
+            let ix = self.selected_ix.map_or(0, |ix| ix.saturating_sub(1));
+            self.select(ix, cx);
+        }
+    }
+
+    /// Opens the diff of the selected commit, for the file whose history is listed, or for its first file.
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some(commit) = self.selected_commit() else {
+            return;
+        };
+        if let Some(file) = commit.files.first().cloned() {
+            self.open_diff(commit.clone(), file, cx);
+        }
+    }
+
+    /// Opens a diff view of the changes of the commit to the file, compared to the first parent of the commit.
+    fn open_diff(&self, commit: CommitDetails, file: CommitFile, cx: &mut ViewContext<Self>) {
+        let Some(log) = self.log.as_ref() else {
+            return;
+        };
+        let old_text = match (commit.parent_shas.first(), file.status) {
+            (Some(parent), status) if status != CommitFileStatus::Added => {
+                Some(self.project.read(cx).load_git_text(
+                    &log.project_path(file.path_before()),
+                    GitRevision::Commit(parent.to_string()),
+                    cx,
+                ))
+            }
+            _ => None,
+        };
+        let new_text = (file.status != CommitFileStatus::Deleted).then(|| {
+            self.project.read(cx).load_git_text(
+                &log.project_path(&file.path),
+                GitRevision::Commit(commit.sha.to_string()),
+                cx,
+            )
+        });
+        let short_sha = short_sha(&commit);
+        let old_label = match commit.parent_shas.first() {
+            Some(parent) => format!(
+                "{} ({})",
+                file.path_before().display(),
+                &parent.to_string()[..7]
+            ),
+            None => format!("{} (empty)", file.path_before().display()),
+        };
+        let new_label = format!("{} ({short_sha})", file.path.display());
+
+        cx.spawn(|this, mut cx| async move {
+            let old_text = match old_text {
+                Some(text) => text.await?.unwrap_or_default(),
+                None => String::new(),
+            };
+            let new_text = match new_text {
+                Some(text) => text.await?.unwrap_or_default(),
+                None => String::new(),
+            };
+            let workspace = this.update(&mut cx, |this, _| this.workspace.clone())?;
+            let old_buffer = revision_buffer(&workspace, old_text, file.path_before(), &mut cx)?;
+            let new_buffer = revision_buffer(&workspace, new_text, &file.path, &mut cx)?;
+            workspace.update(&mut cx, |workspace, cx| {
+                diff_view::open_diff(
+                    workspace,
+                    old_buffer,
+                    old_label.into(),
+                    new_buffer,
+                    new_label.into(),
+                    cx,
+                )
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens the file as it is in the commit, in a read-only editor.
+    fn open_file_at_revision(
+        &self,
+        commit: &CommitDetails,
+        path: &Path,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(log) = self.log.as_ref() else {
+            return;
+        };
+        let text = self.project.read(cx).load_git_text(
+            &log.project_path(path),
+            GitRevision::Commit(commit.sha.to_string()),
+            cx,
+        );
+        let title = format!("{} ({})", path.display(), short_sha(commit));
+        let path = path.to_path_buf();
+        cx.spawn(|this, mut cx| async move {
+            let text = text.await?.unwrap_or_default();
+            let workspace = this.update(&mut cx, |this, _| this.workspace.clone())?;
+            let buffer = revision_buffer(&workspace, text, &path, &mut cx)?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let multibuffer =
+                    cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::for_multibuffer(multibuffer, None, cx);
+                    editor.set_read_only(true);
+                    editor
+                });
+                workspace.add_item_to_active_pane(Box::new(editor), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_commit(&self, ix: usize, commit: &CommitDetails, cx: &ViewContext<Self>) -> ListItem {
+        let timestamp = format_timestamp(commit, TimestampFormat::Relative, cx);
+        ListItem::new(("commit", ix))
+            .selected(self.selected_ix == Some(ix))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        Label::new(short_sha(commit))
+                            .size(LabelSize::Small)
+                            .color(Color::Accent),
+                    )
+                    .child(
+                        div().flex_1().overflow_hidden().child(
+                            Label::new(commit.summary().to_string())
+                                .size(LabelSize::Small)
+                                .single_line(),
+                        ),
+                    )
+                    .child(
+                        Label::new(commit.author.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .child(
+                        Label::new(timestamp)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .on_click(cx.listener(move |this, event: &gpui::ClickEvent, cx| {
+                this.select(ix, cx);
+                if event.up.click_count > 1 {
+                    this.confirm(&Confirm, cx);
+                }
+            }))
+    }
+
+    fn render_details(&self, commit: &CommitDetails, cx: &ViewContext<Self>) -> impl IntoElement {
+        let files = commit
+            .files
+            .iter()
+            .enumerate()
+            .map(|(ix, file)| self.render_file(ix, commit, file, cx));
+        v_flex()
+            .id("commit-details")
+            .size_full()
+            .p_2()
+            .gap_2()
+            .overflow_y_scroll()
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(commit.sha.to_string()).color(Color::Accent))
+                    .child(
+                        Label::new(format!("{} <{}>", commit.author, commit.author_mail))
+                            .size(LabelSize::Small),
+                    )
+                    .child(
+                        Label::new(format_timestamp(commit, TimestampFormat::Absolute, cx))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(div().child(commit.message.clone()))
+            .child(
+                v_flex()
+                    .child(
+                        Label::new(format!("Changed Files ({})", commit.files.len()))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .children(files),
+            )
+    }
+
+    fn render_file(
+        &self,
+        ix: usize,
+        commit: &CommitDetails,
+        file: &CommitFile,
+        cx: &ViewContext<Self>,
+    ) -> ListItem {
+        let color = match file.status {
+            CommitFileStatus::Added | CommitFileStatus::Copied => Color::Created,
+            CommitFileStatus::Deleted => Color::Deleted,
+            _ => Color::Modified,
+        };
+        let label = match &file.old_path {
+            Some(old_path) => format!("{} → {}", old_path.display(), file.path.display()),
+            None => file.path.display().to_string(),
+        };
+        let open_button = (file.status != CommitFileStatus::Deleted).then(|| {
+            let commit = commit.clone();
+            let path = file.path.clone();
+            IconButton::new(("open-file-at-revision", ix), IconName::File)
+                .icon_size(IconSize::XSmall)
+                .tooltip(|cx| Tooltip::text("Open File at Revision", cx))
+                .on_click(
+                    cx.listener(move |this, _, cx| this.open_file_at_revision(&commit, &path, cx)),
+                )
+        });
+        let commit = commit.clone();
+        let file = file.clone();
+        ListItem::new(("commit-file", ix))
+            .start_slot(
+                Label::new(file.status.letter())
+                    .size(LabelSize::Small)
+                    .color(color),
+            )
+            .child(Label::new(label).size(LabelSize::Small).single_line())
+            .end_hover_slot::<IconButton>(open_button)
+            .on_click(
+                cx.listener(move |this, _, cx| this.open_diff(commit.clone(), file.clone(), cx)),
+            )
+    }
+}
+
+fn short_sha(commit: &CommitDetails) -> String {
+    commit.sha.to_string().chars().take(7).collect()
+}
+
+fn format_timestamp(commit: &CommitDetails, format: TimestampFormat, cx: &WindowContext) -> String {
+    match commit.author_offset_date_time() {
+        Ok(timestamp) => time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            cx.local_timezone(),
+            format,
+        ),
+        Err(_) => "Error parsing date".to_string(),
+    }
+}
+
+/// Creates a local buffer with the contents of a file in some revision, with the language of its path.
+fn revision_buffer(
+    workspace: &WeakView<Workspace>,
+    text: String,
+    path: &Path,
+    cx: &mut gpui::AsyncWindowContext,
+) -> Result<Model<Buffer>> {
+    let path = path.to_path_buf();
+    workspace.update(cx, |workspace, cx| {
+        let languages = workspace.project().read(cx).languages().clone();
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            buffer.set_language_registry(languages.clone());
+            buffer
+        });
+        let buffer_handle = buffer.clone();
+        cx.spawn(|_, mut cx| async move {
+            let language = languages.language_for_file_path(&path).await.ok();
+            buffer_handle.update(&mut cx, |buffer, cx| buffer.set_language(language, cx))
+        })
+        .detach_and_log_err(cx);
+        buffer
+    })
+}
+
+impl EventEmitter<ItemEvent> for CommitLog {}
+
+impl FocusableView for CommitLog {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for CommitLog {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::default();
+        key_context.add("CommitLog");
+        key_context.add("menu");
+
+        let content = if let Some(error) = self.error.clone() {
+            div()
+                .p_2()
+                .child(Label::new(error).color(Color::Error))
+                .into_any_element()
+        } else if self.commits().is_empty() {
+            let message = if self.pending_load.is_some() {
+                "Loading commits…"
+            } else {
+                "No commits."
+            };
+            div()
+                .p_2()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            h_flex()
+                .size_full()
+                .child(
+                    div().h_full().w_3_5().child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "commit-log",
+                            self.commits().len(),
+                            |this, range, cx| {
+                                range
+                                    .map(|ix| this.render_commit(ix, &this.commits()[ix], cx))
+                                    .collect()
+                            },
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    ),
+                )
+                .child(
+                    div()
+                        .h_full()
+                        .w_2_5()
+                        .border_l_1()
+                        .border_color(cx.theme().colors().border)
+                        .children(
+                            self.selected_commit()
+                                .map(|commit| self.render_details(commit, cx)),
+                        ),
+                )
+                .into_any_element()
+        };
+
+        div()
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
+
+impl Item for CommitLog {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let title = if self.is_file_history(cx) {
+            let file_name = self
+                .path
+                .path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("History: {file_name}")
+        } else {
+            "Commit Log".to_string()
+        };
+        Label::new(title)
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.path.path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("commit log")
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else {
+            None
+        }
+    }
+}
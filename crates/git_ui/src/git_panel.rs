@@ -0,0 +1,939 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Result};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, EditorEvent};
+use fs::repository::{GitChange, GitStatusEntry};
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View, WeakView,
+};
+use project::{Fs, Project, ProjectPath, RepositoryStatus};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use ui::{prelude::*, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::{git_panel_settings::GitPanelSettings, project_conflicts};
+
+const GIT_PANEL_KEY: &str = "GitPanel";
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+actions!(
+    git_panel,
+    [
+        ToggleFocus,
+        StageAll,
+        UnstageAll,
+        Commit,
+        ToggleAmend,
+        Refresh
+    ]
+);
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    Staged,
+    Unstaged,
+    Untracked,
+}
+
+impl Section {
+    fn title(self) -> &'static str {
+        match self {
+            Section::Staged => "Staged Changes",
+            Section::Unstaged => "Changes",
+            Section::Untracked => "Untracked Files",
+        }
+    }
+
+    /// The change of the entry listed in this section, if it is listed in it.
+    fn change(self, entry: &GitStatusEntry) -> Option<GitChange> {
+        match self {
+            Section::Staged => entry.staged,
+            Section::Unstaged => entry
+                .unstaged
+                .filter(|change| *change != GitChange::Untracked),
+            Section::Untracked => entry
+                .unstaged
+                .filter(|change| *change == GitChange::Untracked),
+        }
+    }
+}
+
+/// A panel listing the staged, unstaged and untracked files of a git repository of the project, with
+/// an editor for the message of the next commit.
+pub struct GitPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    repositories: Vec<RepositoryStatus>,
+    /// The working directory of the repository the panel shows and commits to.
+    active_repository: Option<ProjectPath>,
+    commit_editor: View<Editor>,
+    /// Whether the next commit replaces the HEAD commit.
+    amend: bool,
+    /// Whether the panel is shown, as the changed files are only listed while it is.
+    active: bool,
+    pending_refresh: Option<Task<()>>,
+    pending_commit: Option<Task<()>>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GitPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let commit_editor = cx.new_view(|cx| {
+                let mut editor = Editor::multi_line(cx);
+                editor.set_placeholder_text("Commit message", cx);
+                editor.set_show_gutter(false, cx);
+                editor.set_wrap_guides(vec![GitPanelSettings::get_global(cx).summary_length], cx);
+                editor
+            });
+            let subscriptions = vec![
+                cx.subscribe(&project, |this, _, event, cx| match event {
+                    project::Event::WorktreeAdded
+                    | project::Event::WorktreeRemoved(_)
+                    | project::Event::WorktreeUpdatedGitRepositories
+                        if this.active =>
+                    {
+                        this.refresh(cx)
+                    }
+                    _ => {}
+                }),
+                cx.subscribe(&commit_editor, |_, _, event, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        cx.notify();
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|this, cx| {
+                    let summary_length = GitPanelSettings::get_global(cx).summary_length;
+                    this.commit_editor.update(cx, |editor, cx| {
+                        editor.set_wrap_guides(vec![summary_length], cx)
+                    });
+                }),
+            ];
+
+            Self {
+                workspace: workspace_handle,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                height: None,
+                repositories: Vec::new(),
+                active_repository: None,
+                commit_editor,
+                amend: false,
+                active: false,
+                pending_refresh: None,
+                pending_commit: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+                .await;
+            let serialized_panel = if let Some(panel) = serialized_panel.log_err().flatten() {
+                Some(serde_json::from_str::<SerializedGitPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        panel.height = serialized_panel.height.map(|h| h.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let height = self.height;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width, height })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Lists the changed files of the repositories of the project again. Done whenever the panel is
+    /// shown, and while it is, whenever the git repositories of the project change.
+    pub fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.pending_refresh = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            let Ok(statuses) =
+                this.update(&mut cx, |this, cx| this.project.read(cx).git_statuses(cx))
+            else {
+                return;
+            };
+            let repositories = statuses.await;
+            this.update(&mut cx, |this, cx| {
+                let active_repository_exists =
+                    this.active_repository.as_ref().map_or(false, |active| {
+                        repositories
+                            .iter()
+                            .any(|repository| &repository.work_directory == active)
+                    });
+                if !active_repository_exists {
+                    this.active_repository = repositories
+                        .first()
+                        .map(|repository| repository.work_directory.clone());
+                }
+                this.repositories = repositories;
+                this.pending_refresh = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn active_repository(&self) -> Option<&RepositoryStatus> {
+        let active_repository = self.active_repository.as_ref()?;
+        self.repositories
+            .iter()
+            .find(|repository| &repository.work_directory == active_repository)
+    }
+
+    fn section_paths(&self, section: Section) -> Vec<ProjectPath> {
+        let Some(repository) = self.active_repository() else {
+            return Vec::new();
+        };
+        repository
+            .entries
+            .iter()
+            .filter(|entry| section.change(entry).is_some())
+            .map(|entry| repository.project_path(&entry.repo_path))
+            .collect()
+    }
+
+    /// Stages the changes of all the tracked and untracked files of the active repository.
+    pub fn stage_all(&mut self, cx: &mut ViewContext<Self>) {
+        let mut paths = self.section_paths(Section::Unstaged);
+        paths.extend(self.section_paths(Section::Untracked));
+        self.stage(paths, cx);
+    }
+
+    /// Unstages all the staged changes of the active repository.
+    pub fn unstage_all(&mut self, cx: &mut ViewContext<Self>) {
+        let paths = self.section_paths(Section::Staged);
+        self.unstage(paths, cx);
+    }
+
+    fn stage(&mut self, paths: Vec<ProjectPath>, cx: &mut ViewContext<Self>) {
+        if paths.is_empty() {
+            return;
+        }
+        let task = self
+            .project
+            .update(cx, |project, cx| project.stage_paths(paths, cx));
+        self.refresh_after(task, cx);
+    }
+
+    fn unstage(&mut self, paths: Vec<ProjectPath>, cx: &mut ViewContext<Self>) {
+        if paths.is_empty() {
+            return;
+        }
+        let task = self
+            .project
+            .update(cx, |project, cx| project.unstage_paths(paths, cx));
+        self.refresh_after(task, cx);
+    }
+
+    fn refresh_after(&mut self, task: Task<Result<()>>, cx: &mut ViewContext<Self>) {
+        cx.spawn(|this, mut cx| async move {
+            let result = task.await;
+            this.update(&mut cx, |this, cx| this.refresh(cx))?;
+            result
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Switches between creating a new commit and amending the HEAD commit, filling the empty commit
+    /// message with the one of the HEAD commit when starting to amend.
+    pub fn toggle_amend(&mut self, cx: &mut ViewContext<Self>) {
+        self.amend = !self.amend;
+        if self.amend && self.commit_editor.read(cx).text(cx).trim().is_empty() {
+            if let Some(work_directory) = self.active_repository.as_ref() {
+                let last_commit_message = self
+                    .project
+                    .read(cx)
+                    .last_commit_message(work_directory, cx);
+                cx.spawn(|this, mut cx| async move {
+                    let Some(message) = last_commit_message.await? else {
+                        return Ok(());
+                    };
+                    this.update(&mut cx, |this, cx| {
+                        // The message may have been typed, or amending turned off, while it was loading.
+                        let commit_editor = this.commit_editor.clone();
+                        if this.amend && commit_editor.read(cx).text(cx).trim().is_empty() {
+                            commit_editor
+                                .update(cx, |editor, cx| editor.set_text(message.trim_end(), cx));
+                        }
+                    })
+                })
+                .detach_and_log_err(cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Commits the staged changes of the active repository with the message of the commit editor, or
+    /// amends the HEAD commit with them.
+    pub fn commit(&mut self, cx: &mut ViewContext<Self>) {
+        if self.pending_commit.is_some() {
+            return;
+        }
+        let Some(repository) = self.active_repository() else {
+            return;
+        };
+        let message = self.commit_editor.read(cx).text(cx);
+        let has_staged_changes = repository
+            .entries
+            .iter()
+            .any(|entry| entry.staged.is_some());
+        let error = if message.trim().is_empty() {
+            Some(anyhow!("the commit message is empty"))
+        } else if !has_staged_changes && !self.amend {
+            Some(anyhow!("there are no staged changes to commit"))
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            self.workspace
+                .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                .ok();
+            return;
+        }
+
+        let amend = self.amend;
+        let task = self
+            .project
+            .read(cx)
+            .commit(&repository.work_directory, message, amend, cx);
+        self.pending_commit = Some(cx.spawn(|this, mut cx| async move {
+            let result = task.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_commit = None;
+                match result {
+                    Ok(()) => {
+                        this.amend = false;
+                        this.commit_editor.update(cx, |editor, cx| editor.clear(cx));
+                    }
+                    Err(error) => {
+                        this.workspace
+                            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                            .ok();
+                    }
+                }
+                this.refresh(cx);
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn open_entry(&mut self, project_path: ProjectPath, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = match self.active_repository() {
+            Some(repository) => {
+                let work_directory = self
+                    .project
+                    .read(cx)
+                    .worktree_for_id(repository.work_directory.worktree_id, cx)
+                    .map(|worktree| {
+                        Path::new(worktree.read(cx).root_name())
+                            .join(&repository.work_directory.path)
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+                match &repository.branch {
+                    Some(branch) => format!("{work_directory} ({branch})"),
+                    None => work_directory,
+                }
+            }
+            None if self.pending_refresh.is_some() => "Reading repositories…".to_string(),
+            None => "No git repository".to_string(),
+        };
+        let has_unstaged_changes = !self.section_paths(Section::Unstaged).is_empty()
+            || !self.section_paths(Section::Untracked).is_empty();
+        let has_staged_changes = !self.section_paths(Section::Staged).is_empty();
+        let has_conflicts = self.active_repository().map_or(false, |repository| {
+            repository
+                .entries
+                .iter()
+                .any(|entry| entry.unstaged == Some(GitChange::Conflict))
+        });
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small).single_line())
+            .child(
+                h_flex()
+                    .gap_1()
+                    .when(has_conflicts, |this| {
+                        this.child(render_button(
+                            "git-panel-conflicts",
+                            IconName::ExclamationTriangle,
+                            "Show Merge Conflicts",
+                            project_conflicts::Deploy,
+                            true,
+                        ))
+                    })
+                    .child(render_button(
+                        "git-panel-stage-all",
+                        IconName::Plus,
+                        "Stage All",
+                        StageAll,
+                        has_unstaged_changes,
+                    ))
+                    .child(render_button(
+                        "git-panel-unstage-all",
+                        IconName::Dash,
+                        "Unstage All",
+                        UnstageAll,
+                        has_staged_changes,
+                    ))
+                    .child(render_button(
+                        "git-panel-refresh",
+                        IconName::Update,
+                        "Refresh",
+                        Refresh,
+                        self.pending_refresh.is_none(),
+                    )),
+            )
+    }
+
+    fn render_repositories(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
+        if self.repositories.len() < 2 {
+            return None;
+        }
+        Some(
+            h_flex()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .flex_wrap()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .children(
+                    self.repositories
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, repository)| {
+                            let work_directory = repository.work_directory.clone();
+                            let label = if work_directory.path.as_os_str().is_empty() {
+                                self.project
+                                    .read(cx)
+                                    .worktree_for_id(work_directory.worktree_id, cx)
+                                    .map(|worktree| worktree.read(cx).root_name().to_string())
+                                    .unwrap_or_default()
+                            } else {
+                                work_directory.path.to_string_lossy().to_string()
+                            };
+                            let selected = self.active_repository.as_ref() == Some(&work_directory);
+                            Button::new(("git-panel-repository", ix), label)
+                                .label_size(LabelSize::Small)
+                                .selected(selected)
+                                .on_click(cx.listener(move |this, _, cx| {
+                                    this.active_repository = Some(work_directory.clone());
+                                    cx.notify();
+                                }))
+                        }),
+                ),
+        )
+    }
+
+    fn render_section(
+        &self,
+        section: Section,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<impl IntoElement> {
+        let repository = self.active_repository()?;
+        let entries = repository
+            .entries
+            .iter()
+            .filter_map(|entry| Some((entry, section.change(entry)?)))
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return None;
+        }
+
+        let staged = section == Section::Staged;
+        let header = ListItem::new(SharedString::from(format!("git-section-{section:?}")))
+            .child(
+                Label::new(format!("{} ({})", section.title(), entries.len()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .end_hover_slot(
+                IconButton::new(
+                    SharedString::from(format!("git-section-{section:?}-update")),
+                    if staged {
+                        IconName::Dash
+                    } else {
+                        IconName::Plus
+                    },
+                )
+                .icon_size(IconSize::XSmall)
+                .tooltip(move |cx| {
+                    Tooltip::text(if staged { "Unstage All" } else { "Stage All" }, cx)
+                })
+                .on_click(cx.listener(move |this, _, cx| {
+                    let paths = this.section_paths(section);
+                    if staged {
+                        this.unstage(paths, cx)
+                    } else {
+                        this.stage(paths, cx)
+                    }
+                })),
+            );
+
+        Some(
+            v_flex()
+                .child(header)
+                .children(
+                    entries
+                        .into_iter()
+                        .enumerate()
+                        .map(|(ix, (entry, change))| {
+                            let project_path = repository.project_path(&entry.repo_path);
+                            self.render_entry(section, ix, project_path, entry, change, cx)
+                        }),
+                ),
+        )
+    }
+
+    fn render_entry(
+        &self,
+        section: Section,
+        ix: usize,
+        project_path: ProjectPath,
+        entry: &GitStatusEntry,
+        change: GitChange,
+        cx: &mut ViewContext<Self>,
+    ) -> ListItem {
+        let (status, color) = match change {
+            GitChange::Added => ("A", Color::Created),
+            GitChange::Modified => ("M", Color::Modified),
+            GitChange::Deleted => ("D", Color::Deleted),
+            GitChange::Untracked => ("U", Color::Created),
+            GitChange::Conflict => ("!", Color::Conflict),
+        };
+        let staged = section == Section::Staged;
+        let update_button = {
+            let project_path = project_path.clone();
+            IconButton::new(
+                SharedString::from(format!("git-entry-{section:?}-{ix}-update")),
+                if staged {
+                    IconName::Dash
+                } else {
+                    IconName::Plus
+                },
+            )
+            .icon_size(IconSize::XSmall)
+            .tooltip(move |cx| Tooltip::text(if staged { "Unstage" } else { "Stage" }, cx))
+            .on_click(cx.listener(move |this, _, cx| {
+                if staged {
+                    this.unstage(vec![project_path.clone()], cx)
+                } else {
+                    this.stage(vec![project_path.clone()], cx)
+                }
+            }))
+        };
+        let file_name = entry
+            .repo_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let directory = entry
+            .repo_path
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        ListItem::new(SharedString::from(format!("git-entry-{section:?}-{ix}")))
+            .indent_level(1)
+            .start_slot(Label::new(status).size(LabelSize::Small).color(color))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(file_name).size(LabelSize::Small))
+                    .child(
+                        Label::new(directory)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+            )
+            .end_hover_slot(update_button)
+            .on_click(cx.listener(move |this, _, cx| {
+                if change != GitChange::Deleted {
+                    this.open_entry(project_path.clone(), cx)
+                }
+            }))
+    }
+
+    fn render_commit_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let summary_length = GitPanelSettings::get_global(cx).summary_length;
+        let message = self.commit_editor.read(cx).text(cx);
+        let summary = message.lines().next().unwrap_or_default().chars().count();
+        let title = if self.amend { "Amend" } else { "Commit" };
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .h(rems(8.))
+                    .p_1()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_md()
+                    .child(self.commit_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_between()
+                    .child(
+                        Label::new(format!("{summary}/{summary_length}"))
+                            .size(LabelSize::Small)
+                            .color(if summary > summary_length {
+                                Color::Warning
+                            } else {
+                                Color::Muted
+                            }),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("git-panel-amend", "Amend")
+                                    .label_size(LabelSize::Small)
+                                    .selected(self.amend)
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action(
+                                            "Amend the Last Commit",
+                                            &ToggleAmend,
+                                            cx,
+                                        )
+                                    })
+                                    .on_click(cx.listener(|this, _, cx| this.toggle_amend(cx))),
+                            )
+                            .child(
+                                Button::new("git-panel-commit", title)
+                                    .label_size(LabelSize::Small)
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(
+                                        self.pending_commit.is_some()
+                                            || self.active_repository().is_none(),
+                                    )
+                                    .tooltip(move |cx| Tooltip::for_action(title, &Commit, cx))
+                                    .on_click(cx.listener(|this, _, cx| this.commit(cx))),
+                            ),
+                    ),
+            )
+    }
+}
+
+fn render_button(
+    id: &'static str,
+    icon: IconName,
+    tooltip: &'static str,
+    action: impl gpui::Action,
+    enabled: bool,
+) -> IconButton {
+    let tooltip_action = action.boxed_clone();
+    IconButton::new(id, icon)
+        .icon_size(IconSize::Small)
+        .disabled(!enabled)
+        .tooltip(move |cx| Tooltip::for_action(tooltip, &*tooltip_action, cx))
+        .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let sections = [Section::Staged, Section::Unstaged, Section::Untracked]
+            .into_iter()
+            .filter_map(|section| self.render_section(section, cx))
+            .collect::<Vec<_>>();
+        let content = if sections.is_empty() {
+            let message = if self.active_repository.is_some() {
+                "No changes."
+            } else {
+                "The project has no git repository."
+            };
+            div()
+                .p_2()
+                .flex_1()
+                .child(
+                    Label::new(message)
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                )
+                .into_any_element()
+        } else {
+            v_flex()
+                .id("git-panel-entries")
+                .flex_1()
+                .overflow_y_scroll()
+                .children(sections)
+                .into_any_element()
+        };
+        v_flex()
+            .key_context("GitPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .children(self.render_repositories(cx))
+            .child(content)
+            .child(self.render_commit_editor(cx))
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        "GitPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        GitPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(self.fs.clone(), cx, move |settings| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        let settings = GitPanelSettings::get_global(cx);
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        if active {
+            self.refresh(cx);
+        }
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GitPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_stage_unstage_and_commit(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                ".git": {},
+                "a.txt": "one\nTWO\n",
+                "b.txt": "new\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/dir/.git");
+        fs.set_head_for_repo(dot_git, &[(Path::new("a.txt"), "one\ntwo\n".to_string())]);
+        fs.set_index_for_repo(dot_git, &[(Path::new("a.txt"), "one\ntwo\n".to_string())]);
+        fs.set_worktree_contents_for_repo(
+            dot_git,
+            &[
+                (Path::new("a.txt"), "one\nTWO\n".to_string()),
+                (Path::new("b.txt"), "new\n".to_string()),
+            ],
+        );
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let panel = workspace.update(cx, |workspace, cx| GitPanel::new(workspace, cx));
+        panel.update(cx, |panel, cx| panel.set_active(true, cx));
+        settle(cx);
+        panel.update(cx, |panel, _| {
+            assert!(section_files(panel, Section::Staged).is_empty());
+            assert_eq!(section_files(panel, Section::Unstaged), ["a.txt"]);
+            assert_eq!(section_files(panel, Section::Untracked), ["b.txt"]);
+        });
+
+        panel.update(cx, |panel, cx| panel.stage_all(cx));
+        settle(cx);
+        panel.update(cx, |panel, _| {
+            assert_eq!(section_files(panel, Section::Staged), ["a.txt", "b.txt"]);
+            assert!(section_files(panel, Section::Unstaged).is_empty());
+            assert!(section_files(panel, Section::Untracked).is_empty());
+        });
+
+        panel.update(cx, |panel, cx| panel.unstage_all(cx));
+        settle(cx);
+        panel.update(cx, |panel, _| {
+            assert!(section_files(panel, Section::Staged).is_empty());
+            assert_eq!(section_files(panel, Section::Unstaged), ["a.txt"]);
+            assert_eq!(section_files(panel, Section::Untracked), ["b.txt"]);
+        });
+
+        // Committing without a message leaves the staged changes as they are.
+        panel.update(cx, |panel, cx| panel.stage_all(cx));
+        settle(cx);
+        panel.update(cx, |panel, cx| panel.commit(cx));
+        settle(cx);
+        panel.update(cx, |panel, _| {
+            assert_eq!(section_files(panel, Section::Staged), ["a.txt", "b.txt"]);
+        });
+
+        panel.update(cx, |panel, cx| {
+            panel
+                .commit_editor
+                .update(cx, |editor, cx| editor.set_text("Change a and b", cx));
+            panel.commit(cx);
+        });
+        settle(cx);
+        panel.update(cx, |panel, cx| {
+            assert!(panel.active_repository().unwrap().entries.is_empty());
+            assert_eq!(panel.commit_editor.read(cx).text(cx), "");
+        });
+
+        // Amending starts from the message of the HEAD commit.
+        panel.update(cx, |panel, cx| panel.toggle_amend(cx));
+        settle(cx);
+        panel.update(cx, |panel, cx| {
+            assert!(panel.amend);
+            assert_eq!(panel.commit_editor.read(cx).text(cx), "Change a and b");
+        });
+    }
+
+    fn section_files(panel: &GitPanel, section: Section) -> Vec<String> {
+        panel
+            .section_paths(section)
+            .into_iter()
+            .map(|project_path| project_path.path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Runs the pending git operations and the refresh of the panel that follows them.
+    fn settle(cx: &mut VisualTestContext) {
+        cx.run_until_parked();
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            release_channel::init("0.0.0", cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+}
@@ -0,0 +1,52 @@
+mod commit_log;
+mod git_panel;
+mod git_panel_settings;
+mod project_conflicts;
+
+use gpui::{AppContext, ViewContext};
+use settings::Settings;
+use workspace::Workspace;
+
+pub use commit_log::{CommitLog, ShowFileHistory, ShowRepositoryLog};
+pub use git_panel::{Commit, GitPanel, Refresh, StageAll, ToggleAmend, ToggleFocus, UnstageAll};
+pub use git_panel_settings::GitPanelSettings;
+pub use project_conflicts::ProjectConflictsEditor;
+
+pub fn init(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+    cx.observe_new_views(ProjectConflictsEditor::register)
+        .detach();
+    cx.observe_new_views(commit_log::register).detach();
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<GitPanel>(cx);
+        });
+        workspace.register_action(|workspace, _: &StageAll, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.stage_all(cx))
+        });
+        workspace.register_action(|workspace, _: &UnstageAll, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.unstage_all(cx))
+        });
+        workspace.register_action(|workspace, _: &Commit, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.commit(cx))
+        });
+        workspace.register_action(|workspace, _: &ToggleAmend, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.toggle_amend(cx))
+        });
+        workspace.register_action(|workspace, _: &Refresh, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.refresh(cx))
+        });
+    })
+    .detach();
+}
+
+fn update_panel(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+    update: impl FnOnce(&mut GitPanel, &mut ViewContext<GitPanel>),
+) {
+    if let Some(panel) = workspace.panel::<GitPanel>(cx) {
+        panel.update(cx, update);
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::Result;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use futures::future::join_all;
+use git::conflict::parse_conflicts;
+use gpui::{
+    actions, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View, WeakView,
+};
+use language::Point;
+use project::{Project, ProjectPath};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+actions!(project_conflicts, [Deploy]);
+
+const CONTEXT_LINE_COUNT: u32 = 1;
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// A multibuffer of the merge conflicts left in the files of the project.
+pub struct ProjectConflictsEditor {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    editor: View<Editor>,
+    excerpts: Model<MultiBuffer>,
+    paths: Vec<ProjectPath>,
+    pending_refresh: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<EditorEvent> for ProjectConflictsEditor {}
+
+impl Render for ProjectConflictsEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let child = if self.paths.is_empty() {
+            div()
+                .bg(cx.theme().colors().editor_background)
+                .flex()
+                .items_center()
+                .justify_center()
+                .size_full()
+                .child(Label::new(if self.pending_refresh.is_some() {
+                    "Looking for merge conflicts…"
+                } else {
+                    "No merge conflicts"
+                }))
+        } else {
+            div().size_full().child(self.editor.clone())
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(child)
+    }
+}
+
+impl ProjectConflictsEditor {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::deploy);
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let project_event_subscription = cx.subscribe(&project, |this, _, event, cx| match event {
+            project::Event::WorktreeAdded
+            | project::Event::WorktreeRemoved(_)
+            | project::Event::WorktreeUpdatedEntries(..)
+            | project::Event::WorktreeUpdatedGitRepositories => this.refresh(cx),
+            _ => {}
+        });
+
+        let focus_handle = cx.focus_handle();
+        let focus_in_subscription = cx.on_focus_in(&focus_handle, |this, cx| this.focus_in(cx));
+
+        let excerpts = cx.new_model(|cx| {
+            MultiBuffer::new(project.read(cx).replica_id(), project.read(cx).capability())
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(excerpts.clone(), Some(project.clone()), cx);
+            editor.set_vertical_scroll_margin(5, cx);
+            editor
+        });
+        let editor_event_subscription =
+            cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+                if event == &EditorEvent::Focused && this.paths.is_empty() {
+                    cx.focus(&this.focus_handle);
+                }
+            });
+
+        let mut this = Self {
+            project,
+            workspace,
+            focus_handle,
+            editor,
+            excerpts,
+            paths: Vec::new(),
+            pending_refresh: None,
+            _subscriptions: vec![
+                project_event_subscription,
+                editor_event_subscription,
+                focus_in_subscription,
+            ],
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn deploy(workspace: &mut Workspace, _: &Deploy, cx: &mut ViewContext<Workspace>) {
+        if let Some(existing) = workspace.item_of_type::<ProjectConflictsEditor>(cx) {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let workspace_handle = cx.view().downgrade();
+            let conflicts = cx.new_view(|cx| {
+                ProjectConflictsEditor::new(workspace.project().clone(), workspace_handle, cx)
+            });
+            workspace.add_item_to_active_pane(Box::new(conflicts), cx);
+        }
+    }
+
+    fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
+        if self.focus_handle.is_focused(cx) && !self.paths.is_empty() {
+            self.editor.focus_handle(cx).focus(cx)
+        }
+    }
+
+    /// Lists the conflicted files of the project again, and rebuilds the excerpts if they changed.
+    ///
+    /// Files keep their excerpts until they are no longer conflicted, so resolving the conflicts
+    /// of a file does not remove it from under the cursor before it is staged.
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.pending_refresh = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            let Ok(paths) = this.update(&mut cx, |this, cx| {
+                this.project.read(cx).conflicted_paths(cx)
+            }) else {
+                return;
+            };
+            let mut paths = paths.await;
+            paths.sort_by(|a, b| (a.worktree_id, &a.path).cmp(&(b.worktree_id, &b.path)));
+
+            let Ok(Some(buffers)) = this.update(&mut cx, |this, cx| {
+                if this.paths == paths || this.excerpts.read(cx).is_dirty(cx) {
+                    this.pending_refresh = None;
+                    cx.notify();
+                    return None;
+                }
+                Some(
+                    paths
+                        .iter()
+                        .map(|path| {
+                            this.project
+                                .update(cx, |project, cx| project.open_buffer(path.clone(), cx))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            }) else {
+                return;
+            };
+            let buffers = join_all(buffers).await;
+
+            this.update(&mut cx, |this, cx| {
+                this.excerpts.update(cx, |excerpts, cx| {
+                    excerpts.clear(cx);
+                    for buffer in buffers.into_iter().filter_map(|buffer| buffer.log_err()) {
+                        let snapshot = buffer.read(cx).snapshot();
+                        let ranges = parse_conflicts(&snapshot.text())
+                            .into_iter()
+                            .map(|conflict| {
+                                snapshot.offset_to_point(conflict.range.start)
+                                    ..snapshot.offset_to_point(conflict.range.end)
+                            })
+                            .collect::<Vec<Range<Point>>>();
+                        if !ranges.is_empty() {
+                            excerpts.push_excerpts_with_context_lines(
+                                buffer,
+                                ranges,
+                                CONTEXT_LINE_COUNT,
+                                cx,
+                            );
+                        }
+                    }
+                });
+                this.paths = paths;
+                this.pending_refresh = None;
+                if this.focus_handle.is_focused(cx) {
+                    this.focus_in(cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+}
+
+impl FocusableView for ProjectConflictsEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ProjectConflictsEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Merge Conflicts".into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let label = if self.paths.is_empty() {
+            "No conflicts".to_string()
+        } else {
+            format!("Conflicts ({})", self.paths.len())
+        };
+        Label::new(label)
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("project conflicts")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| {
+            ProjectConflictsEditor::new(self.project.clone(), self.workspace.clone(), cx)
+        }))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: PathBuf,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.reload(project, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+}
@@ -0,0 +1,437 @@
+use anyhow::Result;
+use editor::Editor;
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, ClickEvent, EventEmitter, FocusHandle,
+    FocusableView, Model, Render, ViewContext, WeakView, WindowContext,
+};
+use language::Location;
+use project::{CallHierarchyCall, CallHierarchyItem, Project};
+use ui::prelude::*;
+use workspace::{
+    item::{Item, TabContentParams},
+    Pane, SplitDirection, Workspace,
+};
+
+use crate::hierarchy_tree::HierarchyTree;
+
+actions!(hierarchy_view, [ShowCallHierarchy]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(show_call_hierarchy);
+    })
+    .detach();
+}
+
+fn show_call_hierarchy(
+    workspace: &mut Workspace,
+    _: &ShowCallHierarchy,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+
+    let project = workspace.project().clone();
+    let editor_pane = workspace.active_pane().downgrade();
+    let roots = project.update(cx, |project, cx| {
+        project.prepare_call_hierarchy(&buffer, position, cx)
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let roots = roots.await?;
+        if roots.is_empty() {
+            return Ok(());
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            if let Some(view) = workspace.item_of_type::<CallHierarchyView>(cx) {
+                view.update(cx, |view, cx| view.set_roots(roots, editor_pane, cx));
+                workspace.activate_item(&view, cx);
+            } else {
+                let workspace_handle = workspace.weak_handle();
+                let view = cx.new_view(|cx| {
+                    CallHierarchyView::new(workspace_handle, project, editor_pane, roots, cx)
+                });
+                workspace.split_item(SplitDirection::Right, Box::new(view), cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallHierarchyDirection {
+    /// Show the callers of each item.
+    Incoming,
+    /// Show the functions called by each item.
+    Outgoing,
+}
+
+/// A tree of callers or callees, rooted at the symbols under the cursor
+/// when the hierarchy was requested. Children are fetched when an entry is expanded.
+pub struct CallHierarchyView {
+    project: Model<Project>,
+    direction: CallHierarchyDirection,
+    tree: HierarchyTree<CallHierarchyItem>,
+    focus_handle: FocusHandle,
+}
+
+impl CallHierarchyView {
+    pub fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        editor_pane: WeakView<Pane>,
+        roots: Vec<CallHierarchyItem>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            project,
+            direction: CallHierarchyDirection::Incoming,
+            tree: HierarchyTree::new(workspace, editor_pane.clone()),
+            focus_handle: cx.focus_handle(),
+        };
+        this.set_roots(roots, editor_pane, cx);
+        this
+    }
+
+    pub fn set_roots(
+        &mut self,
+        roots: Vec<CallHierarchyItem>,
+        editor_pane: WeakView<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.tree.set_roots(roots, editor_pane);
+        self.reset_entries(cx);
+    }
+
+    pub fn set_direction(&mut self, direction: CallHierarchyDirection, cx: &mut ViewContext<Self>) {
+        if self.direction != direction {
+            self.direction = direction;
+            self.reset_entries(cx);
+        }
+    }
+
+    fn reset_entries(&mut self, cx: &mut ViewContext<Self>) {
+        // Expand the first root right away, as that's almost always what's being asked for.
+        if let Some(entry_id) = self.tree.reset() {
+            self.toggle_expanded(entry_id, cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_expanded(&mut self, entry_id: usize, cx: &mut ViewContext<Self>) {
+        cx.notify();
+        let Some(item) = self.tree.toggle_expanded(entry_id) else {
+            return;
+        };
+
+        let direction = self.direction;
+        let calls = self.project.update(cx, |project, cx| match direction {
+            CallHierarchyDirection::Incoming => project.incoming_calls(&item, cx),
+            CallHierarchyDirection::Outgoing => project.outgoing_calls(&item, cx),
+        });
+        cx.spawn(|this, mut cx| async move {
+            let calls = calls.await;
+            this.update(&mut cx, |this, cx| this.calls_loaded(entry_id, calls, cx))?
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn calls_loaded(
+        &mut self,
+        entry_id: usize,
+        calls: Result<Vec<CallHierarchyCall>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        cx.notify();
+        let children = calls.map(|calls| {
+            calls
+                .into_iter()
+                .map(|call| (call.item, call.call_sites))
+                .collect::<Vec<(CallHierarchyItem, Vec<Location>)>>()
+        });
+        self.tree.children_loaded(entry_id, children)
+    }
+
+    fn open_entry(&mut self, entry_id: usize, cx: &mut ViewContext<Self>) {
+        self.tree.open_entry(entry_id, cx);
+        cx.notify();
+    }
+
+    fn render_direction_button(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        direction: CallHierarchyDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .selected(self.direction == direction)
+            .on_click(
+                cx.listener(move |this, _: &ClickEvent, cx| this.set_direction(direction, cx)),
+            )
+    }
+}
+
+impl Render for CallHierarchyView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .p_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.render_direction_button(
+                        "incoming-calls",
+                        "Incoming Calls",
+                        CallHierarchyDirection::Incoming,
+                        cx,
+                    ))
+                    .child(self.render_direction_button(
+                        "outgoing-calls",
+                        "Outgoing Calls",
+                        CallHierarchyDirection::Outgoing,
+                        cx,
+                    )),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "call-hierarchy-entries",
+                    self.tree.len(),
+                    |this, range, cx| {
+                        range
+                            .map(|ix| {
+                                let entry_id = this.tree.entry_id(ix);
+                                this.tree
+                                    .render_entry(ix, cx)
+                                    .on_toggle(cx.listener(move |this, _: &ClickEvent, cx| {
+                                        this.toggle_expanded(entry_id, cx)
+                                    }))
+                                    .on_click(cx.listener(move |this, _: &ClickEvent, cx| {
+                                        this.open_entry(entry_id, cx)
+                                    }))
+                            })
+                            .collect()
+                    },
+                )
+                .size_full()
+                .track_scroll(self.tree.list_scroll_handle()),
+            )
+    }
+}
+
+impl EventEmitter<()> for CallHierarchyView {}
+
+impl FocusableView for CallHierarchyView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CallHierarchyView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let title = match self.tree.roots().first() {
+            Some(root) => format!("Call Hierarchy: {}", root.name()),
+            None => "Call Hierarchy".to_string(),
+        };
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use gpui::{TestAppContext, VisualContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_incoming_and_outgoing_calls(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "fn a() { b() }",
+                "b.rs": "fn b() { c() }",
+                "c.rs": "fn c() {}",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(rust_lang());
+        let mut fake_servers = language_registry.register_fake_lsp_adapter(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+            .await
+            .unwrap();
+
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>(|_, _| async move {
+            Ok(Some(vec![function("b", "/dir/b.rs")]))
+        });
+        fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+            |params, _| async move {
+                let calls = match params.item.name.as_str() {
+                    "b" => vec![lsp::CallHierarchyIncomingCall {
+                        from: function("a", "/dir/a.rs"),
+                        from_ranges: vec![lsp::Range::new(
+                            lsp::Position::new(0, 9),
+                            lsp::Position::new(0, 10),
+                        )],
+                    }],
+                    _ => Vec::new(),
+                };
+                Ok(Some(calls))
+            },
+        );
+        fake_server.handle_request::<lsp::request::CallHierarchyOutgoingCalls, _, _>(
+            |params, _| async move {
+                let calls = match params.item.name.as_str() {
+                    "b" => vec![lsp::CallHierarchyOutgoingCall {
+                        to: function("c", "/dir/c.rs"),
+                        from_ranges: vec![lsp::Range::new(
+                            lsp::Position::new(0, 9),
+                            lsp::Position::new(0, 10),
+                        )],
+                    }],
+                    _ => Vec::new(),
+                };
+                Ok(Some(calls))
+            },
+        );
+
+        let roots = project
+            .update(cx, |project, cx| {
+                project.prepare_call_hierarchy(&buffer, 3, cx)
+            })
+            .await
+            .unwrap();
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let editor_pane = workspace.update(cx, |workspace, _| workspace.active_pane().downgrade());
+        let view = cx.new_view(|cx| {
+            CallHierarchyView::new(
+                workspace.downgrade(),
+                project.clone(),
+                editor_pane,
+                roots,
+                cx,
+            )
+        });
+
+        // The root is expanded right away, showing its callers.
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.tree.entry_names(), ["b", "  a"]);
+        });
+
+        // Expanding a caller without callers of its own doesn't add any entries.
+        view.update(cx, |view, cx| {
+            let entry_id = view.tree.entry_id(1);
+            view.toggle_expanded(entry_id, cx);
+        });
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.tree.entry_names(), ["b", "  a"]);
+        });
+
+        view.update(cx, |view, cx| {
+            view.set_direction(CallHierarchyDirection::Outgoing, cx)
+        });
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.tree.entry_names(), ["b", "  c"]);
+        });
+
+        // Collapsing the root hides its callees.
+        view.update(cx, |view, cx| {
+            let entry_id = view.tree.entry_id(0);
+            view.toggle_expanded(entry_id, cx);
+            assert_eq!(view.tree.entry_names(), ["b"]);
+        });
+    }
+
+    fn function(name: &str, path: &str) -> lsp::CallHierarchyItem {
+        lsp::CallHierarchyItem {
+            name: name.into(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path(path).unwrap(),
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 9)),
+            selection_range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+            data: None,
+        }
+    }
+
+    fn rust_lang() -> Arc<Language> {
+        Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        ))
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            release_channel::init("0.0.0", cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+        });
+    }
+}
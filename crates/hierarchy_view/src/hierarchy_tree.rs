@@ -0,0 +1,253 @@
+use anyhow::Result;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{AppContext, UniformListScrollHandle, WeakView, WindowContext};
+use language::{Location, ToPoint};
+use project::{CallHierarchyItem, TypeHierarchyItem};
+use ui::{prelude::*, ListItem};
+use util::{post_inc, ResultExt};
+use workspace::{Pane, Workspace};
+
+/// An item shown in a hierarchy view.
+pub(crate) trait HierarchyItem: Clone + 'static {
+    fn name(&self) -> &str;
+    fn detail(&self) -> Option<&str>;
+    fn location(&self) -> &Location;
+}
+
+impl HierarchyItem for CallHierarchyItem {
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail()
+    }
+
+    fn location(&self) -> &Location {
+        &self.location
+    }
+}
+
+impl HierarchyItem for TypeHierarchyItem {
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail()
+    }
+
+    fn location(&self) -> &Location {
+        &self.location
+    }
+}
+
+/// The entries of a hierarchy view, rooted at the symbols under the cursor
+/// when the hierarchy was requested. The view fetches the children of an entry when it's expanded.
+pub(crate) struct HierarchyTree<T> {
+    workspace: WeakView<Workspace>,
+    /// The pane in which locations are opened.
+    editor_pane: WeakView<Pane>,
+    roots: Vec<T>,
+    /// The visible entries, in depth-first order.
+    entries: Vec<Entry<T>>,
+    next_entry_id: usize,
+    selected_entry_id: Option<usize>,
+    list_scroll_handle: UniformListScrollHandle,
+}
+
+struct Entry<T> {
+    id: usize,
+    depth: usize,
+    item: T,
+    /// Where the call happens. Empty for type hierarchies and for the roots of the hierarchy.
+    call_sites: Vec<Location>,
+    state: EntryState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryState {
+    Collapsed,
+    Loading,
+    Expanded,
+}
+
+impl<T: HierarchyItem> HierarchyTree<T> {
+    pub fn new(workspace: WeakView<Workspace>, editor_pane: WeakView<Pane>) -> Self {
+        Self {
+            workspace,
+            editor_pane,
+            roots: Vec::new(),
+            entries: Vec::new(),
+            next_entry_id: 0,
+            selected_entry_id: None,
+            list_scroll_handle: UniformListScrollHandle::new(),
+        }
+    }
+
+    pub fn roots(&self) -> &[T] {
+        &self.roots
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entry_id(&self, ix: usize) -> usize {
+        self.entries[ix].id
+    }
+
+    pub fn list_scroll_handle(&self) -> UniformListScrollHandle {
+        self.list_scroll_handle.clone()
+    }
+
+    pub fn set_roots(&mut self, roots: Vec<T>, editor_pane: WeakView<Pane>) {
+        self.roots = roots;
+        self.editor_pane = editor_pane;
+    }
+
+    /// Replaces the entries with the collapsed roots, returning the id of the first one.
+    pub fn reset(&mut self) -> Option<usize> {
+        self.selected_entry_id = None;
+        self.entries = self
+            .roots
+            .clone()
+            .into_iter()
+            .map(|root| self.new_entry(0, root, Vec::new()))
+            .collect();
+        self.entries.first().map(|entry| entry.id)
+    }
+
+    fn new_entry(&mut self, depth: usize, item: T, call_sites: Vec<Location>) -> Entry<T> {
+        Entry {
+            id: post_inc(&mut self.next_entry_id),
+            depth,
+            item,
+            call_sites,
+            state: EntryState::Collapsed,
+        }
+    }
+
+    fn entry_ix(&self, entry_id: usize) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.id == entry_id)
+    }
+
+    /// Collapses an expanded entry. A collapsed entry is marked as loading instead,
+    /// and its item is returned for the view to fetch its children.
+    pub fn toggle_expanded(&mut self, entry_id: usize) -> Option<T> {
+        let ix = self.entry_ix(entry_id)?;
+        match self.entries[ix].state {
+            EntryState::Loading => None,
+            EntryState::Expanded => {
+                let depth = self.entries[ix].depth;
+                let subtree_end = self.entries[ix + 1..]
+                    .iter()
+                    .position(|entry| entry.depth <= depth)
+                    .map_or(self.entries.len(), |offset| ix + 1 + offset);
+                self.entries.drain(ix + 1..subtree_end);
+                self.entries[ix].state = EntryState::Collapsed;
+                None
+            }
+            EntryState::Collapsed => {
+                self.entries[ix].state = EntryState::Loading;
+                Some(self.entries[ix].item.clone())
+            }
+        }
+    }
+
+    pub fn children_loaded(
+        &mut self,
+        entry_id: usize,
+        children: Result<Vec<(T, Vec<Location>)>>,
+    ) -> Result<()> {
+        // The entry is gone if the hierarchy was reset while its children were loading.
+        let Some(ix) = self.entry_ix(entry_id) else {
+            return Ok(());
+        };
+
+        let children = match children {
+            Ok(children) => children,
+            Err(error) => {
+                self.entries[ix].state = EntryState::Collapsed;
+                return Err(error);
+            }
+        };
+        let depth = self.entries[ix].depth + 1;
+        let children = children
+            .into_iter()
+            .map(|(item, call_sites)| self.new_entry(depth, item, call_sites))
+            .collect::<Vec<_>>();
+        self.entries[ix].state = EntryState::Expanded;
+        self.entries.splice(ix + 1..ix + 1, children);
+        Ok(())
+    }
+
+    pub fn open_entry(&mut self, entry_id: usize, cx: &mut WindowContext) {
+        let Some(ix) = self.entry_ix(entry_id) else {
+            return;
+        };
+        self.selected_entry_id = Some(entry_id);
+
+        // Jump to the call itself, or to the declaration if there's no call site.
+        let entry = &self.entries[ix];
+        let location = entry
+            .call_sites
+            .first()
+            .unwrap_or(entry.item.location())
+            .clone();
+        let editor_pane = self.editor_pane.upgrade();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = editor_pane.unwrap_or_else(|| workspace.active_pane().clone());
+                let position = location.range.start.to_point(location.buffer.read(cx));
+                let editor = workspace.open_project_item::<Editor>(pane, location.buffer, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([position..position])
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    /// Renders the entry at the given index, leaving it to the view to handle toggles and clicks.
+    pub fn render_entry(&self, ix: usize, cx: &AppContext) -> ListItem {
+        let entry = &self.entries[ix];
+        let location = entry.item.location();
+        let buffer = location.buffer.read(cx);
+        let path = buffer
+            .file()
+            .map(|file| file.path().to_string_lossy().to_string())
+            .unwrap_or_default();
+        let row = location.range.start.to_point(buffer).row + 1;
+
+        ListItem::new(entry.id)
+            .indent_level(entry.depth)
+            .selected(self.selected_entry_id == Some(entry.id))
+            .toggle(entry.state != EntryState::Collapsed)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(entry.item.name().to_string()))
+                    .children(entry.item.detail().map(|detail| {
+                        Label::new(detail.to_string())
+                            .color(Color::Muted)
+                            .size(LabelSize::Small)
+                    }))
+                    .child(
+                        Label::new(format!("{path}:{row}"))
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    ),
+            )
+    }
+
+    /// The names of the visible entries, indented by their depth.
+    #[cfg(test)]
+    pub fn entry_names(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| format!("{}{}", "  ".repeat(entry.depth), entry.item.name()))
+            .collect()
+    }
+}
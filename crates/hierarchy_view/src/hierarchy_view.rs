@@ -0,0 +1,13 @@
+mod call_hierarchy;
+mod hierarchy_tree;
+mod type_hierarchy;
+
+use gpui::AppContext;
+
+pub use call_hierarchy::{CallHierarchyDirection, CallHierarchyView, ShowCallHierarchy};
+pub use type_hierarchy::{ShowTypeHierarchy, TypeHierarchyDirection, TypeHierarchyView};
+
+pub fn init(cx: &mut AppContext) {
+    call_hierarchy::init(cx);
+    type_hierarchy::init(cx);
+}
@@ -0,0 +1,411 @@
+use anyhow::Result;
+use editor::Editor;
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, ClickEvent, EventEmitter, FocusHandle,
+    FocusableView, Model, Render, ViewContext, WeakView, WindowContext,
+};
+use project::{Project, TypeHierarchyItem};
+use ui::prelude::*;
+use workspace::{
+    item::{Item, TabContentParams},
+    Pane, SplitDirection, Workspace,
+};
+
+use crate::hierarchy_tree::HierarchyTree;
+
+actions!(hierarchy_view, [ShowTypeHierarchy]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(show_type_hierarchy);
+    })
+    .detach();
+}
+
+fn show_type_hierarchy(
+    workspace: &mut Workspace,
+    _: &ShowTypeHierarchy,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+
+    let project = workspace.project().clone();
+    let editor_pane = workspace.active_pane().downgrade();
+    let roots = project.update(cx, |project, cx| {
+        project.prepare_type_hierarchy(&buffer, position, cx)
+    });
+    cx.spawn(|workspace, mut cx| async move {
+        let roots = roots.await?;
+        if roots.is_empty() {
+            return Ok(());
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            if let Some(view) = workspace.item_of_type::<TypeHierarchyView>(cx) {
+                view.update(cx, |view, cx| view.set_roots(roots, editor_pane, cx));
+                workspace.activate_item(&view, cx);
+            } else {
+                let workspace_handle = workspace.weak_handle();
+                let view = cx.new_view(|cx| {
+                    TypeHierarchyView::new(workspace_handle, project, editor_pane, roots, cx)
+                });
+                workspace.split_item(SplitDirection::Right, Box::new(view), cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeHierarchyDirection {
+    /// Show the types deriving from each item, e.g. the implementors of a trait.
+    Subtypes,
+    /// Show the types each item derives from, e.g. its supertraits or base classes.
+    Supertypes,
+}
+
+/// A tree of subtypes or supertypes, rooted at the symbols under the cursor
+/// when the hierarchy was requested. Children are fetched when an entry is expanded.
+pub struct TypeHierarchyView {
+    project: Model<Project>,
+    direction: TypeHierarchyDirection,
+    tree: HierarchyTree<TypeHierarchyItem>,
+    focus_handle: FocusHandle,
+}
+
+impl TypeHierarchyView {
+    pub fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        editor_pane: WeakView<Pane>,
+        roots: Vec<TypeHierarchyItem>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            project,
+            direction: TypeHierarchyDirection::Subtypes,
+            tree: HierarchyTree::new(workspace, editor_pane.clone()),
+            focus_handle: cx.focus_handle(),
+        };
+        this.set_roots(roots, editor_pane, cx);
+        this
+    }
+
+    pub fn set_roots(
+        &mut self,
+        roots: Vec<TypeHierarchyItem>,
+        editor_pane: WeakView<Pane>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.tree.set_roots(roots, editor_pane);
+        self.reset_entries(cx);
+    }
+
+    pub fn set_direction(&mut self, direction: TypeHierarchyDirection, cx: &mut ViewContext<Self>) {
+        if self.direction != direction {
+            self.direction = direction;
+            self.reset_entries(cx);
+        }
+    }
+
+    fn reset_entries(&mut self, cx: &mut ViewContext<Self>) {
+        // Expand the first root right away, as that's almost always what's being asked for.
+        if let Some(entry_id) = self.tree.reset() {
+            self.toggle_expanded(entry_id, cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_expanded(&mut self, entry_id: usize, cx: &mut ViewContext<Self>) {
+        cx.notify();
+        let Some(item) = self.tree.toggle_expanded(entry_id) else {
+            return;
+        };
+
+        let direction = self.direction;
+        let types = self.project.update(cx, |project, cx| match direction {
+            TypeHierarchyDirection::Subtypes => project.subtypes(&item, cx),
+            TypeHierarchyDirection::Supertypes => project.supertypes(&item, cx),
+        });
+        cx.spawn(|this, mut cx| async move {
+            let types = types.await;
+            this.update(&mut cx, |this, cx| this.types_loaded(entry_id, types, cx))?
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn types_loaded(
+        &mut self,
+        entry_id: usize,
+        types: Result<Vec<TypeHierarchyItem>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        cx.notify();
+        let children =
+            types.map(|types| types.into_iter().map(|item| (item, Vec::new())).collect());
+        self.tree.children_loaded(entry_id, children)
+    }
+
+    fn open_entry(&mut self, entry_id: usize, cx: &mut ViewContext<Self>) {
+        self.tree.open_entry(entry_id, cx);
+        cx.notify();
+    }
+
+    fn render_direction_button(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        direction: TypeHierarchyDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .selected(self.direction == direction)
+            .on_click(
+                cx.listener(move |this, _: &ClickEvent, cx| this.set_direction(direction, cx)),
+            )
+    }
+}
+
+impl Render for TypeHierarchyView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .p_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.render_direction_button(
+                        "subtypes",
+                        "Subtypes",
+                        TypeHierarchyDirection::Subtypes,
+                        cx,
+                    ))
+                    .child(self.render_direction_button(
+                        "supertypes",
+                        "Supertypes",
+                        TypeHierarchyDirection::Supertypes,
+                        cx,
+                    )),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "type-hierarchy-entries",
+                    self.tree.len(),
+                    |this, range, cx| {
+                        range
+                            .map(|ix| {
+                                let entry_id = this.tree.entry_id(ix);
+                                this.tree
+                                    .render_entry(ix, cx)
+                                    .on_toggle(cx.listener(move |this, _: &ClickEvent, cx| {
+                                        this.toggle_expanded(entry_id, cx)
+                                    }))
+                                    .on_click(cx.listener(move |this, _: &ClickEvent, cx| {
+                                        this.open_entry(entry_id, cx)
+                                    }))
+                            })
+                            .collect()
+                    },
+                )
+                .size_full()
+                .track_scroll(self.tree.list_scroll_handle()),
+            )
+    }
+}
+
+impl EventEmitter<()> for TypeHierarchyView {}
+
+impl FocusableView for TypeHierarchyView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for TypeHierarchyView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let title = match self.tree.roots().first() {
+            Some(root) => format!("Type Hierarchy: {}", root.name()),
+            None => "Type Hierarchy".to_string(),
+        };
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use gpui::{TestAppContext, VisualContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::sync::Arc;
+
+    #[gpui::test]
+    async fn test_subtypes_and_supertypes(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "trait A {}",
+                "b.rs": "trait B: A {}",
+                "c.rs": "trait C: B {}",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(rust_lang());
+        let mut fake_servers =
+            language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+            .await
+            .unwrap();
+
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.handle_request::<lsp::request::TypeHierarchyPrepare, _, _>(|_, _| async move {
+            Ok(Some(vec![trait_item("B", "/dir/b.rs")]))
+        });
+        fake_server.handle_request::<lsp::request::TypeHierarchySubtypes, _, _>(
+            |params, _| async move {
+                let subtypes = match params.item.name.as_str() {
+                    "B" => vec![trait_item("C", "/dir/c.rs")],
+                    _ => Vec::new(),
+                };
+                Ok(Some(subtypes))
+            },
+        );
+        fake_server.handle_request::<lsp::request::TypeHierarchySupertypes, _, _>(
+            |params, _| async move {
+                let supertypes = match params.item.name.as_str() {
+                    "C" => vec![trait_item("B", "/dir/b.rs")],
+                    "B" => vec![trait_item("A", "/dir/a.rs")],
+                    _ => Vec::new(),
+                };
+                Ok(Some(supertypes))
+            },
+        );
+
+        let roots = project
+            .update(cx, |project, cx| {
+                project.prepare_type_hierarchy(&buffer, 6, cx)
+            })
+            .await
+            .unwrap();
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let editor_pane = workspace.update(cx, |workspace, _| workspace.active_pane().downgrade());
+        let view = cx.new_view(|cx| {
+            TypeHierarchyView::new(
+                workspace.downgrade(),
+                project.clone(),
+                editor_pane,
+                roots,
+                cx,
+            )
+        });
+
+        // The root is expanded right away, showing its subtypes.
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.tree.entry_names(), ["B", "  C"]);
+        });
+
+        view.update(cx, |view, cx| {
+            view.set_direction(TypeHierarchyDirection::Supertypes, cx)
+        });
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.tree.entry_names(), ["B", "  A"]);
+        });
+
+        // Switching back walks the subtypes again, which can be expanded in turn.
+        view.update(cx, |view, cx| {
+            view.set_direction(TypeHierarchyDirection::Subtypes, cx)
+        });
+        cx.run_until_parked();
+        view.update(cx, |view, cx| {
+            assert_eq!(view.tree.entry_names(), ["B", "  C"]);
+            let entry_id = view.tree.entry_id(1);
+            view.toggle_expanded(entry_id, cx);
+        });
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.tree.entry_names(), ["B", "  C"]);
+        });
+    }
+
+    fn trait_item(name: &str, path: &str) -> lsp::TypeHierarchyItem {
+        lsp::TypeHierarchyItem {
+            name: name.into(),
+            kind: lsp::SymbolKind::INTERFACE,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path(path).unwrap(),
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 10)),
+            selection_range: lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 7)),
+            data: None,
+        }
+    }
+
+    fn rust_lang() -> Arc<Language> {
+        Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        ))
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            release_channel::init("0.0.0", cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+        });
+    }
+}
@@ -13,10 +13,11 @@ use crate::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
         SyntaxSnapshot, ToTreeSitterPoint,
     },
-    LanguageScope, Outline,
+    LanguageScope, Outline, RunnableTag,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
+use collections::HashMap;
 use futures::channel::oneshot;
 use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel};
 use lazy_static::lazy_static;
@@ -170,6 +171,17 @@ struct SelectionSet {
     lamport_timestamp: clock::Lamport,
 }
 
+/// A match of the runnables query of a language, such as a test function or an entry point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunnableRange {
+    /// The range of the node captured as `@run`, on whose first line the runnable is shown.
+    pub run_range: Range<usize>,
+    /// The tags of the matched pattern, naming the kind of the runnable.
+    pub tags: Vec<RunnableTag>,
+    /// The text of the other named captures of the match, by capture name.
+    pub extra_captures: HashMap<String, String>,
+}
+
 /// A diagnostic associated with a certain range of a buffer.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Diagnostic {
@@ -2911,6 +2923,72 @@ impl BufferSnapshot {
         result
     }
 
+    /// Returns the ranges whose text is linked to the text at the given position according to
+    /// the linked editing query of the language at that position, such as the names of an
+    /// opening and a closing tag.
+    ///
+    /// Returns an empty list when the position isn't within or adjacent to any of those ranges,
+    /// or when their text differs.
+    pub fn linked_editing_ranges<T: ToOffset>(&self, position: T) -> Vec<Range<usize>> {
+        let offset = position.to_offset(self);
+        let range = offset.saturating_sub(1)..self.len().min(offset + 1);
+        let mut syntax_matches = self.syntax.matches(range, self, |grammar| {
+            grammar
+                .linked_editing_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.linked_editing_config.as_ref())
+            .collect::<Vec<_>>();
+
+        // Prefer the innermost pair of ranges, as linked nodes such as tags may be nested.
+        let mut result: Option<(Range<usize>, Range<usize>)> = None;
+        while let Some(mat) = syntax_matches.peek() {
+            let mut open = None;
+            let mut close = None;
+            if let Some(config) = configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    if capture.index == config.open_capture_ix {
+                        open = Some(capture.node.byte_range());
+                    } else if capture.index == config.close_capture_ix {
+                        close = Some(capture.node.byte_range());
+                    }
+                }
+            }
+            syntax_matches.advance();
+
+            let Some((open, close)) = open.zip(close) else {
+                continue;
+            };
+            if !open.contains_inclusive(&(offset..offset))
+                && !close.contains_inclusive(&(offset..offset))
+            {
+                continue;
+            }
+            if !self
+                .text_for_range(open.clone())
+                .flat_map(str::chars)
+                .eq(self.text_for_range(close.clone()).flat_map(str::chars))
+            {
+                continue;
+            }
+            if let Some((existing_open, existing_close)) = &result {
+                if close.end - open.start > existing_close.end - existing_open.start {
+                    continue;
+                }
+            }
+            result = Some((open, close));
+        }
+
+        result
+            .map(|(open, close)| vec![open, close])
+            .unwrap_or_default()
+    }
+
     /// Returns anchor ranges for any matches of the redaction query.
     /// The buffer can be associated with multiple languages, and the redaction query associated with each
     /// will be run on the relevant section of the buffer.
@@ -2948,6 +3026,89 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the matches of the runnables query of each language in the buffer that start
+    /// within the given range, such as test functions or entry points.
+    pub fn runnable_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = RunnableRange> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range.clone(), self, |grammar| {
+            grammar.runnable_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.runnable_config.as_ref())
+            .collect::<Vec<_>>();
+
+        iter::from_fn(move || loop {
+            let mat = syntax_matches.peek()?;
+            let runnable = configs[mat.grammar_index]
+                .and_then(|config| {
+                    config.runnable(mat.pattern_index, mat.captures, |range| {
+                        self.text_for_range(range).collect()
+                    })
+                })
+                .filter(|runnable| offset_range.contains(&runnable.run_range.start));
+            syntax_matches.advance();
+            if runnable.is_some() {
+                return runnable;
+            }
+        })
+    }
+
+    /// Returns the ranges that can be folded according to the folds query of each
+    /// language in the buffer, sorted by their start.
+    ///
+    /// Each range starts at the end of the first line of a captured node and ends at the end
+    /// of its last line, or of the line before that if the last line only closes a delimiter.
+    pub fn foldable_ranges(&self) -> Vec<Range<Point>> {
+        let mut syntax_matches = self.syntax.matches(0..self.len(), self, |grammar| {
+            grammar.folds_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.folds_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut ranges = Vec::new();
+        while let Some(mat) = syntax_matches.peek() {
+            let node = configs[mat.grammar_index].and_then(|config| {
+                mat.captures
+                    .iter()
+                    .find(|capture| capture.index == config.fold_capture_ix)
+            });
+            if let Some(node) = node.map(|capture| capture.node) {
+                let start_row = node.start_position().row as u32;
+                let mut end_row = node.end_position().row as u32;
+                let end_line_start = Point::new(end_row, 0).to_offset(self);
+                let closes_delimiter = self
+                    .text_for_range(end_line_start..node.end_byte())
+                    .flat_map(|chunk| chunk.chars())
+                    .filter(|c| !c.is_whitespace())
+                    .all(|c| matches!(c, ')' | ']' | '}'));
+                if closes_delimiter && end_row > start_row {
+                    end_row -= 1;
+                }
+                if end_row > start_row {
+                    ranges.push(
+                        Point::new(start_row, self.line_len(start_row))
+                            ..Point::new(end_row, self.line_len(end_row)),
+                    );
+                }
+            }
+            syntax_matches.advance();
+        }
+
+        ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        ranges.dedup();
+        ranges
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn remote_selections_in_range(
@@ -712,6 +712,186 @@ async fn test_outline_nodes_with_newlines(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_foldable_ranges(cx: &mut gpui::TestAppContext) {
+    let text = r#"
+        fn a() {
+            let b = [
+                1,
+                2,
+            ];
+        }
+
+        fn c() {}
+    "#
+    .unindent();
+
+    let language = rust_lang()
+        .with_folds_query(
+            r#"
+            (block) @fold
+            (array_expression) @fold
+            "#,
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let ranges = buffer.update(cx, |buffer, _| buffer.snapshot().foldable_ranges());
+
+    assert_eq!(
+        ranges,
+        &[
+            Point::new(0, 8)..Point::new(4, 6),
+            Point::new(1, 13)..Point::new(3, 10),
+        ]
+    );
+}
+
+#[gpui::test]
+fn test_linked_editing_ranges(cx: &mut AppContext) {
+    let language = html_lang()
+        .with_linked_editing_query(
+            r#"
+            (element
+                (start_tag (tag_name) @open)
+                (end_tag (tag_name) @close))
+            "#,
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| {
+        Buffer::local("<div><p>a</p></div>", cx).with_language(Arc::new(language), cx)
+    });
+    let snapshot = buffer.read(cx).snapshot();
+
+    assert_eq!(snapshot.linked_editing_ranges(2), &[1..4, 15..18]);
+    assert_eq!(snapshot.linked_editing_ranges(18), &[1..4, 15..18]);
+    assert_eq!(snapshot.linked_editing_ranges(7), &[6..7, 11..12]);
+    assert!(snapshot.linked_editing_ranges(9).is_empty());
+}
+
+#[gpui::test]
+fn test_runnable_ranges(cx: &mut AppContext) {
+    let language = rust_lang()
+        .with_runnable_query(
+            r#"
+            (
+                (attribute_item (attribute (identifier) @_attribute))
+                .
+                (function_item name: (_) @run @test_name)
+                (#eq? @_attribute "test")
+                (#set! tag rust-test)
+            )
+            (
+                (function_item name: (_) @run)
+                (#eq? @run "main")
+                (#set! tag rust-main)
+            )
+            "#,
+        )
+        .unwrap();
+    let text = r#"
+        fn main() {}
+
+        #[test]
+        fn a() {}
+
+        fn b() {}
+    "#
+    .unindent();
+    let language = Arc::new(language);
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text.clone(), cx).with_language(language.clone(), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    let runnables = snapshot
+        .runnable_ranges(0..snapshot.len())
+        .map(|runnable| {
+            (
+                snapshot
+                    .text_for_range(runnable.run_range)
+                    .collect::<String>(),
+                runnable
+                    .tags
+                    .iter()
+                    .map(|tag| tag.0.to_string())
+                    .collect::<Vec<_>>(),
+                runnable.extra_captures.get("test_name").cloned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        runnables,
+        &[
+            ("main".to_string(), vec!["rust-main".to_string()], None),
+            (
+                "a".to_string(),
+                vec!["rust-test".to_string()],
+                Some("a".to_string())
+            ),
+        ]
+    );
+
+    // Runnables can also be found in text that isn't loaded into a buffer.
+    assert_eq!(
+        language
+            .grammar()
+            .unwrap()
+            .runnables_in_text(&text)
+            .into_iter()
+            .map(|runnable| runnable.run_range)
+            .collect::<Vec<_>>(),
+        snapshot
+            .runnable_ranges(0..snapshot.len())
+            .map(|runnable| runnable.run_range)
+            .collect::<Vec<_>>()
+    );
+
+    // Only the runnables starting within the given range are returned.
+    let second_line = Point::new(2, 0).to_offset(&snapshot);
+    assert_eq!(
+        snapshot
+            .runnable_ranges(second_line..snapshot.len())
+            .count(),
+        1
+    );
+}
+
+#[gpui::test]
+fn test_runnable_capture_paths(cx: &mut AppContext) {
+    let language = rust_lang()
+        .with_runnable_query(
+            r#"
+            (
+                (mod_item
+                    name: (_) @test_module
+                    body: (declaration_list
+                        (function_item name: (_) @run)))
+                (#set! @test_module path "::")
+            )
+            "#,
+        )
+        .unwrap();
+    let text = r#"
+        mod a {
+            fn f() {}
+
+            mod b {
+                mod c {
+                    fn g() {}
+                }
+            }
+        }
+    "#
+    .unindent();
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    let modules = snapshot
+        .runnable_ranges(0..snapshot.len())
+        .map(|runnable| runnable.extra_captures["test_module"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(modules, &["a", "a::b::c"]);
+}
+
 #[gpui::test]
 async fn test_outline_with_extra_context(cx: &mut gpui::TestAppContext) {
     let language = javascript_lang()
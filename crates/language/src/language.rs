@@ -584,6 +584,10 @@ pub struct LanguageConfig {
     /// or a whole-word search in buffer search.
     #[serde(default)]
     pub word_characters: HashSet<char>,
+    /// The separator of the names in the path of a symbol, such as `::` in `module::function`,
+    /// available to tasks as `ZED_SYMBOL_PATH`.
+    #[serde(default = "symbol_path_separator_default")]
+    pub symbol_path_separator: Arc<str>,
     /// The name of a Prettier parser that should be used for this language.
     #[serde(default)]
     pub prettier_parser_name: Option<String>,
@@ -682,6 +686,7 @@ impl Default for LanguageConfig {
             scope_opt_in_language_servers: Default::default(),
             overrides: Default::default(),
             word_characters: Default::default(),
+            symbol_path_separator: symbol_path_separator_default(),
             prettier_parser_name: None,
             prettier_plugins: Default::default(),
             collapsed_placeholder: Default::default(),
@@ -696,6 +701,10 @@ fn auto_indent_using_last_non_empty_line_default() -> bool {
     true
 }
 
+fn symbol_path_separator_default() -> Arc<str> {
+    ".".into()
+}
+
 fn deserialize_regex<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Regex>, D::Error> {
     let source = Option::<String>::deserialize(d)?;
     if let Some(source) = source {
@@ -821,6 +830,11 @@ impl GrammarId {
     }
 }
 
+/// A tag set on a pattern of a runnables query with `(#set! tag <name>)`, naming the kind of
+/// the matched runnable, such as `rust-test`. Tasks list the tags of the runnables they can run.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RunnableTag(pub Arc<str>);
+
 pub struct Grammar {
     id: GrammarId,
     pub ts_language: tree_sitter::Language,
@@ -828,6 +842,9 @@ pub struct Grammar {
     pub(crate) highlights_query: Option<Query>,
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
+    pub(crate) folds_config: Option<FoldConfig>,
+    pub(crate) linked_editing_config: Option<LinkedEditingConfig>,
+    pub(crate) runnable_config: Option<RunnableConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -874,6 +891,89 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct FoldConfig {
+    pub query: Query,
+    pub fold_capture_ix: u32,
+}
+
+struct LinkedEditingConfig {
+    pub query: Query,
+    pub open_capture_ix: u32,
+    pub close_capture_ix: u32,
+}
+
+struct RunnableConfig {
+    pub query: Query,
+    pub run_capture_ix: u32,
+    /// The tags set on each pattern with `(#set! tag <name>)`, by pattern index.
+    pub pattern_tags: Vec<Vec<RunnableTag>>,
+    /// The captures whose text is exposed to tasks, by capture index.
+    pub extra_captures: Vec<(u32, String)>,
+    /// The separators of the captures set with `(#set! @capture path <separator>)`, by capture index.
+    /// The text of such a capture is the name of a node, such as a module, prefixed with the names of
+    /// the enclosing nodes of the same kind.
+    pub capture_path_separators: HashMap<u32, String>,
+}
+
+impl RunnableConfig {
+    /// Returns the runnable of a match of the query, given its pattern and captures.
+    fn runnable(
+        &self,
+        pattern_index: usize,
+        captures: &[tree_sitter::QueryCapture],
+        text_for_range: impl Fn(Range<usize>) -> String,
+    ) -> Option<RunnableRange> {
+        let run_range = captures
+            .iter()
+            .find(|capture| capture.index == self.run_capture_ix)?
+            .node
+            .byte_range();
+        let extra_captures = self
+            .extra_captures
+            .iter()
+            .filter_map(|(capture_ix, name)| {
+                let node = captures
+                    .iter()
+                    .find(|capture| capture.index == *capture_ix)?
+                    .node;
+                let text = match self.capture_path_separators.get(capture_ix) {
+                    Some(separator) => path_of_name(node, separator, &text_for_range),
+                    None => text_for_range(node.byte_range()),
+                };
+                Some((name.clone(), text))
+            })
+            .collect();
+        Some(RunnableRange {
+            run_range,
+            tags: self.pattern_tags[pattern_index].clone(),
+            extra_captures,
+        })
+    }
+}
+
+/// Returns the given name of a node, prefixed with the `name` fields of the enclosing nodes of the same
+/// kind, outermost first.
+fn path_of_name(
+    name: tree_sitter::Node,
+    separator: &str,
+    text_for_range: &impl Fn(Range<usize>) -> String,
+) -> String {
+    let mut names = vec![text_for_range(name.byte_range())];
+    if let Some(named) = name.parent() {
+        let mut ancestor = named.parent();
+        while let Some(node) = ancestor {
+            if node.kind_id() == named.kind_id() {
+                if let Some(name) = node.child_by_field_name("name") {
+                    names.push(text_for_range(name.byte_range()));
+                }
+            }
+            ancestor = node.parent();
+        }
+    }
+    names.reverse();
+    names.join(separator)
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -915,6 +1015,9 @@ impl Language {
                     injection_config: None,
                     override_config: None,
                     redactions_config: None,
+                    folds_config: None,
+                    linked_editing_config: None,
+                    runnable_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -970,6 +1073,21 @@ impl Language {
                 .with_redaction_query(query.as_ref())
                 .context("Error loading redaction query")?;
         }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_folds_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
+        if let Some(query) = queries.linked_editing {
+            self = self
+                .with_linked_editing_query(query.as_ref())
+                .context("Error loading linked editing query")?;
+        }
+        if let Some(query) = queries.runnables {
+            self = self
+                .with_runnable_query(query.as_ref())
+                .context("Error loading runnables query")?;
+        }
         Ok(self)
     }
 
@@ -1241,6 +1359,102 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_folds_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        get_capture_indices(&query, &mut [("fold", &mut fold_capture_ix)]);
+
+        if let Some(fold_capture_ix) = fold_capture_ix {
+            grammar.folds_config = Some(FoldConfig {
+                query,
+                fold_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_linked_editing_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut open_capture_ix = None;
+        let mut close_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("open", &mut open_capture_ix),
+                ("close", &mut close_capture_ix),
+            ],
+        );
+
+        if let Some((open_capture_ix, close_capture_ix)) = open_capture_ix.zip(close_capture_ix) {
+            grammar.linked_editing_config = Some(LinkedEditingConfig {
+                query,
+                open_capture_ix,
+                close_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_runnable_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut run_capture_ix = None;
+        get_capture_indices(&query, &mut [("run", &mut run_capture_ix)]);
+        let pattern_tags = (0..query.pattern_count())
+            .map(|ix| {
+                query
+                    .property_settings(ix)
+                    .iter()
+                    .filter(|setting| setting.key.as_ref() == "tag")
+                    .filter_map(|setting| setting.value.as_deref())
+                    .map(|tag| RunnableTag(tag.into()))
+                    .collect()
+            })
+            .collect();
+        let extra_captures = query
+            .capture_names()
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| !name.starts_with('_') && **name != "run")
+            .map(|(ix, name)| (ix as u32, name.to_string()))
+            .collect();
+        let capture_path_separators = (0..query.pattern_count())
+            .flat_map(|ix| query.property_settings(ix))
+            .filter(|setting| setting.key.as_ref() == "path")
+            .filter_map(|setting| {
+                Some((
+                    setting.capture_id? as u32,
+                    setting.value.as_deref()?.to_string(),
+                ))
+            })
+            .collect();
+
+        if let Some(run_capture_ix) = run_capture_ix {
+            grammar.runnable_config = Some(RunnableConfig {
+                query,
+                run_capture_ix,
+                pattern_tags,
+                extra_captures,
+                capture_path_separators,
+            });
+        }
+
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> Option<&mut Grammar> {
         Arc::get_mut(self.grammar.as_mut()?)
     }
@@ -1454,6 +1668,30 @@ impl Grammar {
         })
     }
 
+    /// Whether the language has a runnables query, finding tests and entry points.
+    pub fn has_runnables(&self) -> bool {
+        self.runnable_config.is_some()
+    }
+
+    /// Parses the given text and returns the matches of the runnables query in it, without those of
+    /// injected languages. Unlike [`BufferSnapshot::runnable_ranges`], this doesn't need the text to
+    /// be loaded into a buffer, e.g. to find the tests of files that aren't open.
+    pub fn runnables_in_text(&self, text: &str) -> Vec<RunnableRange> {
+        let Some(config) = self.runnable_config.as_ref() else {
+            return Vec::new();
+        };
+        let tree = self.parse_text(&Rope::from(text), None);
+        let mut cursor = tree_sitter::QueryCursor::new();
+        cursor
+            .matches(&config.query, tree.root_node(), text.as_bytes())
+            .filter_map(|mat| {
+                config.runnable(mat.pattern_index, mat.captures, |range| {
+                    text[range].to_string()
+                })
+            })
+            .collect()
+    }
+
     pub fn highlight_map(&self) -> HighlightMap {
         self.highlight_map.lock().clone()
     }
@@ -101,6 +101,10 @@ pub struct LanguageSettings {
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
+    /// Whether to highlight code using semantic tokens from language servers.
+    pub semantic_tokens: bool,
+    /// Which syntax theme highlight to use for each semantic token type.
+    pub semantic_token_highlights: HashMap<String, String>,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
     // Controls how the editor handles the autoclosed characters.
@@ -228,6 +232,17 @@ pub struct LanguageSettingsContent {
     /// Inlay hint related settings.
     #[serde(default)]
     pub inlay_hints: Option<InlayHintSettings>,
+    /// Whether to highlight code using semantic tokens provided by language servers,
+    /// on top of the tree-sitter highlights.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub semantic_tokens: Option<bool>,
+    /// Which syntax theme highlight to use for each semantic token type.
+    /// Keys are token types, optionally followed by a modifier (e.g. `variable.readonly`).
+    /// Entries are merged into the default mapping.
+    #[serde(default)]
+    pub semantic_token_highlights: Option<HashMap<String, String>>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
     ///
@@ -652,6 +667,12 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    if let Some(highlights) = &src.semantic_token_highlights {
+        settings
+            .semantic_token_highlights
+            .extend(highlights.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
     fn merge<T>(target: &mut T, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
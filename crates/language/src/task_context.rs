@@ -15,7 +15,8 @@ pub trait ContextProvider: Send + Sync {
     }
 }
 
-/// A context provider that finds out what symbol is currently focused in the buffer.
+/// A context provider that finds out what symbol is currently focused in the buffer,
+/// along with the chain of the symbols enclosing it.
 pub struct SymbolContextProvider;
 
 impl ContextProvider for SymbolContextProvider {
@@ -24,22 +25,32 @@ impl ContextProvider for SymbolContextProvider {
         location: Location,
         cx: &mut AppContext,
     ) -> gpui::Result<TaskVariables> {
-        let symbols = location
-            .buffer
-            .read(cx)
-            .snapshot()
-            .symbols_containing(location.range.start, None);
-        let symbol = symbols.unwrap_or_default().last().map(|symbol| {
-            let range = symbol
-                .name_ranges
-                .last()
-                .cloned()
-                .unwrap_or(0..symbol.text.len());
-            symbol.text[range].to_string()
-        });
-        Ok(TaskVariables::from_iter(
-            Some(VariableName::Symbol).zip(symbol),
-        ))
+        let snapshot = location.buffer.read(cx).snapshot();
+        let symbols = snapshot
+            .symbols_containing(location.range.start, None)
+            .unwrap_or_default();
+        let symbol_names = symbols
+            .iter()
+            .map(|symbol| {
+                let range = symbol
+                    .name_ranges
+                    .last()
+                    .cloned()
+                    .unwrap_or(0..symbol.text.len());
+                symbol.text[range].to_string()
+            })
+            .collect::<Vec<_>>();
+        let mut variables = TaskVariables::default();
+        if let Some(symbol) = symbol_names.last() {
+            variables.insert(VariableName::Symbol, symbol.clone());
+            let separator = snapshot
+                .language_at(location.range.start)
+                .map_or(".", |language| {
+                    language.config.symbol_path_separator.as_ref()
+                });
+            variables.insert(VariableName::SymbolPath, symbol_names.join(separator));
+        }
+        Ok(variables)
     }
 }
 
@@ -12,6 +12,7 @@ use settings::Settings;
 use smol::{fs, process};
 use std::{
     any::Any,
+    borrow::Cow,
     ffi::{OsStr, OsString},
     ops::Range,
     path::PathBuf,
@@ -21,6 +22,7 @@ use std::{
         Arc,
     },
 };
+use task::{TaskTemplate, TaskTemplates, TestReport, TestReportFormat, VariableName};
 use util::{fs::remove_matching, github::latest_github_release, maybe, ResultExt};
 
 fn server_binary_arguments() -> Vec<OsString> {
@@ -437,6 +439,91 @@ fn adjust_runs(
     runs
 }
 
+/// Captures of the runnables query, see `go/runnables.scm`.
+const GO_TEST_NAME_TASK_VARIABLE: VariableName = VariableName::Custom(Cow::Borrowed("test_name"));
+const GO_BENCH_NAME_TASK_VARIABLE: VariableName = VariableName::Custom(Cow::Borrowed("bench_name"));
+
+pub(super) fn go_task_context() -> ContextProviderWithTasks {
+    ContextProviderWithTasks::new(TaskTemplates(vec![
+        TaskTemplate {
+            label: format!(
+                "go test -run ^{}$ ./...",
+                GO_TEST_NAME_TASK_VARIABLE.template_value()
+            ),
+            command: "go".into(),
+            args: vec![
+                "test".into(),
+                "-run".into(),
+                format!("^{}$", GO_TEST_NAME_TASK_VARIABLE.template_value()),
+                "./...".into(),
+            ],
+            tags: vec!["go-test".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "go test -run ^$ -bench ^{}$ ./...",
+                GO_BENCH_NAME_TASK_VARIABLE.template_value()
+            ),
+            command: "go".into(),
+            args: vec![
+                "test".into(),
+                "-run".into(),
+                "^$".into(),
+                "-bench".into(),
+                format!("^{}$", GO_BENCH_NAME_TASK_VARIABLE.template_value()),
+                "./...".into(),
+            ],
+            tags: vec!["go-bench".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "go test -json -run ^{}$ ./...",
+                GO_TEST_NAME_TASK_VARIABLE.template_value()
+            ),
+            command: "go".into(),
+            args: vec![
+                "test".into(),
+                "-json".into(),
+                "-run".into(),
+                format!("^{}$", GO_TEST_NAME_TASK_VARIABLE.template_value()),
+                "./...".into(),
+            ],
+            tags: vec!["go-test".into()],
+            test_report: Some(TestReport {
+                format: TestReportFormat::GoTestJson,
+                file: None,
+            }),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: "go test -json ./...".into(),
+            command: "go".into(),
+            args: vec!["test".into(), "-json".into(), "./...".into()],
+            tags: vec!["go-test".into()],
+            test_report: Some(TestReport {
+                format: TestReportFormat::GoTestJson,
+                file: None,
+            }),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!("go run {}", VariableName::File.template_value()),
+            command: "go".into(),
+            args: vec!["run".into(), VariableName::File.template_value()],
+            tags: vec!["go-main".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: "go test ./...".into(),
+            command: "go".into(),
+            args: vec!["test".into(), "./...".into()],
+            ..TaskTemplate::default()
+        },
+    ]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
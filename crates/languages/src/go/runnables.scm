@@ -0,0 +1,20 @@
+; Test functions, such as `func TestParse(t *testing.T)`.
+(
+    (function_declaration name: (identifier) @run @test_name)
+    (#match? @run "^Test")
+    (#set! tag go-test)
+)
+
+; Benchmark functions, such as `func BenchmarkParse(b *testing.B)`.
+(
+    (function_declaration name: (identifier) @run @bench_name)
+    (#match? @run "^Benchmark")
+    (#set! tag go-bench)
+)
+
+; The entry point of a `main` package.
+(
+    (function_declaration name: (identifier) @run)
+    (#eq? @run "main")
+    (#set! tag go-main)
+)
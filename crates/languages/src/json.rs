@@ -53,6 +53,7 @@ impl JsonLspAdapter {
             cx,
         );
         let tasks_schema = task::TaskTemplates::generate_json_schema();
+        let debug_schema = dap::DebugTemplates::generate_json_schema();
         serde_json::json!({
             "json": {
                 "format": {
@@ -76,6 +77,10 @@ impl JsonLspAdapter {
                             &*paths::LOCAL_TASKS_RELATIVE_PATH,
                         ],
                         "schema": tasks_schema,
+                    },
+                    {
+                        "fileMatch": [&*paths::LOCAL_DEBUG_RELATIVE_PATH],
+                        "schema": debug_schema,
                     }
                 ]
             }
@@ -8,7 +8,10 @@ use smol::stream::StreamExt;
 use std::{str, sync::Arc};
 use util::{asset_str, ResultExt};
 
-use crate::{elixir::elixir_task_context, rust::RustContextProvider};
+use crate::{
+    elixir::elixir_task_context, go::go_task_context, python::python_task_context,
+    rust::RustContextProvider, typescript::typescript_task_context,
+};
 
 use self::{deno::DenoSettings, elixir::ElixirSettings};
 
@@ -34,6 +37,11 @@ mod yaml;
 // 5. Add indents.scm, outline.scm, and brackets.scm to implement indent on newline, outline/breadcrumbs,
 //    and autoclosing brackets respectively
 // 6. If the language has injections add an injections.scm query file
+// 7. Add folds.scm to fold syntax nodes when the language server doesn't report folding ranges
+// 8. Add linked_editing.scm to edit the names of matching tags together when the language server
+//    doesn't report linked editing ranges
+// 9. Add runnables.scm to tag tests and entry points, and tasks with those tags to the language's
+//    context provider to run them from the gutter
 
 #[derive(RustEmbed)]
 #[folder = "src/"]
@@ -173,7 +181,7 @@ pub fn init(
             );
         }
     }
-    language!("go", vec![Arc::new(go::GoLspAdapter)]);
+    language!("go", vec![Arc::new(go::GoLspAdapter)], go_task_context());
     language!("gomod");
     language!("gowork");
     language!(
@@ -195,7 +203,8 @@ pub fn init(
         "python",
         vec![Arc::new(python::PythonLspAdapter::new(
             node_runtime.clone(),
-        ))]
+        ))],
+        python_task_context()
     );
     language!(
         "rust",
@@ -209,15 +218,21 @@ pub fn init(
                 vec![
                     Arc::new(deno::DenoLspAdapter::new()),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript_task_context()
+            );
+            language!(
+                "typescript",
+                vec![Arc::new(deno::DenoLspAdapter::new())],
+                typescript_task_context()
             );
-            language!("typescript", vec![Arc::new(deno::DenoLspAdapter::new())]);
             language!(
                 "javascript",
                 vec![
                     Arc::new(deno::DenoLspAdapter::new()),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript_task_context()
             );
             language!("jsdoc", vec![Arc::new(deno::DenoLspAdapter::new())]);
         }
@@ -228,14 +243,16 @@ pub fn init(
                     Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript_task_context()
             );
             language!(
                 "typescript",
                 vec![
                     Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript_task_context()
             );
             language!(
                 "javascript",
@@ -243,7 +260,8 @@ pub fn init(
                     Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
-                ]
+                ],
+                typescript_task_context()
             );
             language!(
                 "jsdoc",
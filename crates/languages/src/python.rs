@@ -1,14 +1,16 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{ContextProviderWithTasks, LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use node_runtime::NodeRuntime;
 use std::{
     any::Any,
+    borrow::Cow,
     ffi::OsString,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use task::{TaskTemplate, TaskTemplates, TestReport, TestReportFormat, VariableName};
 use util::ResultExt;
 
 const SERVER_PATH: &str = "node_modules/pyright/langserver.index.js";
@@ -180,6 +182,129 @@ async fn get_cached_server_binary(
     }
 }
 
+/// Captures of the runnables query, see `python/runnables.scm`.
+const PYTHON_TEST_CLASS_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("test_class"));
+const PYTHON_TEST_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("test_name"));
+
+pub(super) fn python_task_context() -> ContextProviderWithTasks {
+    // Test reports are written to the cache directory of pytest, which is ignored by git.
+    let junit_report_file = format!(
+        "{}/.pytest_cache/zed-junit.xml",
+        VariableName::WorktreeRoot.template_value()
+    );
+    let junit_report_arg = format!("--junitxml={junit_report_file}");
+    let junit_report = TestReport {
+        format: TestReportFormat::JunitXml,
+        file: Some(junit_report_file),
+    };
+    ContextProviderWithTasks::new(TaskTemplates(vec![
+        TaskTemplate {
+            label: format!(
+                "pytest {}::{}::{}",
+                VariableName::File.template_value(),
+                PYTHON_TEST_CLASS_TASK_VARIABLE.template_value(),
+                PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+            ),
+            command: "pytest".into(),
+            args: vec![format!(
+                "{}::{}::{}",
+                VariableName::File.template_value(),
+                PYTHON_TEST_CLASS_TASK_VARIABLE.template_value(),
+                PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+            )],
+            tags: vec!["python-test".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "pytest {}::{}",
+                VariableName::File.template_value(),
+                PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+            ),
+            command: "pytest".into(),
+            args: vec![format!(
+                "{}::{}",
+                VariableName::File.template_value(),
+                PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+            )],
+            tags: vec!["python-test".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "pytest {}::{}",
+                VariableName::File.template_value(),
+                PYTHON_TEST_CLASS_TASK_VARIABLE.template_value(),
+            ),
+            command: "pytest".into(),
+            args: vec![format!(
+                "{}::{}",
+                VariableName::File.template_value(),
+                PYTHON_TEST_CLASS_TASK_VARIABLE.template_value(),
+            )],
+            tags: vec!["python-test-class".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "pytest {}::{}::{} --junitxml",
+                VariableName::File.template_value(),
+                PYTHON_TEST_CLASS_TASK_VARIABLE.template_value(),
+                PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+            ),
+            command: "pytest".into(),
+            args: vec![
+                format!(
+                    "{}::{}::{}",
+                    VariableName::File.template_value(),
+                    PYTHON_TEST_CLASS_TASK_VARIABLE.template_value(),
+                    PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+                ),
+                junit_report_arg.clone(),
+            ],
+            tags: vec!["python-test".into()],
+            test_report: Some(junit_report.clone()),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "pytest {}::{} --junitxml",
+                VariableName::File.template_value(),
+                PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+            ),
+            command: "pytest".into(),
+            args: vec![
+                format!(
+                    "{}::{}",
+                    VariableName::File.template_value(),
+                    PYTHON_TEST_NAME_TASK_VARIABLE.template_value(),
+                ),
+                junit_report_arg.clone(),
+            ],
+            tags: vec!["python-test".into()],
+            test_report: Some(junit_report.clone()),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!("pytest {} --junitxml", VariableName::File.template_value()),
+            command: "pytest".into(),
+            args: vec![VariableName::File.template_value(), junit_report_arg],
+            tags: vec!["python-test".into()],
+            test_report: Some(junit_report),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!("python3 {}", VariableName::File.template_value()),
+            command: "python3".into(),
+            args: vec![VariableName::File.template_value()],
+            tags: vec!["python-main".into()],
+            ..TaskTemplate::default()
+        },
+    ]))
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::{BorrowAppContext, Context, ModelContext, TestAppContext};
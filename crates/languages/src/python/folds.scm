@@ -0,0 +1,8 @@
+[
+  (function_definition)
+  (class_definition)
+] @fold
+
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
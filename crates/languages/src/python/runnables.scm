@@ -0,0 +1,35 @@
+; Test functions and methods, as collected by pytest.
+(
+    (function_definition name: (identifier) @run @test_name)
+    (#match? @run "^test")
+    (#set! tag python-test)
+)
+
+; The same test methods within a test class, capturing the class's name.
+(
+    (class_definition
+        name: (identifier) @test_class
+        body: (block
+            (function_definition name: (identifier) @run @test_name)))
+    (#match? @test_class "^Test")
+    (#match? @run "^test")
+    (#set! tag python-test)
+)
+
+; Test classes.
+(
+    (class_definition name: (identifier) @run @test_class)
+    (#match? @run "^Test")
+    (#set! tag python-test-class)
+)
+
+; The `if __name__ == "__main__":` block of a script.
+(
+    (if_statement
+        condition: (comparison_operator
+            (identifier) @_name
+            (string) @_main)) @run
+    (#eq? @_name "__name__")
+    (#match? @_main "__main__")
+    (#set! tag python-main)
+)
@@ -17,7 +17,9 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use task::{
+    TaskTemplate, TaskTemplates, TaskVariables, TestReport, TestReportFormat, VariableName,
+};
 use util::{
     fs::remove_matching,
     github::{latest_github_release, GitHubLspBinaryVersion},
@@ -328,6 +330,19 @@ pub(crate) struct RustContextProvider;
 const RUST_PACKAGE_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("RUST_PACKAGE"));
 
+/// Captures of the runnables query, see `rust/runnables.scm`.
+const RUST_TEST_MODULE_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("test_module"));
+const RUST_TEST_NAME_TASK_VARIABLE: VariableName = VariableName::Custom(Cow::Borrowed("test_name"));
+const RUST_BENCH_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("bench_name"));
+
+/// The test results of `cargo test`, read from its output.
+const LIBTEST_REPORT: TestReport = TestReport {
+    format: TestReportFormat::Libtest,
+    file: None,
+};
+
 impl ContextProvider for RustContextProvider {
     fn build_context(
         &self,
@@ -401,6 +416,77 @@ impl ContextProvider for RustContextProvider {
                     "-p".into(),
                     RUST_PACKAGE_TASK_VARIABLE.template_value(),
                 ],
+                tags: vec!["rust-test".into()],
+                test_report: Some(LIBTEST_REPORT),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "cargo test -p {} {}::{}",
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    RUST_TEST_MODULE_TASK_VARIABLE.template_value(),
+                    RUST_TEST_NAME_TASK_VARIABLE.template_value(),
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "test".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    format!(
+                        "{}::{}",
+                        RUST_TEST_MODULE_TASK_VARIABLE.template_value(),
+                        RUST_TEST_NAME_TASK_VARIABLE.template_value(),
+                    ),
+                ],
+                tags: vec!["rust-test".into()],
+                test_report: Some(LIBTEST_REPORT),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "cargo test -p {} {}",
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    RUST_TEST_NAME_TASK_VARIABLE.template_value(),
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "test".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    RUST_TEST_NAME_TASK_VARIABLE.template_value(),
+                ],
+                tags: vec!["rust-test".into()],
+                test_report: Some(LIBTEST_REPORT),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "cargo bench -p {} {}",
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    RUST_BENCH_NAME_TASK_VARIABLE.template_value(),
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "bench".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                    RUST_BENCH_NAME_TASK_VARIABLE.template_value(),
+                ],
+                tags: vec!["rust-bench".into()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: format!(
+                    "cargo run -p {}",
+                    RUST_PACKAGE_TASK_VARIABLE.template_value()
+                ),
+                command: "cargo".into(),
+                args: vec![
+                    "run".into(),
+                    "-p".into(),
+                    RUST_PACKAGE_TASK_VARIABLE.template_value(),
+                ],
+                tags: vec!["rust-main".into()],
                 ..TaskTemplate::default()
             },
             TaskTemplate {
@@ -0,0 +1,4 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+(block_comment) @fold
@@ -0,0 +1,55 @@
+; Functions annotated with `#[test]`, or a path ending with `test` such as `#[tokio::test]`.
+(
+    (attribute_item
+        (attribute
+            [
+                (identifier) @_attribute
+                (scoped_identifier name: (identifier) @_attribute)
+            ]))
+    .
+    (attribute_item)*
+    .
+    (function_item name: (_) @run @test_name)
+    (#eq? @_attribute "test")
+    (#set! tag rust-test)
+)
+
+; The same tests directly within a module, such as `mod tests`, capturing the module's path
+; from the outermost enclosing module, such as `outer::tests`.
+(
+    (mod_item
+        name: (_) @test_module
+        body: (declaration_list
+            (attribute_item
+                (attribute
+                    [
+                        (identifier) @_attribute
+                        (scoped_identifier name: (identifier) @_attribute)
+                    ]))
+            .
+            (attribute_item)*
+            .
+            (function_item name: (_) @run @test_name)))
+    (#eq? @_attribute "test")
+    (#set! @test_module path "::")
+    (#set! tag rust-test)
+)
+
+; Functions annotated with `#[bench]`.
+(
+    (attribute_item (attribute (identifier) @_attribute))
+    .
+    (attribute_item)*
+    .
+    (function_item name: (_) @run @bench_name)
+    (#eq? @_attribute "bench")
+    (#set! tag rust-bench)
+)
+
+; The entry point of a binary.
+(
+    (source_file
+        (function_item name: (identifier) @run))
+    (#eq? @run "main")
+    (#set! tag rust-main)
+)
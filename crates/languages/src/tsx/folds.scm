@@ -0,0 +1,5 @@
+(_ "{" "}") @fold
+(_ "[" "]") @fold
+(_ "(" ")") @fold
+(comment) @fold
+(jsx_element) @fold
@@ -0,0 +1,14 @@
+; Test suites and test cases of test frameworks such as Jest, Mocha or Vitest, e.g.
+; `describe("parser", () => {})`, `it("parses numbers", () => {})` or `test.only("...", () => {})`.
+(
+    (call_expression
+        function: [
+            (identifier) @_name
+            (member_expression object: (identifier) @_name)
+        ]
+        arguments: (arguments
+            .
+            (string (string_fragment) @run @test_name)))
+    (#match? @_name "^(describe|it|test)$")
+    (#set! tag js-test)
+)
@@ -4,7 +4,7 @@ use async_tar::Archive;
 use async_trait::async_trait;
 use collections::HashMap;
 use gpui::AsyncAppContext;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{ContextProviderWithTasks, LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::{CodeActionKind, LanguageServerBinary};
 use node_runtime::NodeRuntime;
 use project::project_settings::ProjectSettings;
@@ -13,10 +13,12 @@ use settings::Settings;
 use smol::{fs, io::BufReader, stream::StreamExt};
 use std::{
     any::Any,
+    borrow::Cow,
     ffi::OsString,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use task::{TaskTemplate, TaskTemplates, TestReport, TestReportFormat, VariableName};
 use util::{
     fs::remove_matching,
     github::{build_tarball_url, GitHubLspBinaryVersion},
@@ -410,6 +412,88 @@ async fn get_cached_eslint_server_binary(
     .log_err()
 }
 
+/// Captures of the runnables query, see `typescript/runnables.scm`.
+const TYPESCRIPT_TEST_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("test_name"));
+
+/// Tasks of the JavaScript and TypeScript languages, running tests through the `test` script of
+/// the package, which test frameworks such as Jest and Vitest accept a file and a test name for.
+pub(super) fn typescript_task_context() -> ContextProviderWithTasks {
+    // Jest-compatible runners write their JSON report along with the other caches of `node_modules`.
+    let json_report_file = format!(
+        "{}/node_modules/.cache/zed-jest.json",
+        VariableName::WorktreeRoot.template_value()
+    );
+    let json_report_arg = format!("--outputFile={json_report_file}");
+    let json_report = TestReport {
+        format: TestReportFormat::JestJson,
+        file: Some(json_report_file),
+    };
+    ContextProviderWithTasks::new(TaskTemplates(vec![
+        TaskTemplate {
+            label: format!(
+                "npm test -- {} -t {}",
+                VariableName::File.template_value(),
+                TYPESCRIPT_TEST_NAME_TASK_VARIABLE.template_value(),
+            ),
+            command: "npm".into(),
+            args: vec![
+                "test".into(),
+                "--".into(),
+                VariableName::File.template_value(),
+                "-t".into(),
+                TYPESCRIPT_TEST_NAME_TASK_VARIABLE.template_value(),
+            ],
+            tags: vec!["js-test".into()],
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!(
+                "npm test -- {} -t {} --json",
+                VariableName::File.template_value(),
+                TYPESCRIPT_TEST_NAME_TASK_VARIABLE.template_value(),
+            ),
+            command: "npm".into(),
+            args: vec![
+                "test".into(),
+                "--".into(),
+                VariableName::File.template_value(),
+                "-t".into(),
+                TYPESCRIPT_TEST_NAME_TASK_VARIABLE.template_value(),
+                "--json".into(),
+                json_report_arg.clone(),
+            ],
+            tags: vec!["js-test".into()],
+            test_report: Some(json_report.clone()),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!("npm test -- {} --json", VariableName::File.template_value()),
+            command: "npm".into(),
+            args: vec![
+                "test".into(),
+                "--".into(),
+                VariableName::File.template_value(),
+                "--json".into(),
+                json_report_arg,
+            ],
+            tags: vec!["js-test".into()],
+            test_report: Some(json_report),
+            ..TaskTemplate::default()
+        },
+        TaskTemplate {
+            label: format!("npm test -- {}", VariableName::File.template_value()),
+            command: "npm".into(),
+            args: vec![
+                "test".into(),
+                "--".into(),
+                VariableName::File.template_value(),
+            ],
+            ..TaskTemplate::default()
+        },
+    ]))
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::{Context, TestAppContext};
@@ -12,8 +12,8 @@ use language::{
     language_settings::{language_settings, LanguageSettings},
     AutoindentMode, Buffer, BufferChunks, BufferSnapshot, Capability, CharKind, Chunk, CursorShape,
     DiagnosticEntry, File, IndentSize, Language, LanguageScope, OffsetRangeExt, OffsetUtf16,
-    Outline, OutlineItem, Point, PointUtf16, Selection, TextDimension, ToOffset as _,
-    ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _, TransactionId, Unclipped,
+    Outline, OutlineItem, Point, PointUtf16, RunnableRange, Selection, TextDimension,
+    ToOffset as _, ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _, TransactionId, Unclipped,
 };
 use std::{
     borrow::Cow,
@@ -3037,6 +3037,35 @@ impl MultiBufferSnapshot {
             .flatten()
     }
 
+    /// Returns the runnables of the excerpts intersecting the given range, with their run ranges
+    /// re-based onto the multibuffer.
+    pub fn runnable_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = RunnableRange> + '_ {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        self.excerpts_for_range(range.clone())
+            .flat_map(move |(excerpt, excerpt_offset)| {
+                let excerpt_buffer_start = excerpt.range.context.start.to_offset(&excerpt.buffer);
+                let excerpt_buffer_end = excerpt.range.context.end.to_offset(&excerpt.buffer);
+
+                excerpt
+                    .buffer
+                    .runnable_ranges(excerpt_buffer_start..excerpt_buffer_end)
+                    .map(move |mut runnable| {
+                        // Re-base onto the excerpts coordinates in the multibuffer
+                        runnable.run_range.start =
+                            excerpt_offset + (runnable.run_range.start - excerpt_buffer_start);
+                        runnable.run_range.end =
+                            excerpt_offset + (runnable.run_range.end - excerpt_buffer_start);
+                        runnable
+                    })
+            })
+            .filter(move |runnable| {
+                runnable.run_range.start >= range.start && runnable.run_range.start < range.end
+            })
+    }
+
     pub fn diagnostics_update_count(&self) -> usize {
         self.diagnostics_update_count
     }
@@ -0,0 +1,229 @@
+//! Rows of local files that carry data, such as breakpoints and bookmarks, tracked as anchors
+//! while their buffers are open so that they move along with edits.
+
+use std::path::{Path, PathBuf};
+
+use collections::{BTreeMap, HashMap};
+use gpui::{AppContext, Model, WeakModel};
+use language::{Buffer, BufferId, Point, ToPoint};
+use text::Anchor;
+use worktree::File;
+
+/// Rows of the project's files with data of type `T`, by file.
+pub(crate) struct AnchoredRows<T> {
+    open_buffers: HashMap<BufferId, BufferRows<T>>,
+    /// Rows of the files with no open buffer.
+    closed_files: BTreeMap<PathBuf, Vec<(u32, T)>>,
+}
+
+struct BufferRows<T> {
+    buffer: WeakModel<Buffer>,
+    rows: Vec<AnchoredRow<T>>,
+}
+
+/// A row of an open buffer, anchored at the start of its line.
+#[derive(Clone, Debug)]
+pub struct AnchoredRow<T> {
+    pub position: Anchor,
+    pub data: T,
+}
+
+impl<T> Default for AnchoredRows<T> {
+    fn default() -> Self {
+        Self {
+            open_buffers: HashMap::default(),
+            closed_files: BTreeMap::default(),
+        }
+    }
+}
+
+impl<T: Clone> AnchoredRows<T> {
+    /// Starts tracking the rows of a newly opened buffer, if it belongs to a local file.
+    pub fn register_buffer(&mut self, buffer: &Model<Buffer>, cx: &AppContext) {
+        let Some(abs_path) = buffer_abs_path(buffer.read(cx), cx) else {
+            return;
+        };
+        let rows = anchor_rows(
+            self.closed_files.remove(&abs_path).unwrap_or_default(),
+            buffer.read(cx),
+        );
+        self.open_buffers.insert(
+            buffer.read(cx).remote_id(),
+            BufferRows {
+                buffer: buffer.downgrade(),
+                rows,
+            },
+        );
+    }
+
+    /// Stops tracking the rows of a released buffer, remembering them by number.
+    pub fn unregister_buffer(&mut self, buffer: &Buffer, cx: &AppContext) {
+        let Some(buffer_rows) = self.open_buffers.remove(&buffer.remote_id()) else {
+            return;
+        };
+        let Some(abs_path) = buffer_abs_path(buffer, cx) else {
+            return;
+        };
+        let rows = row_numbers(&buffer_rows.rows, buffer);
+        if !rows.is_empty() {
+            self.closed_files.insert(abs_path, rows);
+        }
+    }
+
+    /// Adds a row at the given position with the given data, or removes the one already there.
+    /// Returns the path of the buffer's file if its rows changed.
+    pub fn toggle(
+        &mut self,
+        buffer: &Buffer,
+        position: Anchor,
+        data: T,
+        cx: &AppContext,
+    ) -> Option<PathBuf> {
+        let abs_path = buffer_abs_path(buffer, cx)?;
+        let buffer_rows = self.open_buffers.get_mut(&buffer.remote_id())?;
+        let row = position.to_point(buffer).row;
+        match buffer_rows.index_for_row(row, buffer) {
+            Ok(ix) => {
+                buffer_rows.rows.remove(ix);
+            }
+            Err(ix) => buffer_rows.rows.insert(
+                ix,
+                AnchoredRow {
+                    position: buffer.anchor_before(Point::new(row, 0)),
+                    data,
+                },
+            ),
+        }
+        Some(abs_path)
+    }
+
+    /// Sets the data of the row at the given position, adding the row if there is none. Returns
+    /// the path of the buffer's file if its rows changed.
+    pub fn set(
+        &mut self,
+        buffer: &Buffer,
+        position: Anchor,
+        data: T,
+        cx: &AppContext,
+    ) -> Option<PathBuf> {
+        let abs_path = buffer_abs_path(buffer, cx)?;
+        let buffer_rows = self.open_buffers.get_mut(&buffer.remote_id())?;
+        let row = position.to_point(buffer).row;
+        match buffer_rows.index_for_row(row, buffer) {
+            Ok(ix) => buffer_rows.rows[ix].data = data,
+            Err(ix) => buffer_rows.rows.insert(
+                ix,
+                AnchoredRow {
+                    position: buffer.anchor_before(Point::new(row, 0)),
+                    data,
+                },
+            ),
+        }
+        Some(abs_path)
+    }
+
+    /// Returns the rows of the given buffer, ordered by position.
+    pub fn rows_for_buffer(&self, buffer_id: BufferId) -> &[AnchoredRow<T>] {
+        self.open_buffers
+            .get(&buffer_id)
+            .map_or(&[], |buffer_rows| buffer_rows.rows.as_slice())
+    }
+
+    /// Returns the rows of the given file, ordered by number.
+    pub fn rows_for_path(&self, abs_path: &Path, cx: &AppContext) -> Vec<(u32, T)> {
+        for buffer_rows in self.open_buffers.values() {
+            if let Some(buffer) = buffer_rows.buffer.upgrade() {
+                let buffer = buffer.read(cx);
+                if buffer_abs_path(buffer, cx).as_deref() == Some(abs_path) {
+                    return row_numbers(&buffer_rows.rows, buffer);
+                }
+            }
+        }
+        self.closed_files.get(abs_path).cloned().unwrap_or_default()
+    }
+
+    /// Returns the rows of every file with rows, ordered by path and number.
+    pub fn all_rows(&self, cx: &AppContext) -> BTreeMap<PathBuf, Vec<(u32, T)>> {
+        let mut all_rows = self.closed_files.clone();
+        for buffer_rows in self.open_buffers.values() {
+            if buffer_rows.rows.is_empty() {
+                continue;
+            }
+            if let Some(buffer) = buffer_rows.buffer.upgrade() {
+                let buffer = buffer.read(cx);
+                if let Some(abs_path) = buffer_abs_path(buffer, cx) {
+                    all_rows.insert(abs_path, row_numbers(&buffer_rows.rows, buffer));
+                }
+            }
+        }
+        all_rows
+    }
+
+    /// Replaces the rows of the given files. Files with no entry keep their rows.
+    pub fn replace_rows(&mut self, mut rows: BTreeMap<PathBuf, Vec<(u32, T)>>, cx: &AppContext) {
+        for buffer_rows in self.open_buffers.values_mut() {
+            let Some(buffer) = buffer_rows.buffer.upgrade() else {
+                continue;
+            };
+            let buffer = buffer.read(cx);
+            if let Some(replacement) =
+                buffer_abs_path(buffer, cx).and_then(|abs_path| rows.remove(&abs_path))
+            {
+                buffer_rows.rows = anchor_rows(replacement, buffer);
+            }
+        }
+        for (abs_path, replacement) in rows {
+            if replacement.is_empty() {
+                self.closed_files.remove(&abs_path);
+            } else {
+                self.closed_files.insert(abs_path, replacement);
+            }
+        }
+    }
+
+    /// Whether the given buffer has any rows.
+    pub fn has_rows(&self, buffer_id: BufferId) -> bool {
+        !self.rows_for_buffer(buffer_id).is_empty()
+    }
+}
+
+impl<T> BufferRows<T> {
+    fn index_for_row(&self, row: u32, buffer: &Buffer) -> Result<usize, usize> {
+        self.rows
+            .binary_search_by_key(&row, |anchored| anchored.position.to_point(buffer).row)
+    }
+}
+
+pub(crate) fn buffer_abs_path(buffer: &Buffer, cx: &AppContext) -> Option<PathBuf> {
+    File::from_dyn(buffer.file())
+        .filter(|file| file.is_local)
+        .map(|file| file.abs_path(cx))
+}
+
+fn anchor_rows<T>(mut rows: Vec<(u32, T)>, buffer: &Buffer) -> Vec<AnchoredRow<T>> {
+    let max_row = buffer.max_point().row;
+    rows.sort_by_key(|(row, _)| *row);
+    rows.dedup_by_key(|(row, _)| *row);
+    rows.into_iter()
+        .filter(|(row, _)| *row <= max_row)
+        .map(|(row, data)| AnchoredRow {
+            position: buffer.anchor_before(Point::new(row, 0)),
+            data,
+        })
+        .collect()
+}
+
+fn row_numbers<T: Clone>(rows: &[AnchoredRow<T>], buffer: &Buffer) -> Vec<(u32, T)> {
+    let mut numbers = rows
+        .iter()
+        .map(|anchored| {
+            (
+                anchored.position.to_point(buffer).row,
+                anchored.data.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    numbers.sort_by_key(|(row, _)| *row);
+    numbers.dedup_by_key(|(row, _)| *row);
+    numbers
+}
@@ -0,0 +1,138 @@
+//! Project-wide storage of the bookmarks set in local files.
+
+use std::path::{Path, PathBuf};
+
+use collections::BTreeMap;
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext};
+use language::{Buffer, BufferId};
+use text::Anchor;
+
+use crate::anchored_rows::{buffer_abs_path, AnchoredRow, AnchoredRows};
+
+/// Bookmarks of the project, by file.
+pub struct BookmarkStore {
+    bookmarks: AnchoredRows<Option<String>>,
+}
+
+/// A bookmark of a file with no open buffer, or a snapshot of one of an open buffer.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bookmark {
+    /// The 0-based row of the bookmarked line.
+    pub row: u32,
+    pub label: Option<String>,
+}
+
+/// A bookmark of an open buffer, whose data is its label.
+pub type BufferBookmark = AnchoredRow<Option<String>>;
+
+pub enum BookmarkStoreEvent {
+    BookmarksChanged { abs_path: PathBuf },
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+impl BookmarkStore {
+    pub fn new(cx: &mut AppContext) -> Model<Self> {
+        cx.new_model(|_| Self {
+            bookmarks: AnchoredRows::default(),
+        })
+    }
+
+    /// Starts tracking the bookmarks of a newly opened buffer, if it belongs to a local file.
+    pub fn register_buffer(&mut self, buffer: &Model<Buffer>, cx: &AppContext) {
+        self.bookmarks.register_buffer(buffer, cx);
+    }
+
+    /// Stops tracking the bookmarks of a released buffer, remembering them by row.
+    pub fn unregister_buffer(&mut self, buffer: &Buffer, cx: &AppContext) {
+        self.bookmarks.unregister_buffer(buffer, cx);
+    }
+
+    /// Reports the bookmarks of a buffer as changed once it is saved, as edits may have moved
+    /// them to other rows.
+    pub fn buffer_saved(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer = buffer.read(cx);
+        if self.bookmarks.has_rows(buffer.remote_id()) {
+            if let Some(abs_path) = buffer_abs_path(buffer, cx) {
+                cx.emit(BookmarkStoreEvent::BookmarksChanged { abs_path });
+            }
+        }
+    }
+
+    /// Sets a bookmark at the row of the given position, or removes the one already there.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(abs_path) = self.bookmarks.toggle(buffer.read(cx), position, None, cx) {
+            cx.emit(BookmarkStoreEvent::BookmarksChanged { abs_path });
+        }
+    }
+
+    /// Labels the bookmark at the row of the given position, setting a bookmark there first if
+    /// there is none.
+    pub fn set_label(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        label: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let label = label.filter(|label| !label.trim().is_empty());
+        if let Some(abs_path) = self.bookmarks.set(buffer.read(cx), position, label, cx) {
+            cx.emit(BookmarkStoreEvent::BookmarksChanged { abs_path });
+        }
+    }
+
+    /// Returns the bookmarks set in the given buffer, ordered by position.
+    pub fn bookmarks_for_buffer(&self, buffer_id: BufferId) -> &[BufferBookmark] {
+        self.bookmarks.rows_for_buffer(buffer_id)
+    }
+
+    /// Returns the bookmarks set in the given file, ordered by row.
+    pub fn bookmarks_for_path(&self, abs_path: &Path, cx: &AppContext) -> Vec<Bookmark> {
+        bookmarks_from_rows(self.bookmarks.rows_for_path(abs_path, cx))
+    }
+
+    /// Returns the bookmarks of every file with bookmarks, ordered by path and row.
+    pub fn all_bookmarks(&self, cx: &AppContext) -> BTreeMap<PathBuf, Vec<Bookmark>> {
+        self.bookmarks
+            .all_rows(cx)
+            .into_iter()
+            .map(|(abs_path, rows)| (abs_path, bookmarks_from_rows(rows)))
+            .collect()
+    }
+
+    /// Replaces the bookmarks of the given files, e.g. with the ones restored from a previous
+    /// session. Files with no entry keep their bookmarks.
+    pub fn insert_bookmarks(
+        &mut self,
+        bookmarks: BTreeMap<PathBuf, Vec<Bookmark>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let changed_paths = bookmarks.keys().cloned().collect::<Vec<_>>();
+        let rows = bookmarks
+            .into_iter()
+            .map(|(abs_path, bookmarks)| {
+                let rows = bookmarks
+                    .into_iter()
+                    .map(|bookmark| (bookmark.row, bookmark.label))
+                    .collect();
+                (abs_path, rows)
+            })
+            .collect();
+        self.bookmarks.replace_rows(rows, cx);
+
+        for abs_path in changed_paths {
+            cx.emit(BookmarkStoreEvent::BookmarksChanged { abs_path });
+        }
+    }
+}
+
+fn bookmarks_from_rows(rows: Vec<(u32, Option<String>)>) -> Vec<Bookmark> {
+    rows.into_iter()
+        .map(|(row, label)| Bookmark { row, label })
+        .collect()
+}
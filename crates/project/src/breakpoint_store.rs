@@ -0,0 +1,103 @@
+//! Project-wide storage of the breakpoints set in local files.
+
+use std::path::{Path, PathBuf};
+
+use collections::BTreeMap;
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext};
+use language::{Buffer, BufferId};
+use text::Anchor;
+
+use crate::anchored_rows::AnchoredRows;
+
+/// Breakpoints of the project, by file.
+pub struct BreakpointStore {
+    breakpoints: AnchoredRows<()>,
+}
+
+pub enum BreakpointStoreEvent {
+    BreakpointsChanged { abs_path: PathBuf },
+}
+
+impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
+
+impl BreakpointStore {
+    pub fn new(cx: &mut AppContext) -> Model<Self> {
+        cx.new_model(|_| Self {
+            breakpoints: AnchoredRows::default(),
+        })
+    }
+
+    /// Starts tracking the breakpoints of a newly opened buffer, if it belongs to a local file.
+    pub fn register_buffer(&mut self, buffer: &Model<Buffer>, cx: &AppContext) {
+        self.breakpoints.register_buffer(buffer, cx);
+    }
+
+    /// Stops tracking the breakpoints of a released buffer, remembering them by row.
+    pub fn unregister_buffer(&mut self, buffer: &Buffer, cx: &AppContext) {
+        self.breakpoints.unregister_buffer(buffer, cx);
+    }
+
+    /// Sets a breakpoint at the row of the given position, or removes the one already there.
+    pub fn toggle_breakpoint(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(abs_path) = self.breakpoints.toggle(buffer.read(cx), position, (), cx) {
+            cx.emit(BreakpointStoreEvent::BreakpointsChanged { abs_path });
+        }
+    }
+
+    /// Returns the positions of the breakpoints set in the given buffer.
+    pub fn breakpoints_for_buffer(&self, buffer_id: BufferId) -> impl Iterator<Item = &Anchor> {
+        self.breakpoints
+            .rows_for_buffer(buffer_id)
+            .iter()
+            .map(|breakpoint| &breakpoint.position)
+    }
+
+    /// Returns the 0-based rows of the breakpoints set in the given file.
+    pub fn breakpoints_for_path(&self, abs_path: &Path, cx: &AppContext) -> Vec<u32> {
+        self.breakpoints
+            .rows_for_path(abs_path, cx)
+            .into_iter()
+            .map(|(row, ())| row)
+            .collect()
+    }
+
+    /// Returns the 0-based rows of the breakpoints of every file with breakpoints.
+    pub fn all_breakpoints(&self, cx: &AppContext) -> BTreeMap<PathBuf, Vec<u32>> {
+        self.breakpoints
+            .all_rows(cx)
+            .into_iter()
+            .map(|(abs_path, rows)| (abs_path, rows.into_iter().map(|(row, ())| row).collect()))
+            .collect()
+    }
+
+    /// Replaces every breakpoint of the project, e.g. with the ones restored from a previous
+    /// session.
+    pub fn set_breakpoints(
+        &mut self,
+        breakpoints: BTreeMap<PathBuf, Vec<u32>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut rows = self
+            .breakpoints
+            .all_rows(cx)
+            .into_keys()
+            .map(|abs_path| (abs_path, Vec::new()))
+            .collect::<BTreeMap<_, _>>();
+        rows.extend(
+            breakpoints.into_iter().map(|(abs_path, rows)| {
+                (abs_path, rows.into_iter().map(|row| (row, ())).collect())
+            }),
+        );
+        let changed_paths = rows.keys().cloned().collect::<Vec<_>>();
+        self.breakpoints.replace_rows(rows, cx);
+
+        for abs_path in changed_paths {
+            cx.emit(BreakpointStoreEvent::BreakpointsChanged { abs_path });
+        }
+    }
+}
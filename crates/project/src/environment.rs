@@ -0,0 +1,196 @@
+//! Loading of the environment of terminals and tasks from dotenv files and environment scripts.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use gpui::{ModelContext, Task};
+use settings::{Settings, SettingsLocation};
+use terminal::terminal_settings::TerminalSettings;
+use util::{parse_env_output, ResultExt};
+
+use crate::Project;
+
+/// Variables that the shell sourcing an environment script sets for itself, rather than the script.
+const SHELL_VARIABLES: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL"];
+
+/// The variable that the path of an environment script is passed in on Windows, so that the path is
+/// never parsed as part of the command sourcing it.
+const SCRIPT_PATH_VARIABLE: &str = "ZED_ENV_SCRIPT";
+
+impl Project {
+    /// Loads the environment of a terminal or a task running in the given directory.
+    ///
+    /// The dotenv files and the environment script configured in the terminal settings of the directory's worktree are
+    /// loaded first, followed by the task's own `env_files` and `env_script`, each overriding the variables of the
+    /// previous ones. Files that fail to load are skipped, as are the configured files that do not exist.
+    pub fn load_environment(
+        &self,
+        working_directory: Option<&Path>,
+        env_files: &[PathBuf],
+        env_script: Option<&Path>,
+        cx: &ModelContext<Self>,
+    ) -> Task<HashMap<String, String>> {
+        let mut files = Vec::new();
+        let mut scripts = Vec::new();
+        if let Some((worktree, relative_path)) =
+            working_directory.and_then(|directory| self.find_local_worktree(directory, cx))
+        {
+            let worktree = worktree.read(cx);
+            let root = worktree.abs_path();
+            let settings = TerminalSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.id().to_usize(),
+                    path: &relative_path,
+                }),
+                cx,
+            );
+            files.extend(
+                settings
+                    .env_files
+                    .iter()
+                    .map(|file| (root.join(file), true)),
+            );
+            scripts.extend(
+                settings
+                    .env_script
+                    .as_ref()
+                    .map(|script| (root.join(script), true)),
+            );
+        }
+        files.extend(env_files.iter().map(|file| (file.clone(), false)));
+        scripts.extend(env_script.map(|script| (script.to_path_buf(), false)));
+        if files.is_empty() && scripts.is_empty() {
+            return Task::ready(HashMap::default());
+        }
+
+        let fs = self.fs.clone();
+        cx.background_executor().spawn(async move {
+            let mut environment = HashMap::default();
+            for (path, optional) in files {
+                if optional && !fs.is_file(&path).await {
+                    continue;
+                }
+                let variables = async {
+                    let contents = fs.load(&path).await?;
+                    let known_variables = env::vars().chain(environment.clone()).collect();
+                    task::parse_dotenv(&contents, &known_variables)
+                }
+                .await
+                .with_context(|| format!("loading environment file {path:?}"))
+                .log_err();
+                environment.extend(variables.into_iter().flatten());
+            }
+            for (path, optional) in scripts {
+                if optional && !fs.is_file(&path).await {
+                    continue;
+                }
+                let variables = load_environment_script(&path, &environment)
+                    .await
+                    .with_context(|| format!("loading environment script {path:?}"))
+                    .log_err();
+                environment.extend(variables.into_iter().flatten());
+            }
+            environment
+        })
+    }
+}
+
+/// Sources a shell script in its directory, returning the variables that it sets or changes.
+/// Scripts named `.envrc` are loaded with `direnv`, when it is installed, so that they can use its standard library.
+/// On Windows, scripts are PowerShell scripts.
+async fn load_environment_script(
+    script: &Path,
+    environment: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    let marker = "ZED_ENV_SCRIPT_START";
+    let directory = script
+        .parent()
+        .context("environment script has no parent directory")?;
+    let mut command = environment_script_command(script, directory, marker);
+    let output = command
+        .current_dir(directory)
+        .envs(environment)
+        .output()
+        .await
+        .context("failed to spawn shell to source the environment script")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "sourcing the environment script failed with {:?}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n");
+    let env_output_start = stdout
+        .find(marker)
+        .with_context(|| format!("failed to parse output of `env` command: {stdout}"))?;
+    let mut variables = HashMap::default();
+    parse_env_output(&stdout[env_output_start + marker.len()..], |key, value| {
+        if SHELL_VARIABLES.contains(&key.as_str()) || key == SCRIPT_PATH_VARIABLE {
+            return;
+        }
+        let previous_value = environment
+            .get(&key)
+            .cloned()
+            .or_else(|| env::var(&key).ok());
+        if previous_value.as_ref() != Some(&value) {
+            variables.insert(key, value);
+        }
+    });
+    Ok(variables)
+}
+
+/// Returns the command sourcing the given script and printing the marker followed by the resulting
+/// environment. The path of the script is passed as an argument of the shell, so that it is never
+/// parsed as part of the command.
+#[cfg(not(target_os = "windows"))]
+fn environment_script_command(
+    script: &Path,
+    directory: &Path,
+    marker: &str,
+) -> smol::process::Command {
+    let print_environment = format!("printf '%s' {marker}; env");
+    if script.file_name() == Some(".envrc".as_ref()) && which::which("direnv").is_ok() {
+        let mut command = smol::process::Command::new("direnv");
+        command
+            .arg("exec")
+            .arg(directory)
+            .args(["sh", "-c", &print_environment]);
+        command
+    } else {
+        let mut command = smol::process::Command::new("sh");
+        command
+            .args(["-c", &format!(". \"$1\" >&2 && {print_environment}"), "sh"])
+            .arg(script);
+        command
+    }
+}
+
+/// Returns the command sourcing the given PowerShell script and printing the marker followed by the
+/// resulting environment. The path of the script is passed in a variable, so that it is never parsed
+/// as part of the command.
+#[cfg(target_os = "windows")]
+fn environment_script_command(
+    script: &Path,
+    _directory: &Path,
+    marker: &str,
+) -> smol::process::Command {
+    let mut command = smol::process::Command::new("powershell");
+    command
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!(
+                "$ErrorActionPreference = 'Stop'; . $env:{SCRIPT_PATH_VARIABLE} | Out-Host; \
+                 Write-Output {marker}; \
+                 Get-ChildItem env: | ForEach-Object {{ '{{0}}={{1}}' -f $_.Name, $_.Value }}"
+            ),
+        ])
+        .env(SCRIPT_PATH_VARIABLE, script);
+    command
+}
@@ -0,0 +1,489 @@
+//! Reading the status of the git repositories of the project, and writing to them: staging and unstaging
+//! files and hunks, and committing.
+
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use fs::repository::{GitChange, GitRepository, GitStatusEntry, RepoPath};
+use git::{
+    diff::{BufferDiff, DiffHunk, DiffHunkStatus},
+    log::CommitDetails,
+};
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::Buffer;
+use parking_lot::Mutex;
+use rpc::proto;
+use text::{BufferId, LineEnding};
+use util::ResultExt;
+
+use crate::{Item as _, Project, ProjectPath};
+
+/// The files with changes in a git repository of the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepositoryStatus {
+    /// The working directory of the repository.
+    pub work_directory: ProjectPath,
+    pub branch: Option<Arc<str>>,
+    pub entries: Vec<GitStatusEntry>,
+}
+
+/// A version of the files of a git repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitRevision {
+    Head,
+    Index,
+    /// A commit, given by anything `git rev-parse` resolves to one, such as its SHA.
+    Commit(String),
+}
+
+/// Commits of a git repository of the project, as listed by [`Project::git_log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLog {
+    /// The working directory of the repository.
+    pub work_directory: ProjectPath,
+    pub commits: Vec<CommitDetails>,
+}
+
+impl GitLog {
+    /// The project path of a file of the repository, given relative to its working directory.
+    pub fn project_path(&self, path: &Path) -> ProjectPath {
+        ProjectPath {
+            worktree_id: self.work_directory.worktree_id,
+            path: self.work_directory.path.join(path).into(),
+        }
+    }
+}
+
+impl RepositoryStatus {
+    /// The project path of a file of the repository.
+    pub fn project_path(&self, repo_path: &RepoPath) -> ProjectPath {
+        ProjectPath {
+            worktree_id: self.work_directory.worktree_id,
+            path: self.work_directory.path.join(repo_path).into(),
+        }
+    }
+}
+
+impl Project {
+    /// Lists the changed files of every git repository of the local worktrees.
+    pub fn git_statuses(&self, cx: &AppContext) -> Task<Vec<RepositoryStatus>> {
+        let repositories = self.local_git_repositories(cx);
+        cx.background_executor().spawn(async move {
+            repositories
+                .into_iter()
+                .filter_map(|(work_directory, branch, repo)| {
+                    let entries = repo
+                        .lock()
+                        .status_entries()
+                        .with_context(|| format!("listing the changes of {work_directory:?}"))
+                        .log_err()?;
+                    Some(RepositoryStatus {
+                        work_directory,
+                        branch,
+                        entries,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// The working directories of the git repositories of the local worktrees.
+    pub fn git_work_directories(&self, cx: &AppContext) -> Vec<ProjectPath> {
+        self.local_git_repositories(cx)
+            .into_iter()
+            .map(|(work_directory, _, _)| work_directory)
+            .collect()
+    }
+
+    fn local_git_repositories(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(ProjectPath, Option<Arc<str>>, Arc<Mutex<dyn GitRepository>>)> {
+        self.worktrees()
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let snapshot = worktree.as_local()?.snapshot();
+                let worktree_id = worktree.id();
+                Some(
+                    snapshot
+                        .repositories()
+                        .filter_map(|(work_directory, repository_entry)| {
+                            let repo = snapshot.get_local_repo(repository_entry)?.repo().clone();
+                            let work_directory = ProjectPath {
+                                worktree_id,
+                                path: work_directory.clone(),
+                            };
+                            Some((work_directory, repository_entry.branch(), repo))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Lists the files with unresolved merge conflicts in the git repositories of the local worktrees.
+    pub fn conflicted_paths(&self, cx: &AppContext) -> Task<Vec<ProjectPath>> {
+        let statuses = self.git_statuses(cx);
+        cx.background_executor().spawn(async move {
+            let mut paths = Vec::new();
+            for repository in statuses.await {
+                for entry in &repository.entries {
+                    if entry.unstaged == Some(GitChange::Conflict) {
+                        paths.push(repository.project_path(&entry.repo_path));
+                    }
+                }
+            }
+            paths
+        })
+    }
+
+    /// Stages the changes of the files at the given paths, or their deletion if they do not exist anymore.
+    pub fn stage_paths(
+        &mut self,
+        paths: Vec<ProjectPath>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.update_index(paths, |repo, repo_paths| repo.stage_paths(repo_paths), cx)
+    }
+
+    /// Resets the index entries of the files at the given paths to the HEAD commit.
+    pub fn unstage_paths(
+        &mut self,
+        paths: Vec<ProjectPath>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.update_index(paths, |repo, repo_paths| repo.unstage_paths(repo_paths), cx)
+    }
+
+    fn update_index(
+        &mut self,
+        paths: Vec<ProjectPath>,
+        update: impl Fn(&dyn GitRepository, &[RepoPath]) -> Result<()> + Send + 'static,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let mut repositories = Vec::<(Arc<Mutex<dyn GitRepository>>, Vec<RepoPath>)>::new();
+        let mut updated_paths = Vec::new();
+        for path in paths {
+            let (repo, repo_path) = match self.repository_for_path(&path, cx) {
+                Ok(repository) => repository,
+                Err(error) => return Task::ready(Err(error)),
+            };
+            match repositories
+                .iter_mut()
+                .find(|(existing_repo, _)| Arc::ptr_eq(existing_repo, &repo))
+            {
+                Some((_, repo_paths)) => repo_paths.push(repo_path.clone()),
+                None => repositories.push((repo.clone(), vec![repo_path.clone()])),
+            }
+            updated_paths.push((path, repo, repo_path));
+        }
+
+        cx.spawn(|project, mut cx| async move {
+            let index_texts = cx
+                .background_executor()
+                .spawn(async move {
+                    for (repo, repo_paths) in repositories {
+                        update(&*repo.lock(), &repo_paths)?;
+                    }
+                    anyhow::Ok(
+                        updated_paths
+                            .into_iter()
+                            .map(|(path, repo, repo_path)| {
+                                let index_text = repo.lock().load_index_text(&repo_path);
+                                (path, index_text)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .await?;
+            project.update(&mut cx, |project, cx| {
+                for (path, index_text) in index_texts {
+                    project.set_diff_base_of_open_buffer(&path, index_text, cx);
+                }
+            })
+        })
+    }
+
+    /// Stages the hunks of the file's saved changes that intersect the given rows of the buffer.
+    pub fn stage_hunks(
+        &mut self,
+        buffer: &Model<Buffer>,
+        rows: Vec<Range<u32>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let snapshot = buffer.read(cx).text_snapshot();
+        self.update_index_text(
+            buffer,
+            move |repo, repo_path| {
+                let working_text = repo
+                    .load_working_text(repo_path)?
+                    .context("file does not exist in the working directory")?;
+                let line_ending = LineEnding::detect(&working_text);
+                let working_text = normalized_text(working_text);
+                let index_text =
+                    normalized_text(repo.load_index_text(repo_path).unwrap_or_default());
+
+                // Find the rows of the saved file that the rows of the buffer correspond to.
+                let saved_rows = base_rows(&working_text, &snapshot, &rows);
+                let saved = text::Buffer::new(0, BufferId::new(1)?, working_text).snapshot();
+                let diff = BufferDiff::build(&index_text, &saved);
+                let new_index_text = diff.apply_hunks(&index_text, &saved, |hunk| {
+                    intersects_rows(hunk, &saved_rows)
+                });
+                Ok(with_line_ending(new_index_text, line_ending))
+            },
+            cx,
+        )
+    }
+
+    /// Unstages the hunks of the staged changes that correspond to the given rows of the buffer.
+    pub fn unstage_hunks(
+        &mut self,
+        buffer: &Model<Buffer>,
+        rows: Vec<Range<u32>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let snapshot = buffer.read(cx).text_snapshot();
+        self.update_index_text(
+            buffer,
+            move |repo, repo_path| {
+                let head_text = normalized_text(repo.load_head_text(repo_path).unwrap_or_default());
+                let index_text = repo.load_index_text(repo_path).unwrap_or_default();
+                let line_ending = LineEnding::detect(&index_text);
+                let index_text = normalized_text(index_text);
+
+                // Find the rows of the index that the rows of the buffer correspond to.
+                let index_rows = base_rows(&index_text, &snapshot, &rows);
+
+                // Apply all the staged hunks to the HEAD text, except for the ones being unstaged.
+                let index = text::Buffer::new(0, BufferId::new(1)?, index_text).snapshot();
+                let staged_diff = BufferDiff::build(&head_text, &index);
+                let new_index_text = staged_diff.apply_hunks(&head_text, &index, |hunk| {
+                    !intersects_rows(hunk, &index_rows)
+                });
+                Ok(with_line_ending(new_index_text, line_ending))
+            },
+            cx,
+        )
+    }
+
+    /// Replaces the index text of the buffer's file with the one computed from the repository, reading and
+    /// writing the index under the same lock of the repository so that no other change to it is lost in between.
+    fn update_index_text(
+        &mut self,
+        buffer: &Model<Buffer>,
+        new_index_text: impl FnOnce(&dyn GitRepository, &RepoPath) -> Result<String> + Send + 'static,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let project_path = match buffer
+            .read(cx)
+            .project_path(cx)
+            .context("buffer has no file")
+        {
+            Ok(project_path) => project_path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let (repo, repo_path) = match self.repository_for_path(&project_path, cx) {
+            Ok(repository) => repository,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.spawn(|project, mut cx| async move {
+            let new_index_text = cx
+                .background_executor()
+                .spawn(async move {
+                    let repo = repo.lock();
+                    let new_index_text = new_index_text(&*repo, &repo_path)?;
+                    repo.set_index_text(&repo_path, Some(new_index_text.clone()))?;
+                    anyhow::Ok(new_index_text)
+                })
+                .await?;
+            project.update(&mut cx, |project, cx| {
+                project.set_diff_base_of_open_buffer(&project_path, Some(new_index_text), cx);
+            })
+        })
+    }
+
+    /// Commits the staged changes of the repository with the given working directory.
+    pub fn commit(
+        &self,
+        work_directory: &ProjectPath,
+        message: String,
+        amend: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let repo = match self.repository_for_path(work_directory, cx) {
+            Ok((repo, _)) => repo,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor()
+            .spawn(async move { repo.lock().commit(&message, amend) })
+    }
+
+    /// Loads the contents of the file at the path in a revision of its git repository, or `None` if the file does
+    /// not exist in it.
+    pub fn load_git_text(
+        &self,
+        project_path: &ProjectPath,
+        revision: GitRevision,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        let (repo, repo_path) = match self.repository_for_path(project_path, cx) {
+            Ok(repository) => repository,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor().spawn(async move {
+            let repo = repo.lock();
+            match revision {
+                GitRevision::Head => Ok(repo.load_head_text(&repo_path)),
+                GitRevision::Index => Ok(repo.load_index_text(&repo_path)),
+                GitRevision::Commit(commit) => repo.load_commit_text(&repo_path, &commit),
+            }
+        })
+    }
+
+    /// Lists at most `max_count` commits of the repository containing the path, newest first, starting from the
+    /// given revision. Unless the path is the working directory of the repository, only the commits that changed
+    /// the file are listed, following its renames.
+    pub fn git_log(
+        &self,
+        project_path: &ProjectPath,
+        revision: String,
+        max_count: usize,
+        cx: &AppContext,
+    ) -> Task<Result<GitLog>> {
+        let (repo, repo_path) = match self.repository_for_path(project_path, cx) {
+            Ok(repository) => repository,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let work_directory = ProjectPath {
+            worktree_id: project_path.worktree_id,
+            path: project_path
+                .path
+                .ancestors()
+                .nth(repo_path.components().count())
+                .unwrap_or(Path::new(""))
+                .into(),
+        };
+        cx.background_executor().spawn(async move {
+            let path = (!repo_path.as_os_str().is_empty()).then_some(repo_path.as_path());
+            let commits = repo.lock().log(&revision, path, max_count)?;
+            Ok(GitLog {
+                work_directory,
+                commits,
+            })
+        })
+    }
+
+    /// Loads the message of the HEAD commit of the repository containing the path, or `None` if there are no
+    /// commits yet.
+    pub fn last_commit_message(
+        &self,
+        path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        let repo = match self.repository_for_path(path, cx) {
+            Ok((repo, _)) => repo,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.background_executor()
+            .spawn(async move { Ok(repo.lock().last_commit_message()) })
+    }
+
+    /// Finds the git repository containing the path, along with the path relative to its working directory.
+    fn repository_for_path(
+        &self,
+        project_path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Result<(Arc<Mutex<dyn GitRepository>>, RepoPath)> {
+        let worktree = self
+            .worktree_for_id(project_path.worktree_id, cx)
+            .context("worktree not found")?;
+        let snapshot = worktree
+            .read(cx)
+            .as_local()
+            .context("git repositories of remote worktrees cannot be changed")?
+            .snapshot();
+        let (work_directory, repository_entry) = snapshot
+            .repository_and_work_directory_for_path(&project_path.path)
+            .with_context(|| format!("{:?} is not in a git repository", project_path.path))?;
+        let repo = snapshot
+            .get_local_repo(&repository_entry)
+            .context("git repository not found")?
+            .repo()
+            .clone();
+        let repo_path = project_path
+            .path
+            .strip_prefix(AsRef::<Path>::as_ref(&work_directory))?
+            .into();
+        Ok((repo, repo_path))
+    }
+
+    /// Updates the diff base of the buffer of the path, if it is open, after its index entry has changed.
+    fn set_diff_base_of_open_buffer(
+        &mut self,
+        project_path: &ProjectPath,
+        diff_base: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(buffer) = self.get_open_buffer(project_path, cx) else {
+            return;
+        };
+        let buffer_id = buffer.update(cx, |buffer, cx| {
+            buffer.set_diff_base(diff_base.clone(), cx);
+            buffer.remote_id().into()
+        });
+        if let Some(project_id) = self.remote_id() {
+            self.client
+                .send(proto::UpdateDiffBase {
+                    project_id,
+                    buffer_id,
+                    diff_base,
+                })
+                .log_err();
+        }
+    }
+}
+
+/// Whether the hunk intersects any of the row ranges, with the removed hunks, that span no rows, intersecting
+/// the ranges they are adjacent to.
+fn intersects_rows(hunk: &DiffHunk<u32>, rows: &[Range<u32>]) -> bool {
+    rows.iter().any(|rows| {
+        if hunk.status() == DiffHunkStatus::Removed {
+            rows.start <= hunk.associated_range.start && hunk.associated_range.start <= rows.end
+        } else {
+            hunk.associated_range.start < rows.end && rows.start < hunk.associated_range.end
+        }
+    })
+}
+
+/// Converts row ranges of the buffer into the corresponding row ranges of the diff base.
+fn base_rows(
+    diff_base: &str,
+    buffer: &text::BufferSnapshot,
+    rows: &[Range<u32>],
+) -> Vec<Range<u32>> {
+    let diff = BufferDiff::build(diff_base, buffer);
+    rows.iter()
+        .map(|rows| {
+            let start = diff.base_row_for_buffer_row(rows.start, diff_base, buffer);
+            let end = diff.base_row_for_buffer_row(rows.end, diff_base, buffer);
+            start..end.max(start)
+        })
+        .collect()
+}
+
+/// Returns the text with LF line endings, as buffers have them, to diff it against buffers.
+fn normalized_text(mut text: String) -> String {
+    LineEnding::normalize(&mut text);
+    text
+}
+
+/// Converts the LF line endings of the text into the given ones.
+fn with_line_ending(text: String, line_ending: LineEnding) -> String {
+    match line_ending {
+        LineEnding::Unix => text,
+        LineEnding::Windows => text.replace('\n', "\r\n"),
+    }
+}
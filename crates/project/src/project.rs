@@ -1,12 +1,20 @@
+mod anchored_rows;
+mod bookmark_store;
+mod breakpoint_store;
 pub mod connection_manager;
 pub mod debounced_delay;
+mod environment;
+mod git_repositories;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
 pub mod search;
+mod semantic_tokens;
+mod task_diagnostics;
 mod task_inventory;
 pub mod terminals;
+mod test_results_store;
 
 #[cfg(test)]
 mod project_tests;
@@ -67,6 +75,7 @@ use prettier_support::{DefaultPrettier, PrettierInstance};
 use project_settings::{LspSettings, ProjectSettings};
 use rand::prelude::*;
 use search_history::SearchHistory;
+use semantic_tokens::BufferSemanticTokens;
 use worktree::LocalSnapshot;
 
 use rpc::{ErrorCode, ErrorExt as _};
@@ -109,11 +118,18 @@ use util::{
 };
 use worktree::{Snapshot, Traversal};
 
+pub use anchored_rows::AnchoredRow;
+pub use bookmark_store::{Bookmark, BookmarkStore, BookmarkStoreEvent, BufferBookmark};
+pub use breakpoint_store::{BreakpointStore, BreakpointStoreEvent};
 pub use fs::*;
+pub use git_repositories::{GitLog, GitRevision, RepositoryStatus};
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
-pub use task_inventory::{Inventory, TaskSourceKind};
+pub use semantic_tokens::{SemanticToken, SemanticTokens};
+use task_diagnostics::TaskDiagnostics;
+pub use task_inventory::{Inventory, InventoryEvent, TaskRun, TaskRunStatus, TaskSourceKind};
+pub use test_results_store::{TestResultsStore, TestResultsStoreEvent};
 pub use worktree::{
     DiagnosticSummary, Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId,
     RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
@@ -164,6 +180,9 @@ pub struct Project {
     language_servers: HashMap<LanguageServerId, LanguageServerState>,
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
+    /// The names of the sources of diagnostics other than language servers, by the id their
+    /// diagnostics are reported under.
+    diagnostic_sources: HashMap<LanguageServerId, String>,
     last_formatting_failure: Option<String>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     language_server_watched_paths: HashMap<LanguageServerId, HashMap<WorktreeId, GlobSet>>,
@@ -192,6 +211,12 @@ pub struct Project {
     local_buffer_ids_by_path: HashMap<ProjectPath, BufferId>,
     local_buffer_ids_by_entry_id: HashMap<ProjectEntryId, BufferId>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    buffer_semantic_tokens: HashMap<BufferId, BufferSemanticTokens>,
+    buffer_diagnostic_pulls: HashMap<BufferId, Task<()>>,
+    workspace_diagnostic_pulls: HashMap<LanguageServerId, Task<()>>,
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>, // server_id -> uri -> result id of the last pulled report
+    pulled_diagnostic_versions: HashMap<BufferId, HashMap<LanguageServerId, i32>>, // buffer_id -> server_id -> version of the last pull
+    lsp_document_diagnostics: HashMap<LanguageServerId, HashMap<lsp::Url, LspDocumentDiagnostics>>,
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay,
@@ -207,6 +232,10 @@ pub struct Project {
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
     prettier_instances: HashMap<PathBuf, PrettierInstance>,
     tasks: Model<Inventory>,
+    breakpoint_store: Model<BreakpointStore>,
+    bookmark_store: Model<BookmarkStore>,
+    test_results_store: Model<TestResultsStore>,
+    task_diagnostics: TaskDiagnostics,
     hosted_project_id: Option<ProjectId>,
     search_history: SearchHistory,
 }
@@ -332,6 +361,7 @@ pub enum Event {
     CollaboratorJoined(proto::PeerId),
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
 }
 
@@ -352,6 +382,8 @@ pub struct LanguageServerStatus {
     pub pending_work: BTreeMap<String, LanguageServerProgress>,
     pub has_pending_diagnostic_updates: bool,
     progress_tokens: HashSet<String>,
+    /// Characters which, when typed, should request signature help from the server.
+    signature_help_trigger_characters: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -416,6 +448,53 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A code lens provided by a language server.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer the code lens is attached to.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server.
+    pub lsp_lens: lsp::CodeLens,
+}
+
+impl CodeLens {
+    /// The command to run when the lens is clicked, missing if the lens is not resolved yet.
+    pub fn command(&self) -> Option<&lsp::Command> {
+        self.lsp_lens.command.as_ref()
+    }
+}
+
+/// A link to another document provided by a language server, such as an include or an import path.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    /// The range of the buffer the link is attached to.
+    pub range: Range<Anchor>,
+    /// The URI the link points to, missing if the language server only computes it on resolve.
+    pub target: Option<String>,
+    /// The text to show when hovering the link.
+    pub tooltip: Option<String>,
+}
+
+/// A color literal found in a buffer by a language server.
+#[derive(Clone, Debug)]
+pub struct DocumentColor {
+    /// The range of the buffer containing the color literal.
+    pub range: Range<Anchor>,
+    /// The color, with each component in the `0..=1` range.
+    pub color: lsp::Color,
+}
+
+/// One of the ways a color can be written, as offered by a language server.
+#[derive(Clone, Debug)]
+pub struct ColorPresentation {
+    /// The text to show when picking this presentation.
+    pub label: String,
+    /// The edits writing the color in this presentation.
+    pub edits: Vec<(Range<Anchor>, String)>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResolveState {
     Resolved,
@@ -524,6 +603,101 @@ impl Hover {
     }
 }
 
+/// Signatures of the callable surrounding a position, provided by a language server.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    pub active_signature: usize,
+    pub active_parameter: Option<usize>,
+}
+
+impl SignatureHelp {
+    pub fn active_signature(&self) -> Option<&SignatureInformation> {
+        self.signatures
+            .get(self.active_signature)
+            .or_else(|| self.signatures.first())
+    }
+
+    /// The byte range of the active parameter within the active signature's label.
+    pub fn active_parameter_range(&self) -> Option<Range<usize>> {
+        let signature = self.active_signature()?;
+        let parameter_ix = signature.active_parameter.or(self.active_parameter)?;
+        signature
+            .parameters
+            .get(parameter_ix)
+            .map(|parameter| parameter.label_range.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureInformation {
+    pub label: String,
+    pub documentation: Option<MarkupContent>,
+    pub parameters: Vec<ParameterInformation>,
+    /// Overrides [`SignatureHelp::active_parameter`] for this signature.
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterInformation {
+    /// The byte range of this parameter within the signature's label.
+    pub label_range: Range<usize>,
+    pub documentation: Option<MarkupContent>,
+}
+
+/// A symbol that can be expanded in a call hierarchy.
+#[derive(Debug, Clone)]
+pub struct CallHierarchyItem {
+    /// The range to reveal when the item is selected, e.g. the name of a function.
+    pub location: Location,
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+impl CallHierarchyItem {
+    pub fn name(&self) -> &str {
+        &self.lsp_item.name
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.lsp_item.detail.as_deref()
+    }
+
+    pub fn kind(&self) -> lsp::SymbolKind {
+        self.lsp_item.kind
+    }
+}
+
+/// A caller or callee of a [`CallHierarchyItem`].
+#[derive(Debug, Clone)]
+pub struct CallHierarchyCall {
+    pub item: CallHierarchyItem,
+    /// Where the calls happen: inside the caller for incoming calls,
+    /// inside the expanded item for outgoing calls.
+    pub call_sites: Vec<Location>,
+}
+
+/// A type that can be expanded in a type hierarchy, e.g. a trait, class or interface.
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyItem {
+    /// The range to reveal when the item is selected, e.g. the name of a type.
+    pub location: Location,
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
+impl TypeHierarchyItem {
+    pub fn name(&self) -> &str {
+        &self.lsp_item.name
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.lsp_item.detail.as_deref()
+    }
+
+    pub fn kind(&self) -> lsp::SymbolKind {
+        self.lsp_item.kind
+    }
+}
+
 #[derive(Default)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
@@ -629,12 +803,29 @@ impl Project {
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
         client.add_model_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_model_request_handler(Self::handle_get_semantic_tokens);
+        client.add_model_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_model_request_handler(Self::handle_reload_buffers);
         client.add_model_request_handler(Self::handle_synchronize_buffers);
         client.add_model_request_handler(Self::handle_format_buffers);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<ResolveCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetLinkedEditingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentColors>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetColorPresentations>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSignatureHelp>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSubtypes>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
@@ -682,6 +873,12 @@ impl Project {
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 buffer_snapshots: Default::default(),
+                buffer_semantic_tokens: Default::default(),
+                buffer_diagnostic_pulls: Default::default(),
+                workspace_diagnostic_pulls: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                pulled_diagnostic_versions: Default::default(),
+                lsp_document_diagnostics: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 loading_buffers: HashMap::default(),
@@ -705,6 +902,7 @@ impl Project {
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
                 language_server_statuses: Default::default(),
+                diagnostic_sources: HashMap::default(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
@@ -723,6 +921,10 @@ impl Project {
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                breakpoint_store: BreakpointStore::new(cx),
+                bookmark_store: BookmarkStore::new(cx),
+                test_results_store: TestResultsStore::new(cx),
+                task_diagnostics: TaskDiagnostics::default(),
                 hosted_project_id: None,
                 search_history: Self::new_search_history(),
             }
@@ -853,10 +1055,17 @@ impl Project {
                                 pending_work: Default::default(),
                                 has_pending_diagnostic_updates: false,
                                 progress_tokens: Default::default(),
+                                signature_help_trigger_characters: server
+                                    .capabilities
+                                    .map(|capabilities| {
+                                        capabilities.signature_help_trigger_characters
+                                    })
+                                    .unwrap_or_default(),
                             },
                         )
                     })
                     .collect(),
+                diagnostic_sources: HashMap::default(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
@@ -865,6 +1074,12 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
+                buffer_semantic_tokens: Default::default(),
+                buffer_diagnostic_pulls: Default::default(),
+                workspace_diagnostic_pulls: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                pulled_diagnostic_versions: Default::default(),
+                lsp_document_diagnostics: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
@@ -877,6 +1092,10 @@ impl Project {
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                breakpoint_store: BreakpointStore::new(cx),
+                bookmark_store: BookmarkStore::new(cx),
+                test_results_store: TestResultsStore::new(cx),
+                task_diagnostics: TaskDiagnostics::default(),
                 hosted_project_id: None,
                 search_history: Self::new_search_history(),
             };
@@ -1256,6 +1475,18 @@ impl Project {
         &self.tasks
     }
 
+    pub fn breakpoint_store(&self) -> &Model<BreakpointStore> {
+        &self.breakpoint_store
+    }
+
+    pub fn bookmark_store(&self) -> &Model<BookmarkStore> {
+        &self.bookmark_store
+    }
+
+    pub fn test_results_store(&self) -> &Model<TestResultsStore> {
+        &self.test_results_store
+    }
+
     pub fn search_history(&self) -> &SearchHistory {
         &self.search_history
     }
@@ -1588,10 +1819,7 @@ impl Project {
             self.client
                 .send(proto::StartLanguageServer {
                     project_id,
-                    server: Some(proto::LanguageServer {
-                        id: server_id.0 as u64,
-                        name: status.name.clone(),
-                    }),
+                    server: Some(language_server_to_proto(*server_id, status)),
                 })
                 .log_err();
         }
@@ -1745,6 +1973,10 @@ impl Project {
                         pending_work: Default::default(),
                         has_pending_diagnostic_updates: false,
                         progress_tokens: Default::default(),
+                        signature_help_trigger_characters: server
+                            .capabilities
+                            .map(|capabilities| capabilities.signature_help_trigger_characters)
+                            .unwrap_or_default(),
                     },
                 )
             })
@@ -2277,7 +2509,18 @@ impl Project {
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         self.register_buffer_with_copilot(buffer, cx);
+        self.breakpoint_store
+            .update(cx, |store, cx| store.register_buffer(buffer, cx));
+        self.bookmark_store
+            .update(cx, |store, cx| store.register_buffer(buffer, cx));
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.buffer_semantic_tokens.remove(&buffer.remote_id());
+            this.buffer_diagnostic_pulls.remove(&buffer.remote_id());
+            this.pulled_diagnostic_versions.remove(&buffer.remote_id());
+            this.breakpoint_store
+                .update(cx, |store, cx| store.unregister_buffer(buffer, cx));
+            this.bookmark_store
+                .update(cx, |store, cx| store.unregister_buffer(buffer, cx));
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -2384,6 +2627,7 @@ impl Project {
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
                 }
+                self.pull_buffer_diagnostics(buffer_handle, cx);
             }
         }
     }
@@ -2412,6 +2656,9 @@ impl Project {
             }
 
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.buffer_semantic_tokens.remove(&buffer.remote_id());
+            self.buffer_diagnostic_pulls.remove(&buffer.remote_id());
+            self.pulled_diagnostic_versions.remove(&buffer.remote_id());
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
@@ -2555,6 +2802,13 @@ impl Project {
         ) {
             self.request_buffer_diff_recalculation(&buffer, cx);
         }
+        if matches!(event, BufferEvent::Edited { .. } | BufferEvent::Saved) {
+            self.pull_buffer_diagnostics(&buffer, cx);
+        }
+        if matches!(event, BufferEvent::Saved) {
+            self.bookmark_store
+                .update(cx, |store, cx| store.buffer_saved(&buffer, cx));
+        }
 
         match event {
             BufferEvent::Operation(operation) => {
@@ -3280,8 +3534,9 @@ impl Project {
                     if let Some(this) = this.upgrade() {
                         adapter.process_diagnostics(&mut params);
                         this.update(&mut cx, |this, cx| {
-                            this.update_diagnostics(
+                            this.update_lsp_diagnostics(
                                 server_id,
+                                LspDiagnosticsKind::Pushed,
                                 params,
                                 &adapter.disk_based_diagnostic_sources,
                                 cx,
@@ -3406,6 +3661,42 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshSemanticTokens);
+                            project.remote_id().map(|project_id| {
+                                project
+                                    .client
+                                    .send(proto::RefreshSemanticTokens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.refresh_pulled_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -3562,29 +3853,27 @@ impl Project {
             },
         );
 
-        self.language_server_statuses.insert(
-            server_id,
-            LanguageServerStatus {
-                name: language_server.name().to_string(),
-                pending_work: Default::default(),
-                has_pending_diagnostic_updates: false,
-                progress_tokens: Default::default(),
-            },
-        );
-
-        cx.emit(Event::LanguageServerAdded(server_id));
-
+        let status = LanguageServerStatus {
+            name: language_server.name().to_string(),
+            pending_work: Default::default(),
+            has_pending_diagnostic_updates: false,
+            progress_tokens: Default::default(),
+            signature_help_trigger_characters: signature_help_trigger_characters(
+                &language_server.capabilities(),
+            ),
+        };
         if let Some(project_id) = self.remote_id() {
             self.client.send(proto::StartLanguageServer {
                 project_id,
-                server: Some(proto::LanguageServer {
-                    id: server_id.0 as u64,
-                    name: language_server.name().to_string(),
-                }),
+                server: Some(language_server_to_proto(server_id, &status)),
             })?;
         }
+        self.language_server_statuses.insert(server_id, status);
+
+        cx.emit(Event::LanguageServerAdded(server_id));
 
         // Tell the language server about every open buffer in the worktree that matches the language.
+        let mut opened_buffers = Vec::new();
         for buffer in self.opened_buffers.values() {
             if let Some(buffer_handle) = buffer.upgrade() {
                 let buffer = buffer_handle.read(cx);
@@ -3654,9 +3943,15 @@ impl Project {
                         cx,
                     )
                 });
+                opened_buffers.push(buffer_handle);
             }
         }
 
+        for buffer in opened_buffers {
+            self.pull_buffer_diagnostics(&buffer, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+
         cx.notify();
         Ok(())
     }
@@ -3703,6 +3998,12 @@ impl Project {
 
             self.language_server_watched_paths.remove(&server_id);
             self.language_server_statuses.remove(&server_id);
+            self.workspace_diagnostic_pulls.remove(&server_id);
+            self.diagnostic_result_ids.remove(&server_id);
+            self.lsp_document_diagnostics.remove(&server_id);
+            for versions in self.pulled_diagnostic_versions.values_mut() {
+                versions.remove(&server_id);
+            }
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -4218,6 +4519,27 @@ impl Project {
         })
     }
 
+    /// Registers a source of diagnostics other than a language server, such as the problem matchers
+    /// of tasks, and returns the id to report its diagnostics under. No language server gets the same id,
+    /// so the source's diagnostics are stored and replaced independently of theirs.
+    pub fn register_diagnostic_source(&mut self, name: impl Into<String>) -> LanguageServerId {
+        let source_id = self.languages.next_language_server_id();
+        self.diagnostic_sources.insert(source_id, name.into());
+        source_id
+    }
+
+    /// The name of the language server or other source reporting diagnostics under the given id.
+    pub fn diagnostic_source_name(&self, source_id: LanguageServerId) -> Option<&str> {
+        self.diagnostic_sources
+            .get(&source_id)
+            .or_else(|| {
+                self.language_server_statuses
+                    .get(&source_id)
+                    .map(|status| &status.name)
+            })
+            .map(String::as_str)
+    }
+
     pub fn language_server_statuses(
         &self,
     ) -> impl DoubleEndedIterator<Item = &LanguageServerStatus> {
@@ -4228,6 +4550,304 @@ impl Project {
         self.last_formatting_failure.as_deref()
     }
 
+    /// Pulls the diagnostics of the buffer from those of its language servers that support
+    /// pulling diagnostics, once the buffer stops changing for a moment.
+    fn pull_buffer_diagnostics(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        const PULL_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(250);
+
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer = buffer.downgrade();
+        let task = cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(PULL_DIAGNOSTICS_DEBOUNCE)
+                .await;
+            let Some(buffer) = buffer.upgrade() else {
+                return;
+            };
+            let requests = this
+                .update(&mut cx, |this, cx| {
+                    this.request_buffer_diagnostics(&buffer, cx)
+                })
+                .unwrap_or_default();
+            for (server_id, uri, version, request) in requests {
+                let Some(report) = request.await.log_err() else {
+                    continue;
+                };
+                let (report, related_documents) = match report {
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Full(report),
+                    ) => (
+                        Some(lsp::DocumentDiagnosticReportKind::Full(
+                            report.full_document_diagnostic_report,
+                        )),
+                        report.related_documents,
+                    ),
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Unchanged(report),
+                    ) => (
+                        Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                            report.unchanged_document_diagnostic_report,
+                        )),
+                        report.related_documents,
+                    ),
+                    lsp::DocumentDiagnosticReportResult::Partial(report) => {
+                        (None, report.related_documents)
+                    }
+                };
+                this.update(&mut cx, |this, cx| {
+                    // Only a successful pull makes pulling the same version again redundant.
+                    this.pulled_diagnostic_versions
+                        .entry(buffer_id)
+                        .or_default()
+                        .insert(server_id, version);
+                    if let Some(report) = report {
+                        this.update_pulled_diagnostics(server_id, uri, Some(version), report, cx)
+                            .log_err();
+                    }
+                    for (uri, report) in related_documents.into_iter().flatten() {
+                        this.update_pulled_diagnostics(server_id, uri, None, report, cx)
+                            .log_err();
+                    }
+                })
+                .ok();
+            }
+        });
+        self.buffer_diagnostic_pulls.insert(buffer_id, task);
+    }
+
+    /// Requests the diagnostics of the buffer from its language servers that support pulling
+    /// diagnostics, skipping those whose diagnostics only depend on the buffer when it has not
+    /// changed since their last successful pull.
+    #[allow(clippy::type_complexity)]
+    fn request_buffer_diagnostics(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Vec<(
+        LanguageServerId,
+        lsp::Url,
+        i32,
+        impl Future<Output = Result<lsp::DocumentDiagnosticReportResult>>,
+    )> {
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()).and_then(|file| file.as_local()) else {
+            return Vec::new();
+        };
+        let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
+        let buffer_id = buffer.remote_id();
+        self.language_servers_for_buffer(buffer, cx)
+            .filter_map(|(_, server)| {
+                let options = diagnostic_options(server)?;
+                let server_id = server.server_id();
+                let version = self
+                    .buffer_snapshots
+                    .get(&buffer_id)?
+                    .get(&server_id)?
+                    .last()?
+                    .version;
+                let last_pulled_version = self
+                    .pulled_diagnostic_versions
+                    .get(&buffer_id)
+                    .and_then(|versions| versions.get(&server_id));
+                if !options.inter_file_dependencies && last_pulled_version == Some(&version) {
+                    return None;
+                }
+                let previous_result_id = self
+                    .diagnostic_result_ids
+                    .get(&server_id)
+                    .and_then(|result_ids| result_ids.get(&uri))
+                    .cloned();
+                let request = server.request::<lsp::request::DocumentDiagnosticRequest>(
+                    lsp::DocumentDiagnosticParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        identifier: options.identifier.clone(),
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                Some((server_id, uri.clone(), version, request))
+            })
+            .collect()
+    }
+
+    /// Pulls the diagnostics of the whole workspace from the language server, if it supports
+    /// workspace diagnostics.
+    fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running { server, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let Some(options) =
+            diagnostic_options(server).filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+
+        let previous_result_ids = self
+            .diagnostic_result_ids
+            .get(&server_id)
+            .into_iter()
+            .flatten()
+            .map(|(uri, result_id)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: result_id.clone(),
+            })
+            .collect();
+        let request = server.request::<lsp::request::WorkspaceDiagnosticRequest>(
+            lsp::WorkspaceDiagnosticParams {
+                identifier: options.identifier.clone(),
+                previous_result_ids,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        );
+        let task = cx.spawn(move |this, mut cx| async move {
+            let Some(report) = request.await.log_err() else {
+                return;
+            };
+            let items = match report {
+                lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                lsp::WorkspaceDiagnosticReportResult::Partial(report) => report.items,
+            };
+            this.update(&mut cx, |this, cx| {
+                for item in items {
+                    let (uri, version, report) = match item {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                        ),
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                        ),
+                    };
+                    let version = version.and_then(|version| i32::try_from(version).ok());
+                    this.update_pulled_diagnostics(server_id, uri, version, report, cx)
+                        .log_err();
+                }
+            })
+            .ok();
+        });
+        self.workspace_diagnostic_pulls.insert(server_id, task);
+    }
+
+    /// Pulls the diagnostics of every open buffer and of the workspace from the language server
+    /// again, as requested by the server.
+    fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffers = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| buffer.upgrade())
+            .filter(|buffer| {
+                self.buffer_snapshots
+                    .get(&buffer.read(cx).remote_id())
+                    .map_or(false, |snapshots| snapshots.contains_key(&server_id))
+            })
+            .collect::<Vec<_>>();
+        for versions in self.pulled_diagnostic_versions.values_mut() {
+            versions.remove(&server_id);
+        }
+        for buffer in buffers {
+            self.pull_buffer_diagnostics(&buffer, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    /// Records the result id of a pulled diagnostics report, and replaces the diagnostics of the
+    /// document with the report's when they have changed.
+    fn update_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let result_ids = self.diagnostic_result_ids.entry(server_id).or_default();
+        let report = match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => report,
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                result_ids.insert(uri, report.result_id);
+                return Ok(());
+            }
+        };
+        match report.result_id {
+            Some(result_id) => result_ids.insert(uri.clone(), result_id),
+            None => result_ids.remove(&uri),
+        };
+
+        let Some(LanguageServerState::Running { adapter, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return Ok(());
+        };
+        let adapter = adapter.clone();
+        let mut params = lsp::PublishDiagnosticsParams {
+            uri,
+            diagnostics: report.items,
+            version,
+        };
+        adapter.process_diagnostics(&mut params);
+        self.update_lsp_diagnostics(
+            server_id,
+            LspDiagnosticsKind::Pulled,
+            params,
+            &adapter.disk_based_diagnostic_sources,
+            cx,
+        )
+    }
+
+    /// Replaces the diagnostics of the document that the language server pushed or that were
+    /// pulled from it, and updates the document with them merged with the other kind.
+    ///
+    /// Servers that don't support pulling diagnostics only push them, so their diagnostics
+    /// replace those of the document directly.
+    fn update_lsp_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        kind: LspDiagnosticsKind,
+        mut params: lsp::PublishDiagnosticsParams,
+        disk_based_sources: &[String],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let supports_pull = match self.language_servers.get(&server_id) {
+            Some(LanguageServerState::Running { server, .. }) => {
+                diagnostic_options(server).is_some()
+            }
+            _ => false,
+        };
+        if supports_pull {
+            let document = self
+                .lsp_document_diagnostics
+                .entry(server_id)
+                .or_default()
+                .entry(params.uri.clone())
+                .or_default();
+            match kind {
+                LspDiagnosticsKind::Pushed => document.pushed = params.diagnostics,
+                LspDiagnosticsKind::Pulled => document.pulled = params.diagnostics,
+            }
+            params.diagnostics = document.merged();
+        }
+        self.update_diagnostics(server_id, params, disk_based_sources, cx)
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
@@ -5369,6 +5989,136 @@ impl Project {
         self.hover_impl(buffer, position, cx)
     }
 
+    pub fn signature_help<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SignatureHelp>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSignatureHelp { position },
+            cx,
+        )
+    }
+
+    /// Returns the call hierarchy items at the given position, usually the enclosing function.
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    /// Returns the type hierarchy items at the given position, usually the type under the cursor.
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    /// Characters which, when typed, should request signature help for the given buffer.
+    ///
+    /// Guests use the ones their host forwarded for the servers of the buffer's language.
+    pub fn signature_help_trigger_characters(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Vec<String> {
+        if self.is_local() {
+            self.primary_language_server_for_buffer(buffer.read(cx), cx)
+                .map(|(_, server)| signature_help_trigger_characters(&server.capabilities()))
+                .unwrap_or_default()
+        } else {
+            let Some(language) = buffer.read(cx).language() else {
+                return Vec::new();
+            };
+            let adapter_names = self
+                .languages
+                .lsp_adapters(language)
+                .into_iter()
+                .map(|adapter| adapter.name.0.to_string())
+                .collect::<HashSet<_>>();
+            self.language_server_statuses
+                .values()
+                .filter(|status| adapter_names.contains(&status.name))
+                .flat_map(|status| status.signature_help_trigger_characters.iter().cloned())
+                .unique()
+                .collect()
+        }
+    }
+
     #[inline(never)]
     fn completions_impl(
         &self,
@@ -5851,6 +6601,166 @@ impl Project {
         self.code_actions_impl(buffer_handle, range, cx)
     }
 
+    pub fn code_lens(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    /// Returns the ranges that can be folded in the buffer, as reported by its primary language server.
+    ///
+    /// Each range spans from the end of its first line to the end of its last line.
+    pub fn folding_ranges(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    /// Returns the ranges worth selecting around each of the given positions, as reported by
+    /// the buffer's primary language server.
+    ///
+    /// Each position gets a list of ranges ordered from the innermost to the outermost one,
+    /// or an empty list when the server doesn't support selection ranges.
+    pub fn selection_ranges<T: ToPointUtf16>(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        positions: impl IntoIterator<Item = T>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        let buffer = buffer_handle.read(cx);
+        let positions = positions
+            .into_iter()
+            .map(|position| position.to_point_utf16(buffer))
+            .collect();
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
+    /// Returns the ranges whose text is linked to the text around the given position, such as
+    /// the names of an opening and a closing tag, as reported by the buffer's primary language
+    /// server.
+    pub fn linked_editing_ranges<T: ToPointUtf16>(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
+        let position = position.to_point_utf16(buffer_handle.read(cx));
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetLinkedEditingRanges { position },
+            cx,
+        )
+    }
+
+    /// Returns the links to other documents found in the buffer by its primary language server.
+    pub fn document_links(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Returns the color literals found in the buffer by its primary language server.
+    pub fn document_colors(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentColor>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentColors,
+            cx,
+        )
+    }
+
+    /// Returns the ways the given color can be written in place of the text in `range`,
+    /// as offered by the buffer's primary language server.
+    pub fn color_presentations<T: ToPointUtf16>(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        range: Range<T>,
+        color: lsp::Color,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ColorPresentation>>> {
+        let buffer = buffer_handle.read(cx);
+        let range = range.start.to_point_utf16(buffer)..range.end.to_point_utf16(buffer);
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetColorPresentations { range, color },
+            cx,
+        )
+    }
+
+    /// Fills in the command of a code lens, if the language server postponed computing it.
+    pub fn resolve_code_lens(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CodeLens>> {
+        if lens.command().is_some() {
+            return Task::ready(Ok(lens));
+        }
+        let request = self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Other(lens.server_id),
+            ResolveCodeLens { lens: lens.clone() },
+            cx,
+        );
+        cx.spawn(move |_, _| async move { Ok(request.await?.unwrap_or(lens)) })
+    }
+
+    /// Executes the command of a code lens on the language server that produced it.
+    pub fn apply_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        push_to_history: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command else {
+            return Task::ready(Err(anyhow!("code lens is not resolved")));
+        };
+        let action = CodeAction {
+            server_id: lens.server_id,
+            range: lens.range,
+            lsp_action: lsp::CodeAction {
+                title: command.title.clone(),
+                command: Some(command),
+                ..Default::default()
+            },
+        };
+        self.apply_code_action(buffer_handle, action, push_to_history, cx)
+    }
+
     pub fn apply_code_action(
         &self,
         buffer_handle: Model<Buffer>,
@@ -8305,6 +9215,10 @@ impl Project {
                     pending_work: Default::default(),
                     has_pending_diagnostic_updates: false,
                     progress_tokens: Default::default(),
+                    signature_help_trigger_characters: server
+                        .capabilities
+                        .map(|capabilities| capabilities.signature_help_trigger_characters)
+                        .unwrap_or_default(),
                 },
             );
             cx.notify();
@@ -9631,6 +10545,32 @@ impl Project {
         }
     }
 
+    fn serialize_code_lens(lens: &CodeLens) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: lens.server_id.0 as u64,
+            start: Some(serialize_anchor(&lens.range.start)),
+            end: Some(serialize_anchor(&lens.range.end)),
+            lsp_lens: serde_json::to_vec(&lens.lsp_lens).unwrap(),
+        }
+    }
+
+    fn deserialize_code_lens(lens: proto::CodeLens) -> Result<CodeLens> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        let lsp_lens = serde_json::from_slice(&lens.lsp_lens)?;
+        Ok(CodeLens {
+            server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            lsp_lens,
+        })
+    }
+
     fn deserialize_code_action(action: proto::CodeAction) -> Result<CodeAction> {
         let start = action
             .start
@@ -10610,6 +11550,73 @@ fn include_text(server: &lsp::LanguageServer) -> bool {
         .unwrap_or(false)
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LspDiagnosticsKind {
+    Pushed,
+    Pulled,
+}
+
+/// The diagnostics of a document that a language server pushed, and those that were pulled from it.
+#[derive(Default)]
+struct LspDocumentDiagnostics {
+    pushed: Vec<lsp::Diagnostic>,
+    pulled: Vec<lsp::Diagnostic>,
+}
+
+impl LspDocumentDiagnostics {
+    /// The pushed diagnostics, followed by the pulled ones that weren't pushed too.
+    fn merged(&self) -> Vec<lsp::Diagnostic> {
+        let mut diagnostics = self.pushed.clone();
+        diagnostics.extend(
+            self.pulled
+                .iter()
+                .filter(|diagnostic| !self.pushed.contains(diagnostic))
+                .cloned(),
+        );
+        diagnostics
+    }
+}
+
+/// Returns the pull diagnostics options of the server, if it supports pulling diagnostics.
+fn diagnostic_options(server: &lsp::LanguageServer) -> Option<&lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider.as_ref()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(&options.diagnostic_options)
+        }
+    }
+}
+
+/// Returns the characters which, when typed, should request signature help from the server.
+fn signature_help_trigger_characters(capabilities: &lsp::ServerCapabilities) -> Vec<String> {
+    capabilities
+        .signature_help_provider
+        .as_ref()
+        .map(|options| {
+            options
+                .trigger_characters
+                .iter()
+                .flatten()
+                .chain(options.retrigger_characters.iter().flatten())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn language_server_to_proto(
+    server_id: LanguageServerId,
+    status: &LanguageServerStatus,
+) -> proto::LanguageServer {
+    proto::LanguageServer {
+        id: server_id.0 as u64,
+        name: status.name.clone(),
+        capabilities: Some(proto::LanguageServerCapabilities {
+            signature_help_trigger_characters: status.signature_help_trigger_characters.clone(),
+        }),
+    }
+}
+
 async fn load_shell_environment(dir: &Path) -> Result<HashMap<String, String>> {
     let marker = "ZED_SHELL_START";
     let shell = env::var("SHELL").context(
@@ -1,6 +1,17 @@
-use crate::{Event, *};
-use fs::FakeFs;
+use crate::{
+    lsp_command::{GetSignatureHelp, LspCommand as _},
+    semantic_tokens::{
+        SemanticTokensDelta, SemanticTokensDeltaRequest, SemanticTokensDeltaResult,
+        SemanticTokensEdit,
+    },
+    Event, *,
+};
+use fs::{
+    repository::{GitChange, GitFileStatus, GitStatusEntry, RepoPath},
+    FakeFs,
+};
 use futures::{future, StreamExt};
+use git::log::{CommitDetails, CommitFile, CommitFileStatus};
 use gpui::AppContext;
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
@@ -13,6 +24,7 @@ use pretty_assertions::assert_eq;
 use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use std::task::Poll;
 use unindent::Unindent as _;
 use util::{assert_set_eq, paths::PathMatcher, test::temp_tree};
@@ -2263,51 +2275,1432 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
         .await
         .unwrap();
 
-    // Assert no new language server started
-    cx.executor().run_until_parked();
-    assert!(fake_servers.try_next().is_err());
+    // Assert no new language server started
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+
+    assert_eq!(definitions.len(), 1);
+    let definition = definitions.pop().unwrap();
+    cx.update(|cx| {
+        let target_buffer = definition.target.buffer.read(cx);
+        assert_eq!(
+            target_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new("/dir/a.rs"),
+        );
+        assert_eq!(definition.target.range.to_offset(target_buffer), 9..10);
+        assert_eq!(
+            list_worktrees(&project, cx),
+            [("/dir/b.rs".as_ref(), true), ("/dir/a.rs".as_ref(), false)]
+        );
+
+        drop(definition);
+    });
+    cx.update(|cx| {
+        assert_eq!(list_worktrees(&project, cx), [("/dir/b.rs".as_ref(), true)]);
+    });
+
+    fn list_worktrees<'a>(
+        project: &'a Model<Project>,
+        cx: &'a AppContext,
+    ) -> Vec<(&'a Path, bool)> {
+        project
+            .read(cx)
+            .worktrees()
+            .map(|worktree| {
+                let worktree = worktree.read(cx);
+                (
+                    worktree.as_local().unwrap().abs_path().as_ref(),
+                    worktree.is_visible(),
+                )
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let b_item = lsp::CallHierarchyItem {
+        name: "b".into(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: Some("fn b()".into()),
+        uri: lsp::Url::from_file_path("/dir/b.rs").unwrap(),
+        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 9)),
+        selection_range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+        data: None,
+    };
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(vec![b_item]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "b");
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: lsp::CallHierarchyItem {
+                    name: "a".into(),
+                    kind: lsp::SymbolKind::FUNCTION,
+                    tags: None,
+                    detail: None,
+                    uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+                    range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 14)),
+                    selection_range: lsp::Range::new(
+                        lsp::Position::new(0, 3),
+                        lsp::Position::new(0, 4),
+                    ),
+                    data: None,
+                },
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 10),
+                )],
+            }]))
+        },
+    );
+
+    let roots = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].name(), "b");
+    assert_eq!(roots[0].detail(), Some("fn b()"));
+    cx.update(|cx| {
+        assert_eq!(roots[0].location.buffer, buffer);
+        assert_eq!(roots[0].location.range.to_offset(buffer.read(cx)), 3..4);
+    });
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&roots[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name(), "a");
+    cx.update(|cx| {
+        let caller_buffer = calls[0].item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new("/dir/a.rs"),
+        );
+        assert_eq!(calls[0].call_sites.len(), 1);
+        assert_eq!(calls[0].call_sites[0].buffer, calls[0].item.location.buffer);
+        assert_eq!(calls[0].call_sites[0].range.to_offset(caller_buffer), 9..10);
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "trait A {}",
+            "b.rs": "trait B: A {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers =
+        language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 6)
+            );
+            Ok(Some(vec![lsp::TypeHierarchyItem {
+                name: "B".into(),
+                kind: lsp::SymbolKind::INTERFACE,
+                tags: None,
+                detail: None,
+                uri: lsp::Url::from_file_path("/dir/b.rs").unwrap(),
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 13)),
+                selection_range: lsp::Range::new(
+                    lsp::Position::new(0, 6),
+                    lsp::Position::new(0, 7),
+                ),
+                data: None,
+            }]))
+        },
+    );
+    fake_server.handle_request::<lsp::request::TypeHierarchySupertypes, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "B");
+            Ok(Some(vec![lsp::TypeHierarchyItem {
+                name: "A".into(),
+                kind: lsp::SymbolKind::INTERFACE,
+                tags: None,
+                detail: None,
+                uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 10)),
+                selection_range: lsp::Range::new(
+                    lsp::Position::new(0, 6),
+                    lsp::Position::new(0, 7),
+                ),
+                data: None,
+            }]))
+        },
+    );
+
+    let roots = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 6, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].name(), "B");
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&roots[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(supertypes.len(), 1);
+    assert_eq!(supertypes[0].name(), "A");
+    cx.update(|cx| {
+        let supertype_buffer = supertypes[0].location.buffer.read(cx);
+        assert_eq!(
+            supertype_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new("/dir/a.rs"),
+        );
+        assert_eq!(
+            supertypes[0].location.range.to_offset(supertype_buffer),
+            6..7
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn one() {}\nfn two() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::FUNCTION,
+                                    lsp::SemanticTokenType::KEYWORD,
+                                ],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DECLARATION],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(
+        |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".into()),
+                    data: vec![
+                        lsp::SemanticToken {
+                            delta_line: 0,
+                            delta_start: 3,
+                            length: 3,
+                            token_type: 0,
+                            token_modifiers_bitset: 1,
+                        },
+                        lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 3,
+                            length: 3,
+                            token_type: 0,
+                            token_modifiers_bitset: 1,
+                        },
+                    ],
+                },
+            )))
+        },
+    );
+    fake_server.handle_request::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.previous_result_id, "1");
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".into()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 5,
+                        delete_count: 5,
+                        data: Some(vec![lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 0,
+                            length: 2,
+                            token_type: 1,
+                            token_modifiers_bitset: 0,
+                        }]),
+                    }],
+                },
+            )))
+        },
+    );
+
+    let tokens = project
+        .update(cx, |project, cx| project.semantic_tokens(&buffer, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| token.range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..6, 15..18]);
+        assert_eq!(tokens.token_type(&tokens.tokens[0]), Some("function"));
+        assert_eq!(
+            tokens
+                .token_modifiers(&tokens.tokens[0])
+                .collect::<Vec<_>>(),
+            ["declaration"]
+        );
+    });
+
+    let tokens = project
+        .update(cx, |project, cx| project.semantic_tokens(&buffer, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| token.range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..6, 12..14]);
+        assert_eq!(tokens.token_type(&tokens.tokens[1]), Some("keyword"));
+    });
+
+    // Edits can start and end in the middle of a token.
+    fake_server.handle_request::<SemanticTokensDeltaRequest, _, _>(|params, _| async move {
+        assert_eq!(params.previous_result_id, "2");
+        Ok(Some(SemanticTokensDeltaResult::Delta(
+            SemanticTokensDelta {
+                result_id: Some("3".into()),
+                edits: vec![SemanticTokensEdit {
+                    start: 7,
+                    delete_count: 2,
+                    data: vec![3, 0],
+                }],
+            },
+        )))
+    });
+
+    let tokens = project
+        .update(cx, |project, cx| project.semantic_tokens(&buffer, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = tokens
+            .tokens
+            .iter()
+            .map(|token| token.range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..6, 12..15]);
+        assert_eq!(tokens.token_type(&tokens.tokens[1]), Some("function"));
+    });
+}
+
+#[gpui::test]
+async fn test_code_lens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn one() {}\nfn two() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CodeLensRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 6)),
+                command: Some(lsp::Command {
+                    title: "Run".into(),
+                    command: "run".into(),
+                    arguments: None,
+                }),
+                data: None,
+            },
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(1, 3), lsp::Position::new(1, 6)),
+                command: None,
+                data: Some(json!(1)),
+            },
+        ]))
+    });
+    fake_server.handle_request::<lsp::request::CodeLensResolve, _, _>(|lens, _| async move {
+        assert_eq!(lens.data, Some(json!(1)));
+        Ok(lsp::CodeLens {
+            command: Some(lsp::Command {
+                title: "1 reference".into(),
+                command: "references".into(),
+                arguments: None,
+            }),
+            ..lens
+        })
+    });
+
+    let lenses = project
+        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = lenses
+            .iter()
+            .map(|lens| lens.range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [3..6, 15..18]);
+    });
+    assert_eq!(lenses[0].command().unwrap().title, "Run");
+    assert!(lenses[1].command().is_none());
+
+    let resolved = project
+        .update(cx, |project, cx| {
+            project.resolve_code_lens(&buffer, lenses[1].clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(resolved.command().unwrap().title, "1 reference");
+}
+
+#[gpui::test]
+async fn test_folding_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn one() {\n    two();\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::FoldingRangeRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![
+            lsp::FoldingRange {
+                start_line: 0,
+                end_line: 1,
+                ..Default::default()
+            },
+            lsp::FoldingRange {
+                start_line: 2,
+                end_line: 2,
+                ..Default::default()
+            },
+        ]))
+    });
+
+    let ranges = project
+        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = ranges
+            .iter()
+            .map(|range| range.to_point(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [Point::new(0, 10)..Point::new(1, 10)]);
+    });
+}
+
+#[gpui::test]
+async fn test_selection_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn main() { println!(\"{}\", one); }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SelectionRangeRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.positions, [lsp::Position::new(0, 28)]);
+            Ok(Some(vec![lsp::SelectionRange {
+                range: lsp::Range::new(lsp::Position::new(0, 27), lsp::Position::new(0, 30)),
+                parent: Some(Box::new(lsp::SelectionRange {
+                    range: lsp::Range::new(lsp::Position::new(0, 12), lsp::Position::new(0, 31)),
+                    parent: None,
+                })),
+            }]))
+        },
+    );
+
+    let selection_ranges = project
+        .update(cx, |project, cx| {
+            project.selection_ranges(&buffer, [Point::new(0, 28)], cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let ranges = selection_ranges[0]
+            .iter()
+            .map(|range| range.to_offset(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [27..30, 12..31]);
+    });
+}
+
+#[gpui::test]
+async fn test_linked_editing_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "struct Foo; impl Foo {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                linked_editing_range_provider: Some(
+                    lsp::LinkedEditingRangeServerCapabilities::Simple(true),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::LinkedEditingRange, _, _>(|params, _| async move {
+        assert_eq!(
+            params.text_document_position_params.position,
+            lsp::Position::new(0, 18)
+        );
+        Ok(Some(lsp::LinkedEditingRanges {
+            ranges: vec![
+                lsp::Range::new(lsp::Position::new(0, 17), lsp::Position::new(0, 20)),
+                lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 10)),
+            ],
+            word_pattern: None,
+        }))
+    });
+
+    let ranges = project
+        .update(cx, |project, cx| {
+            project.linked_editing_ranges(&buffer, Point::new(0, 18), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|range| range.to_offset(buffer))
+                .collect::<Vec<_>>(),
+            [7..10, 17..20]
+        );
+
+        // The ranges grow with text inserted at their edges.
+        buffer.edit([(10..10, "Bar")], None, cx);
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|range| range.to_offset(buffer))
+                .collect::<Vec<_>>(),
+            [7..13, 20..23]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_document_links_and_colors(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "// See https://zed.dev\nconst RED: &str = \"#ff0000\";",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::DocumentLinkRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![lsp::DocumentLink {
+            range: lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 22)),
+            target: Some(lsp::Url::parse("https://zed.dev").unwrap()),
+            tooltip: Some("Follow link".into()),
+            data: None,
+        }]))
+    });
+    let red = lsp::Color {
+        red: 1.,
+        green: 0.,
+        blue: 0.,
+        alpha: 1.,
+    };
+    fake_server.handle_request::<lsp::request::DocumentColor, _, _>(move |_, _| async move {
+        Ok(vec![lsp::ColorInformation {
+            range: lsp::Range::new(lsp::Position::new(1, 19), lsp::Position::new(1, 26)),
+            color: red,
+        }])
+    });
+    fake_server.handle_request::<lsp::request::ColorPresentationRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.color, red);
+            assert_eq!(
+                params.range,
+                lsp::Range::new(lsp::Position::new(1, 19), lsp::Position::new(1, 26))
+            );
+            Ok(vec![
+                lsp::ColorPresentation {
+                    label: "#ff0000".into(),
+                    text_edit: None,
+                    additional_text_edits: None,
+                },
+                lsp::ColorPresentation {
+                    label: "rgb(255, 0, 0)".into(),
+                    text_edit: Some(lsp::TextEdit {
+                        range: lsp::Range::new(
+                            lsp::Position::new(1, 19),
+                            lsp::Position::new(1, 26),
+                        ),
+                        new_text: "rgb(255, 0, 0)".into(),
+                    }),
+                    additional_text_edits: None,
+                },
+            ])
+        },
+    );
+
+    let links = project
+        .update(cx, |project, cx| project.document_links(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].range.to_offset(buffer), 7..22);
+        assert_eq!(links[0].target.as_deref(), Some("https://zed.dev/"));
+        assert_eq!(links[0].tooltip.as_deref(), Some("Follow link"));
+    });
+
+    let colors = project
+        .update(cx, |project, cx| project.document_colors(&buffer, cx))
+        .await
+        .unwrap();
+    let color_range = buffer.update(cx, |buffer, _| {
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[0].color, red);
+        colors[0].range.to_offset(buffer)
+    });
+    assert_eq!(color_range, 42..49);
+
+    let presentations = project
+        .update(cx, |project, cx| {
+            project.color_presentations(&buffer, color_range, red, cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let presentations = presentations
+            .iter()
+            .map(|presentation| {
+                (
+                    presentation.label.as_str(),
+                    presentation
+                        .edits
+                        .iter()
+                        .map(|(range, text)| (range.to_offset(buffer), text.as_str()))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            presentations,
+            [
+                ("#ff0000", vec![(42..49, "#ff0000")]),
+                ("rgb(255, 0, 0)", vec![(42..49, "rgb(255, 0, 0)")]),
+            ]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn a() { A }" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            previous_result_ids
+                .lock()
+                .push(params.previous_result_id.clone());
+            async move {
+                let report = match params.previous_result_id {
+                    Some(result_id) => lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport { result_id },
+                        },
+                    ),
+                    None => lsp::DocumentDiagnosticReport::Full(
+                        lsp::RelatedFullDocumentDiagnosticReport {
+                            related_documents: None,
+                            full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                                result_id: Some("1".into()),
+                                items: vec![lsp::Diagnostic {
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(0, 9),
+                                        lsp::Position::new(0, 10),
+                                    ),
+                                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                    message: "cannot find value `A`".into(),
+                                    ..Default::default()
+                                }],
+                            },
+                        },
+                    ),
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(*previous_result_ids.lock(), vec![None]);
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message))
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(0, 9)..Point::new(0, 10),
+            "cannot find value `A`".to_string()
+        )]
+    );
+
+    // Pulling again sends the result id of the previous report, and unchanged reports
+    // keep the existing diagnostics.
+    fake_server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *previous_result_ids.lock(),
+        vec![None, Some("1".to_string())]
+    );
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(0, 9)..Point::new(0, 10),
+            "cannot find value `A`".to_string()
+        )]
+    );
+
+    // Pushed diagnostics are merged with the pulled ones instead of replacing them.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path("/dir/a.rs").unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+            severity: Some(lsp::DiagnosticSeverity::WARNING),
+            message: "function `a` is never used".into(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        diagnostics(cx),
+        [
+            (
+                Point::new(0, 3)..Point::new(0, 4),
+                "function `a` is never used".to_string()
+            ),
+            (
+                Point::new(0, 9)..Point::new(0, 10),
+                "cannot find value `A`".to_string()
+            )
+        ]
+    );
+
+    // The server doesn't report inter-file dependencies, so saving the unchanged buffer
+    // doesn't pull its diagnostics again.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *previous_result_ids.lock(),
+        vec![None, Some("1".to_string())]
+    );
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics_after_failed_pull(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn a() { A }" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let pull_count = Arc::new(AtomicUsize::new(0));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let pull_count = pull_count.clone();
+        move |_, _| {
+            let pull_count = pull_count.fetch_add(1, SeqCst) + 1;
+            async move {
+                if pull_count == 1 {
+                    return Err(anyhow::anyhow!("the server is still indexing"));
+                }
+                Ok(lsp::DocumentDiagnosticReportResult::Report(
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: None,
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 9),
+                                    lsp::Position::new(0, 10),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "cannot find value `A`".into(),
+                                ..Default::default()
+                            }],
+                        },
+                    }),
+                ))
+            }
+        }
+    });
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| entry.diagnostic.message)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(pull_count.load(SeqCst), 1);
+    assert_eq!(diagnostics(cx), Vec::<String>::new());
+
+    // The failed pull doesn't count, so saving the unchanged buffer pulls its diagnostics again.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(pull_count.load(SeqCst), 2);
+    assert_eq!(diagnostics(cx), ["cannot find value `A`"]);
+
+    // Once a pull succeeded, saving the unchanged buffer again doesn't pull anymore.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(pull_count.load(SeqCst), 2);
+}
+
+#[gpui::test]
+async fn test_breakpoints(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({ "a.rs": "fn a() {\n    b();\n    c();\n}\n" }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store().clone());
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // Toggling twice on the same row removes the breakpoint.
+    breakpoint_store.update(cx, |store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        store.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(1, 4)), cx);
+        store.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(2, 4)), cx);
+        store.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(2, 0)), cx);
+        store.toggle_breakpoint(&buffer, snapshot.anchor_before(Point::new(3, 0)), cx);
+    });
+    breakpoint_store.read_with(cx, |store, cx| {
+        assert_eq!(
+            store.breakpoints_for_path(Path::new("/dir/a.rs"), cx),
+            vec![1, 3]
+        );
+    });
+
+    // Breakpoints move along with edits.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    breakpoint_store.read_with(cx, |store, cx| {
+        assert_eq!(
+            store.all_breakpoints(cx),
+            BTreeMap::from_iter([(PathBuf::from("/dir/a.rs"), vec![2, 4])])
+        );
+    });
+
+    // Breakpoints are kept by row once the buffer is closed, and restored when it's reopened.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.update(|_| drop(buffer));
+    cx.executor().run_until_parked();
+    breakpoint_store.read_with(cx, |store, cx| {
+        assert_eq!(
+            store.all_breakpoints(cx),
+            BTreeMap::from_iter([(PathBuf::from("/dir/a.rs"), vec![2, 4])])
+        );
+    });
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    breakpoint_store.read_with(cx, |store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        let rows = store
+            .breakpoints_for_buffer(snapshot.remote_id())
+            .map(|anchor| anchor.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![2, 4]);
+    });
+}
+
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {\n    b();\n    c();\n}\n",
+            "b.rs": "fn b() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store().clone());
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // Labeling a row with no bookmark sets one there.
+    bookmark_store.update(cx, |store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        store.toggle_bookmark(&buffer, snapshot.anchor_before(Point::new(1, 4)), cx);
+        store.set_label(
+            &buffer,
+            snapshot.anchor_before(Point::new(2, 4)),
+            Some("call c".to_string()),
+            cx,
+        );
+        store.toggle_bookmark(&buffer, snapshot.anchor_before(Point::new(3, 0)), cx);
+        store.toggle_bookmark(&buffer, snapshot.anchor_before(Point::new(3, 1)), cx);
+    });
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    bookmark_store.read_with(cx, |store, cx| {
+        assert_eq!(
+            store.bookmarks_for_path(Path::new("/dir/a.rs"), cx),
+            vec![
+                Bookmark {
+                    row: 2,
+                    label: None
+                },
+                Bookmark {
+                    row: 3,
+                    label: Some("call c".to_string())
+                },
+            ]
+        );
+    });
+
+    // Restored bookmarks only replace those of their own files.
+    bookmark_store.update(cx, |store, cx| {
+        store.insert_bookmarks(
+            BTreeMap::from_iter([(
+                PathBuf::from("/dir/b.rs"),
+                vec![Bookmark {
+                    row: 0,
+                    label: None,
+                }],
+            )]),
+            cx,
+        )
+    });
+    bookmark_store.read_with(cx, |store, cx| {
+        let all_bookmarks = store.all_bookmarks(cx);
+        assert_eq!(
+            all_bookmarks.keys().collect::<Vec<_>>(),
+            [Path::new("/dir/a.rs"), Path::new("/dir/b.rs")]
+        );
+        assert_eq!(all_bookmarks[Path::new("/dir/a.rs")].len(), 2);
+    });
+    let buffer_b = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+    bookmark_store.read_with(cx, |store, cx| {
+        let snapshot = buffer_b.read(cx).snapshot();
+        let bookmarks = store.bookmarks_for_buffer(snapshot.remote_id());
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].position.to_point(&snapshot).row, 0);
+    });
+}
+
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {\n    b();\n}\n",
+            "b.rs": "fn b() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let problem = |path: &str, row, severity| task::TaskProblem {
+        path: PathBuf::from(path),
+        row,
+        column: 4,
+        end_row: row,
+        end_column: 5,
+        severity,
+        code: None,
+        message: "the message".to_string(),
+        source: Some("rustc".to_string()),
+    };
+
+    project.update(cx, |project, cx| {
+        project.update_task_diagnostics(
+            "cargo check",
+            vec![
+                problem("/dir/a.rs", 1, task::ProblemSeverity::Error),
+                problem("/dir/b.rs", 0, task::ProblemSeverity::Warning),
+                // Problems outside of the project are ignored.
+                problem("/other/c.rs", 0, task::ProblemSeverity::Error),
+            ],
+            cx,
+        );
+        project.update_task_diagnostics(
+            "cargo clippy",
+            vec![problem("/dir/a.rs", 0, task::ProblemSeverity::Warning)],
+            cx,
+        );
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 2,
+            }
+        );
+    });
+
+    // The next run of a task replaces its diagnostics, leaving the ones of other tasks.
+    project.update(cx, |project, cx| {
+        project.update_task_diagnostics(
+            "cargo check",
+            vec![problem("/dir/a.rs", 1, task::ProblemSeverity::Warning)],
+            cx,
+        );
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 0,
+                warning_count: 2,
+            }
+        );
+        assert_eq!(
+            project
+                .diagnostic_summaries(false, cx)
+                .map(|(path, source_id, summary)| (
+                    path.path.to_string_lossy().to_string(),
+                    project.diagnostic_source_name(source_id),
+                    summary
+                ))
+                .collect::<Vec<_>>(),
+            [(
+                "a.rs".to_string(),
+                Some("Tasks"),
+                DiagnosticSummary {
+                    error_count: 0,
+                    warning_count: 2,
+                }
+            )]
+        );
+
+        // Tasks report their diagnostics under a single, named source that isn't a language server.
+        assert_eq!(project.language_server_statuses().count(), 0);
+    });
+}
+
+#[gpui::test]
+async fn test_test_results_store(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
 
-    assert_eq!(definitions.len(), 1);
-    let definition = definitions.pop().unwrap();
-    cx.update(|cx| {
-        let target_buffer = definition.target.buffer.read(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.py": "", "b.py": "" }))
+        .await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let store = project.read_with(cx, |project, _| project.test_results_store().clone());
+    let result = |name: &str, suite: &[&str], path: Option<&str>, status| task::TestResult {
+        name: name.to_string(),
+        suite: suite.iter().map(|suite| suite.to_string()).collect(),
+        status,
+        message: None,
+        path: path.map(PathBuf::from),
+        row: None,
+    };
+
+    store.update(cx, |store, cx| {
+        store.insert_results(
+            vec![
+                result(
+                    "test_a",
+                    &["TestA"],
+                    Some("/dir/a.py"),
+                    task::TestStatus::Failed,
+                ),
+                result("test_b", &[], Some("/dir/b.py"), task::TestStatus::Passed),
+                result("adds", &["tests"], None, task::TestStatus::Skipped),
+                result(
+                    "subtracts",
+                    &["outer", "tests"],
+                    None,
+                    task::TestStatus::Passed,
+                ),
+            ],
+            cx,
+        );
+    });
+    store.read_with(cx, |store, _| {
+        let status = |path: &str, name: &str, suite| {
+            store
+                .result_for(Path::new(path), name, suite)
+                .map(|result| result.status)
+        };
         assert_eq!(
-            target_buffer
-                .file()
-                .unwrap()
-                .as_local()
-                .unwrap()
-                .abs_path(cx),
-            Path::new("/dir/a.rs"),
+            status("/dir/a.py", "test_a", Some("TestA")),
+            Some(task::TestStatus::Failed)
         );
-        assert_eq!(definition.target.range.to_offset(target_buffer), 9..10);
+        assert_eq!(status("/dir/a.py", "test_a", Some("TestB")), None);
+        assert_eq!(status("/dir/b.py", "test_a", None), None);
+        // Results reported without a path match tests in any file.
         assert_eq!(
-            list_worktrees(&project, cx),
-            [("/dir/b.rs".as_ref(), true), ("/dir/a.rs".as_ref(), false)]
+            status("/dir/a.rs", "adds", Some("tests")),
+            Some(task::TestStatus::Skipped)
+        );
+        // Suites can be named by their path.
+        assert_eq!(
+            status("/dir/a.rs", "subtracts", Some("outer::tests")),
+            Some(task::TestStatus::Passed)
         );
+        assert_eq!(status("/dir/a.rs", "subtracts", Some("inner::tests")), None);
+        assert_eq!(store.failed_results().count(), 1);
+    });
 
-        drop(definition);
+    // The next run of a test replaces its result, leaving the results of the other tests.
+    store.update(cx, |store, cx| {
+        store.insert_results(
+            vec![result(
+                "test_a",
+                &["TestA"],
+                Some("/dir/a.py"),
+                task::TestStatus::Passed,
+            )],
+            cx,
+        );
     });
-    cx.update(|cx| {
-        assert_eq!(list_worktrees(&project, cx), [("/dir/b.rs".as_ref(), true)]);
+    store.read_with(cx, |store, _| {
+        assert_eq!(store.results().len(), 4);
+        assert_eq!(store.failed_results().count(), 0);
     });
-
-    fn list_worktrees<'a>(
-        project: &'a Model<Project>,
-        cx: &'a AppContext,
-    ) -> Vec<(&'a Path, bool)> {
-        project
-            .read(cx)
-            .worktrees()
-            .map(|worktree| {
-                let worktree = worktree.read(cx);
-                (
-                    worktree.as_local().unwrap().abs_path().as_ref(),
-                    worktree.is_visible(),
-                )
-            })
-            .collect::<Vec<_>>()
-    }
 }
 
 #[gpui::test]
@@ -4780,6 +6173,384 @@ async fn test_multiple_language_server_actions(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_staging_and_committing(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let head_text = "one\ntwo\nthree\nfour\n".to_string();
+    let worktree_text = "one\nTWO\nthree\nFOUR\n".to_string();
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": worktree_text.clone(),
+            "b.txt": "new file\n",
+        }),
+    )
+    .await;
+    fs.set_head_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("a.txt"), head_text.clone())],
+    );
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("a.txt"), head_text.clone())],
+    );
+    fs.set_worktree_contents_for_repo(
+        Path::new("/dir/.git"),
+        &[
+            (Path::new("a.txt"), worktree_text.clone()),
+            (Path::new("b.txt"), "new file\n".to_string()),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(head_text.as_str()));
+    });
+
+    let statuses = project
+        .update(cx, |project, cx| project.git_statuses(cx))
+        .await;
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(
+        statuses[0].entries,
+        [
+            GitStatusEntry {
+                repo_path: RepoPath(PathBuf::from("a.txt")),
+                staged: None,
+                unstaged: Some(GitChange::Modified),
+            },
+            GitStatusEntry {
+                repo_path: RepoPath(PathBuf::from("b.txt")),
+                staged: None,
+                unstaged: Some(GitChange::Untracked),
+            },
+        ]
+    );
+
+    // Staging the hunk of the second row of the file leaves the one of the fourth row unstaged,
+    // and the unsaved changes of the buffer out of the index.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    project
+        .update(cx, |project, cx| {
+            project.stage_hunks(&buffer, vec![2..3], cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some("one\nTWO\nthree\nfour\n"));
+    });
+
+    // Unstaging it resets the index to the HEAD text.
+    project
+        .update(cx, |project, cx| {
+            project.unstage_hunks(&buffer, vec![2..3], cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(head_text.as_str()));
+    });
+
+    let a_path = ProjectPath {
+        worktree_id,
+        path: Path::new("a.txt").into(),
+    };
+    let b_path = ProjectPath {
+        worktree_id,
+        path: Path::new("b.txt").into(),
+    };
+    project
+        .update(cx, |project, cx| {
+            project.stage_paths(vec![a_path.clone(), b_path.clone()], cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.diff_base(), Some(worktree_text.as_str()));
+    });
+    let statuses = project
+        .update(cx, |project, cx| project.git_statuses(cx))
+        .await;
+    assert_eq!(
+        statuses[0].entries,
+        [
+            GitStatusEntry {
+                repo_path: RepoPath(PathBuf::from("a.txt")),
+                staged: Some(GitChange::Modified),
+                unstaged: None,
+            },
+            GitStatusEntry {
+                repo_path: RepoPath(PathBuf::from("b.txt")),
+                staged: Some(GitChange::Added),
+                unstaged: None,
+            },
+        ]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.unstage_paths(vec![b_path.clone()], cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.commit(&a_path, "Change a".to_string(), false, cx)
+        })
+        .await
+        .unwrap();
+    let statuses = project
+        .update(cx, |project, cx| project.git_statuses(cx))
+        .await;
+    assert_eq!(
+        statuses[0].entries,
+        [GitStatusEntry {
+            repo_path: RepoPath(PathBuf::from("b.txt")),
+            staged: None,
+            unstaged: Some(GitChange::Untracked),
+        }]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.commit(&a_path, "Change a and b".to_string(), true, cx)
+        })
+        .await
+        .unwrap();
+    let last_commit_message = project
+        .read_with(cx, |project, cx| project.last_commit_message(&a_path, cx))
+        .await
+        .unwrap();
+    assert_eq!(last_commit_message.as_deref(), Some("Change a and b"));
+}
+
+#[gpui::test]
+async fn test_conflicted_paths(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "<<<<<<< HEAD\none\n=======\ntwo\n>>>>>>> other\n",
+            "b.txt": "three\n",
+        }),
+    )
+    .await;
+    fs.set_status_for_repo_via_git_operation(
+        Path::new("/dir/.git"),
+        &[
+            (Path::new("a.txt"), GitFileStatus::Conflict),
+            (Path::new("b.txt"), GitFileStatus::Modified),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    cx.executor().run_until_parked();
+    let paths = project
+        .update(cx, |project, cx| project.conflicted_paths(cx))
+        .await;
+    assert_eq!(
+        paths,
+        [ProjectPath {
+            worktree_id,
+            path: Path::new("a.txt").into(),
+        }]
+    );
+}
+
+#[gpui::test]
+async fn test_git_log(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "src": {
+                "new.rs": "",
+                "lib.rs": "",
+            },
+        }),
+    )
+    .await;
+    let commit = |sha: char, files: Vec<CommitFile>| CommitDetails {
+        sha: sha.to_string().repeat(40).parse().unwrap(),
+        parent_shas: Vec::new(),
+        author: "Jane Doe".into(),
+        author_mail: "jane@example.com".into(),
+        author_time: 0,
+        author_tz: "+0000".into(),
+        message: format!("Commit {sha}"),
+        files,
+    };
+    let file = |status, path: &str, old_path: Option<&str>| CommitFile {
+        status,
+        path: path.into(),
+        old_path: old_path.map(Into::into),
+    };
+    fs.set_history_for_repo(
+        Path::new("/dir/.git"),
+        vec![
+            commit(
+                'c',
+                vec![file(CommitFileStatus::Modified, "src/lib.rs", None)],
+            ),
+            commit(
+                'b',
+                vec![
+                    file(CommitFileStatus::Renamed, "src/new.rs", Some("src/old.rs")),
+                    file(CommitFileStatus::Modified, "src/lib.rs", None),
+                ],
+            ),
+            commit('a', vec![file(CommitFileStatus::Added, "src/old.rs", None)]),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    cx.executor().run_until_parked();
+
+    let work_directory = ProjectPath {
+        worktree_id,
+        path: Path::new("").into(),
+    };
+    let log = project
+        .update(cx, |project, cx| {
+            project.git_log(&work_directory, "HEAD".into(), 2, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(log.work_directory, work_directory);
+    assert_eq!(
+        log.commits
+            .iter()
+            .map(|commit| commit.message.as_str())
+            .collect::<Vec<_>>(),
+        ["Commit c", "Commit b"]
+    );
+
+    // The history of a file follows its renames, and only lists its changes.
+    let file_path = ProjectPath {
+        worktree_id,
+        path: Path::new("src/new.rs").into(),
+    };
+    let log = project
+        .update(cx, |project, cx| {
+            project.git_log(&file_path, "HEAD".into(), 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(log.work_directory, work_directory);
+    assert_eq!(
+        log.commits
+            .iter()
+            .map(|commit| (commit.message.as_str(), commit.files.clone()))
+            .collect::<Vec<_>>(),
+        [
+            (
+                "Commit b",
+                vec![file(
+                    CommitFileStatus::Renamed,
+                    "src/new.rs",
+                    Some("src/old.rs")
+                )]
+            ),
+            (
+                "Commit a",
+                vec![file(CommitFileStatus::Added, "src/old.rs", None)]
+            ),
+        ]
+    );
+    assert_eq!(
+        log.project_path(&log.commits[1].files[0].path),
+        ProjectPath {
+            worktree_id,
+            path: Path::new("src/old.rs").into(),
+        }
+    );
+}
+
+#[gpui::test]
+async fn test_signature_help_from_proto(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let response =
+        |label: &str, label_start: u32, label_end: u32| proto::GetSignatureHelpResponse {
+            signatures: vec![proto::SignatureInformation {
+                label: label.to_string(),
+                documentation: None,
+                parameters: vec![proto::ParameterInformation {
+                    label_start,
+                    label_end,
+                    documentation: None,
+                }],
+                active_parameter: None,
+            }],
+            active_signature: 0,
+            active_parameter: Some(0),
+        };
+    let command = || GetSignatureHelp {
+        position: PointUtf16::new(0, 0),
+    };
+
+    let help = command()
+        .response_from_proto(
+            response("fn f(é: u8)", 5, 11),
+            project.clone(),
+            buffer.clone(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(help.active_parameter_range(), Some(5..11));
+
+    // Ranges that split a character, end past the label or are reversed are rejected.
+    for (label_start, label_end) in [(5, 6), (5, 40), (9, 7)] {
+        command()
+            .response_from_proto(
+                response("fn f(é: u8)", label_start, label_end),
+                project.clone(),
+                buffer.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap_err();
+    }
+}
+
 async fn search(
     project: &Model<Project>,
     query: SearchQuery,
@@ -0,0 +1,388 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, Client, TypedEnvelope};
+use gpui::{AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+    Bias, Buffer, File as _, PointUtf16, Unclipped,
+};
+use lsp::{LanguageServerId, ServerCapabilities};
+use serde::{Deserialize, Serialize};
+use text::{Anchor, BufferId};
+
+use crate::{Event, File, Project};
+
+/// Semantic tokens reported by a language server for a single buffer.
+#[derive(Clone, Debug, Default)]
+pub struct SemanticTokens {
+    /// Tokens sorted by their position in the buffer.
+    pub tokens: Vec<SemanticToken>,
+    /// The server's legend, indexed by [`SemanticToken::token_type`].
+    pub token_types: Arc<[String]>,
+    /// The server's legend, indexed by the bits of [`SemanticToken::token_modifiers`].
+    pub token_modifiers: Arc<[String]>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+impl SemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.token_types
+            .get(token.token_type as usize)
+            .map(String::as_str)
+    }
+
+    pub fn token_modifiers<'a>(
+        &'a self,
+        token: &'a SemanticToken,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.token_modifiers
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| *ix < 32 && token.token_modifiers & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+
+    /// Decodes the integers of a semantic tokens response, five per token.
+    fn from_lsp(
+        data: &[u32],
+        legend: &lsp::SemanticTokensLegend,
+        snapshot: &text::BufferSnapshot,
+    ) -> Self {
+        let mut tokens = Vec::with_capacity(data.len() / 5);
+        let mut line = 0;
+        let mut column = 0;
+        for token in data.chunks_exact(5) {
+            let (delta_line, delta_start, length, token_type, token_modifiers) =
+                (token[0], token[1], token[2], token[3], token[4]);
+            if delta_line == 0 {
+                column += delta_start;
+            } else {
+                line += delta_line;
+                column = delta_start;
+            }
+
+            let start =
+                snapshot.clip_point_utf16(Unclipped(PointUtf16::new(line, column)), Bias::Left);
+            let end = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(line, column + length)),
+                Bias::Left,
+            );
+            if start == end {
+                continue;
+            }
+            tokens.push(SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                token_type,
+                token_modifiers,
+            });
+        }
+
+        Self {
+            tokens,
+            token_types: legend
+                .token_types
+                .iter()
+                .map(|token_type| token_type.as_str().to_string())
+                .collect(),
+            token_modifiers: legend
+                .token_modifiers
+                .iter()
+                .map(|modifier| modifier.as_str().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// The last semantic tokens response received for a buffer, kept so that
+/// subsequent requests can ask the server for a delta.
+#[derive(Clone)]
+pub(crate) struct BufferSemanticTokens {
+    server_id: LanguageServerId,
+    result_id: Option<String>,
+    /// The encoded tokens, five integers per token.
+    data: Vec<u32>,
+}
+
+/// `textDocument/semanticTokens/full/delta`, reading the edits as plain integers.
+///
+/// Unlike [`lsp::SemanticTokensEdit`], whose data is decoded into tokens, this allows
+/// edits that start or end in the middle of a token, which the spec doesn't rule out.
+pub(crate) enum SemanticTokensDeltaRequest {}
+
+impl lsp::request::Request for SemanticTokensDeltaRequest {
+    type Params = lsp::SemanticTokensDeltaParams;
+    type Result = Option<SemanticTokensDeltaResult>;
+    const METHOD: &'static str = "textDocument/semanticTokens/full/delta";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum SemanticTokensDeltaResult {
+    Tokens(SemanticTokensData),
+    Delta(SemanticTokensDelta),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SemanticTokensData {
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+/// A delta, or a partial result without a result id.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SemanticTokensDelta {
+    pub result_id: Option<String>,
+    pub edits: Vec<SemanticTokensEdit>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SemanticTokensEdit {
+    pub start: u32,
+    pub delete_count: u32,
+    #[serde(default)]
+    pub data: Vec<u32>,
+}
+
+fn semantic_tokens_options(
+    capabilities: &ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
+fn apply_semantic_token_edits(mut data: Vec<u32>, mut edits: Vec<SemanticTokensEdit>) -> Vec<u32> {
+    // Edit offsets refer to the previous data, so apply the edits back to front.
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+    for edit in edits {
+        let start = (edit.start as usize).min(data.len());
+        let end = (start + edit.delete_count as usize).min(data.len());
+        data.splice(start..end, edit.data);
+    }
+    data
+}
+
+fn encode_semantic_tokens(tokens: Vec<lsp::SemanticToken>) -> Vec<u32> {
+    tokens
+        .into_iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+impl Project {
+    /// Requests semantic tokens for the whole buffer from its primary language server.
+    ///
+    /// Returns `None` when no server providing semantic tokens is running for the buffer.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SemanticTokens>>> {
+        if self.is_local() {
+            self.semantic_tokens_locally(buffer, cx)
+        } else if let Some(project_id) = self.remote_id() {
+            let buffer_handle = buffer.clone();
+            let buffer = buffer.read(cx);
+            let request = self.client.request(proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer.remote_id().into(),
+                version: serialize_version(&buffer.version()),
+            });
+            cx.spawn(move |_, mut cx| async move {
+                let response = request.await.context("semantic tokens proto request")?;
+                let Some(tokens) = response.tokens else {
+                    return Ok(None);
+                };
+                buffer_handle
+                    .update(&mut cx, |buffer, _| {
+                        buffer.wait_for_version(deserialize_version(&tokens.version))
+                    })?
+                    .await?;
+                Ok(Some(SemanticTokens {
+                    tokens: tokens
+                        .tokens
+                        .into_iter()
+                        .filter_map(|token| {
+                            let start = token.start.and_then(deserialize_anchor)?;
+                            let end = token.end.and_then(deserialize_anchor)?;
+                            Some(SemanticToken {
+                                range: start..end,
+                                token_type: token.token_type,
+                                token_modifiers: token.token_modifiers,
+                            })
+                        })
+                        .collect(),
+                    token_types: tokens.token_types.into(),
+                    token_modifiers: tokens.token_modifiers.into(),
+                }))
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
+    fn semantic_tokens_locally(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SemanticTokens>>> {
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()).filter(|file| file.is_local()) else {
+            return Task::ready(Ok(None));
+        };
+        let Some((_, server)) = self.primary_language_server_for_buffer(buffer, cx) else {
+            return Task::ready(Ok(None));
+        };
+        let Some(options) = semantic_tokens_options(server.capabilities()) else {
+            return Task::ready(Ok(None));
+        };
+
+        let server = server.clone();
+        let server_id = server.server_id();
+        let legend = options.legend.clone();
+        let supports_delta = matches!(
+            options.full,
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+        );
+        let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
+        let buffer_id = buffer.remote_id();
+        let snapshot = buffer.text_snapshot();
+        let previous = self
+            .buffer_semantic_tokens
+            .get(&buffer_id)
+            .filter(|previous| supports_delta && previous.server_id == server_id)
+            .and_then(|previous| Some((previous.result_id.clone()?, previous.data.clone())));
+
+        cx.spawn(move |this, mut cx| async move {
+            let text_document = lsp::TextDocumentIdentifier::new(uri);
+            let (result_id, data) = if let Some((previous_result_id, previous_data)) = previous {
+                let response = server
+                    .request::<SemanticTokensDeltaRequest>(lsp::SemanticTokensDeltaParams {
+                        text_document,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    })
+                    .await
+                    .context("semantic tokens delta LSP request")?;
+                match response {
+                    Some(SemanticTokensDeltaResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(SemanticTokensDeltaResult::Delta(delta)) => (
+                        delta.result_id,
+                        apply_semantic_token_edits(previous_data, delta.edits),
+                    ),
+                    None => (None, Vec::new()),
+                }
+            } else {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+                        text_document,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    })
+                    .await
+                    .context("semantic tokens LSP request")?;
+                match response {
+                    Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                        (tokens.result_id, encode_semantic_tokens(tokens.data))
+                    }
+                    Some(lsp::SemanticTokensResult::Partial(partial)) => {
+                        (None, encode_semantic_tokens(partial.data))
+                    }
+                    None => (None, Vec::new()),
+                }
+            };
+
+            let tokens = SemanticTokens::from_lsp(&data, &legend, &snapshot);
+            this.update(&mut cx, |this, _| {
+                this.buffer_semantic_tokens.insert(
+                    buffer_id,
+                    BufferSemanticTokens {
+                        server_id,
+                        result_id,
+                        data,
+                    },
+                );
+            })?;
+            Ok(Some(tokens))
+        })
+    }
+
+    pub(crate) async fn handle_get_semantic_tokens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, _| {
+            this.opened_buffers
+                .get(&buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", envelope.payload.buffer_id))
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        let tokens = this
+            .update(&mut cx, |this, cx| this.semantic_tokens(&buffer, cx))?
+            .await?;
+        let version = buffer.update(&mut cx, |buffer, _| serialize_version(&buffer.version()))?;
+        Ok(proto::GetSemanticTokensResponse {
+            tokens: tokens.map(|tokens| proto::SemanticTokens {
+                token_types: tokens.token_types.to_vec(),
+                token_modifiers: tokens.token_modifiers.to_vec(),
+                tokens: tokens
+                    .tokens
+                    .iter()
+                    .map(|token| proto::SemanticToken {
+                        start: Some(serialize_anchor(&token.range.start)),
+                        end: Some(serialize_anchor(&token.range.end)),
+                        token_type: token.token_type,
+                        token_modifiers: token.token_modifiers,
+                    })
+                    .collect(),
+                version,
+            }),
+        })
+    }
+
+    pub(crate) async fn handle_refresh_semantic_tokens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+}
@@ -0,0 +1,110 @@
+//! Diagnostics found by problem matchers in the output of tasks.
+//!
+//! The problems of every task are reported under a diagnostic source named "Tasks", registered with
+//! the project, so that they are shown and replaced independently of the diagnostics of the language servers.
+
+use std::path::PathBuf;
+
+use collections::{BTreeMap, HashMap, HashSet};
+use gpui::ModelContext;
+use language::{Diagnostic, DiagnosticEntry, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId};
+use task::{ProblemSeverity, TaskProblem};
+use util::{post_inc, ResultExt};
+
+use crate::{Event, Project};
+
+/// The name of the diagnostic source of tasks.
+const TASK_DIAGNOSTICS_SOURCE_NAME: &str = "Tasks";
+
+/// The diagnostics reported by the latest run of each task.
+#[derive(Default)]
+pub(crate) struct TaskDiagnostics {
+    /// The id of the diagnostics source, once registered.
+    source_id: Option<LanguageServerId>,
+    /// The diagnostics of each task, by task label and file.
+    diagnostics_by_task:
+        HashMap<String, HashMap<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>>,
+}
+
+impl Project {
+    /// Replaces the diagnostics reported by the previous run of the task with the given label with the
+    /// problems found in the output of its latest run. Problems in files outside of the project are ignored.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        problems: Vec<TaskProblem>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let source_id = self.task_diagnostics_source_id();
+
+        let mut diagnostics_by_path =
+            HashMap::<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>::default();
+        for problem in problems {
+            if self.find_local_worktree(&problem.path, cx).is_none() {
+                continue;
+            }
+            let start = PointUtf16::new(problem.row, problem.column);
+            let end = PointUtf16::new(problem.end_row, problem.end_column).max(start);
+            diagnostics_by_path
+                .entry(problem.path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end),
+                    diagnostic: Diagnostic {
+                        source: problem.source,
+                        code: problem.code,
+                        severity: match problem.severity {
+                            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                        },
+                        message: problem.message,
+                        group_id: post_inc(&mut self.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        is_unnecessary: false,
+                    },
+                });
+        }
+
+        let mut changed_paths = diagnostics_by_path.keys().cloned().collect::<HashSet<_>>();
+        let diagnostics_by_task = &mut self.task_diagnostics.diagnostics_by_task;
+        if let Some(previous) =
+            diagnostics_by_task.insert(task_label.to_string(), diagnostics_by_path)
+        {
+            changed_paths.extend(previous.into_keys());
+        }
+
+        // Every task's diagnostics of a file are reported together, as they share the source.
+        let mut diagnostics_by_path = changed_paths
+            .into_iter()
+            .map(|abs_path| (abs_path, Vec::new()))
+            .collect::<BTreeMap<_, _>>();
+        for task_diagnostics in diagnostics_by_task.values() {
+            for (abs_path, diagnostics) in &mut diagnostics_by_path {
+                if let Some(task_diagnostics) = task_diagnostics.get(abs_path) {
+                    diagnostics.extend(task_diagnostics.iter().cloned());
+                }
+            }
+        }
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.update_diagnostic_entries(source_id, abs_path, None, diagnostics, cx)
+                .log_err();
+        }
+        cx.emit(Event::DiskBasedDiagnosticsFinished {
+            language_server_id: source_id,
+        });
+    }
+
+    /// Returns the id of the diagnostic source of tasks, registering it on first use.
+    fn task_diagnostics_source_id(&mut self) -> LanguageServerId {
+        if let Some(source_id) = self.task_diagnostics.source_id {
+            return source_id;
+        }
+
+        let source_id = self.register_diagnostic_source(TASK_DIAGNOSTICS_SOURCE_NAME);
+        self.task_diagnostics.source_id = Some(source_id);
+        source_id
+    }
+}
@@ -5,20 +5,62 @@ use std::{
     cmp::{self, Reverse},
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, SystemTime},
 };
 
 use collections::{HashMap, VecDeque};
-use gpui::{AppContext, Context, Model, ModelContext, Subscription};
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext, Subscription};
 use itertools::{Either, Itertools};
 use language::Language;
 use task::{ResolvedTask, TaskContext, TaskId, TaskSource, TaskTemplate, VariableName};
 use util::{post_inc, NumericPrefixWithSuffix};
 use worktree::WorktreeId;
 
+/// The number of task runs kept in the history of the inventory.
+const MAX_TASK_RUNS: usize = 100;
+
 /// Inventory tracks available tasks for a given project.
 pub struct Inventory {
     sources: Vec<SourceInInventory>,
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
+    task_runs: VecDeque<TaskRun>,
+    /// The dependencies of the scheduled tasks, by id, to record their runs once they get started.
+    scheduled_dependencies: HashMap<TaskId, (TaskSourceKind, ResolvedTask)>,
+}
+
+pub enum InventoryEvent {
+    /// A task run was recorded, finished, or runs were restored from a previous session.
+    TaskRunsChanged,
+}
+
+/// A run of a scheduled task, recorded when it is spawned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRun {
+    pub task_source_kind: TaskSourceKind,
+    /// The task, along with the context it got resolved with.
+    pub task: ResolvedTask,
+    pub cwd: Option<PathBuf>,
+    pub started_at: SystemTime,
+    pub status: TaskRunStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskRunStatus {
+    Running,
+    Finished {
+        success: bool,
+        /// The exit code of the task's process, if it exited normally rather than being killed.
+        exit_code: Option<i32>,
+        duration: Duration,
+    },
+    /// The run never reported how it ended, e.g. because Zed was closed while it was running.
+    Unknown,
+}
+
+impl TaskRunStatus {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Finished { success: false, .. })
+    }
 }
 
 struct SourceInInventory {
@@ -86,6 +128,8 @@ impl Inventory {
         cx.new_model(|_| Self {
             sources: Vec::new(),
             last_scheduled_tasks: VecDeque::new(),
+            task_runs: VecDeque::new(),
+            scheduled_dependencies: HashMap::default(),
         })
     }
 
@@ -292,8 +336,118 @@ impl Inventory {
     pub fn delete_previously_used(&mut self, id: &TaskId) {
         self.last_scheduled_tasks.retain(|(_, task)| &task.id != id);
     }
+
+    /// Recorded runs of the tasks, from the oldest to the most recent one.
+    pub fn task_runs(&self) -> impl DoubleEndedIterator<Item = &TaskRun> {
+        self.task_runs.iter()
+    }
+
+    /// The most recent run of a task that did not succeed.
+    pub fn last_failed_run(&self) -> Option<&TaskRun> {
+        self.task_runs
+            .iter()
+            .rev()
+            .find(|run| run.status.is_failure())
+    }
+
+    /// Records a run of the task, starting now.
+    pub fn task_started(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let cwd = task.resolved.as_ref().and_then(|spawn| spawn.cwd.clone());
+        self.task_runs.push_back(TaskRun {
+            task_source_kind,
+            task,
+            cwd,
+            started_at: SystemTime::now(),
+            status: TaskRunStatus::Running,
+        });
+        if self.task_runs.len() > MAX_TASK_RUNS {
+            self.task_runs.pop_front();
+        }
+        cx.emit(InventoryEvent::TaskRunsChanged);
+    }
+
+    /// Registers the tasks a scheduled task depends on, so that their runs are recorded with
+    /// [`Self::dependency_started`] once they get started.
+    pub fn dependencies_scheduled(
+        &mut self,
+        dependencies: impl IntoIterator<Item = (TaskSourceKind, ResolvedTask)>,
+    ) {
+        self.scheduled_dependencies.extend(
+            dependencies
+                .into_iter()
+                .map(|(task_source_kind, task)| (task.id.clone(), (task_source_kind, task))),
+        );
+    }
+
+    /// Records a run of the scheduled dependency with the given id, starting now.
+    pub fn dependency_started(&mut self, id: &TaskId, cx: &mut ModelContext<Self>) {
+        if let Some((task_source_kind, task)) = self.scheduled_dependencies.get(id).cloned() {
+            self.task_started(task_source_kind, task, cx);
+        }
+    }
+
+    /// Completes the oldest running run of the task with the given id, if any.
+    pub fn task_finished(
+        &mut self,
+        id: &TaskId,
+        success: bool,
+        exit_code: Option<i32>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(run) = self
+            .task_runs
+            .iter_mut()
+            .find(|run| &run.task.id == id && run.status == TaskRunStatus::Running)
+        else {
+            return;
+        };
+        run.status = TaskRunStatus::Finished {
+            success,
+            exit_code,
+            duration: run.started_at.elapsed().unwrap_or_default(),
+        };
+        cx.emit(InventoryEvent::TaskRunsChanged);
+    }
+
+    /// Adds the runs of a previous session before the ones recorded in this one.
+    /// The runs that were still running are restored with an unknown status.
+    pub fn restore_task_runs(&mut self, runs: Vec<TaskRun>, cx: &mut ModelContext<Self>) {
+        for mut run in runs.into_iter().rev() {
+            if self.task_runs.len() >= MAX_TASK_RUNS {
+                break;
+            }
+            if run.status == TaskRunStatus::Running {
+                run.status = TaskRunStatus::Unknown;
+            }
+            self.task_runs.push_front(run);
+        }
+        cx.emit(InventoryEvent::TaskRunsChanged);
+    }
+
+    /// Finds the kind of the task source with the given id base, as produced by [`TaskSourceKind::to_id_base`].
+    /// Falls back to the user input kind for sources that are not available anymore.
+    pub fn source_kind_for_id_base(&self, id_base: &str) -> TaskSourceKind {
+        self.sources
+            .iter()
+            .map(|source| &source.kind)
+            .find(|kind| kind.to_id_base() == id_base)
+            .cloned()
+            .or_else(|| {
+                Some(TaskSourceKind::Language {
+                    name: id_base.strip_prefix("language_")?.into(),
+                })
+            })
+            .unwrap_or(TaskSourceKind::UserInput)
+    }
 }
 
+impl EventEmitter<InventoryEvent> for Inventory {}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -794,4 +948,80 @@ mod tests {
                 .collect::<Vec<_>>(),
         );
     }
+
+    #[gpui::test]
+    fn test_task_runs(cx: &mut TestAppContext) {
+        let inventory = cx.update(Inventory::new);
+        let resolve = |label: &str| {
+            TaskTemplate {
+                label: label.to_string(),
+                command: "echo".to_string(),
+                ..TaskTemplate::default()
+            }
+            .resolve_task("test_source", &TaskContext::default())
+            .unwrap()
+        };
+        let (build, test) = (resolve("build"), resolve("test"));
+
+        inventory.update(cx, |inventory, cx| {
+            inventory.task_started(TaskSourceKind::UserInput, build.clone(), cx);
+            inventory.task_started(TaskSourceKind::UserInput, test.clone(), cx);
+            inventory.task_started(TaskSourceKind::UserInput, build.clone(), cx);
+            inventory.task_finished(&build.id, false, Some(101), cx);
+            inventory.task_finished(&test.id, true, Some(0), cx);
+        });
+        inventory.update(cx, |inventory, _| {
+            let statuses = inventory
+                .task_runs()
+                .map(|run| {
+                    let status = match run.status {
+                        TaskRunStatus::Finished {
+                            success, exit_code, ..
+                        } => Some((success, exit_code)),
+                        _ => None,
+                    };
+                    (run.task.resolved_label.as_str(), status)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                statuses,
+                [
+                    ("build", Some((false, Some(101)))),
+                    ("test", Some((true, Some(0)))),
+                    ("build", None),
+                ],
+                "The oldest running run of a task should be finished first"
+            );
+            let last_failed_run = inventory.last_failed_run().unwrap();
+            assert_eq!(last_failed_run.task.id, build.id);
+        });
+
+        inventory.update(cx, |inventory, cx| {
+            let mut restored_run = inventory.task_runs().last().unwrap().clone();
+            restored_run.task = resolve("lint");
+            inventory.restore_task_runs(vec![restored_run], cx);
+            let first_run = inventory.task_runs().next().unwrap();
+            assert_eq!(first_run.task.resolved_label, "lint");
+            assert_eq!(
+                first_run.status,
+                TaskRunStatus::Unknown,
+                "Runs restored while running should not be shown as running"
+            );
+        });
+
+        let format = resolve("format");
+        inventory.update(cx, |inventory, cx| {
+            inventory.dependencies_scheduled([(TaskSourceKind::UserInput, format.clone())]);
+            inventory.dependency_started(&build.id, cx);
+            inventory.dependency_started(&format.id, cx);
+            let last_run = inventory.task_runs().last().unwrap();
+            assert_eq!(last_run.task.id, format.id);
+            assert_eq!(last_run.status, TaskRunStatus::Running);
+            assert_eq!(
+                inventory.task_runs().count(),
+                5,
+                "Only the runs of scheduled dependencies should be recorded"
+            );
+        });
+    }
 }
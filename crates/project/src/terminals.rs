@@ -1,6 +1,6 @@
 use crate::Project;
 use collections::HashMap;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, Task, WeakModel};
 use settings::Settings;
 use smol::channel::bounded;
 use std::path::{Path, PathBuf};
@@ -18,24 +18,58 @@ pub struct Terminals {
 }
 
 impl Project {
+    /// Creates a terminal, once the environment of its working directory and task has been loaded.
     pub fn create_terminal(
         &mut self,
         working_directory: Option<PathBuf>,
         spawn_task: Option<SpawnTask>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
-    ) -> anyhow::Result<Model<Terminal>> {
-        anyhow::ensure!(
-            !self.is_remote(),
-            "creating terminals as a guest is not supported yet"
-        );
+    ) -> Task<anyhow::Result<Model<Terminal>>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow::anyhow!(
+                "creating terminals as a guest is not supported yet"
+            )));
+        }
+
+        let load_environment = match &spawn_task {
+            Some(spawn_task) => self.load_environment(
+                working_directory.as_deref(),
+                &spawn_task.env_files,
+                spawn_task.env_script.as_deref(),
+                cx,
+            ),
+            None => self.load_environment(working_directory.as_deref(), &[], None, cx),
+        };
+        cx.spawn(|project, mut cx| async move {
+            let environment = load_environment.await;
+            project.update(&mut cx, |project, cx| {
+                project.create_terminal_with_environment(
+                    working_directory,
+                    spawn_task,
+                    environment,
+                    window,
+                    cx,
+                )
+            })?
+        })
+    }
 
+    fn create_terminal_with_environment(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        spawn_task: Option<SpawnTask>,
+        environment: HashMap<String, String>,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
+    ) -> anyhow::Result<Model<Terminal>> {
         let is_terminal = spawn_task.is_none();
         let settings = TerminalSettings::get_global(cx);
         let python_settings = settings.detect_venv.clone();
         let (completion_tx, completion_rx) = bounded(1);
 
         let mut env = settings.env.clone();
+        env.extend(environment);
         // Alacritty uses parent project's working directory when no working directory is provided
         // https://github.com/alacritty/alacritty/blob/fd1a3cc79192d1d03839f0fd8c72e1f8d0fce42e/extra/man/alacritty.5.scd?plain=1#L47-L52
 
@@ -55,6 +89,7 @@ impl Project {
                     full_label: spawn_task.full_label,
                     label: spawn_task.label,
                     status: TaskStatus::Running,
+                    exit_code: None,
                     completion_rx,
                 }),
                 Shell::WithArguments {
@@ -0,0 +1,82 @@
+//! Project-wide storage of the latest results of the tests run by tasks with a test report.
+
+use std::path::Path;
+
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext};
+use task::{TestResult, TestStatus};
+
+/// The latest result of each test run in the project, with the paths of the tests made absolute.
+pub struct TestResultsStore {
+    results: Vec<TestResult>,
+}
+
+pub enum TestResultsStoreEvent {
+    ResultsChanged,
+}
+
+impl EventEmitter<TestResultsStoreEvent> for TestResultsStore {}
+
+impl TestResultsStore {
+    pub fn new(cx: &mut AppContext) -> Model<Self> {
+        cx.new_model(|_| Self {
+            results: Vec::new(),
+        })
+    }
+
+    /// Records the results of a test run, replacing the previous results of the same tests.
+    pub fn insert_results(&mut self, results: Vec<TestResult>, cx: &mut ModelContext<Self>) {
+        if results.is_empty() {
+            return;
+        }
+        self.results.retain(|existing| {
+            !results.iter().any(|result| {
+                result.name == existing.name
+                    && result.suite == existing.suite
+                    && result.path == existing.path
+            })
+        });
+        self.results.extend(results);
+        cx.emit(TestResultsStoreEvent::ResultsChanged);
+    }
+
+    pub fn results(&self) -> &[TestResult] {
+        &self.results
+    }
+
+    pub fn failed_results(&self) -> impl Iterator<Item = &TestResult> {
+        self.results
+            .iter()
+            .filter(|result| result.status == TestStatus::Failed)
+    }
+
+    pub fn clear(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.results.is_empty() {
+            self.results.clear();
+            cx.emit(TestResultsStoreEvent::ResultsChanged);
+        }
+    }
+
+    /// Returns the latest result of the test with the given name in the given file.
+    /// Results reported without a file match tests of that name in any file, and `suite_name`, the name
+    /// of the module or class containing the test, if known, must name the innermost suites of the
+    /// result, such as `a::b` for a test of the module `b` within `a`.
+    pub fn result_for(
+        &self,
+        abs_path: &Path,
+        name: &str,
+        suite_name: Option<&str>,
+    ) -> Option<&TestResult> {
+        self.results.iter().rev().find(|result| {
+            result.name == name
+                && result.path.as_deref().map_or(true, |path| path == abs_path)
+                && suite_name.map_or(true, |suite_name| {
+                    result.suite.is_empty()
+                        || suite_name
+                            .split("::")
+                            .rev()
+                            .zip(result.suite.iter().rev())
+                            .all(|(name, suite)| name == suite)
+                })
+        })
+    }
+}
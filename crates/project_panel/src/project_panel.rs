@@ -57,6 +57,7 @@ pub struct ProjectPanel {
     edit_state: Option<EditState>,
     filename_editor: View<Editor>,
     clipboard_entry: Option<ClipboardEntry>,
+    compare_entry: Option<ProjectPath>,
     _dragged_entry_destination: Option<Arc<Path>>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
@@ -137,6 +138,8 @@ actions!(
         NewSearchInDirectory,
         UnfoldDirectory,
         FoldDirectory,
+        SelectForCompare,
+        CompareWithSelected,
     ]
 );
 
@@ -263,6 +266,7 @@ impl ProjectPanel {
                 context_menu: None,
                 filename_editor,
                 clipboard_entry: None,
+                compare_entry: None,
                 _dragged_entry_destination: None,
                 workspace: workspace.weak_handle(),
                 width: None,
@@ -426,6 +430,9 @@ impl ProjectPanel {
             let worktree_id = worktree.id();
             let is_local = project.is_local();
             let is_read_only = project.is_read_only();
+            let can_compare = self.compare_entry.as_ref().is_some_and(|compare_entry| {
+                compare_entry.worktree_id != worktree_id || compare_entry.path != entry.path
+            });
 
             let context_menu = ContextMenu::build(cx, |menu, cx| {
                 menu.context(self.focus_handle.clone()).when_else(
@@ -466,6 +473,16 @@ impl ProjectPanel {
                             .separator()
                             .action("Copy Path", Box::new(CopyPath))
                             .action("Copy Relative Path", Box::new(CopyRelativePath))
+                            .when(!is_dir, |menu| {
+                                menu.separator()
+                                    .action("Select for Compare", Box::new(SelectForCompare))
+                                    .when(can_compare, |menu| {
+                                        menu.action(
+                                            "Compare with Selected",
+                                            Box::new(CompareWithSelected),
+                                        )
+                                    })
+                            })
                             .separator()
                             .action("Rename", Box::new(Rename))
                             .when(!is_root, |menu| {
@@ -1120,6 +1137,38 @@ impl ProjectPanel {
         }
     }
 
+    fn select_for_compare(&mut self, _: &SelectForCompare, cx: &mut ViewContext<Self>) {
+        if let Some((worktree, entry)) = self.selected_entry(cx) {
+            if entry.is_file() {
+                self.compare_entry = Some(ProjectPath {
+                    worktree_id: worktree.id(),
+                    path: entry.path.clone(),
+                });
+            }
+        }
+    }
+
+    fn compare_with_selected(&mut self, _: &CompareWithSelected, cx: &mut ViewContext<Self>) {
+        let Some(old_path) = self.compare_entry.clone() else {
+            return;
+        };
+        let Some((worktree, entry)) = self.selected_entry(cx) else {
+            return;
+        };
+        if !entry.is_file() {
+            return;
+        }
+        let new_path = ProjectPath {
+            worktree_id: worktree.id(),
+            path: entry.path.clone(),
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                diff_view::compare_paths(workspace, old_path, new_path, cx)
+            })
+            .ok();
+    }
+
     fn reveal_in_finder(&mut self, _: &RevealInFinder, cx: &mut ViewContext<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             cx.reveal_path(&worktree.abs_path().join(&entry.path));
@@ -1763,6 +1812,8 @@ impl Render for ProjectPanel {
                         .on_action(cx.listener(Self::cut))
                         .on_action(cx.listener(Self::copy))
                         .on_action(cx.listener(Self::paste))
+                        .on_action(cx.listener(Self::select_for_compare))
+                        .on_action(cx.listener(Self::compare_with_selected))
                 })
                 .when(project.is_local(), |el| {
                     el.on_action(cx.listener(Self::reveal_in_finder))
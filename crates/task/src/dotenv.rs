@@ -0,0 +1,154 @@
+//! Parsing of dotenv files, that tasks and terminals load their environment from.
+
+use anyhow::{anyhow, bail};
+use collections::HashMap;
+
+/// Parses the variables defined by the contents of a dotenv file, in the order of their definitions.
+///
+/// Each line defines a variable as `NAME=value`, optionally prefixed with `export`, and lines starting with `#` are comments.
+/// Values may be quoted: double-quoted and unquoted values may reference the variables defined before them in the file
+/// or in `env` as `$NAME` or `${NAME}`, while single-quoted values are taken literally.
+pub fn parse_dotenv(
+    contents: &str,
+    env: &HashMap<String, String>,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let mut variables = Vec::<(String, String)>::new();
+    for (row, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("line {}: expected a `NAME=value` definition", row + 1))?;
+        let name = name.trim();
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            bail!("line {}: invalid variable name {name:?}", row + 1);
+        }
+
+        let lookup = |name: &str| {
+            variables
+                .iter()
+                .rev()
+                .find(|(defined_name, _)| defined_name == name)
+                .map(|(_, value)| value.as_str())
+                .or_else(|| env.get(name).map(String::as_str))
+        };
+        let value = value.trim_start();
+        let value = if let Some(quoted) = value.strip_prefix('\'') {
+            let (value, _) = quoted
+                .split_once('\'')
+                .ok_or_else(|| anyhow!("line {}: unterminated single quote", row + 1))?;
+            value.to_string()
+        } else if let Some(quoted) = value.strip_prefix('"') {
+            let mut unescaped = String::new();
+            let mut chars = quoted.chars();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => unescaped.push('\n'),
+                        Some('t') => unescaped.push('\t'),
+                        // Keep escaped dollar signs escaped, so that they are not interpolated.
+                        Some('$') => unescaped.push_str("\\$"),
+                        Some(c) => unescaped.push(c),
+                        None => bail!("line {}: unterminated double quote", row + 1),
+                    },
+                    Some(c) => unescaped.push(c),
+                    None => bail!("line {}: unterminated double quote", row + 1),
+                }
+            }
+            interpolate(&unescaped, lookup)
+        } else {
+            // Unquoted values end at a comment.
+            let value = match value.find(" #") {
+                Some(comment_start) => &value[..comment_start],
+                None => value,
+            };
+            interpolate(value.trim_end(), lookup)
+        };
+        variables.push((name.to_string(), value));
+    }
+    Ok(variables)
+}
+
+/// Replaces the `$NAME` and `${NAME}` references to variables in a value, with undefined variables replaced by nothing.
+/// A `\$` is kept as a literal dollar sign.
+fn interpolate<'a>(value: &str, lookup: impl Fn(&str) -> Option<&'a str>) -> String {
+    let mut interpolated = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'$') => {
+                chars.next();
+                interpolated.push('$');
+            }
+            '$' => {
+                let braced = chars.next_if_eq(&'{').is_some();
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                if braced && chars.next_if_eq(&'}').is_none() {
+                    interpolated.push_str("${");
+                    interpolated.push_str(&name);
+                } else if name.is_empty() {
+                    interpolated.push('$');
+                    if braced {
+                        interpolated.push_str("{}");
+                    }
+                } else {
+                    interpolated.push_str(lookup(&name).unwrap_or_default());
+                }
+            }
+            c => interpolated.push(c),
+        }
+    }
+    interpolated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv() {
+        let env = HashMap::from_iter([("HOME".to_string(), "/home/user".to_string())]);
+        let contents = r#"
+# Database settings
+export DB_HOST=localhost
+DB_PORT = 5432 # the default port
+DB_URL="postgres://${DB_HOST}:$DB_PORT/app\n"
+CACHE_DIR=$HOME/.cache/app
+LITERAL='$HOME is not $interpolated'
+ESCAPED="costs \$5 in $MISSING_VARIABLE"
+DB_HOST=db.internal
+"#;
+        assert_eq!(
+            parse_dotenv(contents, &env).unwrap(),
+            vec![
+                ("DB_HOST".to_string(), "localhost".to_string()),
+                ("DB_PORT".to_string(), "5432".to_string()),
+                (
+                    "DB_URL".to_string(),
+                    "postgres://localhost:5432/app\n".to_string()
+                ),
+                ("CACHE_DIR".to_string(), "/home/user/.cache/app".to_string()),
+                (
+                    "LITERAL".to_string(),
+                    "$HOME is not $interpolated".to_string()
+                ),
+                ("ESCAPED".to_string(), "costs $5 in ".to_string()),
+                ("DB_HOST".to_string(), "db.internal".to_string()),
+            ]
+        );
+
+        assert!(parse_dotenv("NOT A DEFINITION", &env).is_err());
+        assert!(parse_dotenv("1NAME=value", &env).is_err());
+        assert!(parse_dotenv("NAME=\"unterminated", &env).is_err());
+    }
+}
@@ -1,18 +1,29 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 #![deny(missing_docs)]
 
+mod dotenv;
+mod problem_matcher;
 pub mod static_source;
 mod task_template;
+mod test_report;
 mod vscode_format;
 
 use collections::{HashMap, HashSet};
 use gpui::ModelContext;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::borrow::Cow;
 use std::path::PathBuf;
 
-pub use task_template::{RevealStrategy, TaskTemplate, TaskTemplates};
+pub use dotenv::parse_dotenv;
+pub use problem_matcher::{
+    CustomProblemMatcher, ProblemMatcher, ProblemPattern, ProblemPatterns, ProblemSeverity,
+    ResolvedProblemMatcher, TaskProblem,
+};
+pub use task_template::{
+    DependsOrder, RevealStrategy, TaskInput, TaskOutput, TaskTemplate, TaskTemplates,
+};
+pub use test_report::{TestReport, TestReportFormat, TestResult, TestStatus};
 pub use vscode_format::VsCodeTaskFile;
 
 /// Task identifier, unique within the application.
@@ -37,12 +48,28 @@ pub struct SpawnInTerminal {
     pub cwd: Option<PathBuf>,
     /// Env overrides for the command, will be appended to the terminal's environment from the settings.
     pub env: HashMap<String, String>,
+    /// Dotenv files to load the environment of the task from, before applying `env`.
+    pub env_files: Vec<PathBuf>,
+    /// A shell script to source for the environment of the task, before applying `env`.
+    pub env_script: Option<PathBuf>,
     /// Whether to use a new terminal tab or reuse the existing one to spawn the process.
     pub use_new_terminal: bool,
     /// Whether to allow multiple instances of the same task to be run, or rather wait for the existing ones to finish.
     pub allow_concurrent_runs: bool,
     /// What to do with the terminal pane and tab, after the command was started.
     pub reveal: RevealStrategy,
+    /// Tasks to run before this one, resolved with the same context.
+    pub dependencies: Vec<SpawnInTerminal>,
+    /// Whether to start all the dependencies at once, or to run them one after another.
+    pub depends_order: DependsOrder,
+    /// Whether the task keeps running in the background, so that its dependents need not wait for it to finish.
+    pub background: bool,
+    /// Matchers to find the problems reported in the output of the task, once it has finished.
+    pub problem_matchers: Vec<ResolvedProblemMatcher>,
+    /// Structured results of the tests run by the task, to read once it has finished.
+    pub test_report: Option<TestReport>,
+    /// Where to show the output of the task.
+    pub output: TaskOutput,
 }
 
 /// A final form of the [`TaskTemplate`], that got resolved with a particualar [`TaskContext`] and now is ready to spawn the actual task.
@@ -60,6 +87,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// A context the task got resolved with.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: Option<SpawnInTerminal>,
@@ -75,17 +104,39 @@ impl ResolvedTask {
     pub fn substituted_variables(&self) -> &HashSet<VariableName> {
         &self.substituted_variables
     }
+
+    /// A context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum VariableName {
     /// An absolute path of the currently opened file.
     File,
+    /// A path of the currently opened file, relative to the worktree root.
+    RelativeFile,
+    /// A name of the currently opened file, with its extension.
+    Filename,
+    /// An absolute path of the directory that contains the currently opened file.
+    Dirname,
+    /// A name of the currently opened file, without its extension.
+    Stem,
+    /// A name of the language of the currently opened file.
+    Language,
     /// An absolute path of the currently opened worktree, that contains the file.
     WorktreeRoot,
+    /// A current git branch of the repository that contains the file or the worktree.
+    GitBranch,
+    /// A sha of the HEAD commit of the repository that contains the file or the worktree.
+    GitSha,
     /// A symbol text, that contains latest cursor/selection position.
     Symbol,
+    /// Texts of the symbols (e.g. modules, classes and functions) that contain latest cursor/selection position,
+    /// from the outermost one, separated by the language's symbol path separator (`.` unless configured otherwise, e.g. `::` in Rust).
+    SymbolPath,
     /// A row with the latest cursor/selection position.
     Row,
     /// A column with the latest cursor/selection position.
@@ -95,13 +146,16 @@ pub enum VariableName {
     /// Custom variable, provided by the plugin or other external source.
     /// Will be printed with `ZED_` prefix to avoid potential conflicts with other variables.
     Custom(Cow<'static, str>),
+    /// Value prompted from the user when the task is spawned, as declared in the `inputs` of the task.
+    /// Will be printed with `ZED_INPUT_` prefix.
+    Input(Cow<'static, str>),
 }
 
 impl VariableName {
     /// Generates a `$VARIABLE`-like string value to be used in templates.
-    /// Custom variables are wrapped in `${}` to avoid substitution issues with whitespaces.
+    /// Custom and input variables are wrapped in `${}` to avoid substitution issues with whitespaces.
     pub fn template_value(&self) -> String {
-        if matches!(self, Self::Custom(_)) {
+        if matches!(self, Self::Custom(_) | Self::Input(_)) {
             format!("${{{self}}}")
         } else {
             format!("${self}")
@@ -116,18 +170,32 @@ impl std::fmt::Display for VariableName {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::File => write!(f, "{ZED_VARIABLE_NAME_PREFIX}FILE"),
+            Self::RelativeFile => write!(f, "{ZED_VARIABLE_NAME_PREFIX}RELATIVE_FILE"),
+            Self::Filename => write!(f, "{ZED_VARIABLE_NAME_PREFIX}FILENAME"),
+            Self::Dirname => write!(f, "{ZED_VARIABLE_NAME_PREFIX}DIRNAME"),
+            Self::Stem => write!(f, "{ZED_VARIABLE_NAME_PREFIX}STEM"),
+            Self::Language => write!(f, "{ZED_VARIABLE_NAME_PREFIX}LANGUAGE"),
             Self::WorktreeRoot => write!(f, "{ZED_VARIABLE_NAME_PREFIX}WORKTREE_ROOT"),
+            Self::GitBranch => write!(f, "{ZED_VARIABLE_NAME_PREFIX}GIT_BRANCH"),
+            Self::GitSha => write!(f, "{ZED_VARIABLE_NAME_PREFIX}GIT_SHA"),
             Self::Symbol => write!(f, "{ZED_VARIABLE_NAME_PREFIX}SYMBOL"),
+            Self::SymbolPath => write!(f, "{ZED_VARIABLE_NAME_PREFIX}SYMBOL_PATH"),
             Self::Row => write!(f, "{ZED_VARIABLE_NAME_PREFIX}ROW"),
             Self::Column => write!(f, "{ZED_VARIABLE_NAME_PREFIX}COLUMN"),
             Self::SelectedText => write!(f, "{ZED_VARIABLE_NAME_PREFIX}SELECTED_TEXT"),
             Self::Custom(s) => write!(f, "{ZED_VARIABLE_NAME_PREFIX}CUSTOM_{s}"),
+            Self::Input(s) => write!(f, "{ZED_VARIABLE_NAME_PREFIX}INPUT_{s}"),
         }
     }
 }
 
 /// Container for predefined environment variables that describe state of Zed at the time the task was spawned.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+/// Serialized as a list of pairs, since the variable names are not plain strings.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    from = "Vec<(VariableName, String)>",
+    into = "Vec<(VariableName, String)>"
+)]
 pub struct TaskVariables(HashMap<VariableName, String>);
 
 impl TaskVariables {
@@ -140,6 +208,11 @@ impl TaskVariables {
     pub fn extend(&mut self, other: Self) {
         self.0.extend(other.0);
     }
+
+    /// Returns the value of the variable, if it is set.
+    pub fn get(&self, variable: &VariableName) -> Option<&str> {
+        self.0.get(variable).map(String::as_str)
+    }
 }
 
 impl FromIterator<(VariableName, String)> for TaskVariables {
@@ -148,9 +221,21 @@ impl FromIterator<(VariableName, String)> for TaskVariables {
     }
 }
 
+impl From<Vec<(VariableName, String)>> for TaskVariables {
+    fn from(variables: Vec<(VariableName, String)>) -> Self {
+        Self::from_iter(variables)
+    }
+}
+
+impl From<TaskVariables> for Vec<(VariableName, String)> {
+    fn from(variables: TaskVariables) -> Self {
+        variables.0.into_iter().collect()
+    }
+}
+
 /// Keeps track of the file associated with a task and context of tasks execution (i.e. current file or current function).
 /// Keeps all Zed-related state inside, used to produce a resolved task out of its template.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TaskContext {
     /// A path to a directory in which the task should be executed.
     pub cwd: Option<PathBuf>,
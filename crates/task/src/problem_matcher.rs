@@ -0,0 +1,662 @@
+//! Problem matchers, that find problems such as compiler errors and warnings in the output of a task.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to find the problems reported in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// Name of a built-in matcher:
+    /// * `$rustc` — errors and warnings of `cargo` and `rustc`
+    /// * `$rustc-json` — messages of `cargo` and `rustc`, run with `--message-format=json`
+    /// * `$tsc` — errors of the TypeScript compiler
+    /// * `$gcc` — errors and warnings of `gcc` and `clang`
+    /// * `$eslint-compact` — problems reported by `eslint --format compact`
+    /// * `$eslint-stylish` — problems reported by `eslint` with its default formatter
+    BuiltIn(String),
+    /// A matcher defined by regular expressions.
+    Custom(CustomProblemMatcher),
+}
+
+/// A problem matcher defined by regular expressions, with capture groups for the parts of a problem.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CustomProblemMatcher {
+    /// Name of the tool reporting the problems, shown along with them.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems that have none captured, `error` by default.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// A pattern matching one line for each problem, or several patterns matching consecutive lines,
+    /// for problems reported over multiple lines.
+    pub pattern: ProblemPatterns,
+}
+
+/// One or more patterns, matching consecutive lines of the output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemPatterns {
+    /// A pattern matching a problem on a single line.
+    Single(ProblemPattern),
+    /// Patterns matching a problem over consecutive lines.
+    Multiple(Vec<ProblemPattern>),
+}
+
+/// A regular expression matching a line of the output, along with the indices of the capture groups for each part
+/// of the problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ProblemPattern {
+    /// The regular expression to match a line with.
+    pub regexp: String,
+    /// Capture group of the path of the file, relative to the task's working directory or absolute.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Capture group of the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Capture group of the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Capture group of the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Capture group of the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Capture group of the severity of the problem, e.g. `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Capture group of the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Capture group of the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the pattern, if the last one of several, matches as many consecutive lines as possible,
+    /// each being a problem in the file matched by the previous patterns.
+    #[serde(default, rename = "loop")]
+    pub loop_: bool,
+}
+
+/// How severe a problem is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// An error.
+    #[default]
+    Error,
+    /// A warning.
+    Warning,
+    /// An informational message, such as a note.
+    Info,
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProblem {
+    /// Absolute path of the file with the problem, or as reported if the task has no working directory.
+    pub path: PathBuf,
+    /// 0-based row the problem starts at.
+    pub row: u32,
+    /// 0-based column the problem starts at.
+    pub column: u32,
+    /// 0-based row the problem ends at.
+    pub end_row: u32,
+    /// 0-based column the problem ends at.
+    pub end_column: u32,
+    /// How severe the problem is.
+    pub severity: ProblemSeverity,
+    /// The code of the problem, e.g. `E0308`.
+    pub code: Option<String>,
+    /// The description of the problem.
+    pub message: String,
+    /// The name of the tool that reported the problem.
+    pub source: Option<String>,
+}
+
+const RUSTC_JSON: &str = "$rustc-json";
+
+/// A problem matcher with its patterns compiled, ready to match the output of tasks.
+#[derive(Clone, Debug)]
+pub struct ResolvedProblemMatcher {
+    /// The matcher as defined, by which resolved matchers are compared.
+    matcher: ProblemMatcher,
+    kind: ResolvedMatcherKind,
+}
+
+#[derive(Clone, Debug)]
+enum ResolvedMatcherKind {
+    RustcJson,
+    Patterns(CompiledPatterns),
+}
+
+#[derive(Clone, Debug)]
+struct CompiledPatterns {
+    source: Option<String>,
+    severity: ProblemSeverity,
+    patterns: Vec<(ProblemPattern, Regex)>,
+}
+
+impl PartialEq for ResolvedProblemMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.matcher == other.matcher
+    }
+}
+
+impl Eq for ResolvedProblemMatcher {}
+
+impl ProblemMatcher {
+    /// Compiles the patterns of the matcher.
+    /// Fails if the matcher is an unknown built-in or has an invalid pattern.
+    pub fn resolve(&self) -> anyhow::Result<ResolvedProblemMatcher> {
+        let kind = match self {
+            Self::BuiltIn(name) if name == RUSTC_JSON => ResolvedMatcherKind::RustcJson,
+            Self::BuiltIn(name) => ResolvedMatcherKind::Patterns(
+                built_in_matcher(name)
+                    .with_context(|| format!("Unknown problem matcher {name:?}"))?
+                    .compile()?,
+            ),
+            Self::Custom(matcher) => ResolvedMatcherKind::Patterns(matcher.compile()?),
+        };
+        Ok(ResolvedProblemMatcher {
+            matcher: self.clone(),
+            kind,
+        })
+    }
+}
+
+impl ResolvedProblemMatcher {
+    /// Finds the problems reported in the output of a task, resolving relative paths against its working directory.
+    pub fn match_output(&self, output: &str, cwd: Option<&Path>) -> Vec<TaskProblem> {
+        match &self.kind {
+            ResolvedMatcherKind::RustcJson => match_rustc_json(output, cwd),
+            ResolvedMatcherKind::Patterns(patterns) => patterns.match_output(output, cwd),
+        }
+    }
+}
+
+impl CustomProblemMatcher {
+    fn compile(&self) -> anyhow::Result<CompiledPatterns> {
+        let patterns = match &self.pattern {
+            ProblemPatterns::Single(pattern) => std::slice::from_ref(pattern),
+            ProblemPatterns::Multiple(patterns) => patterns.as_slice(),
+        };
+        if patterns.is_empty() {
+            return Err(anyhow!("Problem matcher has no patterns"));
+        }
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp)
+                    .with_context(|| format!("Invalid problem pattern {:?}", pattern.regexp))?;
+                Ok((pattern.clone(), regex))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(CompiledPatterns {
+            source: self.source.clone(),
+            severity: self.severity,
+            patterns,
+        })
+    }
+}
+
+impl CompiledPatterns {
+    fn match_output(&self, output: &str, cwd: Option<&Path>) -> Vec<TaskProblem> {
+        let Some(((last_pattern, last_regex), leading_patterns)) = self.patterns.split_last()
+        else {
+            return Vec::new();
+        };
+
+        let lines = output.lines().map(str::trim_end).collect::<Vec<_>>();
+        let mut problems = Vec::new();
+        let mut ix = 0;
+        while ix < lines.len() {
+            let mut partial = PartialProblem::default();
+            let leading_match =
+                leading_patterns
+                    .iter()
+                    .enumerate()
+                    .all(|(pattern_ix, (pattern, regex))| {
+                        let Some(captures) =
+                            lines.get(ix + pattern_ix).and_then(|l| regex.captures(l))
+                        else {
+                            return false;
+                        };
+                        partial.capture(pattern, &captures);
+                        true
+                    });
+            if !leading_match {
+                ix += 1;
+                continue;
+            }
+
+            let mut line_ix = ix + leading_patterns.len();
+            while let Some(captures) = lines.get(line_ix).and_then(|l| last_regex.captures(l)) {
+                let mut problem = partial.clone();
+                problem.capture(last_pattern, &captures);
+                problems.extend(problem.into_problem(self, cwd));
+                line_ix += 1;
+                if !last_pattern.loop_ {
+                    break;
+                }
+            }
+            ix = if line_ix > ix + leading_patterns.len() {
+                line_ix
+            } else {
+                ix + 1
+            };
+        }
+        problems
+    }
+}
+
+#[derive(Clone, Default)]
+struct PartialProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl PartialProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| Some(captures.get(ix?)?.as_str().trim().to_string());
+        let number = |ix: Option<usize>| group(ix)?.parse::<u32>().ok();
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file);
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code);
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message);
+        }
+    }
+
+    fn into_problem(self, matcher: &CompiledPatterns, cwd: Option<&Path>) -> Option<TaskProblem> {
+        let row = self.line?.saturating_sub(1);
+        let column = self.column.unwrap_or(1).saturating_sub(1);
+        Some(TaskProblem {
+            path: resolve_path(&self.file?, cwd),
+            row,
+            column,
+            end_row: self
+                .end_line
+                .map_or(row, |end_line| end_line.saturating_sub(1)),
+            end_column: self
+                .end_column
+                .map_or(column, |end_column| end_column.saturating_sub(1)),
+            severity: self
+                .severity
+                .as_deref()
+                .and_then(parse_severity)
+                .unwrap_or(matcher.severity),
+            code: self.code,
+            message: self.message.filter(|message| !message.is_empty())?,
+            source: matcher.source.clone(),
+        })
+    }
+}
+
+fn built_in_matcher(name: &str) -> Option<CustomProblemMatcher> {
+    let (source, pattern) = match name {
+        "$rustc" => (
+            "rustc",
+            ProblemPatterns::Multiple(vec![
+                ProblemPattern {
+                    regexp: r"^(warning|warn|error)(?:\[(.*?)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^[\s\->=]*(.*?):(\d*):(\d*)\s*$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ]),
+        ),
+        "$tsc" => (
+            "tsc",
+            ProblemPatterns::Single(ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }),
+        ),
+        "$gcc" => (
+            "gcc",
+            ProblemPatterns::Single(ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }),
+        ),
+        "$eslint-compact" => (
+            "eslint",
+            ProblemPatterns::Single(ProblemPattern {
+                regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }),
+        ),
+        "$eslint-stylish" => (
+            "eslint",
+            ProblemPatterns::Multiple(vec![
+                ProblemPattern {
+                    regexp: r"^([^\s].*)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(.*))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    loop_: true,
+                    ..ProblemPattern::default()
+                },
+            ]),
+        ),
+        _ => return None,
+    };
+    Some(CustomProblemMatcher {
+        source: Some(source.to_string()),
+        severity: ProblemSeverity::Error,
+        pattern,
+    })
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: RustcDiagnostic,
+}
+
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    spans: Vec<RustcSpan>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: u32,
+    line_end: u32,
+    column_start: u32,
+    column_end: u32,
+    is_primary: bool,
+}
+
+/// Finds the diagnostics in JSON messages of `cargo` (wrapped in `compiler-message` objects) or `rustc`.
+fn match_rustc_json(output: &str, cwd: Option<&Path>) -> Vec<TaskProblem> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .filter_map(
+            |line| match serde_json_lenient::from_str::<CargoMessage>(line) {
+                Ok(message) if message.reason == "compiler-message" => Some(message.message),
+                Ok(_) => None,
+                Err(_) => serde_json_lenient::from_str::<RustcDiagnostic>(line).ok(),
+            },
+        )
+        .filter_map(|diagnostic| {
+            let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+            Some(TaskProblem {
+                path: resolve_path(&span.file_name, cwd),
+                row: span.line_start.saturating_sub(1),
+                column: span.column_start.saturating_sub(1),
+                end_row: span.line_end.saturating_sub(1),
+                end_column: span.column_end.saturating_sub(1),
+                severity: parse_severity(&diagnostic.level).unwrap_or_default(),
+                code: diagnostic.code.map(|code| code.code),
+                message: diagnostic.message,
+                source: Some("rustc".to_string()),
+            })
+        })
+        .collect()
+}
+
+fn parse_severity(severity: &str) -> Option<ProblemSeverity> {
+    match severity.to_lowercase().as_str() {
+        "error" | "fatal" | "fatal error" => Some(ProblemSeverity::Error),
+        "warning" | "warn" => Some(ProblemSeverity::Warning),
+        "info" | "information" | "note" | "help" | "hint" => Some(ProblemSeverity::Info),
+        _ => None,
+    }
+}
+
+fn resolve_path(path: &str, cwd: Option<&Path>) -> PathBuf {
+    match cwd {
+        Some(cwd) => cwd.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_matchers() {
+        let cwd = Path::new("/project");
+
+        let output = "src/main.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\nFound 1 error.";
+        assert_eq!(
+            ProblemMatcher::BuiltIn("$tsc".to_string())
+                .resolve()
+                .unwrap()
+                .match_output(output, Some(cwd)),
+            vec![TaskProblem {
+                path: PathBuf::from("/project/src/main.ts"),
+                row: 2,
+                column: 6,
+                end_row: 2,
+                end_column: 6,
+                severity: ProblemSeverity::Error,
+                code: Some("2322".to_string()),
+                message: "Type 'string' is not assignable to type 'number'.".to_string(),
+                source: Some("tsc".to_string()),
+            }]
+        );
+
+        let output = "/abs/main.c:10:5: warning: unused variable 'x' [-Wunused-variable]\n   10 |     int x;\n      |         ^";
+        let problems = ProblemMatcher::BuiltIn("$gcc".to_string())
+            .resolve()
+            .unwrap()
+            .match_output(output, Some(cwd));
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("/abs/main.c"));
+        assert_eq!((problems[0].row, problems[0].column), (9, 4));
+        assert_eq!(problems[0].severity, ProblemSeverity::Warning);
+
+        let output = "\n/project/src/a.js\n  1:10  error    'foo' is defined but never used  no-unused-vars\n  2:1   warning  Unexpected console statement     no-console\n\n/project/src/b.js\n  5:3  error  Missing semicolon  semi\n\n✖ 3 problems (2 errors, 1 warning)";
+        let problems = ProblemMatcher::BuiltIn("$eslint-stylish".to_string())
+            .resolve()
+            .unwrap()
+            .match_output(output, Some(cwd));
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().to_string(),
+                    problem.row,
+                    problem.severity,
+                    problem.message.as_str(),
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/project/src/a.js".to_string(),
+                    0,
+                    ProblemSeverity::Error,
+                    "'foo' is defined but never used",
+                    Some("no-unused-vars")
+                ),
+                (
+                    "/project/src/a.js".to_string(),
+                    1,
+                    ProblemSeverity::Warning,
+                    "Unexpected console statement",
+                    Some("no-console")
+                ),
+                (
+                    "/project/src/b.js".to_string(),
+                    4,
+                    ProblemSeverity::Error,
+                    "Missing semicolon",
+                    Some("semi")
+                ),
+            ]
+        );
+
+        assert!(ProblemMatcher::BuiltIn("$unknown".to_string())
+            .resolve()
+            .is_err());
+    }
+
+    #[test]
+    fn test_rustc_matchers() {
+        let output = "   Compiling foo v0.1.0 (/project)\nerror[E0308]: mismatched types\n --> src/lib.rs:4:18\n  |\n4 |     let x: u32 = \"a\";\n  |            ---   ^^^ expected `u32`, found `&str`\n";
+        assert_eq!(
+            ProblemMatcher::BuiltIn("$rustc".to_string())
+                .resolve()
+                .unwrap()
+                .match_output(output, Some(Path::new("/project"))),
+            vec![TaskProblem {
+                path: PathBuf::from("/project/src/lib.rs"),
+                row: 3,
+                column: 17,
+                end_row: 3,
+                end_column: 17,
+                severity: ProblemSeverity::Error,
+                code: Some("E0308".to_string()),
+                message: "mismatched types".to_string(),
+                source: Some("rustc".to_string()),
+            }]
+        );
+
+        let output = r#"{"reason":"compiler-artifact","package_id":"foo 0.1.0","target":{"name":"foo"}}
+{"reason":"compiler-message","package_id":"foo 0.1.0","message":{"message":"mismatched types","code":{"code":"E0308","explanation":null},"level":"error","spans":[{"file_name":"src/lib.rs","line_start":4,"line_end":4,"column_start":18,"column_end":25,"is_primary":true}],"children":[]}}
+{"reason":"compiler-message","package_id":"foo 0.1.0","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[]}}
+{"reason":"build-finished","success":false}"#;
+        assert_eq!(
+            ProblemMatcher::BuiltIn("$rustc-json".to_string())
+                .resolve()
+                .unwrap()
+                .match_output(output, Some(Path::new("/project"))),
+            vec![TaskProblem {
+                path: PathBuf::from("/project/src/lib.rs"),
+                row: 3,
+                column: 17,
+                end_row: 3,
+                end_column: 24,
+                severity: ProblemSeverity::Error,
+                code: Some("E0308".to_string()),
+                message: "mismatched types".to_string(),
+                source: Some("rustc".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(
+            r#"{
+                "source": "lint",
+                "severity": "warning",
+                "pattern": {
+                    "regexp": "^(\\S+):(\\d+): (.*)$",
+                    "file": 1,
+                    "line": 2,
+                    "message": 3
+                }
+            }"#,
+        )
+        .unwrap();
+        let problems = matcher
+            .resolve()
+            .unwrap()
+            .match_output("lint output\nsrc/a.py:12: line too long\n", None);
+        assert_eq!(
+            problems,
+            vec![TaskProblem {
+                path: PathBuf::from("src/a.py"),
+                row: 11,
+                column: 0,
+                end_row: 11,
+                end_column: 0,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "line too long".to_string(),
+                source: Some("lint".to_string()),
+            }]
+        );
+
+        let invalid = ProblemMatcher::Custom(CustomProblemMatcher {
+            source: None,
+            severity: ProblemSeverity::Error,
+            pattern: ProblemPatterns::Single(ProblemPattern {
+                regexp: "^(unclosed".to_string(),
+                ..ProblemPattern::default()
+            }),
+        });
+        assert!(invalid.resolve().is_err());
+    }
+}
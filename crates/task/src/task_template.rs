@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{borrow::Cow, path::PathBuf};
 
 use anyhow::{bail, Context};
 use collections::{HashMap, HashSet};
@@ -8,7 +8,8 @@ use sha2::{Digest, Sha256};
 use util::{truncate_and_remove_front, ResultExt};
 
 use crate::{
-    ResolvedTask, SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    ProblemMatcher, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TestReport, VariableName,
+    ZED_VARIABLE_NAME_PREFIX,
 };
 
 /// A template definition of a Zed task to run.
@@ -30,6 +31,15 @@ pub struct TaskTemplate {
     /// Env overrides for the command, will be appended to the terminal's environment from the settings.
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Dotenv files to load the environment of the task from, relative to its working directory, with the later files
+    /// overriding the variables of the earlier ones, and `env` overriding them all.
+    /// The values may reference the variables defined before them, as `$NAME` or `${NAME}`.
+    #[serde(default)]
+    pub env_files: Vec<String>,
+    /// A shell script to source before running the task, relative to its working directory, such as a direnv `.envrc`:
+    /// the environment variables it sets are added to the environment of the task. On Windows, it is a PowerShell script.
+    #[serde(default)]
+    pub env_script: Option<String>,
     /// Current working directory to spawn the command into, defaults to current project root.
     #[serde(default)]
     pub cwd: Option<String>,
@@ -44,6 +54,66 @@ pub struct TaskTemplate {
     /// * `never` — avoid changing current terminal pane focus, but still add/reuse the task's tab there
     #[serde(default)]
     pub reveal: RevealStrategy,
+    /// Labels of the tasks to run before this one; the task is only spawned once all of them have succeeded.
+    /// A task with dependencies may omit its command, to only run the tasks it depends on.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Whether to start all the tasks this one depends on at once (default), or to run them one after another.
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Whether the task keeps running in the background, e.g. a development server:
+    /// tasks depending on it start as soon as it is spawned, and an already running instance of it is reused instead of being restarted.
+    #[serde(default)]
+    pub background: bool,
+    /// Matchers to find problems, such as compiler errors, in the output of the task once it has finished,
+    /// to be shown as diagnostics until the task runs again.
+    /// Either names of built-in matchers (`$rustc-json`, `$tsc`, `$gcc`, `$eslint-compact`, `$eslint-stylish`)
+    /// or custom matchers, with a regular expression `pattern` and the capture groups of each part of a problem.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Tags of the runnables that the task can run, such as `rust-test`, as set by the runnables queries of languages.
+    /// Running a runnable from the gutter spawns the first task with one of its tags, with the text of the runnable's
+    /// captures available as `ZED_CUSTOM_<capture name>` variables.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Structured results of the tests the task runs, to show in the test explorer once it has finished:
+    /// the `format` of the report (`libtest`, `libtest_json`, `go_test_json`, `junit_xml` or `jest_json`), and
+    /// the `file` it is written to, read from the output of the task when omitted.
+    #[serde(default)]
+    pub test_report: Option<TestReport>,
+    /// Where to show the output of the task:
+    /// * `terminal` — run the task in a terminal tab (default)
+    /// * `buffer` — capture the output of the task into a read-only editor tab, keeping the output of its last runs
+    #[serde(default)]
+    pub output: TaskOutput,
+    /// Values to prompt the user for when the task is spawned, available as `ZED_INPUT_<name>` variables.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
+}
+
+/// A value of a task to prompt the user for when the task is spawned.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// Name of the input, referenced in the task as `${ZED_INPUT_<name>}`.
+    pub name: String,
+    /// Text of the prompt, the name of the input if not set.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Value the prompt is filled with, also used when the task is resolved without prompting, e.g. to list it.
+    #[serde(default)]
+    pub default: String,
+}
+
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all the dependencies at once.
+    #[default]
+    Parallel,
+    /// Run the dependencies one after another, in the listed order, stopping at the first one that fails.
+    Sequence,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -57,6 +127,17 @@ pub enum RevealStrategy {
     Never,
 }
 
+/// Where to show the output of a task.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskOutput {
+    /// Run the task in a terminal tab, where it can be interacted with.
+    #[default]
+    Terminal,
+    /// Capture the output of the task into a read-only editor tab, keeping the output of its last runs.
+    Buffer,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -77,17 +158,20 @@ impl TaskTemplate {
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
+    /// Inputs missing from the context are replaced with their default values.
     ///
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
         let mut variable_names = HashMap::default();
         let mut substituted_variables = HashSet::default();
-        let task_variables = cx
+        let mut task_variables = cx
             .task_variables
             .0
             .iter()
@@ -99,6 +183,14 @@ impl TaskTemplate {
                 (key_string, value.as_str())
             })
             .collect::<HashMap<_, _>>();
+        for input in &self.inputs {
+            let variable = VariableName::Input(Cow::Owned(input.name.clone()));
+            let key_string = variable.to_string();
+            if !task_variables.contains_key(&key_string) {
+                task_variables.insert(key_string.clone(), input.default.as_str());
+                variable_names.insert(key_string, variable);
+            }
+        }
         let truncated_variables = truncate_variables(&task_variables);
         let cwd = match self.cwd.as_deref() {
             Some(cwd) => {
@@ -114,6 +206,43 @@ impl TaskTemplate {
         }
         .map(PathBuf::from)
         .or(cx.cwd.clone());
+        let resolve_path = |path: String| match &cwd {
+            Some(cwd) => cwd.join(path),
+            None => PathBuf::from(path),
+        };
+        let env_files = substitute_all_template_variables_in_vec(
+            &self.env_files,
+            &task_variables,
+            &variable_names,
+            &mut substituted_variables,
+        )?
+        .into_iter()
+        .map(resolve_path)
+        .collect();
+        let env_script = match self.env_script.as_deref() {
+            Some(env_script) => Some(resolve_path(substitute_all_template_variables_in_str(
+                env_script,
+                &task_variables,
+                &variable_names,
+                &mut substituted_variables,
+            )?)),
+            None => None,
+        };
+        let test_report = match &self.test_report {
+            Some(test_report) => Some(TestReport {
+                format: test_report.format,
+                file: match test_report.file.as_deref() {
+                    Some(file) => Some(substitute_all_template_variables_in_str(
+                        file,
+                        &task_variables,
+                        &variable_names,
+                        &mut substituted_variables,
+                    )?),
+                    None => None,
+                },
+            }),
+            None => None,
+        };
         let shortened_label = substitute_all_template_variables_in_str(
             &self.label,
             &truncated_variables,
@@ -157,6 +286,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: Some(SpawnInTerminal {
                 id,
@@ -166,12 +296,67 @@ impl TaskTemplate {
                 command,
                 args,
                 env,
+                env_files,
+                env_script,
                 use_new_terminal: self.use_new_terminal,
                 allow_concurrent_runs: self.allow_concurrent_runs,
                 reveal: self.reveal,
+                dependencies: Vec::new(),
+                depends_order: self.depends_order,
+                background: self.background,
+                problem_matchers: self
+                    .problem_matchers
+                    .iter()
+                    .filter_map(|matcher| matcher.resolve().log_err())
+                    .collect(),
+                test_report,
+                output: self.output,
             }),
         })
     }
+
+    /// Resolves the tasks this one depends on, transitively, with the same [`TaskContext`].
+    /// `find_template` looks a task template up by its label, returning the id base of its source along with it.
+    ///
+    /// Fails if a dependency is unknown, cannot be resolved in the context given, or if the dependencies form a cycle.
+    pub fn resolve_dependencies(
+        &self,
+        cx: &TaskContext,
+        find_template: &dyn Fn(&str) -> Option<(String, TaskTemplate)>,
+    ) -> anyhow::Result<Vec<SpawnInTerminal>> {
+        self.resolve_dependencies_inner(cx, find_template, &mut vec![self.label.clone()])
+    }
+
+    fn resolve_dependencies_inner(
+        &self,
+        cx: &TaskContext,
+        find_template: &dyn Fn(&str) -> Option<(String, TaskTemplate)>,
+        dependents: &mut Vec<String>,
+    ) -> anyhow::Result<Vec<SpawnInTerminal>> {
+        let mut dependencies = Vec::with_capacity(self.depends_on.len());
+        for label in &self.depends_on {
+            if dependents.contains(label) {
+                bail!(
+                    "Task {:?} depends on itself through {}",
+                    label,
+                    dependents.join(" -> ")
+                );
+            }
+            let (id_base, template) = find_template(label).with_context(|| {
+                format!("Task {:?} depends on unknown task {label:?}", self.label)
+            })?;
+            let mut dependency = template
+                .resolve_task(&id_base, cx)
+                .and_then(|resolved_task| resolved_task.resolved)
+                .with_context(|| format!("Cannot resolve task {label:?} in the current context"))?;
+            dependents.push(label.clone());
+            dependency.dependencies =
+                template.resolve_dependencies_inner(cx, find_template, dependents)?;
+            dependents.pop();
+            dependencies.push(dependency);
+        }
+        Ok(dependencies)
+    }
 }
 
 const MAX_DISPLAY_VARIABLE_LENGTH: usize = 15;
@@ -629,6 +814,156 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dependency_resolution() {
+        let templates = [
+            TaskTemplate {
+                label: "build wasm".to_string(),
+                command: "cargo build --target wasm32-unknown-unknown".to_string(),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "serve".to_string(),
+                command: "python -m http.server".to_string(),
+                depends_on: vec!["build wasm".to_string()],
+                background: true,
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "test".to_string(),
+                depends_on: vec!["serve".to_string(), "open runner".to_string()],
+                depends_order: DependsOrder::Sequence,
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "open runner".to_string(),
+                command: "open http://localhost:8000/tests.html".to_string(),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "cycle a".to_string(),
+                command: "echo a".to_string(),
+                depends_on: vec!["cycle b".to_string()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "cycle b".to_string(),
+                command: "echo b".to_string(),
+                depends_on: vec!["cycle a".to_string()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "broken".to_string(),
+                command: "echo".to_string(),
+                depends_on: vec!["missing".to_string()],
+                ..TaskTemplate::default()
+            },
+        ];
+        let find_template = |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .map(|template| (TEST_ID_BASE.to_string(), template.clone()))
+        };
+        let cx = TaskContext::default();
+
+        let test_task = &templates[2];
+        let resolved = test_task
+            .resolve_task(TEST_ID_BASE, &cx)
+            .expect("tasks with dependencies may omit their command")
+            .resolved
+            .unwrap();
+        assert_eq!(resolved.command, "");
+        assert_eq!(resolved.depends_order, DependsOrder::Sequence);
+
+        let dependencies = test_task.resolve_dependencies(&cx, &find_template).unwrap();
+        let labels = dependencies
+            .iter()
+            .map(|dependency| dependency.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["serve", "open runner"]);
+        assert!(dependencies[0].background);
+        assert_eq!(dependencies[0].dependencies.len(), 1);
+        assert_eq!(dependencies[0].dependencies[0].label, "build wasm");
+
+        let error = templates[4]
+            .resolve_dependencies(&cx, &find_template)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task \"cycle a\" depends on itself through cycle a -> cycle b"
+        );
+        let error = templates[6]
+            .resolve_dependencies(&cx, &find_template)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task \"broken\" depends on unknown task \"missing\""
+        );
+    }
+
+    #[test]
+    fn test_input_variables() {
+        let template = TaskTemplate {
+            label: "deploy ${ZED_INPUT_environment}".to_string(),
+            command: "deploy".to_string(),
+            args: vec![
+                "--env".to_string(),
+                "${ZED_INPUT_environment}".to_string(),
+                "--branch".to_string(),
+                VariableName::GitBranch.template_value(),
+            ],
+            inputs: vec![TaskInput {
+                name: "environment".to_string(),
+                description: Some("Environment to deploy to".to_string()),
+                default: "staging".to_string(),
+            }],
+            ..TaskTemplate::default()
+        };
+        let mut cx = TaskContext {
+            cwd: None,
+            task_variables: TaskVariables::from_iter([(
+                VariableName::GitBranch,
+                "main".to_string(),
+            )]),
+        };
+
+        let resolved_task = template.resolve_task(TEST_ID_BASE, &cx).unwrap();
+        let spawn_in_terminal = resolved_task.resolved.as_ref().unwrap();
+        assert_eq!(spawn_in_terminal.label, "deploy staging");
+        assert_eq!(
+            spawn_in_terminal.args,
+            ["--env", "staging", "--branch", "main"]
+        );
+        assert_eq!(
+            spawn_in_terminal
+                .env
+                .get("ZED_INPUT_environment")
+                .map(String::as_str),
+            Some("staging")
+        );
+        assert_eq!(resolved_task.task_context(), &cx);
+
+        cx.task_variables.insert(
+            VariableName::Input(Cow::Borrowed("environment")),
+            "production".to_string(),
+        );
+        let resolved_task = template.resolve_task(TEST_ID_BASE, &cx).unwrap();
+        let spawn_in_terminal = resolved_task.resolved.as_ref().unwrap();
+        assert_eq!(spawn_in_terminal.label, "deploy production");
+        assert_eq!(
+            spawn_in_terminal.args,
+            ["--env", "production", "--branch", "main"]
+        );
+        assert_substituted_variables(
+            &resolved_task,
+            vec![
+                VariableName::Input(Cow::Borrowed("environment")),
+                VariableName::GitBranch,
+            ],
+        );
+    }
+
     #[track_caller]
     fn assert_substituted_variables(resolved_task: &ResolvedTask, mut expected: Vec<VariableName>) {
         let mut resolved_variables = resolved_task
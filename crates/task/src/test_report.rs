@@ -0,0 +1,588 @@
+//! Test reports, the structured results of the tests run by a task, shown in the test explorer.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use collections::HashMap;
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json_lenient::Value;
+
+/// Where and in which format a task reports the results of the tests it runs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TestReport {
+    /// Format of the report.
+    pub format: TestReportFormat,
+    /// Path of the file the report is written to, relative to the task's working directory or absolute.
+    /// The report is read from the output of the task when omitted.
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+/// A format of structured test results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TestReportFormat {
+    /// The default output of the Rust test harness, as printed by `cargo test`.
+    Libtest,
+    /// JSON lines of the Rust test harness, run with `-Z unstable-options --format json`.
+    /// The option is unstable, so it needs a nightly toolchain.
+    LibtestJson,
+    /// JSON lines of `go test -json`.
+    GoTestJson,
+    /// JUnit XML, such as written by `pytest --junitxml`.
+    JunitXml,
+    /// The JSON report of `jest --json`, or of a compatible runner such as Vitest.
+    JestJson,
+}
+
+/// The outcome of a test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TestStatus {
+    /// The test passed.
+    Passed,
+    /// The test failed, or could not run.
+    Failed,
+    /// The test was skipped or ignored.
+    Skipped,
+}
+
+/// The result of a test, as reported by a test runner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestResult {
+    /// Name of the test, without the suites containing it, e.g. the name of a test function.
+    pub name: String,
+    /// Names of the suites containing the test, outermost first, such as its module path or class.
+    pub suite: Vec<String>,
+    /// Outcome of the test.
+    pub status: TestStatus,
+    /// Failure message or output of the test, if any.
+    pub message: Option<String>,
+    /// File of the test, relative to the task's working directory or absolute, if reported.
+    pub path: Option<PathBuf>,
+    /// 0-based row of the test in its file, if reported.
+    pub row: Option<u32>,
+}
+
+impl TestReportFormat {
+    /// Parses the results of the tests in a report of this format.
+    /// Lines that are not part of the report, such as build output printed along with it, are ignored.
+    pub fn parse(self, report: &str) -> anyhow::Result<Vec<TestResult>> {
+        match self {
+            Self::Libtest => Ok(parse_libtest(report)),
+            Self::LibtestJson => Ok(parse_libtest_json(report)),
+            Self::GoTestJson => Ok(parse_go_test_json(report)),
+            Self::JunitXml => parse_junit_xml(report),
+            Self::JestJson => parse_jest_json(report),
+        }
+    }
+}
+
+fn json_lines(report: &str) -> impl Iterator<Item = Value> + '_ {
+    report
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json_lenient::from_str::<Value>(line).ok())
+}
+
+fn parse_libtest(report: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    let mut outputs = HashMap::<String, String>::default();
+    let mut output_of = None;
+    for line in report.lines() {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|line| line.strip_suffix(" stdout ----"))
+        {
+            output_of = Some(name.to_string());
+            continue;
+        }
+        if let Some(name) = &output_of {
+            // The output of failed tests is listed before the names of the failed tests.
+            if line == "failures:" || line == "successes:" {
+                output_of = None;
+            } else {
+                let output = outputs.entry(name.clone()).or_default();
+                output.push_str(line);
+                output.push('\n');
+            }
+            continue;
+        }
+
+        let Some((name, outcome)) = line
+            .strip_prefix("test ")
+            .and_then(|line| line.split_once(" ... "))
+        else {
+            continue;
+        };
+        let status = match outcome.trim() {
+            "ok" => TestStatus::Passed,
+            "FAILED" => TestStatus::Failed,
+            outcome if outcome.starts_with("ignored") => TestStatus::Skipped,
+            _ => continue,
+        };
+        let name = name.strip_suffix(" - should panic").unwrap_or(name);
+        results.push((name.to_string(), status));
+    }
+
+    results
+        .into_iter()
+        .filter_map(|(full_name, status)| {
+            let mut suite = full_name
+                .split("::")
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            let name = suite.pop()?;
+            Some(TestResult {
+                name,
+                suite,
+                status,
+                message: outputs
+                    .remove(&full_name)
+                    .map(|output| output.trim_end().to_string())
+                    .filter(|_| status == TestStatus::Failed),
+                path: None,
+                row: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_libtest_json(report: &str) -> Vec<TestResult> {
+    json_lines(report)
+        .filter(|event| event["type"] == "test")
+        .filter_map(|event| {
+            let status = match event["event"].as_str()? {
+                "ok" => TestStatus::Passed,
+                "failed" | "timeout" => TestStatus::Failed,
+                "ignored" => TestStatus::Skipped,
+                _ => return None,
+            };
+            let mut suite = event["name"]
+                .as_str()?
+                .split("::")
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            let name = suite.pop()?;
+            Some(TestResult {
+                name,
+                suite,
+                status,
+                message: event["stdout"]
+                    .as_str()
+                    .or(event["message"].as_str())
+                    .map(str::to_string),
+                path: None,
+                row: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_go_test_json(report: &str) -> Vec<TestResult> {
+    let mut outputs = HashMap::<(String, String), String>::default();
+    let mut results = Vec::new();
+    for event in json_lines(report) {
+        let (Some(package), Some(test), Some(action)) = (
+            event["Package"].as_str(),
+            event["Test"].as_str(),
+            event["Action"].as_str(),
+        ) else {
+            continue;
+        };
+        let key = (package.to_string(), test.to_string());
+        let status = match action {
+            "output" => {
+                if let Some(output) = event["Output"].as_str() {
+                    outputs.entry(key).or_default().push_str(output);
+                }
+                continue;
+            }
+            "pass" => TestStatus::Passed,
+            "fail" => TestStatus::Failed,
+            "skip" => TestStatus::Skipped,
+            _ => continue,
+        };
+        let output = outputs.remove(&key);
+        results.push(TestResult {
+            name: key.1,
+            suite: vec![key.0],
+            status,
+            message: output.filter(|_| status == TestStatus::Failed),
+            path: None,
+            row: None,
+        });
+    }
+    results
+}
+
+fn parse_jest_json(report: &str) -> anyhow::Result<Vec<TestResult>> {
+    let report = serde_json_lenient::from_str::<Value>(report.trim())
+        .ok()
+        .or_else(|| json_lines(report).find(|value| value.get("testResults").is_some()))
+        .ok_or_else(|| anyhow!("no Jest JSON report found"))?;
+    let test_files = report["testResults"]
+        .as_array()
+        .context("invalid Jest JSON report")?;
+
+    let mut results = Vec::new();
+    for test_file in test_files {
+        let path = test_file["name"].as_str().map(PathBuf::from);
+        let Some(assertions) = test_file["assertionResults"].as_array() else {
+            continue;
+        };
+        for assertion in assertions {
+            let Some(name) = assertion["title"].as_str() else {
+                continue;
+            };
+            let status = match assertion["status"].as_str() {
+                Some("passed") => TestStatus::Passed,
+                Some("failed") => TestStatus::Failed,
+                _ => TestStatus::Skipped,
+            };
+            let suite = assertion["ancestorTitles"]
+                .as_array()
+                .map(|titles| {
+                    titles
+                        .iter()
+                        .filter_map(|title| Some(title.as_str()?.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let message = assertion["failureMessages"]
+                .as_array()
+                .map(|messages| {
+                    messages
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .filter(|message| !message.is_empty());
+            results.push(TestResult {
+                name: name.to_string(),
+                suite,
+                status,
+                message,
+                path: path.clone(),
+                row: assertion["location"]["line"]
+                    .as_u64()
+                    .map(|line| line.saturating_sub(1) as u32),
+            });
+        }
+    }
+    Ok(results)
+}
+
+fn parse_junit_xml(report: &str) -> anyhow::Result<Vec<TestResult>> {
+    // Output printed before the report, such as by a build, is skipped.
+    let start = report
+        .find("<?xml")
+        .or_else(|| report.find("<testsuite"))
+        .unwrap_or(0);
+    let mut reader = Reader::from_str(&report[start..]);
+    let mut results = Vec::new();
+    let mut test_case = None;
+    // The message and text of the failure, error or skipped element being read, if any.
+    let mut outcome: Option<(TestStatus, Option<String>, String)> = None;
+    loop {
+        let event = reader.read_event().context("invalid JUnit XML report")?;
+        let is_empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(element) | Event::Empty(element) => {
+                match element.local_name().as_ref() {
+                    b"testcase" => {
+                        let attributes = xml_attributes(&element)?;
+                        let Some(result) = test_result(attributes) else {
+                            continue;
+                        };
+                        if is_empty {
+                            results.push(result);
+                        } else {
+                            test_case = Some(result);
+                        }
+                    }
+                    name @ (b"failure" | b"error" | b"skipped") => {
+                        let Some(result) = test_case.as_mut() else {
+                            continue;
+                        };
+                        let status = if name == b"skipped" {
+                            TestStatus::Skipped
+                        } else {
+                            TestStatus::Failed
+                        };
+                        // A failure takes precedence over skipping, as the test did not pass.
+                        if result.status == TestStatus::Failed {
+                            continue;
+                        }
+                        let message = xml_attributes(&element)?.remove("message");
+                        if is_empty {
+                            result.status = status;
+                            result.message = message;
+                        } else {
+                            outcome = Some((status, message, String::new()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, _, outcome_text)) = outcome.as_mut() {
+                    outcome_text.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(text) => {
+                if let Some((_, _, outcome_text)) = outcome.as_mut() {
+                    outcome_text.push_str(&String::from_utf8_lossy(&text));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"testcase" => results.extend(test_case.take()),
+                b"failure" | b"error" | b"skipped" => {
+                    if let Some(((status, message, text), result)) =
+                        outcome.take().zip(test_case.as_mut())
+                    {
+                        let text = text.trim();
+                        result.status = status;
+                        result.message = match message {
+                            Some(message) if text.is_empty() => Some(message),
+                            Some(message) if status == TestStatus::Failed => {
+                                Some(format!("{message}\n{text}"))
+                            }
+                            Some(message) => Some(message),
+                            None if text.is_empty() => None,
+                            None => Some(text.to_string()),
+                        };
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    for result in &mut results {
+        result.message = result.message.take().filter(|message| !message.is_empty());
+    }
+    Ok(results)
+}
+
+/// Returns the passed result of the test case with the given attributes, if it has a name.
+fn test_result(mut attributes: HashMap<String, String>) -> Option<TestResult> {
+    Some(TestResult {
+        name: attributes.remove("name")?,
+        suite: attributes
+            .get("classname")
+            .map(|classname| {
+                classname
+                    .split('.')
+                    .filter(|part| !part.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        status: TestStatus::Passed,
+        message: None,
+        path: attributes.get("file").map(PathBuf::from),
+        row: attributes
+            .get("line")
+            .and_then(|line| line.parse::<u32>().ok())
+            .map(|line| line.saturating_sub(1)),
+    })
+}
+
+fn xml_attributes(element: &BytesStart) -> anyhow::Result<HashMap<String, String>> {
+    element
+        .attributes()
+        .map(|attribute| {
+            let attribute = attribute?;
+            let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_string();
+            Ok((key, attribute.unescape_value()?.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_libtest() {
+        let report = r#"
+   Compiling example v0.1.0
+     Running unittests src/lib.rs (target/debug/deps/example-0123456789abcdef)
+
+running 4 tests
+test slow ... ignored, too slow
+test tests::adds ... ok
+test tests::panics - should panic ... ok
+test tests::subtracts ... FAILED
+
+failures:
+
+---- tests::subtracts stdout ----
+thread 'tests::subtracts' panicked at src/lib.rs:12:9:
+assertion failed
+
+
+failures:
+    tests::subtracts
+
+test result: FAILED. 2 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+"#;
+        let results = TestReportFormat::Libtest.parse(report).unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.suite.join("::"), result.name.as_str(), result.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (String::new(), "slow", TestStatus::Skipped),
+                ("tests".to_string(), "adds", TestStatus::Passed),
+                ("tests".to_string(), "panics", TestStatus::Passed),
+                ("tests".to_string(), "subtracts", TestStatus::Failed),
+            ]
+        );
+        assert_eq!(
+            results[3].message.as_deref(),
+            Some("thread 'tests::subtracts' panicked at src/lib.rs:12:9:\nassertion failed")
+        );
+    }
+
+    #[test]
+    fn test_libtest_json() {
+        let report = r#"
+   Compiling example v0.1.0
+{ "type": "suite", "event": "started", "test_count": 3 }
+{ "type": "test", "event": "started", "name": "tests::adds" }
+{ "type": "test", "name": "tests::adds", "event": "ok" }
+{ "type": "test", "name": "tests::subtracts", "event": "failed", "stdout": "assertion failed\n" }
+{ "type": "test", "name": "slow", "event": "ignored" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1 }
+"#;
+        let results = TestReportFormat::LibtestJson.parse(report).unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.suite.join("::"), result.name.as_str(), result.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tests".to_string(), "adds", TestStatus::Passed),
+                ("tests".to_string(), "subtracts", TestStatus::Failed),
+                (String::new(), "slow", TestStatus::Skipped),
+            ]
+        );
+        assert_eq!(results[1].message.as_deref(), Some("assertion failed\n"));
+    }
+
+    #[test]
+    fn test_go_test_json() {
+        let report = r#"
+{"Action":"run","Package":"example.com/calc","Test":"TestAdd"}
+{"Action":"output","Package":"example.com/calc","Test":"TestAdd","Output":"=== RUN   TestAdd\n"}
+{"Action":"pass","Package":"example.com/calc","Test":"TestAdd","Elapsed":0}
+{"Action":"output","Package":"example.com/calc","Test":"TestSub","Output":"calc_test.go:12: got 1\n"}
+{"Action":"fail","Package":"example.com/calc","Test":"TestSub","Elapsed":0}
+{"Action":"fail","Package":"example.com/calc","Elapsed":0.1}
+"#;
+        let results = TestReportFormat::GoTestJson.parse(report).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "TestAdd".to_string(),
+                    suite: vec!["example.com/calc".to_string()],
+                    status: TestStatus::Passed,
+                    message: None,
+                    path: None,
+                    row: None,
+                },
+                TestResult {
+                    name: "TestSub".to_string(),
+                    suite: vec!["example.com/calc".to_string()],
+                    status: TestStatus::Failed,
+                    message: Some("calc_test.go:12: got 1\n".to_string()),
+                    path: None,
+                    row: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let report = r#"<?xml version="1.0" encoding="utf-8"?>
+<testsuites>
+  <testsuite name="pytest" tests="3">
+    <testcase classname="tests.test_calc" name="test_add" file="tests/test_calc.py" line="3" time="0.001" />
+    <testcase classname="tests.test_calc.TestCalc" name="test_sub" time="0.002">
+      <failure message="assert 1 &gt; 2 &amp;&amp; x > 0">def test_sub():
+&gt;       assert 1 &#62; 2</failure>
+    </testcase>
+    <testcase classname="tests.test_calc" name="test_div">
+      <skipped type="pytest.skip" message="not implemented" />
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+        let results = TestReportFormat::JunitXml.parse(report).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[0],
+            TestResult {
+                name: "test_add".to_string(),
+                suite: vec!["tests".to_string(), "test_calc".to_string()],
+                status: TestStatus::Passed,
+                message: None,
+                path: Some(PathBuf::from("tests/test_calc.py")),
+                row: Some(2),
+            }
+        );
+        assert_eq!(results[1].status, TestStatus::Failed);
+        assert_eq!(
+            results[1].suite.last().map(String::as_str),
+            Some("TestCalc")
+        );
+        assert_eq!(
+            results[1].message.as_deref(),
+            Some("assert 1 > 2 && x > 0\ndef test_sub():\n>       assert 1 > 2")
+        );
+        assert_eq!(results[2].status, TestStatus::Skipped);
+        assert_eq!(results[2].message.as_deref(), Some("not implemented"));
+    }
+
+    #[test]
+    fn test_jest_json() {
+        let report = r#"
+> example@1.0.0 test
+> jest --json
+{"numTotalTests":2,"testResults":[{"name":"/project/calc.test.ts","assertionResults":[{"ancestorTitles":["calc"],"title":"adds","status":"passed","failureMessages":[],"location":{"line":4,"column":3}},{"ancestorTitles":["calc"],"title":"subtracts","status":"failed","failureMessages":["Expected: 2"]}]}]}
+"#;
+        let results = TestReportFormat::JestJson.parse(report).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "adds".to_string(),
+                    suite: vec!["calc".to_string()],
+                    status: TestStatus::Passed,
+                    message: None,
+                    path: Some(PathBuf::from("/project/calc.test.ts")),
+                    row: Some(3),
+                },
+                TestResult {
+                    name: "subtracts".to_string(),
+                    suite: vec!["calc".to_string()],
+                    status: TestStatus::Failed,
+                    message: Some("Expected: 2".to_string()),
+                    path: Some(PathBuf::from("/project/calc.test.ts")),
+                    row: None,
+                },
+            ]
+        );
+    }
+}
@@ -3,7 +3,10 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    CustomProblemMatcher, DependsOrder, ProblemMatcher, ProblemPattern, ProblemPatterns,
+    ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +25,130 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    depends_on: Option<DependsOn>,
+    depends_order: Option<DependsOrder>,
+    #[serde(default)]
+    is_background: bool,
+    problem_matcher: Option<VsCodeProblemMatchers>,
+}
+
+/// Code accepts either a single task label or a list of them.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    Single(VsCodeProblemMatcher),
+    Multiple(Vec<VsCodeProblemMatcher>),
+}
+
+/// Either the name of a matcher contributed to Code, or a matcher definition.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Custom(VsCodeCustomProblemMatcher),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeCustomProblemMatcher {
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<String>,
+    pattern: Option<VsCodeProblemPatterns>,
+    /// A named matcher to use the patterns of.
+    base: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    loop_: bool,
+}
+
+impl VsCodeProblemMatcher {
+    /// Converts the matcher, if it is a definition or a named matcher Zed has a counterpart of.
+    fn to_zed_format(self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Named(name) => built_in_counterpart(&name),
+            Self::Custom(matcher) => {
+                let Some(pattern) = matcher.pattern else {
+                    return built_in_counterpart(matcher.base.as_deref()?);
+                };
+                let pattern = match pattern {
+                    VsCodeProblemPatterns::Single(pattern) => {
+                        ProblemPatterns::Single(pattern.to_zed_format())
+                    }
+                    VsCodeProblemPatterns::Multiple(patterns) => ProblemPatterns::Multiple(
+                        patterns
+                            .into_iter()
+                            .map(VsCodeProblemPattern::to_zed_format)
+                            .collect(),
+                    ),
+                };
+                let severity = match matcher.severity.as_deref() {
+                    Some("warning") => ProblemSeverity::Warning,
+                    Some("info") => ProblemSeverity::Info,
+                    _ => ProblemSeverity::Error,
+                };
+                Some(ProblemMatcher::Custom(CustomProblemMatcher {
+                    source: matcher.source.or(matcher.owner),
+                    severity,
+                    pattern,
+                }))
+            }
+        }
+    }
+}
+
+impl VsCodeProblemPattern {
+    fn to_zed_format(self) -> ProblemPattern {
+        ProblemPattern {
+            regexp: self.regexp,
+            file: self.file,
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity,
+            code: self.code,
+            message: self.message,
+            loop_: self.loop_,
+        }
+    }
+}
+
+fn built_in_counterpart(name: &str) -> Option<ProblemMatcher> {
+    let name = match name {
+        "$tsc" | "$gcc" | "$rustc" | "$eslint-compact" | "$eslint-stylish" => name,
+        // Watch mode reports errors the same way, delimited by the start and end of each compilation.
+        "$tsc-watch" => "$tsc",
+        _ => return None,
+    };
+    Some(ProblemMatcher::BuiltIn(name.to_string()))
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -85,21 +212,18 @@ impl EnvVariableReplacer {
 
 impl VsCodeTaskDefinition {
     fn to_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on = match self.depends_on {
+            Some(DependsOn::Single(label)) => vec![label],
+            Some(DependsOn::Multiple(labels)) => labels,
+            None => Vec::new(),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` might not be set in tasks that only use `dependsOn` to run other tasks, hence command is an Option.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -108,6 +232,19 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order: self.depends_order.unwrap_or_default(),
+            background: self.is_background,
+            problem_matchers: match self.problem_matcher {
+                Some(VsCodeProblemMatchers::Single(matcher)) => {
+                    matcher.to_zed_format().into_iter().collect()
+                }
+                Some(VsCodeProblemMatchers::Multiple(matchers)) => matchers
+                    .into_iter()
+                    .filter_map(VsCodeProblemMatcher::to_zed_format)
+                    .collect(),
+                None => Vec::new(),
+            },
             ..Default::default()
         };
         if let Some(options) = self.options {
@@ -134,6 +271,19 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::WorktreeRoot.to_string(),
             ),
             ("file".to_owned(), VariableName::File.to_string()),
+            (
+                "relativeFile".to_owned(),
+                VariableName::RelativeFile.to_string(),
+            ),
+            (
+                "fileBasename".to_owned(),
+                VariableName::Filename.to_string(),
+            ),
+            ("fileDirname".to_owned(), VariableName::Dirname.to_string()),
+            (
+                "fileBasenameNoExtension".to_owned(),
+                VariableName::Stem.to_string(),
+            ),
             ("lineNumber".to_owned(), VariableName::Row.to_string()),
             (
                 "selectedText".to_owned(),
@@ -154,8 +304,11 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        vscode_format::{Command, VsCodeTaskDefinition},
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{
+            Command, DependsOn, VsCodeCustomProblemMatcher, VsCodeProblemMatcher,
+            VsCodeProblemMatchers, VsCodeTaskDefinition,
+        },
+        ProblemMatcher, TaskTemplate, TaskTemplates, VsCodeTaskFile,
     };
 
     use super::EnvVariableReplacer;
@@ -212,6 +365,12 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![
+                    VsCodeProblemMatcher::Named("$tsc".to_string()),
+                ])),
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -226,6 +385,12 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                is_background: true,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![
+                    VsCodeProblemMatcher::Named("$tsc-watch".to_string()),
+                ])),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -234,6 +399,12 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![
+                    VsCodeProblemMatcher::Named("$tsc".to_string()),
+                ])),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -242,6 +413,12 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![
+                    VsCodeProblemMatcher::Named("$tsc".to_string()),
+                ])),
             },
         ];
 
@@ -257,6 +434,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +446,22 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                background: true,
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -301,6 +483,18 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                is_background: true,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Custom(
+                    VsCodeCustomProblemMatcher {
+                        owner: None,
+                        source: None,
+                        severity: None,
+                        pattern: None,
+                        base: Some("$tsc-watch".to_string()),
+                    },
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -309,6 +503,18 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Custom(
+                    VsCodeCustomProblemMatcher {
+                        owner: None,
+                        source: None,
+                        severity: None,
+                        pattern: None,
+                        base: Some("$tsc".to_string()),
+                    },
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -318,6 +524,12 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -327,6 +539,12 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -335,18 +553,48 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Custom(
+                    VsCodeCustomProblemMatcher {
+                        owner: None,
+                        source: None,
+                        severity: None,
+                        pattern: None,
+                        base: Some("$tsc".to_string()),
+                    },
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                is_background: false,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(VsCodeProblemMatcher::Named(
+                    "$rustc".to_string(),
+                ))),
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -360,28 +608,49 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                background: true,
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$tsc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matchers: vec![ProblemMatcher::BuiltIn("$rustc".to_string())],
                 ..Default::default()
             },
         ];
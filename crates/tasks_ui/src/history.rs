@@ -0,0 +1,298 @@
+use std::{sync::Arc, time::Duration};
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusableView, InteractiveElement,
+    IntoElement, Model, ParentElement, Render, SharedString, Styled, Subscription, Task, View,
+    ViewContext, VisualContext, WeakView,
+};
+use picker::{highlighted_match_with_paths::HighlightedText, Picker, PickerDelegate};
+use project::{Inventory, TaskRun, TaskRunStatus};
+use ui::{
+    h_flex, v_flex, Color, Icon, IconName, IconSize, Label, LabelCommon, LabelSize, ListItem,
+    ListItemSpacing, RenderOnce, Selectable, WindowContext,
+};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::schedule_resolved_task;
+
+actions!(task, [History]);
+
+/// A modal listing the recorded runs of the tasks, from the most recent one, that re-runs the selected one
+/// with the context it was originally resolved with.
+pub(crate) struct TaskHistoryDelegate {
+    workspace: WeakView<Workspace>,
+    /// The recorded runs, from the most recent one.
+    runs: Vec<TaskRun>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    /// The run to select once the runs get listed for the first time.
+    initially_selected_run: Option<TaskRun>,
+}
+
+impl TaskHistoryDelegate {
+    fn new(
+        inventory: Model<Inventory>,
+        initially_selected_run: Option<TaskRun>,
+        workspace: WeakView<Workspace>,
+        cx: &AppContext,
+    ) -> Self {
+        Self {
+            workspace,
+            runs: inventory.read(cx).task_runs().rev().cloned().collect(),
+            matches: Vec::new(),
+            selected_index: 0,
+            initially_selected_run,
+        }
+    }
+}
+
+pub(crate) struct TaskHistoryModal {
+    picker: View<Picker<TaskHistoryDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TaskHistoryModal {
+    pub(crate) fn new(
+        inventory: Model<Inventory>,
+        initially_selected_run: Option<TaskRun>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let picker = cx.new_view(|cx| {
+            let delegate =
+                TaskHistoryDelegate::new(inventory, initially_selected_run, workspace, cx);
+            Picker::uniform_list(delegate, cx)
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl Render for TaskHistoryModal {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TaskHistoryModal")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskHistoryModal {}
+
+impl FocusableView for TaskHistoryModal {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for TaskHistoryModal {}
+
+/// Opens the history of the task runs of the workspace's project.
+pub(crate) fn toggle_history(
+    workspace: &mut Workspace,
+    initially_selected_run: Option<TaskRun>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let inventory = workspace.project().read(cx).task_inventory().clone();
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(cx, |cx| {
+        TaskHistoryModal::new(inventory, initially_selected_run, workspace_handle, cx)
+    });
+}
+
+impl PickerDelegate for TaskHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        Arc::from("Re-run a task...")
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .runs
+            .iter()
+            .enumerate()
+            .map(|(id, run)| StringMatchCandidate::new(id, run.task.resolved_label.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.0,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    let initially_selected_index =
+                        delegate.initially_selected_run.take().and_then(|run| {
+                            delegate
+                                .matches
+                                .iter()
+                                .position(|hit| delegate.runs[hit.candidate_id] == run)
+                        });
+                    delegate.selected_index = match initially_selected_index {
+                        Some(index) => index,
+                        None => delegate
+                            .selected_index
+                            .min(delegate.matches.len().saturating_sub(1)),
+                    };
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, omit_history_entry: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(run) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.runs.get(hit.candidate_id))
+        else {
+            return;
+        };
+        let task_source_kind = run.task_source_kind.clone();
+        let Some(task) = run
+            .task
+            .original_task()
+            .resolve_task(&task_source_kind.to_id_base(), run.task.task_context())
+        else {
+            return;
+        };
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                schedule_resolved_task(workspace, task_source_kind, task, omit_history_entry, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let run = self.runs.get(hit.candidate_id)?;
+
+        let highlighted_label = HighlightedText {
+            text: hit.string.clone(),
+            highlight_positions: hit.positions.clone(),
+            char_count: hit.string.chars().count(),
+        };
+        let (icon, color, status) = match run.status {
+            TaskRunStatus::Running => (IconName::Play, Color::Warning, "running".to_string()),
+            TaskRunStatus::Finished {
+                success,
+                exit_code,
+                duration,
+            } => {
+                let duration = format_duration(duration);
+                let status = match exit_code {
+                    Some(exit_code) => format!("exit {exit_code} · {duration}"),
+                    None if success => duration,
+                    None => format!("killed · {duration}"),
+                };
+                if success {
+                    (IconName::Check, Color::Success, status)
+                } else {
+                    (IconName::XCircle, Color::Error, status)
+                }
+            }
+            TaskRunStatus::Unknown => (IconName::Dash, Color::Muted, "unknown".to_string()),
+        };
+        let started_at = time_format::format_localized_timestamp(
+            run.started_at.into(),
+            time::OffsetDateTime::now_utc(),
+            cx.local_timezone(),
+            time_format::TimestampFormat::Relative,
+        );
+        let details = match &run.cwd {
+            Some(cwd) => format!("{started_at} · {}", cwd.display()),
+            None => started_at,
+        };
+
+        Some(
+            ListItem::new(SharedString::from(format!("task-history-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(Icon::new(icon).color(color).size(IconSize::Small))
+                .end_slot(Label::new(status).size(LabelSize::Small).color(color))
+                .child(
+                    v_flex().child(highlighted_label.render(cx)).child(
+                        h_flex().child(
+                            Label::new(details)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line(),
+                        ),
+                    ),
+                ),
+        )
+    }
+}
+
+/// Formats the duration of a task run, e.g. "250ms", "4.2s" or "3m 5s".
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else if duration < Duration::from_secs(60) {
+        format!("{:.1}s", duration.as_secs_f32())
+    } else {
+        let seconds = duration.as_secs();
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(250)), "250ms");
+        assert_eq!(format_duration(Duration::from_millis(4_230)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m 5s");
+    }
+}
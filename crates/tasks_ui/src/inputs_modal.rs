@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+
+use editor::Editor;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription, View,
+    VisualContext, WeakView,
+};
+use project::TaskSourceKind;
+use task::{ResolvedTask, TaskContext, VariableName};
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::schedule_resolved_task;
+
+/// A modal prompting for the values of the inputs of a task, one after another, before spawning it.
+pub(crate) struct TaskInputsModal {
+    input_editor: View<Editor>,
+    workspace: WeakView<Workspace>,
+    task_source_kind: TaskSourceKind,
+    task: ResolvedTask,
+    task_context: TaskContext,
+    current_input: usize,
+    omit_history: bool,
+    _subscription: Subscription,
+}
+
+impl ModalView for TaskInputsModal {}
+
+impl FocusableView for TaskInputsModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.input_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskInputsModal {}
+
+impl TaskInputsModal {
+    pub(crate) fn new(
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+        omit_history: bool,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let input_editor = cx.new_view(Editor::single_line);
+        let _subscription = cx.subscribe(&input_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        let mut this = Self {
+            input_editor,
+            workspace,
+            task_source_kind,
+            task_context: task.task_context().clone(),
+            task,
+            current_input: 0,
+            omit_history,
+            _subscription,
+        };
+        this.prompt_next_input(cx);
+        this
+    }
+
+    /// Skips the inputs that already have a value in the task context, filling the editor with the default
+    /// value of the next one. Returns `false` when there are no more inputs to prompt for.
+    fn prompt_next_input(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let inputs = &self.task.original_task().inputs;
+        while let Some(input) = inputs.get(self.current_input) {
+            let variable = VariableName::Input(Cow::Owned(input.name.clone()));
+            if self.task_context.task_variables.get(&variable).is_none() {
+                let default = input.default.clone();
+                self.input_editor.update(cx, |editor, cx| {
+                    editor.set_text(default, cx);
+                    editor.select_all(&editor::actions::SelectAll, cx);
+                });
+                cx.notify();
+                return true;
+            }
+            self.current_input += 1;
+        }
+        false
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some(input) = self.task.original_task().inputs.get(self.current_input) else {
+            return;
+        };
+        let value = self.input_editor.read(cx).text(cx);
+        self.task_context
+            .task_variables
+            .insert(VariableName::Input(Cow::Owned(input.name.clone())), value);
+        if self.prompt_next_input(cx) {
+            return;
+        }
+
+        cx.emit(DismissEvent);
+        let Some(task) = self
+            .task
+            .original_task()
+            .resolve_task(&self.task_source_kind.to_id_base(), &self.task_context)
+        else {
+            return;
+        };
+        let task_source_kind = self.task_source_kind.clone();
+        let omit_history = self.omit_history;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                schedule_resolved_task(workspace, task_source_kind, task, omit_history, cx);
+            })
+            .ok();
+    }
+}
+
+impl Render for TaskInputsModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let inputs = &self.task.original_task().inputs;
+        let prompt = inputs
+            .get(self.current_input)
+            .map(|input| {
+                input
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| input.name.clone())
+            })
+            .unwrap_or_default();
+
+        div()
+            .elevation_2(cx)
+            .key_context("TaskInputsModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.input_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new(prompt).color(Color::Muted))
+                            .child(
+                                Label::new(format!(
+                                    "{}/{} · {}",
+                                    self.current_input + 1,
+                                    inputs.len(),
+                                    self.task.resolved_label
+                                ))
+                                .color(Color::Muted),
+                            ),
+                    ),
+            )
+    }
+}
@@ -1,16 +1,29 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
 
 use ::settings::Settings;
-use editor::Editor;
+use anyhow::Context as _;
+use editor::{actions::SpawnRunnable, Editor};
 use gpui::{AppContext, ViewContext, WindowContext};
-use language::{Language, Point};
+use inputs_modal::TaskInputsModal;
+use language::{Language, Point, RunnableRange, ToPoint as _};
 use modal::{Spawn, TasksModal};
-use project::{Location, TaskSourceKind, WorktreeId};
-use task::{ResolvedTask, TaskContext, TaskTemplate, TaskVariables, VariableName};
+use persistence::{SerializedTaskRun, TASK_HISTORY_DB};
+use project::{
+    InventoryEvent, Location, ProjectPath, TaskRun, TaskRunStatus, TaskSourceKind, WorktreeId,
+};
+use task::{ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate, TaskVariables, VariableName};
 use util::ResultExt;
 use workspace::Workspace;
 
+mod history;
+mod inputs_modal;
 mod modal;
+pub mod persistence;
 mod settings;
 mod status_indicator;
 
@@ -19,9 +32,14 @@ pub use status_indicator::TaskStatusIndicator;
 pub fn init(cx: &mut AppContext) {
     settings::TaskSettings::register(cx);
     cx.observe_new_views(
-        |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            persist_task_runs(workspace, cx);
             workspace
                 .register_action(spawn_task_or_modal)
+                .register_action(spawn_runnable)
+                .register_action(|workspace, _: &history::History, cx| {
+                    history::toggle_history(workspace, None, cx)
+                })
                 .register_action(move |workspace, action: &modal::Rerun, cx| {
                     if let Some((task_source_kind, mut last_scheduled_task)) =
                         workspace.project().update(cx, |project, cx| {
@@ -121,6 +139,83 @@ fn spawn_task_with_name(name: String, cx: &mut ViewContext<Workspace>) {
     .detach();
 }
 
+/// Spawns the first task tagged with one of the tags of the runnable on the cursor line of the active
+/// editor that resolves with the captures of the runnable added to the task context, or opens the
+/// tasks modal with that context when there is no such task.
+fn spawn_runnable(workspace: &mut Workspace, _: &SpawnRunnable, cx: &mut ViewContext<Workspace>) {
+    let Some(editor) = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+    else {
+        return;
+    };
+    let Some((buffer, runnable)) = editor.read(cx).runnable_at_cursor(cx) else {
+        return;
+    };
+    let position = buffer.read(cx).anchor_after(runnable.run_range.start);
+    let location = Location {
+        buffer,
+        range: position..position,
+    };
+    match resolve_runnable_task(workspace, location, runnable, |_| true, cx) {
+        Some((task_source_kind, resolved_task)) => {
+            schedule_resolved_task(workspace, task_source_kind, resolved_task, false, cx)
+        }
+        None => {
+            let inventory = workspace.project().read(cx).task_inventory().clone();
+            let task_context = task_context(workspace, cx);
+            let workspace_handle = workspace.weak_handle();
+            workspace.toggle_modal(cx, |cx| {
+                TasksModal::new(inventory, task_context, workspace_handle, cx)
+            })
+        }
+    }
+}
+
+/// Resolves the first task accepted by `filter` that is tagged with one of the tags of the runnable
+/// and resolves in the context of its location, with the captures of the runnable added to the context
+/// as custom variables.
+pub fn resolve_runnable_task(
+    workspace: &Workspace,
+    location: Location,
+    runnable: RunnableRange,
+    filter: impl Fn(&TaskTemplate) -> bool,
+    cx: &mut WindowContext,
+) -> Option<(TaskSourceKind, ResolvedTask)> {
+    let language = location.buffer.read(cx).language_at(location.range.start);
+    let worktree = location
+        .buffer
+        .read(cx)
+        .file()
+        .map(|file| WorktreeId::from_usize(file.worktree_id()));
+
+    let mut task_context = location_task_context(workspace, location, cx);
+    for (capture_name, text) in runnable.extra_captures {
+        task_context
+            .task_variables
+            .insert(VariableName::Custom(capture_name.into()), text);
+    }
+
+    let tasks = workspace.project().update(cx, |project, cx| {
+        project.task_inventory().update(cx, |inventory, cx| {
+            inventory.list_tasks(language, worktree, cx)
+        })
+    });
+    tasks.into_iter().find_map(|(task_source_kind, task)| {
+        let is_tagged = task.tags.iter().any(|tag| {
+            runnable
+                .tags
+                .iter()
+                .any(|runnable_tag| *runnable_tag.0 == **tag)
+        });
+        if !is_tagged || !filter(&task) {
+            return None;
+        }
+        let resolved_task = task.resolve_task(&task_source_kind.to_id_base(), &task_context)?;
+        Some((task_source_kind, resolved_task))
+    })
+}
+
 fn active_item_selection_properties(
     workspace: &Workspace,
     cx: &mut WindowContext,
@@ -146,105 +241,155 @@ fn active_item_selection_properties(
 }
 
 fn task_context(workspace: &Workspace, cx: &mut WindowContext<'_>) -> TaskContext {
-    let cwd = task_cwd(workspace, cx).log_err().flatten();
     let current_editor = workspace
         .active_item(cx)
         .and_then(|item| item.act_as::<Editor>(cx));
-    if let Some(current_editor) = current_editor {
-        (|| {
-            let editor = current_editor.read(cx);
-            let selection = editor.selections.newest::<usize>(cx);
-            let (buffer, _, _) = editor
-                .buffer()
-                .read(cx)
-                .point_to_buffer_offset(selection.start, cx)?;
-
-            current_editor.update(cx, |editor, cx| {
-                let snapshot = editor.snapshot(cx);
-                let selection_range = selection.range();
-                let start = snapshot
-                    .display_snapshot
-                    .buffer_snapshot
-                    .anchor_after(selection_range.start)
-                    .text_anchor;
-                let end = snapshot
-                    .display_snapshot
-                    .buffer_snapshot
-                    .anchor_after(selection_range.end)
-                    .text_anchor;
-                let Point { row, column } = snapshot
-                    .display_snapshot
-                    .buffer_snapshot
-                    .offset_to_point(selection_range.start);
-                let row = row + 1;
-                let column = column + 1;
-                let location = Location {
-                    buffer: buffer.clone(),
-                    range: start..end,
-                };
-
-                let current_file = location
-                    .buffer
-                    .read(cx)
-                    .file()
-                    .and_then(|file| file.as_local())
-                    .map(|file| file.abs_path(cx).to_string_lossy().to_string());
-                let worktree_id = location
-                    .buffer
-                    .read(cx)
-                    .file()
-                    .map(|file| WorktreeId::from_usize(file.worktree_id()));
-                let context = buffer
-                    .read(cx)
-                    .language()
-                    .and_then(|language| language.context_provider())
-                    .and_then(|provider| provider.build_context(location, cx).ok());
-
-                let worktree_path = worktree_id.and_then(|worktree_id| {
-                    workspace
-                        .project()
-                        .read(cx)
-                        .worktree_for_id(worktree_id, cx)
-                        .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().to_string())
-                });
+    let location = current_editor.and_then(|current_editor| {
+        let editor = current_editor.read(cx);
+        let selection = editor.selections.newest::<usize>(cx);
+        let (buffer, _, _) = editor
+            .buffer()
+            .read(cx)
+            .point_to_buffer_offset(selection.start, cx)?;
+        let buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+        let start = buffer_snapshot.anchor_after(selection.start).text_anchor;
+        let end = buffer_snapshot.anchor_after(selection.end).text_anchor;
+        Some(Location {
+            buffer,
+            range: start..end,
+        })
+    });
+    match location {
+        Some(location) => location_task_context(workspace, location, cx),
+        None => {
+            let cwd = task_cwd(workspace, cx).log_err().flatten();
+            let project_path = cwd.as_deref().and_then(|cwd| {
+                let (worktree, path) = workspace.project().read(cx).find_local_worktree(cwd, cx)?;
+                Some(ProjectPath {
+                    worktree_id: worktree.read(cx).id(),
+                    path: path.into(),
+                })
+            });
+            TaskContext {
+                cwd,
+                task_variables: project_path
+                    .map(|project_path| git_task_variables(workspace, &project_path, cx))
+                    .unwrap_or_default(),
+            }
+        }
+    }
+}
 
-                let selected_text = buffer.read(cx).chars_for_range(selection_range).collect();
+/// Builds the context of the tasks run at the given location, with the row and column of its start
+/// and its text as the selected text, along with the variables describing its file and repository.
+fn location_task_context(
+    workspace: &Workspace,
+    location: Location,
+    cx: &mut WindowContext<'_>,
+) -> TaskContext {
+    let cwd = task_cwd(workspace, cx).log_err().flatten();
+    let buffer = location.buffer.read(cx);
+    let Point { row, column } = location.range.start.to_point(buffer);
+    let row = row + 1;
+    let column = column + 1;
+    let selected_text = buffer
+        .text_for_range(location.range.clone())
+        .collect::<String>();
+    let current_file = buffer
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx));
+    let project_path = buffer.file().map(|file| ProjectPath {
+        worktree_id: WorktreeId::from_usize(file.worktree_id()),
+        path: file.path().clone(),
+    });
+    let worktree_id = project_path
+        .as_ref()
+        .map(|project_path| project_path.worktree_id);
+    let language_name = buffer.language().map(|language| language.name());
+    let context_provider = buffer
+        .language()
+        .and_then(|language| language.context_provider());
 
-                let mut task_variables = TaskVariables::from_iter([
-                    (VariableName::Row, row.to_string()),
-                    (VariableName::Column, column.to_string()),
-                    (VariableName::SelectedText, selected_text),
-                ]);
-                if let Some(path) = current_file {
-                    task_variables.insert(VariableName::File, path);
-                }
-                if let Some(worktree_path) = worktree_path {
-                    task_variables.insert(VariableName::WorktreeRoot, worktree_path);
-                }
-                if let Some(language_context) = context {
-                    task_variables.extend(language_context);
-                }
+    let worktree_path = worktree_id.and_then(|worktree_id| {
+        workspace
+            .project()
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().to_string())
+    });
 
-                Some(TaskContext {
-                    cwd: cwd.clone(),
-                    task_variables,
-                })
-            })
-        })()
-        .unwrap_or_else(|| TaskContext {
-            cwd,
-            task_variables: Default::default(),
-        })
-    } else {
-        TaskContext {
-            cwd,
-            task_variables: Default::default(),
+    let mut task_variables = TaskVariables::from_iter([
+        (VariableName::Row, row.to_string()),
+        (VariableName::Column, column.to_string()),
+        (VariableName::SelectedText, selected_text),
+    ]);
+    if let Some(path) = current_file {
+        task_variables.insert(VariableName::File, path.to_string_lossy().to_string());
+        if let Some(dirname) = path.parent() {
+            task_variables.insert(VariableName::Dirname, dirname.to_string_lossy().to_string());
+        }
+        if let Some(filename) = path.file_name() {
+            task_variables.insert(
+                VariableName::Filename,
+                filename.to_string_lossy().to_string(),
+            );
         }
+        if let Some(stem) = path.file_stem() {
+            task_variables.insert(VariableName::Stem, stem.to_string_lossy().to_string());
+        }
+    }
+    if let Some(project_path) = &project_path {
+        task_variables.insert(
+            VariableName::RelativeFile,
+            project_path.path.to_string_lossy().to_string(),
+        );
+        task_variables.extend(git_task_variables(workspace, project_path, cx));
+    }
+    if let Some(language_name) = language_name {
+        task_variables.insert(VariableName::Language, language_name.to_string());
+    }
+    if let Some(worktree_path) = worktree_path {
+        task_variables.insert(VariableName::WorktreeRoot, worktree_path);
+    }
+    if let Some(language_context) =
+        context_provider.and_then(|provider| provider.build_context(location, cx).ok())
+    {
+        task_variables.extend(language_context);
+    }
+
+    TaskContext {
+        cwd,
+        task_variables,
     }
 }
 
-fn schedule_task(
+/// Builds the variables with the current branch and HEAD commit of the repository containing the given path.
+fn git_task_variables(
     workspace: &Workspace,
+    project_path: &ProjectPath,
+    cx: &AppContext,
+) -> TaskVariables {
+    let Some(repository) = workspace
+        .project()
+        .read(cx)
+        .worktree_for_id(project_path.worktree_id, cx)
+        .and_then(|worktree| worktree.read(cx).repository_for_path(&project_path.path))
+    else {
+        return TaskVariables::default();
+    };
+    TaskVariables::from_iter(
+        [
+            (VariableName::GitBranch, repository.branch()),
+            (VariableName::GitSha, repository.head_sha()),
+        ]
+        .into_iter()
+        .filter_map(|(variable, value)| Some((variable, value?.to_string()))),
+    )
+}
+
+fn schedule_task(
+    workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
     task_to_resolve: &TaskTemplate,
     task_cx: &TaskContext,
@@ -264,26 +409,204 @@ fn schedule_task(
     }
 }
 
-fn schedule_resolved_task(
-    workspace: &Workspace,
+/// Spawns the resolved task, after resolving the tasks it depends on, and records it in the task history
+/// unless `omit_history` is set.
+/// Tasks with inputs missing from the context they got resolved with are resolved again once the user
+/// has been prompted for them.
+pub fn schedule_resolved_task(
+    workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
     mut resolved_task: ResolvedTask,
     omit_history: bool,
     cx: &mut ViewContext<'_, Workspace>,
 ) {
-    if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
-        if !omit_history {
-            resolved_task.resolved = Some(spawn_in_terminal.clone());
-            workspace.project().update(cx, |project, cx| {
-                project.task_inventory().update(cx, |inventory, _| {
-                    inventory.task_scheduled(task_source_kind, resolved_task);
-                })
-            });
+    let has_missing_inputs = resolved_task.original_task().inputs.iter().any(|input| {
+        let variable = VariableName::Input(input.name.clone().into());
+        resolved_task
+            .task_context()
+            .task_variables
+            .get(&variable)
+            .is_none()
+    });
+    if has_missing_inputs {
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(cx, |cx| {
+            TaskInputsModal::new(
+                task_source_kind,
+                resolved_task,
+                omit_history,
+                workspace_handle,
+                cx,
+            )
+        });
+        return;
+    }
+
+    if let Some(mut spawn_in_terminal) = resolved_task.resolved.take() {
+        if spawn_in_terminal.dependencies.is_empty()
+            && !resolved_task.original_task().depends_on.is_empty()
+        {
+            match resolve_dependencies(
+                workspace,
+                resolved_task.original_task(),
+                resolved_task.task_context(),
+                cx,
+            ) {
+                Ok((dependencies, dependency_tasks)) => {
+                    spawn_in_terminal.dependencies = dependencies;
+                    workspace.project().update(cx, |project, cx| {
+                        project.task_inventory().update(cx, |inventory, _| {
+                            inventory.dependencies_scheduled(dependency_tasks)
+                        })
+                    });
+                }
+                Err(e) => {
+                    workspace.show_error(&e, cx);
+                    return;
+                }
+            }
         }
+        resolved_task.resolved = Some(spawn_in_terminal.clone());
+        workspace.project().update(cx, |project, cx| {
+            project.task_inventory().update(cx, |inventory, cx| {
+                inventory.task_started(task_source_kind.clone(), resolved_task.clone(), cx);
+                if !omit_history {
+                    inventory.task_scheduled(task_source_kind, resolved_task);
+                }
+            })
+        });
         cx.emit(workspace::Event::SpawnTask(spawn_in_terminal));
     }
 }
 
+/// Restores the task runs saved for the workspace into its project's inventory, and saves them again
+/// whenever they change.
+fn persist_task_runs(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        return;
+    }
+    let inventory = project.read(cx).task_inventory().clone();
+    let workspace_id = workspace.database_id();
+
+    let restored_inventory = inventory.downgrade();
+    cx.spawn(|_, mut cx| async move {
+        let saved_runs = TASK_HISTORY_DB
+            .get_task_runs(workspace_id)
+            .log_err()
+            .unwrap_or_default();
+        if saved_runs.is_empty() {
+            return anyhow::Ok(());
+        }
+        restored_inventory.update(&mut cx, |inventory, cx| {
+            let runs = saved_runs
+                .into_iter()
+                .filter_map(|run| deserialize_task_run(run, inventory).log_err())
+                .collect();
+            inventory.restore_task_runs(runs, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+
+    cx.subscribe(&inventory, move |_, inventory, event, cx| {
+        let InventoryEvent::TaskRunsChanged = event;
+        let runs = inventory
+            .read(cx)
+            .task_runs()
+            .filter_map(|run| serialize_task_run(run).log_err())
+            .collect();
+        cx.background_executor()
+            .spawn(TASK_HISTORY_DB.save_task_runs(workspace_id, runs))
+            .detach_and_log_err(cx);
+    })
+    .detach();
+}
+
+fn serialize_task_run(run: &TaskRun) -> anyhow::Result<SerializedTaskRun> {
+    let (duration, exit_code, success) = match run.status {
+        TaskRunStatus::Finished {
+            success,
+            exit_code,
+            duration,
+        } => (Some(duration.as_millis() as i64), exit_code, Some(success)),
+        TaskRunStatus::Running | TaskRunStatus::Unknown => (None, None, None),
+    };
+    let started_at = run.started_at.duration_since(UNIX_EPOCH)?.as_millis() as i64;
+    Ok((
+        run.task_source_kind.to_id_base(),
+        serde_json::to_string(run.task.original_task())?,
+        serde_json::to_string(run.task.task_context())?,
+        run.cwd.clone(),
+        started_at,
+        duration,
+        exit_code,
+        success,
+    ))
+}
+
+/// Resolves a saved task run again, with the context it was originally resolved with.
+fn deserialize_task_run(
+    (id_base, template, context, cwd, started_at, duration, exit_code, success): SerializedTaskRun,
+    inventory: &project::Inventory,
+) -> anyhow::Result<TaskRun> {
+    let template = serde_json::from_str::<TaskTemplate>(&template)?;
+    let task_context = serde_json::from_str::<TaskContext>(&context)?;
+    let task = template
+        .resolve_task(&id_base, &task_context)
+        .with_context(|| format!("failed to resolve saved task {:?}", template.label))?;
+    let status = match (duration, success) {
+        (Some(duration), Some(success)) => TaskRunStatus::Finished {
+            success,
+            exit_code,
+            duration: Duration::from_millis(duration as u64),
+        },
+        _ => TaskRunStatus::Unknown,
+    };
+    Ok(TaskRun {
+        task_source_kind: inventory.source_kind_for_id_base(&id_base),
+        task,
+        cwd,
+        started_at: UNIX_EPOCH + Duration::from_millis(started_at as u64),
+        status,
+    })
+}
+
+/// Resolves the tasks the given one depends on with the context it was resolved with, looking them
+/// up by label among the tasks available for the active item.
+/// Returns the dependencies to spawn along with the task, and every task among them, transitively.
+fn resolve_dependencies(
+    workspace: &Workspace,
+    task: &TaskTemplate,
+    task_context: &TaskContext,
+    cx: &mut ViewContext<'_, Workspace>,
+) -> anyhow::Result<(Vec<SpawnInTerminal>, Vec<(TaskSourceKind, ResolvedTask)>)> {
+    let (worktree, language) = active_item_selection_properties(workspace, cx);
+    let available_tasks = workspace.project().update(cx, |project, cx| {
+        project.task_inventory().update(cx, |inventory, cx| {
+            inventory.list_tasks(language, worktree, cx)
+        })
+    });
+    let found_tasks = RefCell::new(Vec::new());
+    let dependencies = task.resolve_dependencies(task_context, &|label| {
+        let (task_source_kind, template) = available_tasks
+            .iter()
+            .find(|(_, template)| template.label == label)?;
+        found_tasks
+            .borrow_mut()
+            .push((task_source_kind.clone(), template.clone()));
+        Some((task_source_kind.to_id_base(), template.clone()))
+    })?;
+    let dependency_tasks = found_tasks
+        .into_inner()
+        .into_iter()
+        .filter_map(|(task_source_kind, template)| {
+            let task = template.resolve_task(&task_source_kind.to_id_base(), task_context)?;
+            Some((task_source_kind, task))
+        })
+        .collect();
+    Ok((dependencies, dependency_tasks))
+}
+
 fn task_cwd(workspace: &Workspace, cx: &mut WindowContext) -> anyhow::Result<Option<PathBuf>> {
     let project = workspace.project().read(cx);
     let available_worktrees = project
@@ -366,7 +689,10 @@ mod tests {
 
         let rust_language = Arc::new(
             Language::new(
-                LanguageConfig::default(),
+                LanguageConfig {
+                    name: "Rust".into(),
+                    ..LanguageConfig::default()
+                },
                 Some(tree_sitter_rust::language()),
             )
             .with_outline_query(
@@ -380,7 +706,10 @@ mod tests {
 
         let typescript_language = Arc::new(
             Language::new(
-                LanguageConfig::default(),
+                LanguageConfig {
+                    name: "TypeScript".into(),
+                    ..LanguageConfig::default()
+                },
                 Some(tree_sitter_typescript::language_typescript()),
             )
             .with_outline_query(
@@ -432,6 +761,11 @@ mod tests {
                     cwd: Some("/dir".into()),
                     task_variables: TaskVariables::from_iter([
                         (VariableName::File, "/dir/rust/b.rs".into()),
+                        (VariableName::RelativeFile, "rust/b.rs".into()),
+                        (VariableName::Filename, "b.rs".into()),
+                        (VariableName::Dirname, "/dir/rust".into()),
+                        (VariableName::Stem, "b".into()),
+                        (VariableName::Language, "Rust".into()),
                         (VariableName::WorktreeRoot, "/dir".into()),
                         (VariableName::Row, "1".into()),
                         (VariableName::Column, "1".into()),
@@ -449,11 +783,17 @@ mod tests {
                     cwd: Some("/dir".into()),
                     task_variables: TaskVariables::from_iter([
                         (VariableName::File, "/dir/rust/b.rs".into()),
+                        (VariableName::RelativeFile, "rust/b.rs".into()),
+                        (VariableName::Filename, "b.rs".into()),
+                        (VariableName::Dirname, "/dir/rust".into()),
+                        (VariableName::Stem, "b".into()),
+                        (VariableName::Language, "Rust".into()),
                         (VariableName::WorktreeRoot, "/dir".into()),
                         (VariableName::Row, "1".into()),
                         (VariableName::Column, "15".into()),
                         (VariableName::SelectedText, "is_i".into()),
                         (VariableName::Symbol, "this_is_a_rust_file".into()),
+                        (VariableName::SymbolPath, "this_is_a_rust_file".into()),
                     ])
                 }
             );
@@ -466,11 +806,17 @@ mod tests {
                     cwd: Some("/dir".into()),
                     task_variables: TaskVariables::from_iter([
                         (VariableName::File, "/dir/a.ts".into()),
+                        (VariableName::RelativeFile, "a.ts".into()),
+                        (VariableName::Filename, "a.ts".into()),
+                        (VariableName::Dirname, "/dir".into()),
+                        (VariableName::Stem, "a".into()),
+                        (VariableName::Language, "TypeScript".into()),
                         (VariableName::WorktreeRoot, "/dir".into()),
                         (VariableName::Row, "1".into()),
                         (VariableName::Column, "1".into()),
                         (VariableName::SelectedText, "".into()),
                         (VariableName::Symbol, "this_is_a_test".into()),
+                        (VariableName::SymbolPath, "this_is_a_test".into()),
                     ])
                 }
             );
@@ -1,19 +1,20 @@
 use std::sync::Arc;
 
 use crate::{active_item_selection_properties, schedule_resolved_task};
+use collections::HashMap;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
-    impl_actions, rems, AppContext, DismissEvent, EventEmitter, FocusableView, Global,
-    InteractiveElement, Model, ParentElement, Render, SharedString, Styled, Subscription, View,
-    ViewContext, VisualContext, WeakView,
+    impl_actions, rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusableView, Global,
+    InteractiveElement, IntoElement, Model, ParentElement, Render, SharedString, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView,
 };
 use picker::{highlighted_match_with_paths::HighlightedText, Picker, PickerDelegate};
 use project::{Inventory, TaskSourceKind};
-use task::{ResolvedTask, TaskContext, TaskTemplate};
+use task::{ResolvedTask, TaskContext, TaskId, TaskTemplate};
 use ui::{
-    div, v_flex, ButtonCommon, ButtonSize, Clickable, Color, FluentBuilder as _, Icon, IconButton,
-    IconButtonShape, IconName, IconSize, ListItem, ListItemSpacing, RenderOnce, Selectable,
-    Tooltip, WindowContext,
+    div, v_flex, ActiveTheme, ButtonCommon, ButtonSize, Clickable, Color, FluentBuilder as _, Icon,
+    IconButton, IconButtonShape, IconName, IconSize, Label, LabelCommon, LabelSize, ListItem,
+    ListItemSpacing, RenderOnce, Selectable, Tooltip, WindowContext,
 };
 use util::ResultExt;
 use workspace::{ModalView, Workspace};
@@ -56,6 +57,9 @@ pub struct Rerun {
 
 impl_actions!(task, [Rerun, Spawn]);
 
+/// The number of environment variables of the selected task listed in the footer of the modal.
+const MAX_PREVIEWED_VARIABLES: usize = 8;
+
 /// A modal used to spawn new tasks.
 pub(crate) struct TasksModalDelegate {
     inventory: Model<Inventory>,
@@ -67,6 +71,9 @@ pub(crate) struct TasksModalDelegate {
     prompt: String,
     task_context: TaskContext,
     placeholder_text: Arc<str>,
+    /// The resolved environment of the selected task, previewed in the footer.
+    environment: Option<(TaskId, HashMap<String, String>)>,
+    load_environment: Task<()>,
 }
 
 impl TasksModalDelegate {
@@ -85,9 +92,54 @@ impl TasksModalDelegate {
             prompt: String::default(),
             task_context,
             placeholder_text: Arc::from("Run a task..."),
+            environment: None,
+            load_environment: Task::ready(()),
         }
     }
 
+    fn selected_task(&self) -> Option<&ResolvedTask> {
+        let task_index = self.matches.get(self.selected_index)?.candidate_id;
+        let (_, task) = self.candidates.as_ref()?.get(task_index)?;
+        Some(task)
+    }
+
+    /// Loads the environment of the selected task from its dotenv files and environment script, for the preview.
+    fn load_selected_environment(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(spawn_in_terminal) = self.selected_task().and_then(|task| task.resolved.clone())
+        else {
+            return;
+        };
+        if self
+            .environment
+            .as_ref()
+            .is_some_and(|(id, _)| id == &spawn_in_terminal.id)
+        {
+            return;
+        }
+        let Ok(load_environment) = self.workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.load_environment(
+                    spawn_in_terminal.cwd.as_deref(),
+                    &spawn_in_terminal.env_files,
+                    spawn_in_terminal.env_script.as_deref(),
+                    cx,
+                )
+            })
+        }) else {
+            return;
+        };
+        self.load_environment = cx.spawn(|picker, mut cx| async move {
+            let mut environment = load_environment.await;
+            environment.extend(spawn_in_terminal.env);
+            picker
+                .update(&mut cx, |picker, cx| {
+                    picker.delegate.environment = Some((spawn_in_terminal.id, environment));
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
     fn spawn_oneshot(&mut self) -> Option<(TaskSourceKind, ResolvedTask)> {
         if self.prompt.trim().is_empty() {
             return None;
@@ -181,8 +233,9 @@ impl PickerDelegate for TasksModalDelegate {
         self.selected_index
     }
 
-    fn set_selected_index(&mut self, ix: usize, _cx: &mut ViewContext<picker::Picker<Self>>) {
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<picker::Picker<Self>>) {
         self.selected_index = ix;
+        self.load_selected_environment(cx);
     }
 
     fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
@@ -255,7 +308,7 @@ impl PickerDelegate for TasksModalDelegate {
             )
             .await;
             picker
-                .update(&mut cx, |picker, _| {
+                .update(&mut cx, |picker, cx| {
                     let delegate = &mut picker.delegate;
                     delegate.matches = matches;
                     delegate.prompt = query;
@@ -266,6 +319,7 @@ impl PickerDelegate for TasksModalDelegate {
                         delegate.selected_index =
                             delegate.selected_index.min(delegate.matches.len() - 1);
                     }
+                    delegate.load_selected_environment(cx);
                 })
                 .log_err();
         })
@@ -401,6 +455,49 @@ impl PickerDelegate for TasksModalDelegate {
             Vec::new()
         }
     }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let task_id = &self.selected_task()?.id;
+        let (_, environment) = self.environment.as_ref().filter(|(id, _)| id == task_id)?;
+        let mut variables = environment
+            .iter()
+            .filter(|(name, _)| !name.starts_with("ZED_"))
+            .collect::<Vec<_>>();
+        if variables.is_empty() {
+            return None;
+        }
+        variables.sort();
+        let hidden_variables = variables.len().saturating_sub(MAX_PREVIEWED_VARIABLES);
+
+        Some(
+            v_flex()
+                .px_3()
+                .py_2()
+                .gap_0p5()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Label::new("Environment")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .children(variables.into_iter().take(MAX_PREVIEWED_VARIABLES).map(
+                    |(name, value)| {
+                        Label::new(format!("{name}={value}"))
+                            .size(LabelSize::Small)
+                            .single_line()
+                    },
+                ))
+                .when(hidden_variables > 0, |footer| {
+                    footer.child(
+                        Label::new(format!("and {hidden_variables} more"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    pub static ref TASK_HISTORY_DB: TaskHistoryDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE task_runs (
+                workspace_id INTEGER,
+                position INTEGER NOT NULL,
+                id_base TEXT NOT NULL,
+                template TEXT NOT NULL,
+                context TEXT NOT NULL,
+                cwd BLOB,
+                started_at INTEGER NOT NULL,
+                duration INTEGER,
+                exit_code INTEGER,
+                success INTEGER,
+                PRIMARY KEY(workspace_id, position),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        )];
+}
+
+/// A task run as it is stored: the id base of its source, its template and context serialized as JSON,
+/// its working directory, its start as milliseconds since the Unix epoch, and, once it has finished,
+/// its duration in milliseconds, exit code and success.
+pub type SerializedTaskRun = (
+    String,
+    String,
+    String,
+    Option<PathBuf>,
+    i64,
+    Option<i64>,
+    Option<i32>,
+    Option<bool>,
+);
+
+impl TaskHistoryDb {
+    query! {
+        pub fn get_task_runs(workspace_id: WorkspaceId) -> Result<Vec<SerializedTaskRun>> {
+            SELECT id_base, template, context, cwd, started_at, duration, exit_code, success
+            FROM task_runs
+            WHERE workspace_id = ?
+            ORDER BY position
+        }
+    }
+
+    /// Replaces the task runs saved for the workspace, ordered from the oldest to the most recent one.
+    pub async fn save_task_runs(
+        &self,
+        workspace_id: WorkspaceId,
+        task_runs: Vec<SerializedTaskRun>,
+    ) -> anyhow::Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_task_runs", || {
+                conn.exec_bound(sql!(DELETE FROM task_runs WHERE workspace_id = ?))?(workspace_id)
+                    .context("clearing old task runs")?;
+                for (position, task_run) in task_runs.into_iter().enumerate() {
+                    conn.exec_bound(sql!(
+                        INSERT INTO task_runs(
+                            workspace_id,
+                            position,
+                            id_base,
+                            template,
+                            context,
+                            cwd,
+                            started_at,
+                            duration,
+                            exit_code,
+                            success
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    ))?(((workspace_id, position), task_run))
+                    .context("inserting task run")?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -1,4 +1,8 @@
-use gpui::{IntoElement, Render, View, WeakView};
+use std::time::SystemTime;
+
+use collections::HashSet;
+use gpui::{IntoElement, Render, Subscription, View, WeakView};
+use project::{TaskRun, TaskRunStatus};
 use settings::Settings;
 use ui::{
     div, ButtonCommon, Clickable, Color, FluentBuilder, IconButton, IconName, Tooltip,
@@ -6,7 +10,7 @@ use ui::{
 };
 use workspace::{item::ItemHandle, StatusItemView, Workspace};
 
-use crate::{modal::Spawn, settings::TaskSettings};
+use crate::{history::toggle_history, modal::Spawn, settings::TaskSettings};
 
 enum TaskStatus {
     Failed,
@@ -20,14 +24,56 @@ enum TaskStatus {
 /// - else, yellow if any open task tab is still running
 /// - else, green if there tasks tabs open, and they have all succeeded
 /// - else, no indicator if there are no open task tabs
+///
+/// When a task run of this session failed, it is red as well and opens the task history with that
+/// run selected, instead of the tasks modal, until the run is viewed there or a newer run of the
+/// same task succeeds.
 pub struct TaskStatusIndicator {
     workspace: WeakView<Workspace>,
+    /// Failed runs started up to this time are not surfaced anymore: they were either viewed, or
+    /// restored from a previous session.
+    failures_seen_until: SystemTime,
+    _inventory_subscription: Subscription,
 }
 
 impl TaskStatusIndicator {
-    pub fn new(workspace: WeakView<Workspace>, cx: &mut WindowContext) -> View<Self> {
-        cx.new_view(|_| Self { workspace })
+    pub fn new(workspace: &Workspace, cx: &mut WindowContext) -> View<Self> {
+        let inventory = workspace.project().read(cx).task_inventory().clone();
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| Self {
+            workspace,
+            failures_seen_until: SystemTime::now(),
+            _inventory_subscription: cx.subscribe(&inventory, |_, _, _, cx| cx.notify()),
+        })
     }
+
+    /// The most recent failed task run that was not seen yet, unless a newer run of the same task
+    /// succeeded.
+    fn unseen_failed_run(&self, cx: &mut WindowContext) -> Option<TaskRun> {
+        self.workspace
+            .update(cx, |this, cx| {
+                let inventory = this.project().read(cx).task_inventory().read(cx);
+                let mut succeeded_tasks = HashSet::default();
+                inventory
+                    .task_runs()
+                    .rev()
+                    .take_while(|run| run.started_at > self.failures_seen_until)
+                    .find(|run| match run.status {
+                        TaskRunStatus::Finished { success: true, .. } => {
+                            succeeded_tasks.insert(run.task.id.clone());
+                            false
+                        }
+                        TaskRunStatus::Finished { success: false, .. } => {
+                            !succeeded_tasks.contains(&run.task.id)
+                        }
+                        TaskRunStatus::Running | TaskRunStatus::Unknown => false,
+                    })
+                    .cloned()
+            })
+            .ok()
+            .flatten()
+    }
+
     fn current_status(&self, cx: &mut WindowContext) -> Option<TaskStatus> {
         self.workspace
             .update(cx, |this, cx| {
@@ -68,6 +114,30 @@ impl Render for TaskStatusIndicator {
         if !TaskSettings::get_global(cx).show_status_indicator {
             return div().into_any_element();
         }
+        if let Some(failed_run) = self.unseen_failed_run(cx) {
+            let tooltip = match failed_run.status {
+                TaskRunStatus::Finished {
+                    exit_code: Some(exit_code),
+                    ..
+                } => format!(
+                    "Task \"{}\" failed with exit code {exit_code}",
+                    failed_run.task.resolved_label
+                ),
+                _ => format!("Task \"{}\" failed", failed_run.task.resolved_label),
+            };
+            return IconButton::new("tasks-activity-indicator", IconName::Play)
+                .icon_color(Color::Error)
+                .on_click(cx.listener(move |this, _, cx| {
+                    let failed_run = failed_run.clone();
+                    this.failures_seen_until = failed_run.started_at;
+                    cx.notify();
+                    this.workspace
+                        .update(cx, |this, cx| toggle_history(this, Some(failed_run), cx))
+                        .ok();
+                }))
+                .tooltip(move |cx| Tooltip::text(tooltip.clone(), cx))
+                .into_any_element();
+        }
         let current_status = self.current_status(cx);
         let color = current_status.map(|status| match status {
             TaskStatus::Failed => Color::Error,
@@ -293,6 +293,8 @@ pub struct SpawnTask {
     pub command: String,
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
+    pub env_files: Vec<PathBuf>,
+    pub env_script: Option<PathBuf>,
     pub reveal: RevealStrategy,
 }
 
@@ -598,6 +600,8 @@ pub struct TaskState {
     pub full_label: String,
     pub label: String,
     pub status: TaskStatus,
+    /// The exit code of the task's process, once it has exited.
+    pub exit_code: Option<i32>,
     pub completion_rx: Receiver<()>,
 }
 
@@ -680,6 +684,7 @@ impl Terminal {
             AlacTermEvent::ChildExit(error_code) => {
                 if let Some(task) = &mut self.task {
                     task.status.register_task_exit(*error_code);
+                    task.exit_code = Some(*error_code);
                     self.completion_tx.try_send(()).ok();
                 }
             }
@@ -1413,6 +1418,14 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// Returns the whole text of the terminal, scrollback included, with soft-wrapped lines joined.
+    pub fn output_text(&self) -> String {
+        let term = self.term.lock();
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
     pub fn wait_for_completed_task(&self, cx: &mut AppContext) -> Task<()> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
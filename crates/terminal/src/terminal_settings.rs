@@ -32,6 +32,8 @@ pub struct TerminalSettings {
     pub line_height: TerminalLineHeight,
     pub font_features: Option<FontFeatures>,
     pub env: HashMap<String, String>,
+    pub env_files: Vec<String>,
+    pub env_script: Option<String>,
     pub blinking: TerminalBlink,
     pub alternate_scroll: AlternateScroll,
     pub option_as_meta: bool,
@@ -118,6 +120,19 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: {}
     pub env: Option<HashMap<String, String>>,
+    /// Dotenv files to load the environment of the terminals and tasks from,
+    /// relative to the root of their worktree, such as `.env`. Files that do
+    /// not exist are skipped. We recommend setting this in your project's settings.
+    ///
+    /// Default: []
+    pub env_files: Option<Vec<String>>,
+    /// A shell script to source for the environment of the terminals and
+    /// tasks, relative to the root of their worktree, such as a direnv
+    /// `.envrc`, or a PowerShell script on Windows. It is skipped if it does
+    /// not exist.
+    ///
+    /// Default: null
+    pub env_script: Option<String>,
     /// Sets the cursor blinking behavior in the terminal.
     ///
     /// Default: terminal_controlled
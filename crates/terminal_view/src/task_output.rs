@@ -0,0 +1,742 @@
+//! A read-only editor tab with the output of a task that captures its output into a buffer instead of running in a terminal.
+//! Keeps the output of the last runs of the task, with the colors of the output, the start and end time of each run,
+//! and links to the `file:line:column` locations mentioned.
+
+use std::{
+    collections::VecDeque,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{Arc, OnceLock},
+    time::Instant,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use editor::{actions::MoveToEnd, Editor, EditorEvent};
+use futures::{channel::oneshot, stream, FutureExt, StreamExt};
+use gpui::{
+    div, px, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, FontStyle,
+    FontWeight, HighlightStyle, Hsla, IntoElement, Model, ParentElement, Render, Styled,
+    Subscription, Task, UnderlineStyle, View, ViewContext, VisualContext, WindowContext,
+};
+use language::{Anchor, Buffer, ToOffset};
+use project::{search::SearchQuery, DocumentLink};
+use regex::Regex;
+use smol::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+};
+use task::SpawnInTerminal;
+use theme::{ActiveTheme, Theme};
+use time::{macros::format_description, OffsetDateTime};
+use ui::{h_flex, prelude::*, Icon, IconName, Label};
+use util::post_inc;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+    Workspace,
+};
+
+/// How many runs of a task to keep the output of.
+const MAX_RUNS: usize = 5;
+
+/// The output of a task, captured into a read-only buffer.
+pub struct TaskOutputView {
+    full_label: String,
+    label: String,
+    buffer: Model<Buffer>,
+    editor: View<Editor>,
+    runs: VecDeque<TaskRun>,
+    pending_run: Option<(usize, Task<()>)>,
+    next_run_id: usize,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The output of a finished run of a task, without its escape sequences.
+pub struct TaskRunOutput {
+    pub text: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+}
+
+struct TaskRun {
+    /// Where the run starts in the buffer, with the header of the run.
+    start: Anchor,
+    started_at: Instant,
+    cwd: Option<PathBuf>,
+    style: AnsiStyle,
+    text: String,
+    success: Option<bool>,
+    highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    links: Vec<DocumentLink>,
+}
+
+enum RunOutcome {
+    Exited(Option<i32>),
+    Failed(anyhow::Error),
+    Stopped,
+}
+
+impl TaskOutputView {
+    pub fn new(spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) -> Self {
+        let buffer = cx.new_model(|cx| Buffer::local("", cx));
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), None, cx);
+            editor.set_read_only(true);
+            editor.set_show_inline_completions(false);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            cx.subscribe(&editor, |_, _, event: &SearchEvent, cx| {
+                cx.emit(event.clone())
+            }),
+        ];
+        Self {
+            full_label: spawn_in_terminal.full_label.clone(),
+            label: spawn_in_terminal.label.clone(),
+            buffer,
+            editor,
+            runs: VecDeque::new(),
+            pending_run: None,
+            next_run_id: 0,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn full_label(&self) -> &str {
+        &self.full_label
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.pending_run.is_some()
+    }
+
+    /// Runs the command of the task, capturing its output, once the previous run of the task has finished.
+    /// Tasks that allow concurrent runs stop their previous run instead.
+    pub fn run(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        mut command: Command,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<TaskRunOutput>> {
+        let mut previous_run = self.pending_run.take().map(|(_, task)| task);
+        if spawn_in_terminal.allow_concurrent_runs
+            && previous_run.take().is_some()
+            && self.runs.back().map_or(false, |run| run.success.is_none())
+        {
+            self.finish_run(RunOutcome::Stopped, cx).ok();
+        }
+
+        let run_id = post_inc(&mut self.next_run_id);
+        let cwd = spawn_in_terminal.cwd.clone();
+        let (output_tx, output_rx) = oneshot::channel();
+        let run = cx.spawn(|this, mut cx| async move {
+            if let Some(previous_run) = previous_run {
+                previous_run.await;
+            }
+            if this
+                .update(&mut cx, |this, cx| this.start_run(cwd, cx))
+                .is_err()
+            {
+                return;
+            }
+
+            let status = async {
+                let mut child = command
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .context("spawning the task")?;
+                let stdout = BufReader::new(child.stdout.take().context("missing stdout")?);
+                let stderr = BufReader::new(child.stderr.take().context("missing stderr")?);
+                let mut lines = stream::select(stdout.split(b'\n'), stderr.split(b'\n'));
+                while let Some(line) = lines.next().await {
+                    // Append all the lines that are already available at once.
+                    let mut chunk = vec![line?];
+                    while let Some(Some(line)) = lines.next().now_or_never() {
+                        chunk.push(line?);
+                    }
+                    this.update(&mut cx, |this, cx| this.append_output(chunk, cx))?;
+                }
+                anyhow::Ok(child.status().await?)
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                if this
+                    .pending_run
+                    .as_ref()
+                    .map_or(false, |(id, _)| *id == run_id)
+                {
+                    this.pending_run = None;
+                }
+                let outcome = match status {
+                    Ok(status) => RunOutcome::Exited(status.code()),
+                    Err(error) => RunOutcome::Failed(error),
+                };
+                output_tx.send(this.finish_run(outcome, cx)).ok();
+            })
+            .ok();
+        });
+        self.pending_run = Some((run_id, run));
+        cx.notify();
+
+        cx.spawn(|_, _| async move {
+            output_rx
+                .await
+                .map_err(|_| anyhow!("The task run was stopped"))?
+        })
+    }
+
+    fn start_run(&mut self, cwd: Option<PathBuf>, cx: &mut ViewContext<Self>) {
+        if self.runs.len() >= MAX_RUNS {
+            self.runs.pop_front();
+            let next_run_start = self.runs.front().map(|run| run.start);
+            self.buffer.update(cx, |buffer, cx| {
+                let end = next_run_start.map_or(buffer.len(), |start| start.to_offset(buffer));
+                buffer.edit([(0..end, "")], None, cx);
+            });
+        }
+
+        let start = self
+            .buffer
+            .read(cx)
+            .anchor_before(self.buffer.read(cx).len());
+        self.runs.push_back(TaskRun {
+            start,
+            started_at: Instant::now(),
+            cwd,
+            style: AnsiStyle::default(),
+            text: String::new(),
+            success: None,
+            highlights: Vec::new(),
+            links: Vec::new(),
+        });
+        let header = format!("> {} (started at {})\n", self.full_label, timestamp(cx));
+        self.append_status_line(header, cx.theme().colors().text_muted, cx);
+    }
+
+    /// Appends the ending of the run to the buffer, resolving to the output of the run if it finished.
+    fn finish_run(
+        &mut self,
+        outcome: RunOutcome,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<TaskRunOutput> {
+        let Some(run) = self.runs.back_mut() else {
+            return Err(anyhow!("The task has not run"));
+        };
+        let elapsed = format!("{:.2}s", run.started_at.elapsed().as_secs_f32());
+        let now = timestamp(cx);
+        let colors = cx.theme().status();
+        let (footer, color) = match &outcome {
+            RunOutcome::Exited(Some(0)) => {
+                (format!("Finished in {elapsed} at {now}"), colors.success)
+            }
+            RunOutcome::Exited(Some(code)) => (
+                format!("Failed with exit code {code} in {elapsed} at {now}"),
+                colors.error,
+            ),
+            RunOutcome::Exited(None) => (
+                format!("Terminated by a signal after {elapsed} at {now}"),
+                colors.error,
+            ),
+            RunOutcome::Failed(error) => (format!("Failed to run: {error:#}"), colors.error),
+            RunOutcome::Stopped => (format!("Stopped after {elapsed} at {now}"), colors.warning),
+        };
+        let success = matches!(outcome, RunOutcome::Exited(Some(0)));
+        run.success = Some(success);
+        let text = run.text.clone();
+        self.append_status_line(format!("{footer}\n\n"), color, cx);
+        cx.notify();
+
+        match outcome {
+            RunOutcome::Exited(exit_code) => Ok(TaskRunOutput {
+                text,
+                success,
+                exit_code,
+            }),
+            RunOutcome::Failed(error) => Err(error),
+            RunOutcome::Stopped => Err(anyhow!("The task run was stopped")),
+        }
+    }
+
+    fn append_status_line(&mut self, line: String, color: Hsla, cx: &mut ViewContext<Self>) {
+        let style = HighlightStyle {
+            color: Some(color),
+            font_weight: Some(FontWeight::BOLD),
+            ..HighlightStyle::default()
+        };
+        let range = 0..line.trim_end().len();
+        self.append(line, vec![(range, style)], Vec::new(), cx);
+    }
+
+    fn append_output(&mut self, lines: Vec<Vec<u8>>, cx: &mut ViewContext<Self>) {
+        let Some(run) = self.runs.back_mut() else {
+            return;
+        };
+        let theme = cx.theme().clone();
+        let mut text = String::new();
+        let mut styles = Vec::new();
+        let mut links = Vec::new();
+        for line in lines {
+            let line = String::from_utf8_lossy(&line);
+            let (line, line_styles) = parse_ansi(line.trim_end_matches('\r'), &mut run.style);
+            let offset = text.len();
+            styles.extend(line_styles.into_iter().map(|(range, style)| {
+                (
+                    offset + range.start..offset + range.end,
+                    style.highlight_style(&theme),
+                )
+            }));
+            for location in find_locations(&line) {
+                if let Some(link) = location.link(run.cwd.as_deref()) {
+                    let range = offset + location.range.start..offset + location.range.end;
+                    links.push((range, link));
+                }
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+        run.text.push_str(&text);
+        self.append(text, styles, links, cx);
+    }
+
+    /// Appends text to the current run, with the styles and the links of ranges of the text.
+    fn append(
+        &mut self,
+        text: String,
+        styles: Vec<(Range<usize>, HighlightStyle)>,
+        links: Vec<(Range<usize>, (String, String))>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(run) = self.runs.back_mut() else {
+            return;
+        };
+        let start = self.buffer.read(cx).len();
+        let follow_output = self.editor.update(cx, |editor, cx| {
+            editor.selections.newest::<usize>(cx).head() >= start
+        });
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.edit([(start..start, text)], None, cx);
+            let anchor_range = |range: Range<usize>| {
+                buffer.anchor_after(start + range.start)..buffer.anchor_before(start + range.end)
+            };
+            run.highlights.extend(
+                styles
+                    .into_iter()
+                    .map(|(range, style)| (anchor_range(range), style)),
+            );
+            run.links.extend(
+                links
+                    .into_iter()
+                    .map(|(range, (target, tooltip))| DocumentLink {
+                        range: anchor_range(range),
+                        target: Some(target),
+                        tooltip: Some(tooltip),
+                    }),
+            );
+        });
+
+        let highlights = self
+            .runs
+            .iter()
+            .flat_map(|run| run.highlights.iter().cloned())
+            .collect::<Vec<_>>();
+        let links = self
+            .runs
+            .iter()
+            .flat_map(|run| run.links.iter().cloned())
+            .collect::<Vec<_>>();
+        let buffer = self.buffer.clone();
+        self.editor.update(cx, |editor, cx| {
+            editor.set_styled_highlights(&buffer, &highlights, cx);
+            editor.set_document_links(buffer.read(cx).remote_id(), links);
+            if follow_output {
+                editor.move_to_end(&MoveToEnd, cx);
+            }
+        });
+    }
+}
+
+fn timestamp(cx: &WindowContext) -> String {
+    OffsetDateTime::now_utc()
+        .to_offset(cx.local_timezone())
+        .format(format_description!("[hour]:[minute]:[second]"))
+        .unwrap_or_default()
+}
+
+/// A color set by an SGR escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnsiColor {
+    /// One of the 256 indexed colors, the first 16 of which are the named colors of the theme.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl AnsiColor {
+    fn to_hsla(self, theme: &Theme) -> Hsla {
+        match self {
+            Self::Indexed(index) => terminal::get_color_at_index(index as usize, theme),
+            Self::Rgb(r, g, b) => terminal::rgba_color(r, g, b),
+        }
+    }
+}
+
+/// The text style set by the SGR escape sequences of an output, so far.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct AnsiStyle {
+    foreground: Option<AnsiColor>,
+    background: Option<AnsiColor>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl AnsiStyle {
+    fn highlight_style(&self, theme: &Theme) -> HighlightStyle {
+        HighlightStyle {
+            color: self.foreground.map(|color| color.to_hsla(theme)),
+            background_color: self.background.map(|color| color.to_hsla(theme)),
+            font_weight: self.bold.then_some(FontWeight::BOLD),
+            font_style: self.italic.then_some(FontStyle::Italic),
+            underline: self.underline.then(|| UnderlineStyle {
+                thickness: px(1.),
+                ..UnderlineStyle::default()
+            }),
+            fade_out: self.dim.then_some(0.3),
+            ..HighlightStyle::default()
+        }
+    }
+
+    /// Applies the parameters of an SGR escape sequence, such as `1;31` in `\x1b[1;31m`.
+    fn apply_sgr(&mut self, parameters: &str) {
+        let mut parameters = parameters
+            .split([';', ':'])
+            .map(|parameter| parameter.parse::<u8>().unwrap_or(0));
+        while let Some(parameter) = parameters.next() {
+            match parameter {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.foreground = Some(AnsiColor::Indexed(parameter - 30)),
+                38 => self.foreground = parse_extended_color(&mut parameters),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(AnsiColor::Indexed(parameter - 40)),
+                48 => self.background = parse_extended_color(&mut parameters),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(AnsiColor::Indexed(parameter - 90 + 8)),
+                100..=107 => self.background = Some(AnsiColor::Indexed(parameter - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Parses the `5;<index>` or `2;<r>;<g>;<b>` parameters following a `38` or `48` SGR parameter.
+fn parse_extended_color(parameters: &mut impl Iterator<Item = u8>) -> Option<AnsiColor> {
+    match parameters.next()? {
+        5 => Some(AnsiColor::Indexed(parameters.next()?)),
+        2 => Some(AnsiColor::Rgb(
+            parameters.next()?,
+            parameters.next()?,
+            parameters.next()?,
+        )),
+        _ => None,
+    }
+}
+
+/// Removes the escape sequences from a line of output, returning the styled ranges of the remaining text.
+/// The style is carried over from the previous lines, and a carriage return starts the line over, as progress bars do.
+fn parse_ansi(line: &str, style: &mut AnsiStyle) -> (String, Vec<(Range<usize>, AnsiStyle)>) {
+    let mut text = String::new();
+    let mut styles: Vec<(Range<usize>, AnsiStyle)> = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    let mut parameters = String::new();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            if c == 'm' {
+                                style.apply_sgr(&parameters);
+                            }
+                            break;
+                        }
+                        parameters.push(c);
+                    }
+                }
+                Some(']') => {
+                    // Operating system commands, such as hyperlinks, end with a bell or a string terminator.
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {
+                text.clear();
+                styles.clear();
+            }
+            c if c.is_control() && c != '\t' => {}
+            c => {
+                let start = text.len();
+                text.push(c);
+                if *style != AnsiStyle::default() {
+                    match styles.last_mut() {
+                        Some((range, last_style)) if range.end == start && last_style == style => {
+                            range.end = text.len();
+                        }
+                        _ => styles.push((start..text.len(), *style)),
+                    }
+                }
+            }
+        }
+    }
+    (text, styles)
+}
+
+/// A `path:line` or `path:line:column` location mentioned in the output.
+#[derive(Debug, PartialEq, Eq)]
+struct OutputLocation {
+    range: Range<usize>,
+    path: String,
+    row: u32,
+    column: Option<u32>,
+}
+
+impl OutputLocation {
+    /// The target and the tooltip of the link to the location, with relative paths resolved against the directory of the task.
+    fn link(&self, cwd: Option<&Path>) -> Option<(String, String)> {
+        let path = Path::new(&self.path);
+        let path = match cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        };
+        let mut url = url::Url::from_file_path(&path).ok()?;
+        let fragment = match self.column {
+            Some(column) => format!("L{},{column}", self.row),
+            None => format!("L{}", self.row),
+        };
+        url.set_fragment(Some(&fragment));
+        let tooltip = format!("Open {}", &self.path);
+        Some((url.to_string(), tooltip))
+    }
+}
+
+/// Finds the file locations in a line of output, such as `src/main.rs:12:5`.
+fn find_locations(line: &str) -> Vec<OutputLocation> {
+    static LOCATION_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = LOCATION_REGEX.get_or_init(|| {
+        Regex::new(r"((?:[A-Za-z]:)?[\w\-./\\]*\w\.[A-Za-z]\w*):(\d+)(?::(\d+))?").unwrap()
+    });
+    regex
+        .captures_iter(line)
+        .filter_map(|captures| {
+            let location = captures.get(0)?;
+            Some(OutputLocation {
+                range: location.range(),
+                path: captures.get(1)?.as_str().to_string(),
+                row: captures.get(2)?.as_str().parse().ok()?,
+                column: captures
+                    .get(3)
+                    .and_then(|column| column.as_str().parse().ok()),
+            })
+        })
+        .collect()
+}
+
+impl EventEmitter<EditorEvent> for TaskOutputView {}
+impl EventEmitter<SearchEvent> for TaskOutputView {}
+
+impl Render for TaskOutputView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        div().size_full().child(self.editor.clone())
+    }
+}
+
+impl FocusableView for TaskOutputView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for TaskOutputView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let icon = if self.is_running() {
+            IconName::Play
+        } else {
+            match self.runs.back().and_then(|run| run.success) {
+                Some(true) => IconName::Check,
+                Some(false) => IconName::XCircle,
+                None => IconName::FileDoc,
+            }
+        };
+        h_flex()
+            .gap_2()
+            .child(Icon::new(icon))
+            .child(Label::new(self.label.clone()).color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            }))
+            .into_any()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.full_label.clone().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+
+    fn as_searchable(&self, handle: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+}
+
+impl SearchableItem for TaskOutputView {
+    type Match = <Editor as SearchableItem>::Match;
+
+    fn clear_matches(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |e, cx| e.clear_matches(cx))
+    }
+
+    fn update_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |e, cx| e.update_matches(matches, cx))
+    }
+
+    fn query_suggestion(&mut self, cx: &mut ViewContext<Self>) -> String {
+        self.editor.update(cx, |e, cx| e.query_suggestion(cx))
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.editor
+            .update(cx, |e, cx| e.activate_match(index, matches, cx))
+    }
+
+    fn select_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |e, cx| e.select_matches(matches, cx))
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        self.editor.update(cx, |e, cx| e.find_matches(query, cx))
+    }
+
+    fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut ViewContext<Self>) {
+        // The output of a task is read-only.
+    }
+
+    fn supported_options() -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: true,
+            regex: true,
+            replacement: false,
+        }
+    }
+
+    fn active_match_index(
+        &mut self,
+        matches: &[Self::Match],
+        cx: &mut ViewContext<Self>,
+    ) -> Option<usize> {
+        self.editor
+            .update(cx, |e, cx| e.active_match_index(matches, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ansi() {
+        let mut style = AnsiStyle::default();
+        let (text, styles) = parse_ansi(
+            "\x1b[1m\x1b[31merror\x1b[0m: \x1b]8;;file:///a\x07mismatched\x1b]8;;\x07 \x1b[38;5;12mtypes",
+            &mut style,
+        );
+        assert_eq!(text, "error: mismatched types");
+        let bold_red = AnsiStyle {
+            foreground: Some(AnsiColor::Indexed(1)),
+            bold: true,
+            ..AnsiStyle::default()
+        };
+        let blue = AnsiStyle {
+            foreground: Some(AnsiColor::Indexed(12)),
+            ..AnsiStyle::default()
+        };
+        assert_eq!(styles, vec![(0..5, bold_red), (18..23, blue)]);
+
+        // The style carries over to the next line, and a carriage return starts the line over.
+        let (text, styles) = parse_ansi("50%\r\x1b[38;2;1;2;3m100%\x1b[39m done", &mut style);
+        assert_eq!(text, "100% done");
+        let rgb = AnsiStyle {
+            foreground: Some(AnsiColor::Rgb(1, 2, 3)),
+            ..AnsiStyle::default()
+        };
+        assert_eq!(styles, vec![(0..4, rgb)]);
+        assert_eq!(style, AnsiStyle::default());
+    }
+
+    #[test]
+    fn test_find_locations() {
+        assert_eq!(
+            find_locations("  --> src/main.rs:12:5 and C:\\code\\lib.c:3, not v1.2:3 or main.rs"),
+            vec![
+                OutputLocation {
+                    range: 6..22,
+                    path: "src/main.rs".to_string(),
+                    row: 12,
+                    column: Some(5),
+                },
+                OutputLocation {
+                    range: 27..42,
+                    path: "C:\\code\\lib.c".to_string(),
+                    row: 3,
+                    column: None,
+                },
+            ]
+        );
+    }
+}
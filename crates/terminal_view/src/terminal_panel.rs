@@ -1,23 +1,32 @@
-use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
+use std::{cell::RefCell, ops::ControlFlow, path::PathBuf, rc::Rc, sync::Arc};
 
-use crate::TerminalView;
+use crate::{
+    task_output::{TaskOutputView, TaskRunOutput},
+    TerminalView,
+};
 use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
-use futures::future::join_all;
+use futures::{
+    channel::oneshot,
+    future::{join_all, LocalBoxFuture, Shared},
+    FutureExt,
+};
 use gpui::{
     actions, Action, AppContext, AsyncWindowContext, Entity, EventEmitter, ExternalPaths,
-    FocusHandle, FocusableView, IntoElement, ParentElement, Pixels, Render, Styled, Subscription,
-    Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use project::{Fs, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use task::{RevealStrategy, SpawnInTerminal, TaskId};
+use task::{
+    DependsOrder, RevealStrategy, SpawnInTerminal, TaskId, TaskOutput, TaskProblem, TestResult,
+};
 use terminal::{
     terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
-    SpawnTask,
+    SpawnTask, TaskStatus, Terminal,
 };
 use ui::{h_flex, ButtonCommon, Clickable, IconButton, IconSize, Selectable, Tooltip};
 use util::{ResultExt, TryFutureExt};
@@ -29,7 +38,7 @@ use workspace::{
     DraggedTab, NewTerminal, Pane, Workspace,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Context as _, Result};
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
@@ -84,7 +93,9 @@ impl TerminalPanel {
                             .icon_size(IconSize::Small)
                             .on_click(move |_, cx| {
                                 terminal_panel
-                                    .update(cx, |panel, cx| panel.add_terminal(None, None, cx))
+                                    .update(cx, |panel, cx| {
+                                        panel.add_terminal(None, None, cx).detach_and_log_err(cx)
+                                    })
                                     .log_err();
                             })
                             .tooltip(|cx| Tooltip::text("New Terminal", cx)),
@@ -114,7 +125,9 @@ impl TerminalPanel {
                         tab.pane.read(cx).item_for_index(tab.ix)
                     };
                     if let Some(item) = item {
-                        if item.downcast::<TerminalView>().is_some() {
+                        if item.downcast::<TerminalView>().is_some()
+                            || item.downcast::<TaskOutputView>().is_some()
+                        {
                             return ControlFlow::Continue(());
                         } else if let Some(project_path) = item.project_path(cx) {
                             if let Some(entry_path) = workspace
@@ -293,10 +306,336 @@ impl TerminalPanel {
 
         this.update(cx, |this, cx| {
             this.add_terminal(Some(action.working_directory.clone()), None, cx)
+                .detach_and_log_err(cx)
         })
     }
 
     fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        let run_task = self.run_task(spawn_in_terminal.clone(), cx);
+        let workspace = self.workspace.clone();
+        let task_id = spawn_in_terminal.id.clone();
+        cx.spawn(|terminal_panel, mut cx| async move {
+            if let Err(e) = run_task.await {
+                // Record the runs that did not get to start as failed, e.g. after a failed dependency.
+                terminal_panel
+                    .update(&mut cx, |terminal_panel, cx| {
+                        terminal_panel.task_finished(&task_id, false, None, cx)
+                    })
+                    .ok();
+                workspace
+                    .update(&mut cx, |workspace, cx| workspace.show_error(&e, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    /// Completes the run of the task recorded in the task inventory.
+    fn task_finished(
+        &self,
+        task_id: &TaskId,
+        success: bool,
+        exit_code: Option<i32>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let inventory = workspace
+                .read(cx)
+                .project()
+                .read(cx)
+                .task_inventory()
+                .clone();
+            inventory.update(cx, |inventory, cx| {
+                inventory.task_finished(task_id, success, exit_code, cx)
+            });
+        }
+    }
+
+    /// Records the run of a dependency of a scheduled task in the task inventory.
+    fn dependency_started(&self, task_id: &TaskId, cx: &mut ViewContext<Self>) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let inventory = workspace
+                .read(cx)
+                .project()
+                .read(cx)
+                .task_inventory()
+                .clone();
+            inventory.update(cx, |inventory, cx| {
+                inventory.dependency_started(task_id, cx)
+            });
+        }
+    }
+
+    /// Runs the task after the tasks it depends on, resolving to whether the task succeeded.
+    /// Fails if any of its dependencies did not succeed, in which case the task is not started.
+    fn run_task(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        let task_id = spawn_in_terminal.id.clone();
+        cx.spawn(|terminal_panel, cx| {
+            let run_task: RunTask = Rc::new(move |spawn_in_terminal| {
+                let terminal_panel = terminal_panel.clone();
+                let mut cx = cx.clone();
+                // The run of the task itself is recorded when it gets scheduled, and completed by
+                // `spawn_task` if it fails to start, while the ones of its dependencies are recorded here.
+                let is_dependency = spawn_in_terminal.id != task_id;
+                async move {
+                    let dependency_id = spawn_in_terminal.id.clone();
+                    let result = terminal_panel
+                        .update(&mut cx, |terminal_panel, cx| {
+                            terminal_panel.run_task_itself(spawn_in_terminal, is_dependency, cx)
+                        })?
+                        .await;
+                    if is_dependency && result.is_err() {
+                        terminal_panel
+                            .update(&mut cx, |terminal_panel, cx| {
+                                terminal_panel.task_finished(&dependency_id, false, None, cx)
+                            })
+                            .ok();
+                    }
+                    result
+                }
+                .boxed_local()
+            });
+            run_with_dependencies(spawn_in_terminal, TaskRuns::default(), run_task)
+        })
+    }
+
+    /// Runs the task without its dependencies, resolving to whether it succeeded, and completes
+    /// its run in the task inventory. Runs of dependencies are recorded once they get started.
+    /// Background tasks resolve once started, and complete their run when they finish.
+    fn run_task_itself(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        is_dependency: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        // Background tasks, such as watchers, are only started once.
+        if spawn_in_terminal.background && self.is_task_running(&spawn_in_terminal.full_label, cx) {
+            return Task::ready(Ok(true));
+        }
+        if is_dependency {
+            self.dependency_started(&spawn_in_terminal.id, cx);
+        }
+
+        cx.spawn(|terminal_panel, mut cx| async move {
+            // Tasks that only group their dependencies have nothing to run themselves.
+            if spawn_in_terminal.command.is_empty() {
+                terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                    terminal_panel.task_finished(&spawn_in_terminal.id, true, None, cx)
+                })?;
+                return Ok(true);
+            }
+            let matches_problems = !spawn_in_terminal.problem_matchers.is_empty();
+            if matches_problems {
+                terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                    terminal_panel.update_task_diagnostics(
+                        &spawn_in_terminal.full_label,
+                        Vec::new(),
+                        cx,
+                    )
+                })?;
+            }
+            let (output, succeeded, exit_code) = match spawn_in_terminal.output {
+                TaskOutput::Terminal => {
+                    let terminal = terminal_panel
+                        .update(&mut cx, |terminal_panel, cx| {
+                            terminal_panel.start_task(&spawn_in_terminal, cx)
+                        })?
+                        .await?;
+                    if spawn_in_terminal.background {
+                        let terminal_panel = terminal_panel.clone();
+                        cx.spawn(|mut cx| async move {
+                            terminal
+                                .update(&mut cx, |terminal, cx| {
+                                    terminal.wait_for_completed_task(cx)
+                                })?
+                                .await;
+                            let (output, succeeded, exit_code) =
+                                terminal.update(&mut cx, |terminal, _| {
+                                    let (succeeded, exit_code) = task_outcome(terminal);
+                                    (
+                                        matches_problems.then(|| terminal.output_text()),
+                                        succeeded,
+                                        exit_code,
+                                    )
+                                })?;
+                            terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                                terminal_panel.task_finished(
+                                    &spawn_in_terminal.id,
+                                    succeeded,
+                                    exit_code,
+                                    cx,
+                                )
+                            })?;
+                            if let Some(output) = output {
+                                report_task_problems(
+                                    &terminal_panel,
+                                    &spawn_in_terminal,
+                                    output,
+                                    &mut cx,
+                                )
+                                .await?;
+                            }
+                            anyhow::Ok(())
+                        })
+                        .detach();
+                        return Ok(true);
+                    }
+                    terminal
+                        .update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
+                        .await;
+                    let reads_output = matches_problems
+                        || spawn_in_terminal
+                            .test_report
+                            .as_ref()
+                            .is_some_and(|test_report| test_report.file.is_none());
+                    terminal.update(&mut cx, |terminal, _| {
+                        let (succeeded, exit_code) = task_outcome(terminal);
+                        (
+                            reads_output.then(|| terminal.output_text()),
+                            succeeded,
+                            exit_code,
+                        )
+                    })?
+                }
+                TaskOutput::Buffer => {
+                    let run = terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        terminal_panel.start_task_in_buffer(&spawn_in_terminal, cx)
+                    })?;
+                    if spawn_in_terminal.background {
+                        let terminal_panel = terminal_panel.clone();
+                        cx.spawn(|mut cx| async move {
+                            let run = run.await.log_err();
+                            let (succeeded, exit_code) = run
+                                .as_ref()
+                                .map_or((false, None), |run| (run.success, run.exit_code));
+                            terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                                terminal_panel.task_finished(
+                                    &spawn_in_terminal.id,
+                                    succeeded,
+                                    exit_code,
+                                    cx,
+                                )
+                            })?;
+                            if let Some(run) = run.filter(|_| matches_problems) {
+                                report_task_problems(
+                                    &terminal_panel,
+                                    &spawn_in_terminal,
+                                    run.text,
+                                    &mut cx,
+                                )
+                                .await?;
+                            }
+                            anyhow::Ok(())
+                        })
+                        .detach();
+                        return Ok(true);
+                    }
+                    let run = run.await?;
+                    (Some(run.text), run.success, run.exit_code)
+                }
+            };
+            terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                terminal_panel.task_finished(&spawn_in_terminal.id, succeeded, exit_code, cx)
+            })?;
+            if matches_problems {
+                let output = output.clone().unwrap_or_default();
+                report_task_problems(&terminal_panel, &spawn_in_terminal, output, &mut cx).await?;
+            }
+            if let Some(test_report) = spawn_in_terminal.test_report.clone() {
+                let cwd = spawn_in_terminal.cwd.clone();
+                let report = match test_report.file.as_deref() {
+                    Some(file) => {
+                        let path = cwd
+                            .as_deref()
+                            .map_or(PathBuf::from(file), |cwd| cwd.join(file));
+                        let fs = terminal_panel
+                            .update(&mut cx, |terminal_panel, _| terminal_panel.fs.clone())?;
+                        fs.load(&path)
+                            .await
+                            .with_context(|| format!("reading the test report at {path:?}"))
+                            .log_err()
+                    }
+                    None => output,
+                };
+                if let Some(report) = report {
+                    let results = cx
+                        .background_executor()
+                        .spawn(async move {
+                            let mut results = test_report.format.parse(&report)?;
+                            if let Some(cwd) = cwd {
+                                for path in
+                                    results.iter_mut().filter_map(|result| result.path.as_mut())
+                                {
+                                    *path = cwd.join(path.as_path());
+                                }
+                            }
+                            anyhow::Ok(results)
+                        })
+                        .await
+                        .log_err()
+                        .unwrap_or_default();
+                    terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        terminal_panel.insert_test_results(results, cx)
+                    })?;
+                }
+            }
+            Ok(succeeded)
+        })
+    }
+
+    fn update_task_diagnostics(
+        &self,
+        task_label: &str,
+        problems: Vec<TaskProblem>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let project = workspace.read(cx).project().clone();
+            project.update(cx, |project, cx| {
+                project.update_task_diagnostics(task_label, problems, cx)
+            });
+        }
+    }
+
+    fn insert_test_results(&self, results: Vec<TestResult>, cx: &mut ViewContext<Self>) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let test_results_store = workspace
+                .read(cx)
+                .project()
+                .read(cx)
+                .test_results_store()
+                .clone();
+            test_results_store.update(cx, |store, cx| store.insert_results(results, cx));
+        }
+    }
+
+    fn is_task_running(&self, full_label: &str, cx: &mut AppContext) -> bool {
+        self.terminals_for_task(full_label, cx)
+            .iter()
+            .any(|(_, terminal_view)| {
+                terminal_view
+                    .read(cx)
+                    .terminal()
+                    .read(cx)
+                    .task()
+                    .map_or(false, |task_state| task_state.status == TaskStatus::Running)
+            })
+            || self
+                .output_views_for_task(full_label, cx)
+                .iter()
+                .any(|(_, output_view)| output_view.read(cx).is_running())
+    }
+
+    /// Starts the task in a terminal, resolving to the terminal once it has been started.
+    fn start_task(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Model<Terminal>>> {
         let mut spawn_task = SpawnTask {
             id: spawn_in_terminal.id.clone(),
             full_label: spawn_in_terminal.full_label.clone(),
@@ -304,15 +643,15 @@ impl TerminalPanel {
             command: spawn_in_terminal.command.clone(),
             args: spawn_in_terminal.args.clone(),
             env: spawn_in_terminal.env.clone(),
+            env_files: spawn_in_terminal.env_files.clone(),
+            env_script: spawn_in_terminal.env_script.clone(),
             reveal: spawn_in_terminal.reveal,
         };
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
-        let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
-            Shell::System => std::env::var("SHELL").ok().map(|shell| (shell, vec![])),
-            Shell::Program(shell) => Some((shell, vec![])),
-            Shell::WithArguments { program, args } => Some((program, args)),
-        }) else {
-            return;
+        let Some((shell, mut user_args)) = task_shell(cx) else {
+            return Task::ready(Err(anyhow!(
+                "Cannot determine the shell to run the task in"
+            )));
         };
 
         let mut command = std::mem::take(&mut spawn_task.command);
@@ -331,14 +670,12 @@ impl TerminalPanel {
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
         if allow_concurrent_runs && use_new_terminal {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
-            return;
+            return self.spawn_in_new_terminal(spawn_task, working_directory, cx);
         }
 
         let terminals_for_task = self.terminals_for_task(&spawn_in_terminal.full_label, cx);
         if terminals_for_task.is_empty() {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
-            return;
+            return self.spawn_in_new_terminal(spawn_task, working_directory, cx);
         }
         let (existing_item_index, existing_terminal) = terminals_for_task
             .last()
@@ -355,31 +692,31 @@ impl TerminalPanel {
                 existing_item_index,
                 existing_terminal,
                 cx,
-            );
+            )
         } else {
+            let (new_terminal_tx, new_terminal_rx) = oneshot::channel();
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
                 cx.spawn(|terminal_panel, mut cx| async move {
                     wait_for_terminals_tasks(terminals_for_task, &mut cx).await;
-                    terminal_panel
-                        .update(&mut cx, |terminal_panel, cx| {
-                            if use_new_terminal {
-                                terminal_panel.spawn_in_new_terminal(
-                                    spawn_task,
-                                    working_directory,
-                                    cx,
-                                );
-                            } else {
-                                terminal_panel.replace_terminal(
-                                    working_directory,
-                                    spawn_task,
-                                    existing_item_index,
-                                    existing_terminal,
-                                    cx,
-                                );
-                            }
-                        })
-                        .ok();
+                    let new_terminal = terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        if use_new_terminal {
+                            terminal_panel.spawn_in_new_terminal(spawn_task, working_directory, cx)
+                        } else {
+                            terminal_panel.replace_terminal(
+                                working_directory,
+                                spawn_task,
+                                existing_item_index,
+                                existing_terminal,
+                                cx,
+                            )
+                        }
+                    });
+                    let new_terminal = match new_terminal {
+                        Ok(new_terminal) => new_terminal.await,
+                        Err(e) => Err(e),
+                    };
+                    new_terminal_tx.send(new_terminal).ok();
                 }),
             );
 
@@ -396,17 +733,108 @@ impl TerminalPanel {
                 }
                 RevealStrategy::Never => {}
             }
+
+            cx.spawn(|_, _| async move { new_terminal_rx.await? })
         }
     }
 
+    /// Runs the task with its output captured into a read-only buffer, in the tab of its previous runs if any,
+    /// resolving to the output of the task once it has finished.
+    fn start_task_in_buffer(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<TaskRunOutput>> {
+        let Some((shell, mut args)) = task_shell(cx) else {
+            return Task::ready(Err(anyhow!(
+                "Cannot determine the shell to run the task in"
+            )));
+        };
+        let mut command_line = spawn_in_terminal.command.clone();
+        for arg in &spawn_in_terminal.args {
+            command_line.push(' ');
+            command_line.push_str(arg);
+        }
+        args.extend(["-c".to_owned(), command_line]);
+        let mut command = smol::process::Command::new(shell);
+        command
+            .args(args)
+            .envs(&TerminalSettings::get_global(cx).env);
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("Workspace was dropped")));
+        };
+        let working_directory = spawn_in_terminal.cwd.clone().or_else(|| {
+            let strategy = TerminalSettings::get_global(cx).working_directory.clone();
+            crate::get_working_directory(workspace.read(cx), cx, strategy)
+        });
+        if let Some(working_directory) = &working_directory {
+            command.current_dir(working_directory);
+        }
+        let load_environment = workspace.read(cx).project().update(cx, |project, cx| {
+            project.load_environment(
+                working_directory.as_deref(),
+                &spawn_in_terminal.env_files,
+                spawn_in_terminal.env_script.as_deref(),
+                cx,
+            )
+        });
+
+        let existing_view =
+            if spawn_in_terminal.use_new_terminal && spawn_in_terminal.allow_concurrent_runs {
+                None
+            } else {
+                self.output_views_for_task(&spawn_in_terminal.full_label, cx)
+                    .pop()
+            };
+        let output_view = match existing_view {
+            Some((item_index, output_view)) => {
+                if spawn_in_terminal.reveal == RevealStrategy::Always {
+                    self.activate_terminal_view(item_index, cx);
+                }
+                output_view
+            }
+            None => {
+                let output_view = cx.new_view(|cx| TaskOutputView::new(spawn_in_terminal, cx));
+                self.pane.update(cx, |pane, cx| {
+                    let focus = pane.has_focus(cx);
+                    pane.add_item(Box::new(output_view.clone()), true, focus, None, cx);
+                });
+                output_view
+            }
+        };
+        if spawn_in_terminal.reveal == RevealStrategy::Always {
+            let task_workspace = self.workspace.clone();
+            cx.spawn(|_, mut cx| async move {
+                task_workspace
+                    .update(&mut cx, |workspace, cx| workspace.focus_panel::<Self>(cx))
+                    .ok()
+            })
+            .detach();
+        }
+
+        let spawn_in_terminal = SpawnInTerminal {
+            cwd: working_directory,
+            ..spawn_in_terminal.clone()
+        };
+        cx.spawn(|_, mut cx| async move {
+            let environment = load_environment.await;
+            command.envs(environment).envs(&spawn_in_terminal.env);
+            output_view
+                .update(&mut cx, |output_view, cx| {
+                    output_view.run(&spawn_in_terminal, command, cx)
+                })?
+                .await
+        })
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnTask,
         working_directory: Option<PathBuf>,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let reveal = spawn_task.reveal;
-        self.add_terminal(working_directory, Some(spawn_task), cx);
+        let new_terminal = self.add_terminal(working_directory, Some(spawn_task), cx);
         match reveal {
             RevealStrategy::Always => {
                 let task_workspace = self.workspace.clone();
@@ -419,6 +847,7 @@ impl TerminalPanel {
             }
             RevealStrategy::Never => {}
         }
+        new_terminal
     }
 
     ///Create a new Terminal in the current working directory or the user's home directory
@@ -431,7 +860,9 @@ impl TerminalPanel {
             return;
         };
 
-        this.update(cx, |this, cx| this.add_terminal(None, None, cx))
+        this.update(cx, |this, cx| {
+            this.add_terminal(None, None, cx).detach_and_log_err(cx)
+        })
     }
 
     fn terminals_for_task(
@@ -455,6 +886,20 @@ impl TerminalPanel {
             .collect()
     }
 
+    fn output_views_for_task(
+        &self,
+        label: &str,
+        cx: &mut AppContext,
+    ) -> Vec<(usize, View<TaskOutputView>)> {
+        self.pane
+            .read(cx)
+            .items()
+            .enumerate()
+            .filter_map(|(index, item)| Some((index, item.act_as::<TaskOutputView>(cx)?)))
+            .filter(|(_, output_view)| output_view.read(cx).full_label() == label)
+            .collect()
+    }
+
     fn activate_terminal_view(&self, item_index: usize, cx: &mut WindowContext) {
         self.pane.update(cx, |pane, cx| {
             pane.activate_item(item_index, true, true, cx)
@@ -466,12 +911,12 @@ impl TerminalPanel {
         working_directory: Option<PathBuf>,
         spawn_task: Option<SpawnTask>,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> Task<Result<Model<Terminal>>> {
         let workspace = self.workspace.clone();
         self.pending_terminals_to_add += 1;
         cx.spawn(|terminal_panel, mut cx| async move {
             let pane = terminal_panel.update(&mut cx, |this, _| this.pane.clone())?;
-            workspace.update(&mut cx, |workspace, cx| {
+            let create_terminal = workspace.update(&mut cx, |workspace, cx| {
                 let working_directory = if let Some(working_directory) = working_directory {
                     Some(working_directory)
                 } else {
@@ -481,32 +926,33 @@ impl TerminalPanel {
                 };
 
                 let window = cx.window_handle();
-                if let Some(terminal) = workspace.project().update(cx, |project, cx| {
-                    project
-                        .create_terminal(working_directory, spawn_task, window, cx)
-                        .log_err()
-                }) {
-                    let terminal = Box::new(cx.new_view(|cx| {
-                        TerminalView::new(
-                            terminal,
-                            workspace.weak_handle(),
-                            workspace.database_id(),
-                            cx,
-                        )
-                    }));
-                    pane.update(cx, |pane, cx| {
-                        let focus = pane.has_focus(cx);
-                        pane.add_item(terminal, true, focus, None, cx);
-                    });
-                }
+                workspace.project().update(cx, |project, cx| {
+                    project.create_terminal(working_directory, spawn_task, window, cx)
+                })
+            })?;
+            let new_terminal = create_terminal.await;
+            let new_terminal = workspace.update(&mut cx, |workspace, cx| {
+                let terminal = new_terminal?;
+                let terminal_view = Box::new(cx.new_view(|cx| {
+                    TerminalView::new(
+                        terminal.clone(),
+                        workspace.weak_handle(),
+                        workspace.database_id(),
+                        cx,
+                    )
+                }));
+                pane.update(cx, |pane, cx| {
+                    let focus = pane.has_focus(cx);
+                    pane.add_item(terminal_view, true, focus, None, cx);
+                });
+                anyhow::Ok(terminal)
             })?;
             terminal_panel.update(&mut cx, |this, cx| {
                 this.pending_terminals_to_add = this.pending_terminals_to_add.saturating_sub(1);
                 this.serialize(cx)
             })?;
-            anyhow::Ok(())
+            new_terminal
         })
-        .detach_and_log_err(cx);
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
@@ -560,43 +1006,157 @@ impl TerminalPanel {
         terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
-    ) -> Option<()> {
-        let project = self
+    ) -> Task<Result<Model<Terminal>>> {
+        let project = match self
             .workspace
             .update(cx, |workspace, _| workspace.project().clone())
-            .ok()?;
+        {
+            Ok(project) => project,
+            Err(e) => return Task::ready(Err(e)),
+        };
         let reveal = spawn_task.reveal;
         let window = cx.window_handle();
-        let new_terminal = project.update(cx, |project, cx| {
-            project
-                .create_terminal(working_directory, Some(spawn_task), window, cx)
-                .log_err()
-        })?;
-        terminal_to_replace.update(cx, |terminal_to_replace, cx| {
-            terminal_to_replace.set_terminal(new_terminal, cx);
+        let create_terminal = project.update(cx, |project, cx| {
+            project.create_terminal(working_directory, Some(spawn_task), window, cx)
         });
+        cx.spawn(|terminal_panel, mut cx| async move {
+            let new_terminal = create_terminal
+                .await
+                .context("Failed to replace the task terminal")?;
+            terminal_to_replace.update(&mut cx, |terminal_to_replace, cx| {
+                terminal_to_replace.set_terminal(new_terminal.clone(), cx);
+            })?;
 
-        match reveal {
-            RevealStrategy::Always => {
-                self.activate_terminal_view(terminal_item_index, cx);
-                let task_workspace = self.workspace.clone();
-                cx.spawn(|_, mut cx| async move {
+            match reveal {
+                RevealStrategy::Always => {
+                    terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        terminal_panel.activate_terminal_view(terminal_item_index, cx)
+                    })?;
+                    let task_workspace = terminal_panel.update(&mut cx, |terminal_panel, _| {
+                        terminal_panel.workspace.clone()
+                    })?;
                     task_workspace
                         .update(&mut cx, |workspace, cx| workspace.focus_panel::<Self>(cx))
-                        .ok()
-                })
-                .detach();
+                        .ok();
+                }
+                RevealStrategy::Never => {}
             }
-            RevealStrategy::Never => {}
-        }
 
-        Some(())
+            Ok(new_terminal)
+        })
     }
     pub fn pane(&self) -> &View<Pane> {
         &self.pane
     }
 }
 
+/// The shell to run tasks in, with its arguments, as the tasks are always spawned inside of a shell.
+fn task_shell(cx: &AppContext) -> Option<(String, Vec<String>)> {
+    match TerminalSettings::get_global(cx).shell.clone() {
+        Shell::System => std::env::var("SHELL").ok().map(|shell| (shell, vec![])),
+        Shell::Program(shell) => Some((shell, vec![])),
+        Shell::WithArguments { program, args } => Some((program, args)),
+    }
+}
+
+/// Runs a task itself, without its dependencies, resolving to whether it succeeded.
+type RunTask = Rc<dyn Fn(SpawnInTerminal) -> LocalBoxFuture<'static, Result<bool>>>;
+
+/// The runs of the tasks a spawned task depends on, by id, so that tasks several others depend on
+/// only run once.
+type TaskRuns =
+    Rc<RefCell<HashMap<TaskId, Shared<LocalBoxFuture<'static, Result<bool, Arc<anyhow::Error>>>>>>>;
+
+/// Runs the task with `run_task` after the tasks it depends on, resolving to whether the task
+/// succeeded. Fails if any of its dependencies did not succeed, in which case the task is not run.
+fn run_with_dependencies(
+    spawn_in_terminal: SpawnInTerminal,
+    runs: TaskRuns,
+    run_task: RunTask,
+) -> LocalBoxFuture<'static, Result<bool>> {
+    async move {
+        let run_dependency = |dependency: &SpawnInTerminal| {
+            runs.borrow_mut()
+                .entry(dependency.id.clone())
+                .or_insert_with(|| {
+                    run_with_dependencies(dependency.clone(), runs.clone(), run_task.clone())
+                        .map(|result| result.map_err(Arc::new))
+                        .boxed_local()
+                        .shared()
+                })
+                .clone()
+        };
+        let dependency_failed = |dependency: &SpawnInTerminal| {
+            anyhow!(
+                "Task {:?} did not run, as its dependency {:?} failed",
+                spawn_in_terminal.label,
+                dependency.label
+            )
+        };
+        match spawn_in_terminal.depends_order {
+            DependsOrder::Sequence => {
+                for dependency in &spawn_in_terminal.dependencies {
+                    let succeeded = run_dependency(dependency)
+                        .await
+                        .map_err(|e| anyhow!("{e:#}"))?;
+                    if !succeeded {
+                        return Err(dependency_failed(dependency));
+                    }
+                }
+            }
+            DependsOrder::Parallel => {
+                let results = join_all(
+                    spawn_in_terminal
+                        .dependencies
+                        .iter()
+                        .map(run_dependency)
+                        .collect::<Vec<_>>(),
+                )
+                .await;
+                for (dependency, succeeded) in spawn_in_terminal.dependencies.iter().zip(results) {
+                    if !succeeded.map_err(|e| anyhow!("{e:#}"))? {
+                        return Err(dependency_failed(dependency));
+                    }
+                }
+            }
+        }
+        run_task(spawn_in_terminal).await
+    }
+    .boxed_local()
+}
+
+/// Whether the task of the terminal succeeded, along with its exit code.
+/// Matches the output of a finished task against its problem matchers, replacing the diagnostics of its previous run.
+async fn report_task_problems(
+    terminal_panel: &WeakView<TerminalPanel>,
+    spawn_in_terminal: &SpawnInTerminal,
+    output: String,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let problem_matchers = spawn_in_terminal.problem_matchers.clone();
+    let cwd = spawn_in_terminal.cwd.clone();
+    let problems = cx
+        .background_executor()
+        .spawn(async move {
+            problem_matchers
+                .iter()
+                .flat_map(|matcher| matcher.match_output(&output, cwd.as_deref()))
+                .collect::<Vec<_>>()
+        })
+        .await;
+    terminal_panel.update(cx, |terminal_panel, cx| {
+        terminal_panel.update_task_diagnostics(&spawn_in_terminal.full_label, problems, cx)
+    })
+}
+
+fn task_outcome(terminal: &Terminal) -> (bool, Option<i32>) {
+    let succeeded = terminal.task().map_or(false, |task_state| {
+        task_state.status == TaskStatus::Completed { success: true }
+    });
+    let exit_code = terminal.task().and_then(|task_state| task_state.exit_code);
+    (succeeded, exit_code)
+}
+
 async fn wait_for_terminals_tasks(
     terminals_for_task: Vec<(usize, View<TerminalView>)>,
     cx: &mut AsyncWindowContext,
@@ -708,7 +1268,7 @@ impl Panel for TerminalPanel {
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
         if active && self.pane.read(cx).items_len() == 0 && self.pending_terminals_to_add == 0 {
-            self.add_terminal(None, None, cx)
+            self.add_terminal(None, None, cx).detach_and_log_err(cx)
         }
     }
 
@@ -745,3 +1305,95 @@ struct SerializedTerminalPanel {
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use task::{TaskContext, TaskTemplate};
+
+    fn spawn_in_terminal(templates: &[TaskTemplate], label: &str) -> SpawnInTerminal {
+        let find_template = |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .map(|template| ("test".to_string(), template.clone()))
+        };
+        let (id_base, template) = find_template(label).unwrap();
+        let cx = TaskContext::default();
+        let mut spawn_in_terminal = template
+            .resolve_task(&id_base, &cx)
+            .unwrap()
+            .resolved
+            .unwrap();
+        spawn_in_terminal.dependencies =
+            template.resolve_dependencies(&cx, &find_template).unwrap();
+        spawn_in_terminal
+    }
+
+    fn template(label: &str, depends_on: &[&str], depends_order: DependsOrder) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            depends_order,
+            ..TaskTemplate::default()
+        }
+    }
+
+    /// Runs the task with a runner that records the labels of the tasks it runs, and fails the
+    /// tasks labeled `failing`.
+    fn run(
+        spawn_in_terminal: SpawnInTerminal,
+        failing: &'static str,
+    ) -> (Result<bool>, Vec<String>) {
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let run_task: RunTask = Rc::new({
+            let runs = runs.clone();
+            move |spawn_in_terminal| {
+                runs.borrow_mut().push(spawn_in_terminal.label.clone());
+                futures::future::ready(Ok(spawn_in_terminal.label != failing)).boxed_local()
+            }
+        });
+        let result = smol::block_on(run_with_dependencies(
+            spawn_in_terminal,
+            TaskRuns::default(),
+            run_task,
+        ));
+        let runs = runs.borrow().clone();
+        (result, runs)
+    }
+
+    #[test]
+    fn test_failed_dependency_stops_the_chain() {
+        let templates = [
+            template("build", &[], DependsOrder::Parallel),
+            template("test", &[], DependsOrder::Parallel),
+            template("deploy", &["build", "test"], DependsOrder::Sequence),
+        ];
+
+        let (result, runs) = run(spawn_in_terminal(&templates, "deploy"), "none");
+        assert!(result.unwrap());
+        assert_eq!(runs, ["build", "test", "deploy"]);
+
+        let (result, runs) = run(spawn_in_terminal(&templates, "deploy"), "build");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Task \"deploy\" did not run, as its dependency \"build\" failed"
+        );
+        assert_eq!(runs, ["build"]);
+    }
+
+    #[test]
+    fn test_shared_dependency_runs_once() {
+        let templates = [
+            template("install", &[], DependsOrder::Parallel),
+            template("lint", &["install"], DependsOrder::Parallel),
+            template("test", &["install"], DependsOrder::Parallel),
+            template("check", &["lint", "test"], DependsOrder::Parallel),
+        ];
+
+        let (result, runs) = run(spawn_in_terminal(&templates, "check"), "none");
+        assert!(result.unwrap());
+        assert_eq!(runs, ["install", "lint", "test", "check"]);
+    }
+}
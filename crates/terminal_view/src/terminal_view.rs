@@ -1,4 +1,5 @@
 mod persistence;
+pub mod task_output;
 pub mod terminal_element;
 pub mod terminal_panel;
 
@@ -118,24 +119,27 @@ impl TerminalView {
             get_working_directory(workspace, cx, strategy.working_directory.clone());
 
         let window = cx.window_handle();
-        let terminal = workspace
-            .project()
-            .update(cx, |project, cx| {
-                project.create_terminal(working_directory, None, window, cx)
-            })
-            .notify_err(workspace, cx);
+        let create_terminal = workspace.project().update(cx, |project, cx| {
+            project.create_terminal(working_directory, None, window, cx)
+        });
 
-        if let Some(terminal) = terminal {
-            let view = cx.new_view(|cx| {
-                TerminalView::new(
-                    terminal,
-                    workspace.weak_handle(),
-                    workspace.database_id(),
-                    cx,
-                )
-            });
-            workspace.add_item_to_active_pane(Box::new(view), cx)
-        }
+        cx.spawn(|workspace, mut cx| async move {
+            let terminal = create_terminal.await;
+            workspace.update(&mut cx, |workspace, cx| {
+                if let Some(terminal) = terminal.notify_err(workspace, cx) {
+                    let view = cx.new_view(|cx| {
+                        TerminalView::new(
+                            terminal,
+                            workspace.weak_handle(),
+                            workspace.database_id(),
+                            cx,
+                        )
+                    });
+                    workspace.add_item_to_active_pane(Box::new(view), cx)
+                }
+            })
+        })
+        .detach_and_log_err(cx);
     }
 
     pub fn new(
@@ -894,9 +898,11 @@ impl Item for TerminalView {
                 })
                 .filter(|cwd| !cwd.as_os_str().is_empty());
 
-            let terminal = project.update(&mut cx, |project, cx| {
-                project.create_terminal(cwd, None, window, cx)
-            })??;
+            let terminal = project
+                .update(&mut cx, |project, cx| {
+                    project.create_terminal(cwd, None, window, cx)
+                })?
+                .await?;
             pane.update(&mut cx, |_, cx| {
                 cx.new_view(|cx| TerminalView::new(terminal, workspace, workspace_id, cx))
             })
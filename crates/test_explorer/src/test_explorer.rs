@@ -0,0 +1,44 @@
+mod test_explorer_panel;
+mod test_explorer_settings;
+
+use gpui::{AppContext, ViewContext};
+use settings::Settings;
+use workspace::Workspace;
+
+pub use test_explorer_panel::{
+    GoToNextFailure, Refresh, RunAll, RunFailed, TestExplorerPanel, ToggleFocus,
+};
+pub use test_explorer_settings::TestExplorerSettings;
+
+pub fn init(cx: &mut AppContext) {
+    TestExplorerSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<TestExplorerPanel>(cx);
+        });
+        workspace.register_action(|workspace, _: &RunAll, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.run_all(cx))
+        });
+        workspace.register_action(|workspace, _: &RunFailed, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.run_failed(cx))
+        });
+        workspace.register_action(|workspace, _: &Refresh, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.refresh(cx))
+        });
+        workspace.register_action(|workspace, _: &GoToNextFailure, cx| {
+            update_panel(workspace, cx, |panel, cx| panel.go_to_next_failure(cx))
+        });
+    })
+    .detach();
+}
+
+fn update_panel(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+    update: impl FnOnce(&mut TestExplorerPanel, &mut ViewContext<TestExplorerPanel>),
+) {
+    if let Some(panel) = workspace.panel::<TestExplorerPanel>(cx) {
+        panel.update(cx, update);
+    }
+}
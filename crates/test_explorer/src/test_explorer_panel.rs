@@ -0,0 +1,1058 @@
+use std::{
+    collections::BTreeSet,
+    mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use futures::StreamExt as _;
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View, WeakView,
+};
+use language::{Bias, Grammar, Point, RunnableRange};
+use project::{Fs, Location, Project, TestResultsStoreEvent, WorktreeId};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use task::{TestResult, TestStatus};
+use ui::{prelude::*, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::test_explorer_settings::TestExplorerSettings;
+
+const TEST_EXPLORER_PANEL_KEY: &str = "TestExplorerPanel";
+/// How many files are loaded and searched for tests at once.
+const DISCOVERY_CONCURRENCY: usize = 16;
+
+actions!(
+    test_explorer,
+    [ToggleFocus, RunAll, RunFailed, Refresh, GoToNextFailure]
+);
+
+#[derive(Serialize, Deserialize)]
+struct SerializedTestExplorerPanel {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+/// A test found by the runnables query of the language of its file.
+#[derive(Clone, Debug, PartialEq)]
+struct DiscoveredTest {
+    name: String,
+    /// The name of the module or class containing the test, if the query captures it.
+    suite: Option<String>,
+    /// The 0-based row the test starts on.
+    row: u32,
+    runnable: RunnableRange,
+}
+
+struct TestFile {
+    abs_path: PathBuf,
+    /// The path of the file relative to its worktree, prefixed with the name of the worktree.
+    display_path: String,
+    tests: Vec<DiscoveredTest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TestEntry {
+    File {
+        file_ix: usize,
+    },
+    Suite {
+        file_ix: usize,
+        name: String,
+    },
+    Test {
+        file_ix: usize,
+        test_ix: usize,
+        depth: usize,
+    },
+}
+
+impl TestEntry {
+    fn file_ix(&self) -> usize {
+        match self {
+            Self::File { file_ix } | Self::Suite { file_ix, .. } | Self::Test { file_ix, .. } => {
+                *file_ix
+            }
+        }
+    }
+
+    fn set_file_ix(&mut self, ix: usize) {
+        match self {
+            Self::File { file_ix } | Self::Suite { file_ix, .. } | Self::Test { file_ix, .. } => {
+                *file_ix = ix
+            }
+        }
+    }
+}
+
+/// A panel listing the tests of the project, as found by the runnables queries of their languages,
+/// with the results of their latest runs. Tests are run through the tasks carrying a test report.
+pub struct TestExplorerPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    /// The files with tests, ordered by display path.
+    files: Vec<TestFile>,
+    /// Whether the tests of every file of the worktrees were found, since worktrees were last added or removed.
+    discovered: bool,
+    /// The files, and the directories, that changed since their tests were last found, with the display
+    /// paths of the files that still exist.
+    changed_paths: HashMap<PathBuf, Option<String>>,
+    active: bool,
+    collapsed_files: HashSet<PathBuf>,
+    selected_entry: Option<TestEntry>,
+    pending_discovery: Option<Task<()>>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TestExplorerPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let test_results_store = project.read(cx).test_results_store().clone();
+            let subscriptions = vec![
+                cx.subscribe(
+                    &test_results_store,
+                    |_, _, _: &TestResultsStoreEvent, cx| cx.notify(),
+                ),
+                cx.subscribe(&project, |this, _, event, cx| match event {
+                    project::Event::WorktreeAdded | project::Event::WorktreeRemoved(_) => {
+                        this.refresh(cx)
+                    }
+                    project::Event::WorktreeUpdatedEntries(worktree_id, changes) => this
+                        .paths_changed(
+                            *worktree_id,
+                            changes.iter().map(|(path, _, _)| path.as_ref()),
+                            cx,
+                        ),
+                    _ => {}
+                }),
+            ];
+
+            Self {
+                workspace: workspace_handle,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                height: None,
+                files: Vec::new(),
+                discovered: false,
+                changed_paths: HashMap::default(),
+                active: false,
+                collapsed_files: HashSet::default(),
+                selected_entry: None,
+                pending_discovery: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(TEST_EXPLORER_PANEL_KEY) })
+                .await;
+            let serialized_panel = if let Some(panel) = serialized_panel.log_err().flatten() {
+                Some(serde_json::from_str::<SerializedTestExplorerPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        panel.height = serialized_panel.height.map(|h| h.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let height = self.height;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        TEST_EXPLORER_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedTestExplorerPanel { width, height })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Finds the tests of every file of the visible local worktrees again, reading the files from disk.
+    pub fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.discovered = false;
+        self.changed_paths.clear();
+        self.pending_discovery = None;
+        self.discover(cx);
+    }
+
+    /// Records that the given paths of a worktree changed, to find their tests again while the panel is
+    /// active.
+    fn paths_changed<'a>(
+        &mut self,
+        worktree_id: WorktreeId,
+        paths: impl Iterator<Item = &'a Path>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        // Changes are found along with every other file when the tests were not found yet, unless they
+        // are being found already.
+        if !self.discovered && self.pending_discovery.is_none() {
+            return;
+        }
+        let Some(worktree) = self.project.read(cx).worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let worktree = worktree.read(cx);
+        if !worktree.is_local() || !worktree.is_visible() {
+            return;
+        }
+        let abs_path = worktree.abs_path();
+        let root_name = Path::new(worktree.root_name());
+        for path in paths {
+            let display_path = worktree
+                .entry_for_path(path)
+                .filter(|entry| entry.is_file())
+                .map(|_| root_name.join(path).to_string_lossy().to_string());
+            self.changed_paths.insert(abs_path.join(path), display_path);
+        }
+        self.discover(cx);
+    }
+
+    /// Finds the tests of the files that changed since their tests were last found, or of every file of
+    /// the visible local worktrees if they were not found yet, loading and searching the files in the
+    /// background. Nothing is found while the panel is inactive.
+    fn discover(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.active || self.pending_discovery.is_some() {
+            return;
+        }
+        let project = self.project.read(cx);
+        let languages = project.languages().clone();
+        let (paths, changed_paths) = if self.discovered {
+            if self.changed_paths.is_empty() {
+                return;
+            }
+            let changed_paths = mem::take(&mut self.changed_paths);
+            let paths = changed_paths
+                .iter()
+                .filter_map(|(abs_path, display_path)| {
+                    Some((abs_path.clone(), display_path.clone()?))
+                })
+                .collect::<Vec<_>>();
+            (paths, Some(changed_paths))
+        } else {
+            let paths = project
+                .visible_worktrees(cx)
+                .filter(|worktree| worktree.read(cx).is_local())
+                .flat_map(|worktree| {
+                    let worktree = worktree.read(cx);
+                    let abs_path = worktree.abs_path();
+                    let root_name = Path::new(worktree.root_name());
+                    worktree
+                        .files(false, 0)
+                        .map(|entry| {
+                            (
+                                abs_path.join(&entry.path),
+                                root_name.join(&entry.path).to_string_lossy().to_string(),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            (paths, None)
+        };
+        let fs = self.fs.clone();
+
+        let files = cx.background_executor().spawn(async move {
+            futures::stream::iter(paths)
+                .map(|(abs_path, display_path)| {
+                    let languages = languages.clone();
+                    let fs = fs.clone();
+                    async move {
+                        let language = languages.language_for_file_path(&abs_path).await.ok()?;
+                        let grammar = language
+                            .grammar()
+                            .filter(|grammar| grammar.has_runnables())?
+                            .clone();
+                        let text = fs.load(&abs_path).await.ok()?;
+                        let tests = discover_tests(&grammar, &text);
+                        (!tests.is_empty()).then_some(TestFile {
+                            abs_path,
+                            display_path,
+                            tests,
+                        })
+                    }
+                })
+                .buffered(DISCOVERY_CONCURRENCY)
+                .filter_map(|file| async move { file })
+                .collect::<Vec<_>>()
+                .await
+        });
+        self.pending_discovery = Some(cx.spawn(|this, mut cx| async move {
+            let files = files.await;
+            this.update(&mut cx, |this, cx| {
+                let selection = this.selected_entry.take().and_then(|entry| {
+                    let abs_path = this.files.get(entry.file_ix())?.abs_path.clone();
+                    Some((entry, abs_path))
+                });
+                match &changed_paths {
+                    Some(changed_paths) => {
+                        this.files
+                            .retain(|file| !is_changed(&file.abs_path, changed_paths));
+                        this.files.extend(files);
+                    }
+                    None => {
+                        this.files = files;
+                        this.discovered = true;
+                    }
+                }
+                this.files
+                    .sort_by(|a, b| a.display_path.cmp(&b.display_path));
+                // The selection is kept unless the tests of its file may have changed.
+                this.selected_entry = changed_paths.and_then(|changed_paths| {
+                    let (mut entry, abs_path) = selection?;
+                    if is_changed(&abs_path, &changed_paths) {
+                        return None;
+                    }
+                    let file_ix = this
+                        .files
+                        .iter()
+                        .position(|file| file.abs_path == abs_path)?;
+                    entry.set_file_ix(file_ix);
+                    Some(entry)
+                });
+                this.pending_discovery = None;
+                this.discover(cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn test_result(&self, file_ix: usize, test_ix: usize, cx: &AppContext) -> Option<TestResult> {
+        let file = self.files.get(file_ix)?;
+        let test = file.tests.get(test_ix)?;
+        self.project
+            .read(cx)
+            .test_results_store()
+            .read(cx)
+            .result_for(&file.abs_path, &test.name, test.suite.as_deref())
+            .cloned()
+    }
+
+    /// Returns the combined status of the given tests of a file: failed if any of them failed, and
+    /// passed if all of them ran and none failed.
+    fn combined_status(
+        &self,
+        file_ix: usize,
+        test_ixs: impl IntoIterator<Item = usize>,
+        cx: &AppContext,
+    ) -> Option<TestStatus> {
+        let mut all_ran = true;
+        let mut any_passed = false;
+        for test_ix in test_ixs {
+            match self
+                .test_result(file_ix, test_ix, cx)
+                .map(|result| result.status)
+            {
+                Some(TestStatus::Failed) => return Some(TestStatus::Failed),
+                Some(TestStatus::Passed) => any_passed = true,
+                Some(TestStatus::Skipped) => {}
+                None => all_ran = false,
+            }
+        }
+        (all_ran && any_passed).then_some(TestStatus::Passed)
+    }
+
+    fn entries(&self) -> Vec<TestEntry> {
+        let mut entries = Vec::new();
+        for (file_ix, file) in self.files.iter().enumerate() {
+            entries.push(TestEntry::File { file_ix });
+            if self.collapsed_files.contains(&file.abs_path) {
+                continue;
+            }
+            let mut suites = Vec::<Option<&str>>::new();
+            for test in &file.tests {
+                if !suites.contains(&test.suite.as_deref()) {
+                    suites.push(test.suite.as_deref());
+                }
+            }
+            for suite in suites {
+                if let Some(name) = suite {
+                    entries.push(TestEntry::Suite {
+                        file_ix,
+                        name: name.to_string(),
+                    });
+                }
+                for (test_ix, test) in file.tests.iter().enumerate() {
+                    if test.suite.as_deref() == suite {
+                        entries.push(TestEntry::Test {
+                            file_ix,
+                            test_ix,
+                            depth: if suite.is_some() { 2 } else { 1 },
+                        });
+                    }
+                }
+            }
+        }
+        entries
+    }
+
+    /// Runs all the tests of each file, through the tasks that run all the tests of a file or of its
+    /// package. Files of the same package are run once.
+    pub fn run_all(&mut self, cx: &mut ViewContext<Self>) {
+        let targets = self
+            .files
+            .iter()
+            .map(|file| (file.abs_path.clone(), 0, file_runnable(file)))
+            .collect();
+        self.run(targets, cx);
+    }
+
+    /// Runs the tests that failed in their latest run.
+    pub fn run_failed(&mut self, cx: &mut ViewContext<Self>) {
+        let mut targets = Vec::new();
+        for (file_ix, file) in self.files.iter().enumerate() {
+            for (test_ix, test) in file.tests.iter().enumerate() {
+                let status = self
+                    .test_result(file_ix, test_ix, cx)
+                    .map(|result| result.status);
+                if status == Some(TestStatus::Failed) {
+                    targets.push((file.abs_path.clone(), test.row, test.runnable.clone()));
+                }
+            }
+        }
+        self.run(targets, cx);
+    }
+
+    fn run_file(&mut self, file_ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(file) = self.files.get(file_ix) {
+            let target = (file.abs_path.clone(), 0, file_runnable(file));
+            self.run(vec![target], cx);
+        }
+    }
+
+    fn run_test(&mut self, file_ix: usize, test_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(file) = self.files.get(file_ix) else {
+            return;
+        };
+        let Some(test) = file.tests.get(test_ix) else {
+            return;
+        };
+        self.run(
+            vec![(file.abs_path.clone(), test.row, test.runnable.clone())],
+            cx,
+        );
+    }
+
+    /// Spawns, for each runnable, the first task with a test report that runs one of its tags,
+    /// spawning tasks that resolve to the same command only once.
+    fn run(&mut self, targets: Vec<(PathBuf, u32, RunnableRange)>, cx: &mut ViewContext<Self>) {
+        if targets.is_empty() {
+            return;
+        }
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let mut spawned_tasks = HashSet::default();
+            let mut unrunnable_tags = BTreeSet::new();
+            for (abs_path, row, runnable) in targets {
+                let buffer = project
+                    .update(&mut cx, |project, cx| {
+                        project.open_local_buffer(&abs_path, cx)
+                    })?
+                    .await?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    let buffer_snapshot = buffer.read(cx);
+                    let point = buffer_snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                    let position = buffer_snapshot.anchor_after(point);
+                    let location = Location {
+                        buffer,
+                        range: position..position,
+                    };
+                    let tags = runnable.tags.clone();
+                    match tasks_ui::resolve_runnable_task(
+                        workspace,
+                        location,
+                        runnable,
+                        |task| task.test_report.is_some(),
+                        cx,
+                    ) {
+                        Some((task_source_kind, resolved_task)) => {
+                            if spawned_tasks.insert(resolved_task.id.clone()) {
+                                tasks_ui::schedule_resolved_task(
+                                    workspace,
+                                    task_source_kind,
+                                    resolved_task,
+                                    false,
+                                    cx,
+                                );
+                            }
+                        }
+                        None => unrunnable_tags.extend(tags.into_iter().map(|tag| tag.0)),
+                    }
+                })?;
+            }
+            if !unrunnable_tags.is_empty() {
+                let tags = unrunnable_tags.into_iter().collect::<Vec<_>>().join(", ");
+                workspace.update(&mut cx, |workspace, cx| {
+                    workspace.show_error(
+                        &anyhow!("no task with a test report runs the tests tagged {tags}"),
+                        cx,
+                    )
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Selects and opens the next test that failed in its latest run, after the selected entry.
+    pub fn go_to_next_failure(&mut self, cx: &mut ViewContext<Self>) {
+        let entries = self.entries();
+        let start = self
+            .selected_entry
+            .as_ref()
+            .and_then(|selected| entries.iter().position(|entry| entry == selected))
+            .map_or(0, |ix| ix + 1);
+        let next_failure = entries[start..]
+            .iter()
+            .chain(&entries[..start])
+            .find_map(|entry| match *entry {
+                TestEntry::Test {
+                    file_ix, test_ix, ..
+                } => {
+                    let status = self
+                        .test_result(file_ix, test_ix, cx)
+                        .map(|result| result.status);
+                    (status == Some(TestStatus::Failed)).then_some(entry.clone())
+                }
+                _ => None,
+            });
+        if let Some(entry) = next_failure {
+            self.select_entry(entry, cx);
+        }
+    }
+
+    fn select_entry(&mut self, entry: TestEntry, cx: &mut ViewContext<Self>) {
+        match &entry {
+            TestEntry::File { file_ix } => self.open_file(*file_ix, 0, cx),
+            TestEntry::Suite { file_ix, name } => {
+                let row = self.files.get(*file_ix).and_then(|file| {
+                    file.tests
+                        .iter()
+                        .find(|test| test.suite.as_ref() == Some(name))
+                        .map(|test| test.row)
+                });
+                self.open_file(*file_ix, row.unwrap_or(0), cx);
+            }
+            TestEntry::Test {
+                file_ix, test_ix, ..
+            } => {
+                let row = self
+                    .test_result(*file_ix, *test_ix, cx)
+                    .filter(|result| result.status == TestStatus::Failed)
+                    .and_then(|result| result.row)
+                    .or_else(|| Some(self.files.get(*file_ix)?.tests.get(*test_ix)?.row));
+                self.open_file(*file_ix, row.unwrap_or(0), cx);
+            }
+        }
+        self.selected_entry = Some(entry);
+        cx.notify();
+    }
+
+    fn open_file(&mut self, file_ix: usize, row: u32, cx: &mut ViewContext<Self>) {
+        let Some(file) = self.files.get(file_ix) else {
+            return;
+        };
+        let abs_path = file.abs_path.clone();
+        let Some(open) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(abs_path, true, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let editor = open
+                .await?
+                .downcast::<Editor>()
+                .context("test file isn't opened in an editor")?;
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let point = snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([point..point])
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn toggle_file(&mut self, file_ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(file) = self.files.get(file_ix) {
+            if !self.collapsed_files.remove(&file.abs_path) {
+                self.collapsed_files.insert(file.abs_path.clone());
+            }
+            cx.notify();
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let (mut passed, mut failed) = (0, 0);
+        for (file_ix, file) in self.files.iter().enumerate() {
+            for test_ix in 0..file.tests.len() {
+                match self
+                    .test_result(file_ix, test_ix, cx)
+                    .map(|result| result.status)
+                {
+                    Some(TestStatus::Passed) => passed += 1,
+                    Some(TestStatus::Failed) => failed += 1,
+                    _ => {}
+                }
+            }
+        }
+        let title = if !self.discovered {
+            "Finding tests…".to_string()
+        } else {
+            let total = self
+                .files
+                .iter()
+                .map(|file| file.tests.len())
+                .sum::<usize>();
+            format!("{total} tests, {passed} passed, {failed} failed")
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(render_button(
+                        "test-explorer-run-all",
+                        IconName::Play,
+                        "Run All Tests",
+                        RunAll,
+                        !self.files.is_empty(),
+                    ))
+                    .child(render_button(
+                        "test-explorer-run-failed",
+                        IconName::ReplyArrowRight,
+                        "Run Failed Tests",
+                        RunFailed,
+                        failed > 0,
+                    ))
+                    .child(render_button(
+                        "test-explorer-next-failure",
+                        IconName::ArrowDown,
+                        "Go to Next Failure",
+                        GoToNextFailure,
+                        failed > 0,
+                    ))
+                    .child(render_button(
+                        "test-explorer-refresh",
+                        IconName::Update,
+                        "Refresh",
+                        Refresh,
+                        self.discovered,
+                    )),
+            )
+    }
+
+    fn render_entry(&self, ix: usize, entry: TestEntry, cx: &mut ViewContext<Self>) -> ListItem {
+        let selected = self.selected_entry.as_ref() == Some(&entry);
+        let (label, depth, status, message, run_button) = match &entry {
+            TestEntry::File { file_ix } => {
+                let file = &self.files[*file_ix];
+                let file_ix = *file_ix;
+                (
+                    file.display_path.clone(),
+                    0,
+                    self.combined_status(file_ix, 0..file.tests.len(), cx),
+                    None,
+                    render_run_button(
+                        ix,
+                        cx.listener(move |this, _, cx| this.run_file(file_ix, cx)),
+                    ),
+                )
+            }
+            TestEntry::Suite { file_ix, name } => {
+                let file = &self.files[*file_ix];
+                let test_ixs = file
+                    .tests
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, test)| test.suite.as_ref() == Some(name))
+                    .map(|(test_ix, _)| test_ix)
+                    .collect::<Vec<_>>();
+                let file_ix = *file_ix;
+                (
+                    name.clone(),
+                    1,
+                    self.combined_status(file_ix, test_ixs, cx),
+                    None,
+                    // Test tasks run single tests or whole files, so suites run along with their file.
+                    render_run_button(
+                        ix,
+                        cx.listener(move |this, _, cx| this.run_file(file_ix, cx)),
+                    ),
+                )
+            }
+            TestEntry::Test {
+                file_ix,
+                test_ix,
+                depth,
+            } => {
+                let test = &self.files[*file_ix].tests[*test_ix];
+                let result = self.test_result(*file_ix, *test_ix, cx);
+                let (file_ix, test_ix) = (*file_ix, *test_ix);
+                (
+                    test.name.clone(),
+                    *depth,
+                    result.as_ref().map(|result| result.status),
+                    result
+                        .filter(|result| result.status == TestStatus::Failed)
+                        .and_then(|result| result.message),
+                    render_run_button(
+                        ix,
+                        cx.listener(move |this, _, cx| this.run_test(file_ix, test_ix, cx)),
+                    ),
+                )
+            }
+        };
+
+        let status_icon = match status {
+            Some(TestStatus::Passed) => Icon::new(IconName::Check).color(Color::Success),
+            Some(TestStatus::Failed) => Icon::new(IconName::XCircle).color(Color::Error),
+            Some(TestStatus::Skipped) => Icon::new(IconName::Dash).color(Color::Muted),
+            None => Icon::new(IconName::Dash).color(Color::Disabled),
+        };
+        let mut item = ListItem::new(("test-entry", ix))
+            .indent_level(depth)
+            .selected(selected)
+            .start_slot(status_icon.size(IconSize::Small))
+            .child(Label::new(label).size(LabelSize::Small))
+            .end_hover_slot(run_button);
+        if let TestEntry::File { file_ix } = entry {
+            let expanded = self
+                .files
+                .get(file_ix)
+                .map_or(false, |file| !self.collapsed_files.contains(&file.abs_path));
+            item = item
+                .toggle(expanded)
+                .on_toggle(cx.listener(move |this, _, cx| this.toggle_file(file_ix, cx)));
+        }
+        if let Some(message) = message {
+            let first_line = message.lines().next().unwrap_or_default().to_string();
+            item = item
+                .end_slot(
+                    Label::new(first_line)
+                        .color(Color::Error)
+                        .size(LabelSize::Small),
+                )
+                .tooltip(move |cx| Tooltip::text(message.clone(), cx));
+        }
+        item.on_click(cx.listener(move |this, _, cx| this.select_entry(entry.clone(), cx)))
+    }
+}
+
+/// Returns the tests in the given text of a file, as found by the runnables query of its language.
+/// Matches starting on the same row, such as a test matched both on its own and within its module,
+/// are merged into one, and runnables that aren't tests, such as entry points, are left out.
+fn discover_tests(grammar: &Grammar, text: &str) -> Vec<DiscoveredTest> {
+    let mut runnables_by_row = Vec::<(u32, RunnableRange)>::new();
+    for runnable in grammar.runnables_in_text(text) {
+        let row = text[..runnable.run_range.start].matches('\n').count() as u32;
+        match runnables_by_row.iter_mut().find(|(r, _)| *r == row) {
+            Some((_, merged)) => {
+                for tag in runnable.tags {
+                    if !merged.tags.contains(&tag) {
+                        merged.tags.push(tag);
+                    }
+                }
+                for (capture_name, text) in runnable.extra_captures {
+                    merged.extra_captures.entry(capture_name).or_insert(text);
+                }
+            }
+            None => runnables_by_row.push((row, runnable)),
+        }
+    }
+    runnables_by_row.sort_by_key(|(row, _)| *row);
+
+    runnables_by_row
+        .into_iter()
+        .filter(|(_, runnable)| runnable.tags.iter().any(|tag| tag.0.ends_with("-test")))
+        .filter_map(|(row, runnable)| {
+            let name = runnable.extra_captures.get("test_name")?.clone();
+            let suite = runnable
+                .extra_captures
+                .get("test_module")
+                .or_else(|| runnable.extra_captures.get("test_class"))
+                .cloned();
+            Some(DiscoveredTest {
+                name,
+                suite,
+                row,
+                runnable,
+            })
+        })
+        .collect()
+}
+
+/// Whether the given file is one of the changed paths, or within one of them.
+fn is_changed(abs_path: &Path, changed_paths: &HashMap<PathBuf, Option<String>>) -> bool {
+    changed_paths
+        .keys()
+        .any(|changed_path| abs_path.starts_with(changed_path))
+}
+
+/// Returns a runnable with the tags of all the tests of the file and no captures, to run the tasks
+/// running all the tests of a file or of its package.
+fn file_runnable(file: &TestFile) -> RunnableRange {
+    let mut tags = Vec::new();
+    for test in &file.tests {
+        for tag in &test.runnable.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+    }
+    RunnableRange {
+        run_range: 0..0,
+        tags,
+        extra_captures: HashMap::default(),
+    }
+}
+
+fn render_run_button(
+    ix: usize,
+    on_click: impl Fn(&gpui::ClickEvent, &mut WindowContext) + 'static,
+) -> IconButton {
+    IconButton::new(("test-entry-run", ix), IconName::Play)
+        .icon_size(IconSize::XSmall)
+        .icon_color(Color::Success)
+        .on_click(on_click)
+}
+
+fn render_button(
+    id: &'static str,
+    icon: IconName,
+    tooltip: &'static str,
+    action: impl gpui::Action,
+    enabled: bool,
+) -> IconButton {
+    let tooltip_action = action.boxed_clone();
+    IconButton::new(id, icon)
+        .icon_size(IconSize::Small)
+        .disabled(!enabled)
+        .tooltip(move |cx| Tooltip::for_action(tooltip, &*tooltip_action, cx))
+        .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+}
+
+impl Render for TestExplorerPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entries = self.entries();
+        let content = if entries.is_empty() && self.discovered {
+            div()
+                .p_2()
+                .child(
+                    Label::new("No tests found in the project.")
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                )
+                .into_any_element()
+        } else {
+            v_flex()
+                .id("test-explorer-entries")
+                .flex_1()
+                .overflow_y_scroll()
+                .children(
+                    entries
+                        .into_iter()
+                        .enumerate()
+                        .map(|(ix, entry)| self.render_entry(ix, entry, cx)),
+                )
+                .into_any_element()
+        };
+        v_flex()
+            .key_context("TestExplorerPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(content)
+    }
+}
+
+impl FocusableView for TestExplorerPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TestExplorerPanel {}
+
+impl Panel for TestExplorerPanel {
+    fn persistent_name() -> &'static str {
+        "TestExplorerPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        TestExplorerSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<TestExplorerSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        let settings = TestExplorerSettings::get_global(cx);
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        self.discover(cx);
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        TestExplorerSettings::get_global(cx)
+            .button
+            .then_some(IconName::Flask)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Test Explorer")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use language::{tree_sitter_rust, Language, LanguageConfig};
+    use unindent::Unindent as _;
+
+    use super::*;
+
+    #[test]
+    fn test_discover_tests() {
+        let language = Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_runnable_query(
+            r#"
+            (
+                (attribute_item (attribute (identifier) @_attribute))
+                .
+                (function_item name: (_) @run @test_name)
+                (#eq? @_attribute "test")
+                (#set! tag rust-test)
+            )
+            (
+                (mod_item
+                    name: (_) @test_module
+                    body: (declaration_list
+                        (attribute_item (attribute (identifier) @_attribute))
+                        .
+                        (function_item name: (_) @run @test_name)))
+                (#eq? @_attribute "test")
+                (#set! tag rust-test)
+            )
+            (
+                (source_file (function_item name: (identifier) @run))
+                (#eq? @run "main")
+                (#set! tag rust-main)
+            )
+            "#,
+        )
+        .unwrap();
+        let text = r#"
+            fn main() {}
+
+            #[test]
+            fn top_level() {}
+
+            mod tests {
+                #[test]
+                fn nested() {}
+            }
+        "#
+        .unindent();
+
+        let tests = discover_tests(language.grammar().unwrap(), &text);
+        assert_eq!(
+            tests
+                .iter()
+                .map(|test| (test.name.as_str(), test.suite.as_deref(), test.row))
+                .collect::<Vec<_>>(),
+            vec![("top_level", None, 3), ("nested", Some("tests"), 7)]
+        );
+    }
+}
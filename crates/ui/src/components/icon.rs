@@ -41,6 +41,7 @@ pub enum IconName {
     BellRing,
     BellDot,
     Bolt,
+    Bookmark,
     CaseSensitive,
     Check,
     ChevronDown,
@@ -57,6 +58,14 @@ pub enum IconName {
     CopilotInit,
     Copy,
     Dash,
+    Debug,
+    DebugBreakpoint,
+    DebugContinue,
+    DebugPause,
+    DebugStepInto,
+    DebugStepOut,
+    DebugStepOver,
+    DebugStop,
     Delete,
     Disconnected,
     Ellipsis,
@@ -74,6 +83,7 @@ pub enum IconName {
     FileToml,
     FileTree,
     Filter,
+    Flask,
     Folder,
     FolderOpen,
     FolderX,
@@ -140,6 +150,7 @@ impl IconName {
             IconName::BellRing => "icons/bell_ring.svg",
             IconName::BellDot => "icons/bell_dot.svg",
             IconName::Bolt => "icons/bolt.svg",
+            IconName::Bookmark => "icons/bookmark.svg",
             IconName::CaseSensitive => "icons/case_insensitive.svg",
             IconName::Check => "icons/check.svg",
             IconName::ChevronDown => "icons/chevron_down.svg",
@@ -156,6 +167,14 @@ impl IconName {
             IconName::CopilotInit => "icons/copilot_init.svg",
             IconName::Copy => "icons/copy.svg",
             IconName::Dash => "icons/dash.svg",
+            IconName::Debug => "icons/debug.svg",
+            IconName::DebugBreakpoint => "icons/debug_breakpoint.svg",
+            IconName::DebugContinue => "icons/debug_continue.svg",
+            IconName::DebugPause => "icons/debug_pause.svg",
+            IconName::DebugStepInto => "icons/debug_step_into.svg",
+            IconName::DebugStepOut => "icons/debug_step_out.svg",
+            IconName::DebugStepOver => "icons/debug_step_over.svg",
+            IconName::DebugStop => "icons/debug_stop.svg",
             IconName::Delete => "icons/delete.svg",
             IconName::Disconnected => "icons/disconnected.svg",
             IconName::Ellipsis => "icons/ellipsis.svg",
@@ -173,6 +192,7 @@ impl IconName {
             IconName::FileToml => "icons/file_icons/toml.svg",
             IconName::FileTree => "icons/project.svg",
             IconName::Filter => "icons/filter.svg",
+            IconName::Flask => "icons/flask.svg",
             IconName::Folder => "icons/file_icons/folder.svg",
             IconName::FolderOpen => "icons/file_icons/folder_open.svg",
             IconName::FolderX => "icons/stop_sharing.svg",
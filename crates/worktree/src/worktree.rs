@@ -160,6 +160,8 @@ pub struct Snapshot {
 pub struct RepositoryEntry {
     pub(crate) work_directory: WorkDirectoryEntry,
     pub(crate) branch: Option<Arc<str>>,
+    /// The commit checked out in the repository. Only known for local worktrees.
+    pub(crate) head_sha: Option<Arc<str>>,
 }
 
 impl RepositoryEntry {
@@ -167,6 +169,10 @@ impl RepositoryEntry {
         self.branch.clone()
     }
 
+    pub fn head_sha(&self) -> Option<Arc<str>> {
+        self.head_sha.clone()
+    }
+
     pub fn work_directory_id(&self) -> ProjectEntryId {
         *self.work_directory
     }
@@ -1885,6 +1891,7 @@ impl Snapshot {
                         RepositoryEntry {
                             work_directory: work_directory_entry,
                             branch: repository.branch.map(Into::into),
+                            head_sha: None,
                         },
                     )
                 }
@@ -2669,6 +2676,7 @@ impl BackgroundScannerState {
                     log::info!("reload git repository {dot_git_dir:?}");
                     let repository = repository.repo_ptr.lock();
                     let branch = repository.branch_name();
+                    let head_sha = repository.head_sha();
                     repository.reload_index();
 
                     self.snapshot
@@ -2677,7 +2685,10 @@ impl BackgroundScannerState {
                     self.snapshot
                         .snapshot
                         .repository_entries
-                        .update(&work_dir, |entry| entry.branch = branch.map(Into::into));
+                        .update(&work_dir, |entry| {
+                            entry.branch = branch.map(Into::into);
+                            entry.head_sha = head_sha.map(Into::into);
+                        });
 
                     self.update_git_statuses(&work_dir, &*repository);
                 }
@@ -2765,6 +2776,7 @@ impl BackgroundScannerState {
             RepositoryEntry {
                 work_directory: work_dir_id.into(),
                 branch: repo_lock.branch_name().map(Into::into),
+                head_sha: repo_lock.head_sha().map(Into::into),
             },
         );
 
@@ -269,6 +269,7 @@ fn init_ui() {
         editor::init(cx);
         image_viewer::init(cx);
         diagnostics::init(cx);
+        diff_view::init(cx);
         copilot::init(
             copilot_language_server_id,
             http.clone(),
@@ -353,6 +354,7 @@ fn init_ui() {
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
+        bookmarks::init(cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
         tasks_ui::init(cx);
@@ -365,6 +367,10 @@ fn init_ui() {
         language_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
+        hierarchy_view::init(cx);
+        debugger_ui::init(cx);
+        test_explorer::init(cx);
+        git_ui::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         collab_ui::init(&app_state, cx);
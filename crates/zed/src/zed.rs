@@ -131,7 +131,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             cx.new_view(|cx| diagnostics::items::DiagnosticIndicator::new(workspace, cx));
         let activity_indicator =
             activity_indicator::ActivityIndicator::new(workspace, app_state.languages.clone(), cx);
-        let tasks_indicator = tasks_ui::TaskStatusIndicator::new(workspace.weak_handle(), cx);
+        let tasks_indicator = tasks_ui::TaskStatusIndicator::new(workspace, cx);
         let active_buffer_language =
             cx.new_view(|_| language_selector::ActiveBufferLanguage::new(workspace));
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
@@ -195,6 +195,11 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let debugger_panel =
+                debugger_ui::DebuggerPanel::load(workspace_handle.clone(), cx.clone());
+            let test_explorer_panel =
+                test_explorer::TestExplorerPanel::load(workspace_handle.clone(), cx.clone());
+            let git_panel = git_ui::GitPanel::load(workspace_handle.clone(), cx.clone());
             let (
                 project_panel,
                 terminal_panel,
@@ -202,6 +207,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                debugger_panel,
+                test_explorer_panel,
+                git_panel,
             ) = futures::try_join!(
                 project_panel,
                 terminal_panel,
@@ -209,6 +217,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                debugger_panel,
+                test_explorer_panel,
+                git_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -218,6 +229,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(debugger_panel, cx);
+                workspace.add_panel(test_explorer_panel, cx);
+                workspace.add_panel(git_panel, cx);
                 cx.focus_self();
             })
         })